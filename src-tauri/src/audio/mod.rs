@@ -1,11 +1,16 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use hound::{WavSpec, WavWriter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, TryRecvError};
 use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
 use std::fs::File;
 use std::io::BufWriter;
+use std::thread;
+use std::time::Duration;
 use chrono::Utc;
 use anyhow::{Context, Result};
+use crate::clock::{Clocks, SystemClocks};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum RecordingState {
@@ -14,144 +19,339 @@ pub enum RecordingState {
     Stopped,
 }
 
+/// Outcome of a stop. A take with zero samples written is deleted rather
+/// than left behind as a useless 44-byte WAV header.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopResult {
+    Saved(PathBuf),
+    Empty,
+}
+
+/// Commands sent from the Tauri command handlers to the recording
+/// controller thread.
+#[derive(Debug, Clone)]
+pub enum AudioControlMessage {
+    Pause,
+    Resume,
+    Stop,
+    SetGain(f32),
+}
+
+/// Status pushed back from the controller thread to whoever is driving it
+/// (elapsed-time polling, VU metering, completion notification).
+#[derive(Debug, Clone)]
+pub enum AudioStatusMessage {
+    Elapsed(i32),
+    /// Throttled (~every 50ms) input level, in dBFS.
+    Peak(AudioLevel),
+    Stopped(StopResult),
+    Error(String),
+}
+
+/// Input level for VU metering, in dBFS (clamped at a -60dB floor).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioLevel {
+    pub peak_dbfs: f32,
+    pub rms_dbfs: f32,
+}
+
+const DBFS_FLOOR: f32 = -60.0;
+
+fn amplitude_to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return DBFS_FLOOR;
+    }
+    (20.0 * amplitude.log10()).max(DBFS_FLOOR)
+}
+
+const LEVEL_THROTTLE: Duration = Duration::from_millis(50);
+
 pub struct AudioRecorder {
     sample_rate: u32,
     channels: u16,
+    clock: Arc<dyn Clocks>,
 }
 
-pub struct RecordingHandle {
-    stream: cpal::Stream,
-    writer: Arc<Mutex<Option<WavWriter<BufWriter<File>>>>>,
-    state: Arc<Mutex<RecordingState>>,
-    output_path: PathBuf,
-    start_time: chrono::DateTime<Utc>,
+/// Public handle to a running recording. The cpal callback only ever pushes
+/// raw sample buffers onto a bounded channel - it never blocks or takes a
+/// mutex - so a contended writer can't cause dropped/glitched audio. A
+/// dedicated controller thread owns the `WavWriter`, drains the sample
+/// channel, and reacts to `AudioControlMessage`s sent through this handle.
+pub struct AudioController {
+    control_tx: mpsc::Sender<AudioControlMessage>,
+    status_rx: mpsc::Receiver<AudioStatusMessage>,
+    recording_state: Arc<Mutex<RecordingState>>,
+    latest_level: Arc<Mutex<AudioLevel>>,
 }
 
 impl AudioRecorder {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClocks))
+    }
+
+    /// Like `new`, but with an injected clock - lets tests drive elapsed-time
+    /// assertions with a `SimulatedClocks` instead of real sleeps.
+    pub fn with_clock(clock: Arc<dyn Clocks>) -> Self {
         Self {
             sample_rate: 48000,
             channels: 2,
+            clock,
         }
     }
-    
-    pub fn start_recording(&self, output_path: PathBuf) -> Result<RecordingHandle> {
-        // Get default audio input device
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
-        
-        let supported_config = device
-            .default_input_config()
-            .context("Failed to get default input config")?;
-        
-        let config: cpal::StreamConfig = supported_config.clone().into();
-        
-        // Create WAV file with device's actual configuration
-        let spec = WavSpec {
-            channels: config.channels,
-            sample_rate: config.sample_rate.0,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-        
-        let writer = WavWriter::create(&output_path, spec)
-            .context("Failed to create WAV file")?;
-        let writer = Arc::new(Mutex::new(Some(writer)));
-        let writer_clone = writer.clone();
-        
-        // Shared state for pause/resume control
+
+    pub fn start_recording(&self, output_path: PathBuf) -> Result<AudioController> {
+        self.start_recording_with_device(output_path, None)
+    }
+
+    /// Like `start_recording`, but selects the input device by name instead of
+    /// always using the host default. Falls back to the default device when
+    /// `device_name` is `None` or doesn't match any enumerated device.
+    pub fn start_recording_with_device(
+        &self,
+        output_path: PathBuf,
+        device_name: Option<String>,
+    ) -> Result<AudioController> {
         let recording_state = Arc::new(Mutex::new(RecordingState::Recording));
-        let state_clone = recording_state.clone();
-        
-        // Build audio input stream
-        let stream = device.build_input_stream(
-            &config,
-            move |data: &[f32], _: &_| {
-                // Only write when state is Recording (not Paused)
-                if let Ok(state_guard) = state_clone.try_lock() {
-                    if *state_guard == RecordingState::Recording {
-                        if let Ok(mut guard) = writer_clone.try_lock() {
-                            if let Some(ref mut writer) = *guard {
-                                for &sample in data {
-                                    let sample = (sample * i16::MAX as f32) as i16;
-                                    let _ = writer.write_sample(sample);
-                                }
-                            }
-                        }
+        let latest_level = Arc::new(Mutex::new(AudioLevel { peak_dbfs: DBFS_FLOOR, rms_dbfs: DBFS_FLOOR }));
+
+        let (control_tx, control_rx) = mpsc::channel::<AudioControlMessage>();
+        let (status_tx, status_rx) = mpsc::channel::<AudioStatusMessage>();
+        // Used to report stream-setup failures back from the controller
+        // thread, since `cpal::Stream` isn't `Send` and must be both built
+        // and dropped on the thread that owns it.
+        let (setup_tx, setup_rx) = mpsc::channel::<Result<(), String>>();
+
+        let clock = self.clock.clone();
+        let controller_state = recording_state.clone();
+        let controller_level = latest_level.clone();
+        thread::spawn(move || {
+            run_controller(output_path, device_name, clock, controller_state, controller_level, control_rx, status_tx, setup_tx);
+        });
+
+        setup_rx.recv()
+            .context("Controller thread exited before reporting setup status")?
+            .map_err(anyhow::Error::msg)?;
+
+        Ok(AudioController {
+            control_tx,
+            status_rx,
+            recording_state,
+            latest_level,
+        })
+    }
+}
+
+/// Builds the cpal stream and owns it (along with the `WavWriter`) for the
+/// life of the recording - `cpal::Stream` isn't `Send`, so it must be both
+/// created and dropped on this same thread. The stream's real-time callback
+/// only pushes sample buffers onto a bounded channel via `try_send`, which
+/// never blocks; this loop is the only place that drains it, writes to the
+/// WAV file, and reacts to control messages off that hot path.
+fn run_controller(
+    output_path: PathBuf,
+    device_name: Option<String>,
+    clock: Arc<dyn Clocks>,
+    recording_state: Arc<Mutex<RecordingState>>,
+    latest_level: Arc<Mutex<AudioLevel>>,
+    control_rx: mpsc::Receiver<AudioControlMessage>,
+    status_tx: mpsc::Sender<AudioStatusMessage>,
+    setup_tx: mpsc::Sender<Result<(), String>>,
+) {
+    let (sample_tx, sample_rx) = mpsc::sync_channel::<Vec<f32>>(64);
+
+    let (stream, mut writer) = match build_input_stream(&output_path, device_name.as_deref(), sample_tx) {
+        Ok(built) => built,
+        Err(e) => {
+            let _ = setup_tx.send(Err(e.to_string()));
+            return;
+        }
+    };
+    let _ = setup_tx.send(Ok(()));
+
+    let start_time = clock.now();
+    let samples_written = AtomicU64::new(0);
+    let mut gain: f32 = 1.0;
+    let mut pause_started_at: Option<chrono::DateTime<Utc>> = None;
+    let mut pause_duration: i32 = 0;
+    // Level metering is throttled to ~50ms of wall-clock time so the status
+    // channel and frontend aren't flooded at full buffer rate.
+    let mut last_level_emit = clock.now();
+    let mut level_peak_since_emit: f32 = 0.0;
+    let mut level_sum_sq_since_emit: f64 = 0.0;
+    let mut level_samples_since_emit: usize = 0;
+
+    loop {
+        match control_rx.try_recv() {
+            Ok(AudioControlMessage::Pause) => {
+                *recording_state.lock().unwrap() = RecordingState::Paused;
+                pause_started_at = Some(clock.now());
+            }
+            Ok(AudioControlMessage::Resume) => {
+                *recording_state.lock().unwrap() = RecordingState::Recording;
+                if let Some(paused_at) = pause_started_at.take() {
+                    pause_duration += (clock.now() - paused_at).num_seconds() as i32;
+                }
+            }
+            Ok(AudioControlMessage::Stop) => break,
+            Ok(AudioControlMessage::SetGain(new_gain)) => gain = new_gain,
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => break,
+        }
+
+        match sample_rx.recv_timeout(Duration::from_millis(50)) {
+            Ok(data) => {
+                if *recording_state.lock().unwrap() == RecordingState::Recording {
+                    for &sample in &data {
+                        let abs = sample.abs();
+                        level_peak_since_emit = level_peak_since_emit.max(abs);
+                        level_sum_sq_since_emit += (sample as f64) * (sample as f64);
+                        level_samples_since_emit += 1;
+
+                        let sample = (sample * gain).clamp(-1.0, 1.0);
+                        let sample = (sample * i16::MAX as f32) as i16;
+                        let _ = writer.write_sample(sample);
                     }
+                    samples_written.fetch_add(data.len() as u64, Ordering::Relaxed);
                 }
-            },
-            |err| eprintln!("Error in audio stream: {}", err),
-            None,
-        ).context("Failed to build input stream")?;
-        
-        stream.play().context("Failed to start audio stream")?;
-        
-        Ok(RecordingHandle {
-            stream,
-            writer,
-            state: recording_state,
-            output_path,
-            start_time: Utc::now(),
-        })
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if level_samples_since_emit > 0 && (clock.now() - last_level_emit).num_milliseconds() >= LEVEL_THROTTLE.as_millis() as i64 {
+            let rms = (level_sum_sq_since_emit / level_samples_since_emit as f64).sqrt() as f32;
+            let level = AudioLevel {
+                peak_dbfs: amplitude_to_dbfs(level_peak_since_emit),
+                rms_dbfs: amplitude_to_dbfs(rms),
+            };
+            *latest_level.lock().unwrap() = level;
+            let _ = status_tx.send(AudioStatusMessage::Peak(level));
+
+            last_level_emit = clock.now();
+            level_peak_since_emit = 0.0;
+            level_sum_sq_since_emit = 0.0;
+            level_samples_since_emit = 0;
+        }
+
+        let now = pause_started_at.unwrap_or_else(|| clock.now());
+        let elapsed = (now - start_time).num_seconds() as i32 - pause_duration;
+        let _ = status_tx.send(AudioStatusMessage::Elapsed(elapsed));
+    }
+
+    drop(stream);
+
+    let stop_result = match writer.finalize() {
+        Ok(()) => {
+            if samples_written.load(Ordering::Relaxed) == 0 {
+                let _ = std::fs::remove_file(&output_path);
+                StopResult::Empty
+            } else {
+                StopResult::Saved(output_path)
+            }
+        }
+        Err(e) => {
+            let _ = status_tx.send(AudioStatusMessage::Error(format!("Failed to finalize WAV file: {}", e)));
+            StopResult::Empty
+        }
+    };
+
+    let _ = status_tx.send(AudioStatusMessage::Stopped(stop_result));
+}
+
+/// Opens the requested input device (by name) and wires its callback to push
+/// sample buffers onto `sample_tx` via `try_send` - never blocking, never
+/// taking a lock - so the real-time audio thread can't stall or drop frames
+/// because of writer contention. Falls back to the host default device when
+/// `device_name` is `None` or doesn't match any enumerated device.
+fn build_input_stream(
+    output_path: &PathBuf,
+    device_name: Option<&str>,
+    sample_tx: mpsc::SyncSender<Vec<f32>>,
+) -> Result<(cpal::Stream, WavWriter<BufWriter<File>>)> {
+    let host = cpal::default_host();
+    let device = find_input_device(&host, device_name)
+        .context("No input device available")?;
+
+    let supported_config = device
+        .default_input_config()
+        .context("Failed to get default input config")?;
+
+    let config: cpal::StreamConfig = supported_config.clone().into();
+
+    let spec = WavSpec {
+        channels: config.channels,
+        sample_rate: config.sample_rate.0,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let writer = WavWriter::create(output_path, spec)
+        .context("Failed to create WAV file")?;
+
+    let stream = device.build_input_stream(
+        &config,
+        move |data: &[f32], _: &_| {
+            let _ = sample_tx.try_send(data.to_vec());
+        },
+        |err| eprintln!("Error in audio stream: {}", err),
+        None,
+    ).context("Failed to build input stream")?;
+
+    stream.play().context("Failed to start audio stream")?;
+
+    Ok((stream, writer))
+}
+
+/// Looks up an input device by name among the host's enumerated input
+/// devices, falling back to the host default when `device_name` is `None` or
+/// doesn't match any of them.
+fn find_input_device(host: &cpal::Host, device_name: Option<&str>) -> Option<cpal::Device> {
+    if let Some(wanted) = device_name {
+        if let Ok(mut devices) = host.input_devices() {
+            if let Some(device) = devices.find(|d| d.name().map(|n| n == wanted).unwrap_or(false)) {
+                return Some(device);
+            }
+        }
     }
+    host.default_input_device()
 }
 
-impl RecordingHandle {
+impl AudioController {
     pub fn pause(&self) -> Result<()> {
-        let mut state = self.state.lock().unwrap();
-        *state = RecordingState::Paused;
-        Ok(())
+        self.control_tx.send(AudioControlMessage::Pause).context("Failed to send pause command")
     }
-    
+
     pub fn resume(&self) -> Result<()> {
-        let mut state = self.state.lock().unwrap();
-        *state = RecordingState::Recording;
-        Ok(())
+        self.control_tx.send(AudioControlMessage::Resume).context("Failed to send resume command")
     }
-    
-    pub fn stop(self) -> Result<PathBuf> {
-        // Update state
-        {
-            let mut state = self.state.lock().unwrap();
-            *state = RecordingState::Stopped;
-        }
-        
-        // Stop the stream
-        self.stream.pause().context("Failed to pause stream")?;
-        
-        // Finalize the WAV file
-        {
-            let mut writer_guard = self.writer.lock().unwrap();
-            if let Some(writer) = writer_guard.take() {
-                writer.finalize().context("Failed to finalize WAV file")?;
-            }
-        }
-        
-        Ok(self.output_path)
+
+    pub fn set_gain(&self, gain: f32) -> Result<()> {
+        self.control_tx.send(AudioControlMessage::SetGain(gain)).context("Failed to send gain command")
     }
-    
-    pub fn get_elapsed_time(&self) -> i32 {
-        let now = Utc::now();
-        (now - self.start_time).num_seconds() as i32
+
+    /// Signal the controller thread to finalize and stop. The eventual
+    /// `AudioStatusMessage::Stopped` arrives asynchronously via `try_recv_status`.
+    pub fn stop(&self) -> Result<()> {
+        self.control_tx.send(AudioControlMessage::Stop).context("Failed to send stop command")
     }
-    
+
+    /// Drain the next pending status message, if any, without blocking.
+    pub fn try_recv_status(&self) -> Option<AudioStatusMessage> {
+        self.status_rx.try_recv().ok()
+    }
+
+    /// Latest throttled input level (dBFS), for a `get_recording_level`-style
+    /// command to poll instead of draining the status channel.
+    pub fn get_level(&self) -> AudioLevel {
+        *self.latest_level.lock().unwrap()
+    }
+
     pub fn is_paused(&self) -> bool {
-        if let Ok(state) = self.state.try_lock() {
-            *state == RecordingState::Paused
-        } else {
-            false
-        }
+        *self.recording_state.lock().unwrap() == RecordingState::Paused
     }
-    
+
     pub fn is_recording(&self) -> bool {
-        if let Ok(state) = self.state.try_lock() {
-            *state == RecordingState::Recording
-        } else {
-            false
-        }
+        *self.recording_state.lock().unwrap() == RecordingState::Recording
     }
 }
 
@@ -161,10 +361,10 @@ pub fn get_recordings_directory() -> Result<PathBuf> {
         .context("Failed to get current directory")?
         .join("data")
         .join("recordings");
-    
+
     std::fs::create_dir_all(&app_data_dir)
         .context("Failed to create recordings directory")?;
-    
+
     Ok(app_data_dir)
 }
 
@@ -176,10 +376,10 @@ pub fn generate_recording_filename() -> String {
 pub fn get_audio_duration(filepath: &str) -> Result<i32> {
     let reader = hound::WavReader::open(filepath)
         .context("Failed to open audio file")?;
-    
+
     let spec = reader.spec();
     let samples = reader.len();
     let duration_seconds = samples as f64 / (spec.sample_rate as f64 * spec.channels as f64);
-    
+
     Ok(duration_seconds as i32)
-}
\ No newline at end of file
+}
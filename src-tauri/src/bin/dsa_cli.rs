@@ -0,0 +1,226 @@
+//! Headless companion to the Tauri app: scriptable access to the same
+//! SQLite database, for backing up or batch-editing a problem set outside
+//! the GUI. Opens the database the same way `DatabaseManager::new` does and
+//! calls straight into the existing `database::DatabaseManager` methods -
+//! no separate code path for the data layer.
+
+use clap::{Args, Parser, Subcommand};
+use dsa_learning_app::database::DatabaseManager;
+use dsa_learning_app::models::FrontendProblem;
+
+#[derive(Parser)]
+#[command(name = "dsa-cli", about = "Manage and search the DSA learning app's problem database")]
+struct Cli {
+    /// Print results as JSON instead of a human-readable table.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Search, relate, and delete problems.
+    #[command(subcommand)]
+    Problems(ProblemsCommand),
+    /// Tag autocomplete.
+    #[command(subcommand)]
+    Tags(TagsCommand),
+    /// Read and edit a problem's solution card.
+    #[command(subcommand)]
+    Solution(SolutionCommand),
+}
+
+#[derive(Subcommand)]
+enum ProblemsCommand {
+    /// Search problems by title, topic, or tags.
+    Search(SearchArgs),
+    /// Manage the related-problems graph.
+    #[command(subcommand)]
+    Related(RelatedCommand),
+    /// Move a problem to the recycle bin (soft delete).
+    Delete {
+        id: String,
+        /// Skip the confirmation prompt.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    query: String,
+    /// Which field to search by.
+    #[arg(long = "by", value_enum, default_value_t = SearchField::Title)]
+    by: SearchField,
+    #[arg(long, default_value_t = 10)]
+    limit: i32,
+}
+
+#[derive(Clone, clap::ValueEnum)]
+enum SearchField {
+    Title,
+    Topic,
+    Tags,
+}
+
+#[derive(Subcommand)]
+enum RelatedCommand {
+    /// Add a (bidirectional) relation between two problems.
+    Add { id: String, other_id: String },
+    /// Remove the relation between two problems.
+    Remove { id: String, other_id: String },
+    /// List a problem's related problems.
+    List { id: String },
+}
+
+#[derive(Subcommand)]
+enum TagsCommand {
+    /// Suggest tag names completing `prefix`.
+    Suggest {
+        prefix: String,
+        #[arg(long, default_value_t = 10)]
+        limit: i32,
+    },
+}
+
+#[derive(Subcommand)]
+enum SolutionCommand {
+    /// Print a problem's solution card.
+    Get { problem_id: String },
+    /// Replace a problem's solution code, creating the card if needed.
+    SetCode {
+        problem_id: String,
+        code: String,
+        #[arg(long, default_value = "javascript")]
+        language: String,
+    },
+    /// Replace a problem's solution notes, creating the card if needed.
+    SetNotes { problem_id: String, notes: String },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let mut db = DatabaseManager::new().await?;
+
+    match cli.command {
+        Command::Problems(cmd) => run_problems(&mut db, cmd, cli.json)?,
+        Command::Tags(cmd) => run_tags(&db, cmd, cli.json)?,
+        Command::Solution(cmd) => run_solution(&db, cmd, cli.json)?,
+    }
+
+    Ok(())
+}
+
+fn run_problems(db: &mut DatabaseManager, cmd: ProblemsCommand, json: bool) -> anyhow::Result<()> {
+    match cmd {
+        ProblemsCommand::Search(args) => {
+            let results = match args.by {
+                SearchField::Title => db.search_problems_by_title(&args.query, args.limit, None)?,
+                SearchField::Topic => db.search_problems_by_topic(&args.query)?,
+                SearchField::Tags => db.search_problems_by_tags(&args.query)?,
+            };
+            print_problems(&results, json);
+        }
+        ProblemsCommand::Related(RelatedCommand::Add { id, other_id }) => {
+            db.add_problem_relation(&id, &other_id)?;
+            println!("Related {} <-> {}", id, other_id);
+        }
+        ProblemsCommand::Related(RelatedCommand::Remove { id, other_id }) => {
+            db.remove_problem_relation(&id, &other_id)?;
+            println!("Unrelated {} <-> {}", id, other_id);
+        }
+        ProblemsCommand::Related(RelatedCommand::List { id }) => {
+            let results = db.get_related_problems(&id)?;
+            print_problems(&results, json);
+        }
+        ProblemsCommand::Delete { id, yes } => {
+            if !yes {
+                anyhow::bail!("This will move problem '{}' to the recycle bin. Re-run with --yes to confirm.", id);
+            }
+            db.soft_delete_problem(&id)?;
+            println!("Moved problem {} to the recycle bin.", id);
+        }
+    }
+    Ok(())
+}
+
+fn run_tags(db: &DatabaseManager, cmd: TagsCommand, json: bool) -> anyhow::Result<()> {
+    let TagsCommand::Suggest { prefix, limit } = cmd;
+    let suggestions = db.get_tag_suggestions(&prefix, limit)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&suggestions)?);
+    } else {
+        for tag in &suggestions {
+            println!("{}", tag);
+        }
+    }
+    Ok(())
+}
+
+fn run_solution(db: &DatabaseManager, cmd: SolutionCommand, json: bool) -> anyhow::Result<()> {
+    match cmd {
+        SolutionCommand::Get { problem_id } => {
+            let card = db.get_solution_card(&problem_id)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&card)?);
+            } else {
+                match card {
+                    Some(card) => {
+                        println!("language: {}", card.language);
+                        println!("status: {}", card.status);
+                        println!("--- code ---\n{}", card.code);
+                        println!("--- notes ---\n{}", card.notes);
+                    }
+                    None => println!("No solution card for problem {}", problem_id),
+                }
+            }
+        }
+        SolutionCommand::SetCode { problem_id, code, language } => {
+            let card_id = existing_or_new_solution_card(db, &problem_id)?;
+            db.update_solution_card_code(&card_id, &code, &language)?;
+            println!("Updated solution code for problem {}", problem_id);
+        }
+        SolutionCommand::SetNotes { problem_id, notes } => {
+            let card_id = existing_or_new_solution_card(db, &problem_id)?;
+            db.update_solution_card_notes(&card_id, &notes)?;
+            println!("Updated solution notes for problem {}", problem_id);
+        }
+    }
+    Ok(())
+}
+
+fn existing_or_new_solution_card(db: &DatabaseManager, problem_id: &str) -> anyhow::Result<String> {
+    match db.get_solution_card(problem_id)? {
+        Some(card) => Ok(card.id),
+        None => Ok(db.create_solution_card(problem_id)?.id),
+    }
+}
+
+fn print_problems(problems: &[FrontendProblem], json: bool) {
+    if json {
+        match serde_json::to_string_pretty(problems) {
+            Ok(text) => println!("{}", text),
+            Err(e) => eprintln!("Failed to serialize results: {}", e),
+        }
+        return;
+    }
+
+    if problems.is_empty() {
+        println!("No problems found.");
+        return;
+    }
+
+    println!("{:<38} {:<10} {:<30} TITLE", "ID", "DIFFICULTY", "TOPIC");
+    for problem in problems {
+        println!(
+            "{:<38} {:<10} {:<30} {}",
+            problem.id,
+            problem.difficulty,
+            problem.topic.join(","),
+            problem.title,
+        );
+    }
+}
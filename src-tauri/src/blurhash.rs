@@ -0,0 +1,147 @@
+// BlurHash encoding (https://blurha.sh) for `save_problem_image`: a short
+// string the frontend can decode into a blurred placeholder and paint
+// immediately, instead of waiting for the full image (or even its
+// thumbnail, see `commands/images.rs`) to load.
+
+const BASE83_ALPHABET: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// sRGB -> linear light, applied per channel before any DCT math, per the
+/// BlurHash spec.
+fn srgb_to_linear(value: u8) -> f64 {
+    let c = value as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let c = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+fn encode_base83(mut value: u32, length: usize) -> String {
+    let mut digits = vec![0u8; length];
+    for i in (0..length).rev() {
+        digits[i] = BASE83_ALPHABET[(value % 83) as usize];
+        value /= 83;
+    }
+    String::from_utf8(digits).expect("base83 alphabet is ASCII")
+}
+
+/// One (i, j) component of the 2D DCT over the image's linear-light pixels.
+fn multiply_basis_function(
+    i: u32,
+    j: u32,
+    width: u32,
+    height: u32,
+    pixels: &[[f64; 3]],
+) -> [f64; 3] {
+    let mut sum = [0.0f64; 3];
+    let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+
+    for y in 0..height {
+        for x in 0..width {
+            let basis = (std::f64::consts::PI * i as f64 * x as f64 / width as f64).cos()
+                * (std::f64::consts::PI * j as f64 * y as f64 / height as f64).cos();
+            let pixel = &pixels[(y * width + x) as usize];
+            sum[0] += basis * pixel[0];
+            sum[1] += basis * pixel[1];
+            sum[2] += basis * pixel[2];
+        }
+    }
+
+    let scale = normalisation / (width as f64 * height as f64);
+    [sum[0] * scale, sum[1] * scale, sum[2] * scale]
+}
+
+fn encode_dc(rgb: [f64; 3]) -> u32 {
+    let r = linear_to_srgb(rgb[0]) as u32;
+    let g = linear_to_srgb(rgb[1]) as u32;
+    let b = linear_to_srgb(rgb[2]) as u32;
+    (r << 16) + (g << 8) + b
+}
+
+fn encode_ac(rgb: [f64; 3], max_ac: f64) -> u32 {
+    // Per the BlurHash spec, AC components are companded with a signed
+    // square root before quantizing - `sign(x) * sqrt(|x| / max)` - not a
+    // plain linear scale; decoders invert this with a square, so skipping it
+    // here would make every placeholder systematically low-contrast.
+    let quantize = |value: f64| -> f64 {
+        let normalised = value / max_ac;
+        (normalised.signum() * normalised.abs().sqrt() * 9.0 + 9.5).clamp(0.0, 18.0).floor()
+    };
+    let r = quantize(rgb[0]) as u32;
+    let g = quantize(rgb[1]) as u32;
+    let b = quantize(rgb[2]) as u32;
+    r * 19 * 19 + g * 19 + b
+}
+
+/// Encodes `rgb8` (tightly packed 8-bit RGB, `width * height * 3` bytes) as
+/// a BlurHash string using `comp_x` x `comp_y` DCT components (both in
+/// `1..=9`; the repo's default is 4x3).
+pub fn encode(rgb8: &[u8], width: u32, height: u32, comp_x: u32, comp_y: u32) -> String {
+    assert!((1..=9).contains(&comp_x) && (1..=9).contains(&comp_y));
+    assert_eq!(rgb8.len(), (width * height * 3) as usize);
+
+    let pixels: Vec<[f64; 3]> = rgb8
+        .chunks_exact(3)
+        .map(|p| {
+            [
+                srgb_to_linear(p[0]),
+                srgb_to_linear(p[1]),
+                srgb_to_linear(p[2]),
+            ]
+        })
+        .collect();
+
+    let mut factors = Vec::with_capacity((comp_x * comp_y) as usize);
+    for j in 0..comp_y {
+        for i in 0..comp_x {
+            factors.push(multiply_basis_function(i, j, width, height, &pixels));
+        }
+    }
+
+    let dc = factors[0];
+    let ac = &factors[1..];
+
+    let mut hash = String::new();
+
+    let size_flag = (comp_x - 1) + (comp_y - 1) * 9;
+    hash.push_str(&encode_base83(size_flag, 1));
+
+    let max_ac = if ac.is_empty() {
+        1.0
+    } else {
+        ac.iter()
+            .flat_map(|c| c.iter())
+            .fold(0.0f64, |acc, &v| acc.max(v.abs()))
+    };
+
+    let quantized_max_ac = if ac.is_empty() {
+        0
+    } else {
+        ((max_ac * 166.0 - 0.5).clamp(0.0, 82.0).floor()) as u32
+    };
+    hash.push_str(&encode_base83(quantized_max_ac, 1));
+
+    hash.push_str(&encode_base83(encode_dc(dc), 4));
+
+    let actual_max_ac = if ac.is_empty() {
+        1.0
+    } else {
+        (quantized_max_ac as f64 + 1.0) / 166.0
+    };
+    for component in ac {
+        hash.push_str(&encode_base83(encode_ac(*component, actual_max_ac), 2));
+    }
+
+    hash
+}
@@ -3,25 +3,8 @@ use crate::models::*;
 
 #[tauri::command]
 pub async fn get_study_analytics(state: State<'_, AppState>) -> Result<StudyAnalytics, String> {
-    // TODO: Implement comprehensive analytics
-    // This is a basic implementation - would need complex SQL queries for real analytics
-    
+    let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+
     let db = state.db.lock().map_err(|e| e.to_string())?;
-    let problems = db.get_problems().map_err(|e| e.to_string())?;
-    
-    Ok(StudyAnalytics {
-        total_problems: problems.len() as i32,
-        completed_problems: 0, // TODO: Count completed cards
-        total_study_time: 0,   // TODO: Sum all session durations
-        average_session_time: 0.0, // TODO: Calculate average
-        problems_by_difficulty: serde_json::json!({
-            "Easy": 0,
-            "Medium": 0, 
-            "Hard": 0
-        }),
-        study_streak_days: 0,
-        most_productive_hour: 14, // Default to 2 PM
-        weekly_progress: vec![],
-        top_tags: vec![],
-    })
+    db.get_study_analytics(&today).map_err(|e| e.to_string())
 }
\ No newline at end of file
@@ -0,0 +1,100 @@
+// InfluxDB line-protocol export of study session history, so users can graph
+// their practice time in Grafana instead of relying on the in-app dashboard.
+
+use crate::models::*;
+use tauri::State;
+
+/// Escapes a tag key/value for InfluxDB line protocol: commas, spaces, and
+/// equals signs must be backslash-escaped wherever they appear in a tag.
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(' ', "\\ ")
+        .replace('=', "\\=")
+}
+
+/// Serializes one completed time session as an InfluxDB line-protocol point,
+/// e.g. `study_session,card_id=abc,difficulty=Medium duration=120i,problems_worked=1i 1699999999000000000`.
+/// Sessions that never finished (no `end_time`/`duration` yet) are skipped
+/// rather than exported as a bogus zero-duration point.
+fn time_session_to_line(session: &TimeSession, difficulty: Option<&str>) -> Option<String> {
+    let duration = session.duration?;
+    let end_time = session.end_time?;
+
+    let mut tags = vec![format!("card_id={}", escape_tag(&session.card_id))];
+    if let Some(d) = difficulty {
+        tags.push(format!("difficulty={}", escape_tag(d)));
+    }
+
+    let timestamp_ns = end_time.timestamp_nanos_opt().unwrap_or(0);
+
+    Some(format!(
+        "study_session,{} duration={}i,problems_worked=1i {}",
+        tags.join(","),
+        duration,
+        timestamp_ns
+    ))
+}
+
+fn sessions_to_lines(sessions: &[(TimeSession, Option<String>)]) -> Vec<String> {
+    sessions
+        .iter()
+        .filter_map(|(session, difficulty)| time_session_to_line(session, difficulty.as_deref()))
+        .collect()
+}
+
+/// Returns every session in `[start_date, end_date]` (inclusive, `YYYY-MM-DD`)
+/// as a newline-joined InfluxDB line-protocol string, ready to write to a file.
+#[tauri::command]
+pub async fn export_study_metrics_line_protocol(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> Result<String, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let sessions = db.get_sessions_in_range(&start_date, &end_date).map_err(|e| e.to_string())?;
+
+    Ok(sessions_to_lines(&sessions).join("\n"))
+}
+
+/// Same range export as `export_study_metrics_line_protocol`, but POSTs the
+/// points directly to an InfluxDB `/write` endpoint in batches instead of
+/// returning them for a file export.
+#[tauri::command]
+pub async fn export_study_metrics_to_influxdb(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    write_url: String,
+    auth_token: Option<String>,
+) -> Result<(), String> {
+    let lines = {
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        let sessions = db.get_sessions_in_range(&start_date, &end_date).map_err(|e| e.to_string())?;
+        sessions_to_lines(&sessions)
+    };
+
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let client = reqwest::Client::new();
+    for batch in lines.chunks(500) {
+        let mut request = client.post(&write_url).body(batch.join("\n"));
+        if let Some(token) = &auth_token {
+            request = request.header("Authorization", format!("Token {}", token));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach InfluxDB: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("InfluxDB responded with status {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
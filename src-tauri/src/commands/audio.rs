@@ -2,15 +2,18 @@ use tauri::State;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::io::BufWriter;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::io::{BufWriter, Read, Seek, SeekFrom};
 use std::thread;
 use std::sync::mpsc;
+use std::time::Duration;
 use base64::{Engine as _, engine::general_purpose};
 use chrono::{Utc, Local};
 use uuid::Uuid;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use hound::{WavWriter, WavSpec, SampleFormat};
+use hound::{WavReader, WavWriter, WavSpec, SampleFormat};
 use crate::models::*;
+use crate::database::storage_roots::StorageRoots;
 use std::collections::HashMap;
 
 // Real audio recording system using cpal with thread-based stream management
@@ -24,61 +27,131 @@ enum AudioStreamState {
     Stopped,
 }
 
-// Import AudioCommand from models to avoid circular dependency
-use crate::models::AudioCommand;
+// Import AudioCommand/AudioAck/AudioError from models to avoid circular dependency
+use crate::models::{AudioCommand, AudioAck, AudioError};
 
-// Response from the audio thread
-#[derive(Debug)]
-enum AudioResponse {
-    Started,
-    Stopped,
-    Paused,
-    Resumed,
-    DevicesRefreshed(Vec<AudioDevice>),
-    DeviceSwitched(String),
-    Error(String),
-}
+/// How long a command-issuing Tauri command waits for the audio thread's
+/// ack before giving up and reporting it as unreachable.
+const AUDIO_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often the watchdog pings the audio thread, and how long it gives the
+/// thread to reply before considering it dead.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(2);
 
 // Global audio thread manager
 static AUDIO_THREAD_INITIALIZED: std::sync::Once = std::sync::Once::new();
 
+/// How many `LevelFrame`s the input-level ring buffer holds before the
+/// capture callback starts dropping newly computed ones. `get_input_level`
+/// is expected to be polled roughly once per UI animation frame, so this only
+/// needs to cover a short burst of missed polls, not a long backlog.
+const LEVEL_RING_CAPACITY: usize = 64;
+
+/// How many resampled, normalized `f32` samples the disk-writer ring buffer
+/// holds between the audio callback (producer) and the disk-writer thread
+/// (consumer). Sized generously compared to `LEVEL_RING_CAPACITY` since
+/// dropping an audio sample is audible, not just a missed meter tick -
+/// the disk-writer thread only needs to keep up on average, not per-callback.
+const SAMPLE_RING_CAPACITY: usize = 16384;
+/// How long the disk-writer thread sleeps after draining an empty ring,
+/// before checking again for new samples or a stop signal.
+const DISK_WRITER_IDLE_SLEEP: Duration = Duration::from_millis(5);
+/// Free space a root must report to be picked for a new recording - mirrors
+/// `commands::card_images::MIN_FREE_BYTES_FOR_WRITE`, just larger since audio
+/// files run bigger than a pasted image.
+const MIN_FREE_BYTES_FOR_RECORDING: u64 = 100 * 1024 * 1024;
+
 /// Initialize the audio recording thread
 /// This creates a dedicated thread for audio operations to avoid Send+Sync issues
 fn ensure_audio_thread_started(state: &AppState) -> Result<mpsc::Sender<AudioCommand>, String> {
     let sender_guard = state.audio_thread_sender.lock().map_err(|e| e.to_string())?;
-    
+
     if let Some(ref sender) = *sender_guard {
         return Ok(sender.clone());
     }
-    
+
     // Drop the guard before initializing
     drop(sender_guard);
-    
+
     let (command_sender, command_receiver) = mpsc::channel::<AudioCommand>();
-    let (response_sender, _response_receiver) = mpsc::channel::<AudioResponse>();
-    
+
+    // Lock-free SPSC ring buffer the capture callback publishes input levels
+    // into - the Producer half is shared (behind a lock only the audio
+    // thread itself contends on) with every recording started on this
+    // thread; the Consumer half lives in `AppState` for `get_input_level`.
+    let (level_producer, level_consumer) = ringbuf::HeapRb::<LevelFrame>::new(LEVEL_RING_CAPACITY).split();
+    let level_producer = Arc::new(Mutex::new(level_producer));
+
+    {
+        let mut level_guard = state.level_consumer.lock().map_err(|e| e.to_string())?;
+        *level_guard = Some(level_consumer);
+    }
+
     // Spawn the audio recording thread
     thread::spawn(move || {
-        audio_recording_thread(command_receiver, response_sender);
+        audio_recording_thread(command_receiver, level_producer);
     });
-    
+
     // Store the sender in the app state
     let mut sender_guard = state.audio_thread_sender.lock().map_err(|e| e.to_string())?;
     *sender_guard = Some(command_sender.clone());
-    
+    drop(sender_guard);
+
+    spawn_heartbeat_watchdog(command_sender.clone(), state.recording_state.clone(), state.app_handle.clone());
+
     Ok(command_sender)
 }
 
+/// Pings the audio thread on `HEARTBEAT_INTERVAL` and expects an
+/// `AudioAck::Pong` within `HEARTBEAT_TIMEOUT`. A missed/late reply means the
+/// capture thread has died or deadlocked, so the in-progress recording is
+/// cleared and an error event is emitted instead of letting the UI hang
+/// waiting on a thread that will never respond again.
+fn spawn_heartbeat_watchdog(
+    command_sender: mpsc::Sender<AudioCommand>,
+    recording_state: Arc<Mutex<Option<RecordingSession>>>,
+    app_handle: tauri::AppHandle,
+) {
+    thread::spawn(move || loop {
+        thread::sleep(HEARTBEAT_INTERVAL);
+
+        let (ack_tx, ack_rx) = mpsc::channel();
+        let healthy = command_sender.send(AudioCommand::Ping { ack_tx }).is_ok()
+            && matches!(ack_rx.recv_timeout(HEARTBEAT_TIMEOUT), Ok(Ok(AudioAck::Pong)));
+
+        if !healthy {
+            eprintln!("⚠️ Audio thread missed its heartbeat - marking recording as failed");
+            if let Ok(mut guard) = recording_state.lock() {
+                *guard = None;
+            }
+            crate::events::emit(&app_handle, crate::events::AppEvent::Error {
+                message: "Audio capture thread stopped responding".to_string(),
+            });
+            break;
+        }
+    });
+}
+
 /// The dedicated audio recording thread with enhanced device management
 /// This runs in its own thread to handle cpal streams without Send+Sync issues
 fn audio_recording_thread(
     command_receiver: mpsc::Receiver<AudioCommand>,
-    response_sender: mpsc::Sender<AudioResponse>,
+    level_producer: Arc<Mutex<ringbuf::HeapProducer<LevelFrame>>>,
 ) {
     let mut current_stream: Option<cpal::Stream> = None;
-    let mut current_writer: Option<Arc<Mutex<Option<WavWriter<BufWriter<fs::File>>>>>> = None;
+    let mut current_disk_writer: Option<DiskWriter> = None;
     let mut current_state: Option<Arc<Mutex<AudioStreamState>>> = None;
-    
+    // The WAV file's target sample rate, channel count, and buffering config
+    // of the in-progress recording, if any - needed by `SwitchDevice` to
+    // rebuild the stream on the new device with the same negotiated
+    // settings instead of restarting the recording from scratch.
+    let mut current_target_sample_rate: u32 = 0;
+    let mut current_target_channels: u16 = 0;
+    let mut current_buffering: AudioBufferingConfig = AudioBufferingConfig::default();
+    // The silence threshold the in-progress recording was started with, used
+    // by `StopRecording` to decide whether the finalized file should be kept.
+    let mut current_silence_threshold: f32 = DEFAULT_SILENCE_RMS_THRESHOLD;
+
     // Enhanced device management
     let host = cpal::default_host();
     let mut available_devices: HashMap<String, cpal::Device> = HashMap::new();
@@ -88,98 +161,137 @@ fn audio_recording_thread(
     // Initialize available devices
     if let Err(e) = refresh_available_devices(&host, &mut available_devices, &mut current_device, &mut current_device_name) {
         eprintln!("Failed to initialize audio devices: {}", e);
-        let _ = response_sender.send(AudioResponse::Error(format!("Failed to initialize audio devices: {}", e)));
         return;
     }
-    
+
     while let Ok(command) = command_receiver.recv() {
         match command {
-            AudioCommand::StartRecording { filepath, sample_rate, channels } => {
+            AudioCommand::StartRecording { filepath, sample_rate, format, buffering, silence_rms_threshold, ack_tx } => {
                 if let Some(ref device) = current_device {
-                    match start_recording_stream(device, filepath.clone(), sample_rate, channels) {
-                        Ok((stream, writer, state)) => {
+                    match start_recording_stream(device, filepath.clone(), sample_rate, format, Arc::clone(&level_producer), buffering) {
+                        Ok((stream, disk_writer, state, negotiated_rate, negotiated_channels)) => {
                             current_stream = Some(stream);
-                            current_writer = Some(writer);
+                            current_disk_writer = Some(disk_writer);
                             current_state = Some(state);
-                            let _ = response_sender.send(AudioResponse::Started);
+                            current_target_sample_rate = negotiated_rate;
+                            current_target_channels = negotiated_channels;
+                            current_buffering = buffering;
+                            current_silence_threshold = silence_rms_threshold;
+                            let _ = ack_tx.send(Ok(AudioAck::Started { sample_rate: negotiated_rate, channels: negotiated_channels }));
                         }
                         Err(e) => {
                             // Try to refresh devices and retry once
                             if refresh_available_devices(&host, &mut available_devices, &mut current_device, &mut current_device_name).is_ok() {
                                 if let Some(ref device) = current_device {
-                                    match start_recording_stream(device, filepath.clone(), sample_rate, channels) {
-                                        Ok((stream, writer, state)) => {
+                                    match start_recording_stream(device, filepath.clone(), sample_rate, format, Arc::clone(&level_producer), buffering) {
+                                        Ok((stream, disk_writer, state, negotiated_rate, negotiated_channels)) => {
                                             current_stream = Some(stream);
-                                            current_writer = Some(writer);
+                                            current_disk_writer = Some(disk_writer);
                                             current_state = Some(state);
-                                            let _ = response_sender.send(AudioResponse::Started);
+                                            current_target_sample_rate = negotiated_rate;
+                                            current_target_channels = negotiated_channels;
+                                            current_buffering = buffering;
+                                            current_silence_threshold = silence_rms_threshold;
+                                            let _ = ack_tx.send(Ok(AudioAck::Started { sample_rate: negotiated_rate, channels: negotiated_channels }));
                                         }
                                         Err(retry_e) => {
-                                            let _ = response_sender.send(AudioResponse::Error(format!("Audio start failed: {} (retry: {})", e, retry_e)));
+                                            let _ = ack_tx.send(Err(AudioError(format!("Audio start failed: {} (retry: {})", e, retry_e))));
                                         }
                                     }
                                 } else {
-                                    let _ = response_sender.send(AudioResponse::Error(format!("No audio device available after refresh: {}", e)));
+                                    let _ = ack_tx.send(Err(AudioError(format!("No audio device available after refresh: {}", e))));
                                 }
                             } else {
-                                let _ = response_sender.send(AudioResponse::Error(format!("Audio start failed: {}", e)));
+                                let _ = ack_tx.send(Err(AudioError(format!("Audio start failed: {}", e))));
                             }
                         }
                     }
                 } else {
-                    let _ = response_sender.send(AudioResponse::Error("No audio device available".to_string()));
+                    let _ = ack_tx.send(Err(AudioError("No audio device available".to_string())));
                 }
             }
-            AudioCommand::StopRecording => {
+            AudioCommand::StopRecording { ack_tx } => {
                 if let Some(stream) = current_stream.take() {
                     drop(stream); // This stops the stream
                 }
-                
-                // Finalize the WAV file
-                if let Some(writer_arc) = current_writer.take() {
-                    if let Ok(mut writer_guard) = writer_arc.lock() {
-                        if let Some(writer) = writer_guard.take() {
-                            let _ = writer.finalize();
-                        }
-                    }
-                }
-                
+
+                // Drain the disk-writer thread's ring and finalize the WAV file
+                let stats = current_disk_writer.take()
+                    .map(|disk_writer| disk_writer.finish())
+                    .unwrap_or_default();
+                let had_audio = stats.samples_written > 0 && stats.rms() >= current_silence_threshold;
+
                 current_state = None;
-                let _ = response_sender.send(AudioResponse::Stopped);
+                current_target_sample_rate = 0;
+                current_target_channels = 0;
+                current_buffering = AudioBufferingConfig::default();
+                current_silence_threshold = DEFAULT_SILENCE_RMS_THRESHOLD;
+                let _ = ack_tx.send(Ok(AudioAck::Stopped { had_audio }));
             }
-            AudioCommand::PauseRecording => {
+            AudioCommand::PauseRecording { ack_tx } => {
                 if let Some(ref state_arc) = current_state {
                     if let Ok(mut state_guard) = state_arc.lock() {
                         *state_guard = AudioStreamState::Paused;
                     }
                 }
-                let _ = response_sender.send(AudioResponse::Paused);
+                let _ = ack_tx.send(Ok(AudioAck::Paused));
             }
-            AudioCommand::ResumeRecording => {
+            AudioCommand::ResumeRecording { ack_tx } => {
                 if let Some(ref state_arc) = current_state {
                     if let Ok(mut state_guard) = state_arc.lock() {
                         *state_guard = AudioStreamState::Recording;
                     }
                 }
-                let _ = response_sender.send(AudioResponse::Resumed);
+                let _ = ack_tx.send(Ok(AudioAck::Resumed));
             }
-            AudioCommand::RefreshDevices => {
+            AudioCommand::RefreshDevices { ack_tx } => {
                 if let Err(e) = refresh_available_devices(&host, &mut available_devices, &mut current_device, &mut current_device_name) {
-                    let _ = response_sender.send(AudioResponse::Error(format!("Failed to refresh devices: {}", e)));
+                    let _ = ack_tx.send(Err(AudioError(format!("Failed to refresh devices: {}", e))));
                 } else {
                     let device_list = create_device_list(&available_devices, &current_device_name);
-                    let _ = response_sender.send(AudioResponse::DevicesRefreshed(device_list));
+                    let _ = ack_tx.send(Ok(AudioAck::DevicesRefreshed(device_list)));
                 }
             }
-            AudioCommand::SwitchDevice { device_name } => {
-                if let Some(device) = available_devices.get(&device_name) {
-                    current_device = Some(device.clone());
-                    current_device_name = device_name.clone();
-                    let _ = response_sender.send(AudioResponse::DeviceSwitched(device_name));
+            AudioCommand::SwitchDevice { device_name, ack_tx } => {
+                if let Some(device) = available_devices.get(&device_name).cloned() {
+                    // If a recording is in progress, hot-swap the live stream onto the new
+                    // device instead of just updating `current_device` for the next
+                    // `StartRecording` - the writer and pause/resume state carry over
+                    // unchanged, so the WAV file stays one continuous recording.
+                    if let (Some(ref disk_writer), Some(ref state_arc)) = (&current_disk_writer, &current_state) {
+                        match build_device_input_stream(
+                            &device,
+                            Arc::clone(&disk_writer.sample_producer),
+                            Arc::clone(state_arc),
+                            current_target_sample_rate,
+                            current_target_channels,
+                            Arc::clone(&level_producer),
+                            current_buffering,
+                        ) {
+                            Ok((stream, _device_sample_rate)) => {
+                                current_stream = Some(stream); // dropping the old stream here stops it
+                                current_device = Some(device);
+                                current_device_name = device_name.clone();
+                                let _ = ack_tx.send(Ok(AudioAck::DeviceSwitched(device_name)));
+                            }
+                            Err(e) => {
+                                let _ = ack_tx.send(Err(AudioError(format!(
+                                    "Failed to switch to device '{}' mid-recording: {}", device_name, e
+                                ))));
+                            }
+                        }
+                    } else {
+                        current_device = Some(device);
+                        current_device_name = device_name.clone();
+                        let _ = ack_tx.send(Ok(AudioAck::DeviceSwitched(device_name)));
+                    }
                 } else {
-                    let _ = response_sender.send(AudioResponse::Error(format!("Device '{}' not found", device_name)));
+                    let _ = ack_tx.send(Err(AudioError(format!("Device '{}' not found", device_name))));
                 }
             }
+            AudioCommand::Ping { ack_tx } => {
+                let _ = ack_tx.send(Ok(AudioAck::Pong));
+            }
         }
     }
 }
@@ -257,162 +369,448 @@ fn create_device_list(available_devices: &HashMap<String, cpal::Device>, current
         .collect()
 }
 
-/// Create and start an audio recording stream with adaptive sample rate and mono configuration
-fn start_recording_stream(
+/// Computes peak/RMS loudness over a block of normalized mono samples
+/// (`-1.0..=1.0`) and publishes it as a [`LevelFrame`]. Never blocks or
+/// allocates: a contended lock or a full ring buffer just drops the frame,
+/// since a missed VU-meter tick is harmless but a stall in the capture
+/// callback would corrupt the recording.
+fn push_level_frame(producer: &Arc<Mutex<ringbuf::HeapProducer<LevelFrame>>>, samples: impl Iterator<Item = f32>) {
+    let mut peak = 0.0f32;
+    let mut sum_sq = 0.0f32;
+    let mut count = 0u32;
+    for s in samples {
+        let abs = s.abs();
+        if abs > peak {
+            peak = abs;
+        }
+        sum_sq += s * s;
+        count += 1;
+    }
+    if count == 0 {
+        return;
+    }
+    let rms = (sum_sq / count as f32).sqrt();
+    let frame = LevelFrame { peak, rms, timestamp: Utc::now() };
+
+    if let Ok(mut producer) = producer.try_lock() {
+        let _ = producer.try_push(frame);
+    }
+}
+
+/// Maps the caller's requested frames-per-callback onto a `cpal::BufferSize`,
+/// falling back to `Default` when the caller didn't request a specific size
+/// or the device's `SupportedBufferSize` range can't accommodate it.
+fn resolve_buffer_size(config: &cpal::SupportedStreamConfig, buffering: AudioBufferingConfig) -> cpal::BufferSize {
+    let Some(frames) = buffering.frames_per_callback else {
+        return cpal::BufferSize::Default;
+    };
+    match config.buffer_size() {
+        cpal::SupportedBufferSize::Range { min, max } if frames >= *min && frames <= *max => {
+            cpal::BufferSize::Fixed(frames)
+        }
+        _ => cpal::BufferSize::Default,
+    }
+}
+
+/// Quantizes one normalized (`-1.0..=1.0`) sample to the wire format implied
+/// by `bit_depth` and writes it. 24-bit has no native Rust integer type, so
+/// it's carried as an `i32` the way `hound` expects for `SampleFormat::Int`
+/// with `bits_per_sample: 24`.
+fn write_quantized_sample(
+    writer: &mut WavWriter<BufWriter<fs::File>>,
+    sample: f32,
+    bit_depth: RecordingBitDepth,
+) {
+    let clamped = sample.clamp(-1.0, 1.0);
+    match bit_depth {
+        RecordingBitDepth::Sixteen => {
+            let _ = writer.write_sample((clamped * i16::MAX as f32) as i16);
+        }
+        RecordingBitDepth::TwentyFour => {
+            let _ = writer.write_sample((clamped * 8_388_607.0) as i32);
+        }
+        RecordingBitDepth::ThirtyTwoFloat => {
+            let _ = writer.write_sample(clamped);
+        }
+    }
+}
+
+/// Sample count and loudness accumulated by `disk_writer_loop`, returned when
+/// the thread exits so `StopRecording` can decide whether the finalized file
+/// is silent/empty and should be discarded instead of saved.
+#[derive(Debug, Clone, Copy, Default)]
+struct RecordingStats {
+    samples_written: u64,
+    sum_sq: f64,
+}
+
+impl RecordingStats {
+    fn rms(self) -> f32 {
+        if self.samples_written == 0 {
+            0.0
+        } else {
+            ((self.sum_sq / self.samples_written as f64).sqrt()) as f32
+        }
+    }
+}
+
+/// Drains resampled samples off `consumer` onto the WAV writer, running on
+/// its own thread so the audio callback's `try_push` never has to wait on
+/// disk I/O. Keeps polling until told to stop *and* the ring is empty, so a
+/// `StopRecording` doesn't truncate whatever was still buffered. Samples
+/// arrive as normalized `f32` (interleaved by channel) regardless of the
+/// recording's bit depth - quantization to the actual wire format happens
+/// here, once, instead of duplicated across every capture-format callback.
+fn disk_writer_loop(
+    writer_arc: Arc<Mutex<Option<WavWriter<BufWriter<fs::File>>>>>,
+    mut consumer: ringbuf::HeapConsumer<f32>,
+    stop: Arc<AtomicBool>,
+    bit_depth: RecordingBitDepth,
+) -> RecordingStats {
+    let mut stats = RecordingStats::default();
+    loop {
+        let mut wrote_any = false;
+        while let Some(sample) = consumer.pop() {
+            if let Ok(mut writer_guard) = writer_arc.lock() {
+                if let Some(ref mut writer) = *writer_guard {
+                    write_quantized_sample(writer, sample, bit_depth);
+                }
+            }
+            stats.samples_written += 1;
+            stats.sum_sq += (sample as f64) * (sample as f64);
+            wrote_any = true;
+        }
+
+        if stop.load(Ordering::Acquire) && consumer.is_empty() {
+            break;
+        }
+        if !wrote_any {
+            thread::sleep(DISK_WRITER_IDLE_SLEEP);
+        }
+    }
+    stats
+}
+
+/// Owns the WAV writer together with the SPSC ring that feeds it and the
+/// thread draining that ring, so the audio callback only ever does a
+/// non-blocking `try_push` of already-resampled samples instead of locking
+/// the writer directly. Kept as a single handle because `SwitchDevice` must
+/// hand the *same* instance to the new device's stream rather than starting
+/// a new file.
+struct DiskWriter {
+    writer: Arc<Mutex<Option<WavWriter<BufWriter<fs::File>>>>>,
+    sample_producer: Arc<Mutex<ringbuf::HeapProducer<f32>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<RecordingStats>>,
+}
+
+impl DiskWriter {
+    fn new(writer_arc: Arc<Mutex<Option<WavWriter<BufWriter<fs::File>>>>>, bit_depth: RecordingBitDepth) -> Self {
+        let (producer, consumer) = ringbuf::HeapRb::<f32>::new(SAMPLE_RING_CAPACITY).split();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_writer = Arc::clone(&writer_arc);
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || disk_writer_loop(thread_writer, consumer, thread_stop, bit_depth));
+
+        Self {
+            writer: writer_arc,
+            sample_producer: Arc::new(Mutex::new(producer)),
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Signals the disk-writer thread to drain any buffered samples and
+    /// stop, joins it so every sample is on disk before returning, then
+    /// finalizes the WAV file. Returns the stats the thread accumulated so
+    /// the caller can decide whether the finalized recording is silent/empty.
+    fn finish(mut self) -> RecordingStats {
+        self.stop.store(true, Ordering::Release);
+        let stats = self.thread.take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+        if let Ok(mut writer_guard) = self.writer.lock() {
+            if let Some(writer) = writer_guard.take() {
+                let _ = writer.finalize();
+            }
+        }
+        stats
+    }
+}
+
+/// Linear-interpolation resampler used to convert the device's native
+/// capture rate to the WAV file's target rate inside the audio callback.
+/// Carries the fractional read cursor and the last sample of the previous
+/// block across calls so interpolation stays continuous at block
+/// boundaries, instead of clicking at every callback edge.
+///
+/// `pub(crate)` so `commands::playback` can reuse it for the symmetric
+/// file-rate-to-device-rate conversion on the way out.
+pub(crate) struct LinearResampler {
+    ratio: f64,
+    pos: f64,
+    last_sample: Option<f32>,
+}
+
+impl LinearResampler {
+    pub(crate) fn new(device_rate: u32, target_rate: u32) -> Self {
+        Self {
+            ratio: device_rate as f64 / target_rate as f64,
+            pos: 0.0,
+            last_sample: None,
+        }
+    }
+
+    /// Resamples a mono, already-downmixed `input` block and appends the
+    /// result to `output`. A no-op copy when the rates match.
+    pub(crate) fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        if input.is_empty() {
+            return;
+        }
+        if (self.ratio - 1.0).abs() < f64::EPSILON {
+            output.extend_from_slice(input);
+            return;
+        }
+
+        // Conceptually we resample over `[prev] ++ input`, so that
+        // interpolation for the first output sample of this block can still
+        // reach back to the last sample of the previous one.
+        let prev = self.last_sample.unwrap_or(input[0]);
+        let extended_len = input.len() + 1;
+
+        while (self.pos.floor() as usize) + 1 < extended_len {
+            let idx = self.pos.floor() as usize;
+            let frac = self.pos.fract() as f32;
+            let a = if idx == 0 { prev } else { input[idx - 1] };
+            let b = input[idx];
+            output.push(a * (1.0 - frac) + b * frac);
+            self.pos += self.ratio;
+        }
+
+        self.pos -= (extended_len - 1) as f64;
+        self.last_sample = Some(input[input.len() - 1]);
+    }
+}
+
+/// Creates the WAV file and writer for a new recording, independent of any
+/// capture device. Split out from stream creation so a device hot-swap
+/// mid-recording (`AudioCommand::SwitchDevice`) can build a fresh input
+/// stream that writes into the *same* writer instead of starting a new file.
+fn create_wav_writer(
+    filepath: &str,
+    target_sample_rate: u32,
+    target_channels: u16,
+    bit_depth: RecordingBitDepth,
+) -> Result<Arc<Mutex<Option<WavWriter<BufWriter<fs::File>>>>>, String> {
+    let file = fs::File::create(filepath)
+        .map_err(|e| format!("Failed to create audio file: {}", e))?;
+
+    let (bits_per_sample, sample_format) = match bit_depth {
+        RecordingBitDepth::Sixteen => (16, SampleFormat::Int),
+        RecordingBitDepth::TwentyFour => (24, SampleFormat::Int),
+        RecordingBitDepth::ThirtyTwoFloat => (32, SampleFormat::Float),
+    };
+
+    let spec = WavSpec {
+        channels: target_channels,
+        sample_rate: target_sample_rate,
+        bits_per_sample,
+        sample_format,
+    };
+
+    let writer = WavWriter::new(BufWriter::new(file), spec)
+        .map_err(|e| format!("Failed to create WAV writer: {}", e))?;
+
+    Ok(Arc::new(Mutex::new(Some(writer))))
+}
+
+/// Downmixes (`channel_bufs.len() == 1`) or channel-selects
+/// (`channel_bufs.len() >= 2`) an interleaved, already-normalized
+/// (`-1.0..=1.0`) block of `in_ch` device channels into independent
+/// per-channel buffers. Mono keeps the original averaging downmix; stereo
+/// (or more) takes the device's first `channel_bufs.len()` channels directly
+/// rather than synthesizing channels the device doesn't have -
+/// `start_recording_stream` validates that up front.
+fn split_channels(normalized: &[f32], in_ch: usize, channel_bufs: &mut [Vec<f32>]) {
+    for buf in channel_bufs.iter_mut() {
+        buf.clear();
+    }
+    if channel_bufs.len() <= 1 {
+        if in_ch <= 1 {
+            channel_bufs[0].extend(normalized.iter().copied());
+        } else {
+            channel_bufs[0].extend(normalized.chunks(in_ch).map(|chunk| {
+                chunk.iter().sum::<f32>() / in_ch as f32
+            }));
+        }
+    } else {
+        for chunk in normalized.chunks(in_ch) {
+            for (ch, buf) in channel_bufs.iter_mut().enumerate() {
+                buf.push(chunk[ch]);
+            }
+        }
+    }
+}
+
+/// Resamples each per-channel buffer independently - same device/target
+/// rate for every channel, so they stay frame-aligned - then interleaves
+/// the result and pushes it into the disk-writer ring. Never blocks: a
+/// contended lock just drops the block, same rationale as the level meter.
+fn resample_and_push_interleaved(
+    resamplers: &mut [LinearResampler],
+    channel_bufs: &[Vec<f32>],
+    resampled_bufs: &mut [Vec<f32>],
+    sample_producer: &Arc<Mutex<ringbuf::HeapProducer<f32>>>,
+) {
+    for (ch, resampler) in resamplers.iter_mut().enumerate() {
+        resampled_bufs[ch].clear();
+        resampler.process(&channel_bufs[ch], &mut resampled_bufs[ch]);
+    }
+    let frames = resampled_bufs[0].len();
+    if let Ok(mut producer_guard) = sample_producer.try_lock() {
+        for i in 0..frames {
+            for buf in resampled_bufs.iter() {
+                let _ = producer_guard.try_push(buf[i]);
+            }
+        }
+    }
+}
+
+/// Builds and starts an input stream on `device` that extracts
+/// `target_channels` channel(s) (downmixing to mono or taking the device's
+/// first channels directly for stereo), resamples each to
+/// `target_sample_rate`, and pushes the interleaved result into
+/// `sample_producer` for the paired `DiskWriter` to write - the callback
+/// never touches the WAV writer or the filesystem directly. Used both for
+/// the initial `StartRecording` and to hot-swap devices mid-recording, where
+/// the disk writer and pause/resume state must carry over across the swap
+/// unchanged.
+fn build_device_input_stream(
     device: &cpal::Device,
-    filepath: String,
-    _requested_sample_rate: u32,
-    _requested_channels: u16,
-) -> Result<(cpal::Stream, Arc<Mutex<Option<WavWriter<BufWriter<fs::File>>>>>, Arc<Mutex<AudioStreamState>>), String> {
+    sample_producer: Arc<Mutex<ringbuf::HeapProducer<f32>>>,
+    state_arc: Arc<Mutex<AudioStreamState>>,
+    target_sample_rate: u32,
+    target_channels: u16,
+    level_producer: Arc<Mutex<ringbuf::HeapProducer<LevelFrame>>>,
+    buffering: AudioBufferingConfig,
+) -> Result<(cpal::Stream, u32), String> {
     // Get the default input configuration from the device
     let config = device.default_input_config()
         .map_err(|e| format!("Failed to get default input config: {}", e))?;
-    
+
     // Use device's native configuration to avoid silent streams on devices that don't support our overrides
     let device_sample_rate = config.sample_rate().0;
     let input_channels = config.channels() as u16;
-    // We'll always write MONO to the WAV for simplicity and compatibility, downmixing if needed
-    let target_channels = 1u16;
-    
+
+    if target_channels > input_channels {
+        return Err(format!(
+            "Requested {} channel(s) but device only exposes {}",
+            target_channels, input_channels
+        ));
+    }
+
     println!("Device config: {:?}", config);
-    println!("Using optimal settings - Sample Rate: {}Hz, Channels: {}, Device Native: {}Hz", 
-             optimal_sample_rate, optimal_channels, device_sample_rate);
-    
-    // Create WAV file with optimal settings
-    let file = fs::File::create(&filepath)
-        .map_err(|e| format!("Failed to create audio file: {}", e))?;
-    
-    let spec = WavSpec {
-        channels: target_channels,
-        sample_rate: device_sample_rate,
-        bits_per_sample: 16, // Standard 16-bit for voice (24-bit can cause compatibility issues)
-        sample_format: SampleFormat::Int,
-    };
-    
-    let writer = WavWriter::new(BufWriter::new(file), spec)
-        .map_err(|e| format!("Failed to create WAV writer: {}", e))?;
-    
-    let writer_arc = Arc::new(Mutex::new(Some(writer)));
-    let writer_clone = Arc::clone(&writer_arc);
-    
-    // Create shared state for pause/resume
-    let state_arc = Arc::new(Mutex::new(AudioStreamState::Recording));
-    
+    println!("Recording at {}Hz (device native: {}Hz), {} channel(s) from {} device channels",
+             target_sample_rate, device_sample_rate, target_channels, input_channels);
+
     // Create config that matches our target format for proper recording
     let stream_config = cpal::StreamConfig {
         channels: input_channels,
         sample_rate: cpal::SampleRate(device_sample_rate),
-        buffer_size: cpal::BufferSize::Default,
+        buffer_size: resolve_buffer_size(&config, buffering),
     };
-    
+
+    let out_ch = target_channels as usize;
+
     // Create the audio stream based on sample format with proper resampling if needed
     let stream = match config.sample_format() {
         cpal::SampleFormat::F32 => {
+            let sample_producer_f32 = Arc::clone(&sample_producer);
             let state_clone_f32 = Arc::clone(&state_arc);
+            let level_producer_f32 = Arc::clone(&level_producer);
             let in_ch = input_channels as usize;
+            let mut resamplers: Vec<LinearResampler> = (0..out_ch)
+                .map(|_| LinearResampler::new(device_sample_rate, target_sample_rate))
+                .collect();
+            let mut normalized_buf: Vec<f32> = Vec::new();
+            let mut channel_bufs: Vec<Vec<f32>> = (0..out_ch).map(|_| Vec::new()).collect();
+            let mut resampled_bufs: Vec<Vec<f32>> = (0..out_ch).map(|_| Vec::new()).collect();
             device.build_input_stream(
                 &stream_config,
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    normalized_buf.clear();
+                    normalized_buf.extend(data.iter().map(|&s| s.clamp(-1.0, 1.0)));
+
                     // Check if we're recording (not paused)
                     if let Ok(state_guard) = state_clone_f32.try_lock() {
                         if *state_guard == AudioStreamState::Recording {
-                            if let Ok(mut writer_guard) = writer_clone.try_lock() {
-                                if let Some(ref mut writer) = *writer_guard {
-                                    if in_ch <= 1 {
-                                        for &sample in data {
-                                            let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-                                            let _ = writer.write_sample(s);
-                                        }
-                                    } else {
-                                        // Downmix interleaved multi-channel to mono by averaging
-                                        let mut i = 0usize;
-                                        while i + in_ch <= data.len() {
-                                            let mut sum = 0.0f32;
-                                            for c in 0..in_ch { sum += data[i + c]; }
-                                            let avg = (sum / in_ch as f32).clamp(-1.0, 1.0);
-                                            let s = (avg * i16::MAX as f32) as i16;
-                                            let _ = writer.write_sample(s);
-                                            i += in_ch;
-                                        }
-                                    }
-                                }
-                            }
+                            split_channels(&normalized_buf, in_ch, &mut channel_bufs);
+                            resample_and_push_interleaved(&mut resamplers, &channel_bufs, &mut resampled_bufs, &sample_producer_f32);
                         }
                         // If paused, we simply skip writing but keep the stream alive
                     }
+                    push_level_frame(&level_producer_f32, normalized_buf.iter().copied());
                 },
                 |err| eprintln!("Audio stream error: {}", err),
                 None,
             )
         }
         cpal::SampleFormat::I16 => {
-            let writer_clone_i16 = Arc::clone(&writer_arc);
+            let sample_producer_i16 = Arc::clone(&sample_producer);
             let state_clone_i16 = Arc::clone(&state_arc);
+            let level_producer_i16 = Arc::clone(&level_producer);
             let in_ch = input_channels as usize;
+            let mut resamplers: Vec<LinearResampler> = (0..out_ch)
+                .map(|_| LinearResampler::new(device_sample_rate, target_sample_rate))
+                .collect();
+            let mut normalized_buf: Vec<f32> = Vec::new();
+            let mut channel_bufs: Vec<Vec<f32>> = (0..out_ch).map(|_| Vec::new()).collect();
+            let mut resampled_bufs: Vec<Vec<f32>> = (0..out_ch).map(|_| Vec::new()).collect();
             device.build_input_stream(
                 &stream_config,
                 move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                    normalized_buf.clear();
+                    normalized_buf.extend(data.iter().map(|&s| s as f32 / 32768.0));
+
                     // Check if we're recording (not paused)
                     if let Ok(state_guard) = state_clone_i16.try_lock() {
                         if *state_guard == AudioStreamState::Recording {
-                            if let Ok(mut writer_guard) = writer_clone_i16.try_lock() {
-                                if let Some(ref mut writer) = *writer_guard {
-                                    if in_ch <= 1 {
-                                        for &sample in data {
-                                            let _ = writer.write_sample(sample);
-                                        }
-                                    } else {
-                                        let mut i = 0usize;
-                                        while i + in_ch <= data.len() {
-                                            let mut sum = 0i32;
-                                            for c in 0..in_ch { sum += data[i + c] as i32; }
-                                            let avg = (sum / in_ch as i32) as i16;
-                                            let _ = writer.write_sample(avg);
-                                            i += in_ch;
-                                        }
-                                    }
-                                }
-                            }
+                            split_channels(&normalized_buf, in_ch, &mut channel_bufs);
+                            resample_and_push_interleaved(&mut resamplers, &channel_bufs, &mut resampled_bufs, &sample_producer_i16);
                         }
                     }
+                    push_level_frame(&level_producer_i16, normalized_buf.iter().copied());
                 },
                 |err| eprintln!("Audio stream error: {}", err),
                 None,
             )
         }
         cpal::SampleFormat::U16 => {
-            let writer_clone_u16 = Arc::clone(&writer_arc);
+            let sample_producer_u16 = Arc::clone(&sample_producer);
             let state_clone_u16 = Arc::clone(&state_arc);
+            let level_producer_u16 = Arc::clone(&level_producer);
             let in_ch = input_channels as usize;
+            let mut resamplers: Vec<LinearResampler> = (0..out_ch)
+                .map(|_| LinearResampler::new(device_sample_rate, target_sample_rate))
+                .collect();
+            let mut normalized_buf: Vec<f32> = Vec::new();
+            let mut channel_bufs: Vec<Vec<f32>> = (0..out_ch).map(|_| Vec::new()).collect();
+            let mut resampled_bufs: Vec<Vec<f32>> = (0..out_ch).map(|_| Vec::new()).collect();
             device.build_input_stream(
                 &stream_config,
                 move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                    normalized_buf.clear();
+                    normalized_buf.extend(data.iter().map(|&s| (s as i32 - 32768) as f32 / 32768.0));
+
                     // Check if we're recording (not paused)
                     if let Ok(state_guard) = state_clone_u16.try_lock() {
                         if *state_guard == AudioStreamState::Recording {
-                            if let Ok(mut writer_guard) = writer_clone_u16.try_lock() {
-                                if let Some(ref mut writer) = *writer_guard {
-                                    if in_ch <= 1 {
-                                        for &sample in data {
-                                            let sample_i16 = (sample as i32 - 32768) as i16;
-                                            let _ = writer.write_sample(sample_i16);
-                                        }
-                                    } else {
-                                        let mut i = 0usize;
-                                        while i + in_ch <= data.len() {
-                                            let mut sum = 0i32;
-                                            for c in 0..in_ch {
-                                                sum += (data[i + c] as i32 - 32768);
-                                            }
-                                            let avg = (sum / in_ch as i32) as i16;
-                                            let _ = writer.write_sample(avg);
-                                            i += in_ch;
-                                        }
-                                    }
-                                }
-                            }
+                            split_channels(&normalized_buf, in_ch, &mut channel_bufs);
+                            resample_and_push_interleaved(&mut resamplers, &channel_bufs, &mut resampled_bufs, &sample_producer_u16);
                         }
                     }
+                    push_level_frame(&level_producer_u16, normalized_buf.iter().copied());
                 },
                 |err| eprintln!("Audio stream error: {}", err),
                 None,
@@ -420,11 +818,54 @@ fn start_recording_stream(
         }
         _ => return Err("Unsupported sample format".to_string()),
     }.map_err(|e| format!("Failed to build input stream: {}", e))?;
-    
+
     // Start the stream
     stream.play().map_err(|e| format!("Failed to start stream: {}", e))?;
-    
-    Ok((stream, writer_arc, state_arc))
+
+    Ok((stream, device_sample_rate))
+}
+
+/// Create and start an audio recording stream with adaptive sample rate and
+/// caller-selectable channel layout / bit depth.
+fn start_recording_stream(
+    device: &cpal::Device,
+    filepath: String,
+    requested_sample_rate: u32,
+    format: RecordingFormat,
+    level_producer: Arc<Mutex<ringbuf::HeapProducer<LevelFrame>>>,
+    buffering: AudioBufferingConfig,
+) -> Result<(cpal::Stream, DiskWriter, Arc<Mutex<AudioStreamState>>, u32, u16), String> {
+    let config = device.default_input_config()
+        .map_err(|e| format!("Failed to get default input config: {}", e))?;
+    let device_sample_rate = config.sample_rate().0;
+    let device_channels = config.channels() as u16;
+    let target_channels = format.channels.count();
+    if target_channels > device_channels {
+        return Err(format!(
+            "Requested {:?} ({} channel(s)) but the device only exposes {}",
+            format.channels, target_channels, device_channels
+        ));
+    }
+    // The WAV file is always written at the caller's requested rate, resampling
+    // in the callback from whatever rate the device actually captures at - a
+    // rate of 0 would mean "no preference", so fall back to the device's native rate.
+    let target_sample_rate = if requested_sample_rate > 0 { requested_sample_rate } else { device_sample_rate };
+
+    let writer_arc = create_wav_writer(&filepath, target_sample_rate, target_channels, format.bit_depth)?;
+    let disk_writer = DiskWriter::new(writer_arc, format.bit_depth);
+    let state_arc = Arc::new(Mutex::new(AudioStreamState::Recording));
+
+    let (stream, _device_sample_rate) = build_device_input_stream(
+        device,
+        Arc::clone(&disk_writer.sample_producer),
+        Arc::clone(&state_arc),
+        target_sample_rate,
+        target_channels,
+        level_producer,
+        buffering,
+    )?;
+
+    Ok((stream, disk_writer, state_arc, target_sample_rate, target_channels))
 }
 
 /// Legacy fallback - will be removed once all functions use PathResolver
@@ -435,17 +876,33 @@ fn get_app_data_dir() -> PathBuf {
 }
 
 #[tauri::command]
-pub async fn start_recording(state: State<'_, AppState>, card_id: String) -> Result<RecordingInfo, String> {
-    // Create recordings directory using the proper path resolver
-    let recordings_dir = state.path_resolver.ensure_subdir("recordings")
+pub async fn start_recording(
+    state: State<'_, AppState>,
+    card_id: String,
+    buffer_frames: Option<u32>,
+    format: Option<RecordingFormat>,
+    silence_rms_threshold: Option<f32>,
+) -> Result<RecordingInfo, String> {
+    // Pick the highest-priority storage root with room for this recording,
+    // the same way `save_card_image_bytes` does for images, instead of
+    // always writing under the single `path_resolver` app-data root - lets a
+    // long study habit's audio outgrow the drive the SQLite DB lives on.
+    let root = {
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        db.pick_storage_root_for_write(MIN_FREE_BYTES_FOR_RECORDING).map_err(|e| e.to_string())?
+    };
+    let recordings_dir = root.path.join("recordings");
+    fs::create_dir_all(&recordings_dir)
         .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
-    
+
     let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
     // Include card ID in filename to prevent conflicts between cards
     let card_prefix = &card_id[..8.min(card_id.len())]; // Use first 8 chars of card ID for brevity
     let filename = format!("recording_{}_{}.wav", card_prefix, timestamp);
     let filepath = recordings_dir.join(&filename);
-    
+    let storage_path = StorageRoots::to_stored_path(&root.id, &format!("recordings/{}", filename));
+    let silence_rms_threshold = silence_rms_threshold.unwrap_or(DEFAULT_SILENCE_RMS_THRESHOLD);
+
     // Store recording session in AppState
     let recording_session = RecordingSession {
         id: Uuid::new_v4().to_string(),
@@ -454,8 +911,10 @@ pub async fn start_recording(state: State<'_, AppState>, card_id: String) -> Res
         is_paused: false,
         filename: filename.clone(),
         filepath: filepath.to_string_lossy().to_string(),
+        silence_rms_threshold,
+        storage_path: storage_path.clone(),
     };
-    
+
     let mut recording_state = state.recording_state.lock().map_err(|e| e.to_string())?;
     *recording_state = Some(recording_session);
     
@@ -463,18 +922,31 @@ pub async fn start_recording(state: State<'_, AppState>, card_id: String) -> Res
     let audio_sender = ensure_audio_thread_started(&state)?;
     
     // Send start recording command to audio thread with voice-optimized settings
-    // Note: sample_rate and channels are now determined automatically by the device capabilities
+    // Note: the WAV file is written at this sample_rate, resampled in the callback
+    // from whatever rate the device captures at, and `format` defaults to mono 16-bit PCM (the app's original behavior) unless
+    // the caller explicitly asks for stereo and/or a different bit depth.
+    let (ack_tx, ack_rx) = mpsc::channel();
     audio_sender.send(AudioCommand::StartRecording {
         filepath: filepath.to_string_lossy().to_string(),
         sample_rate: 44100, // Standard CD quality (will be auto-adjusted based on device)
-        channels: 1, // Mono for voice recording (will be auto-adjusted for optimal quality)
+        format: format.unwrap_or_default(),
+        buffering: AudioBufferingConfig { frames_per_callback: buffer_frames },
+        silence_rms_threshold,
+        ack_tx,
     }).map_err(|e| format!("Failed to send start command to audio thread: {}", e))?;
-    
-    println!("Started real audio recording: {}", filepath.display());
+
+    match ack_rx.recv_timeout(AUDIO_COMMAND_TIMEOUT) {
+        Ok(Ok(AudioAck::Started { sample_rate, channels })) => {
+            println!("Started real audio recording: {} ({} Hz, {} ch)", filepath.display(), sample_rate, channels);
+        }
+        Ok(Ok(_)) => return Err("Audio thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => return Err(e.into()),
+        Err(_) => return Err("Audio thread did not acknowledge start command in time".to_string()),
+    }
     
     Ok(RecordingInfo {
         filename: filename.clone(),
-        filepath: state.path_resolver.to_relative_path(&filepath),
+        filepath: storage_path,
     })
 }
 
@@ -487,24 +959,39 @@ pub async fn stop_recording(state: State<'_, AppState>, _card_id: String) -> Res
     // Send stop command to audio thread
     let audio_sender_guard = state.audio_thread_sender.lock().map_err(|e| e.to_string())?;
     if let Some(ref audio_sender) = *audio_sender_guard {
-        audio_sender.send(AudioCommand::StopRecording)
+        let (ack_tx, ack_rx) = mpsc::channel();
+        audio_sender.send(AudioCommand::StopRecording { ack_tx })
             .map_err(|e| format!("Failed to send stop command to audio thread: {}", e))?;
+        match ack_rx.recv_timeout(AUDIO_COMMAND_TIMEOUT) {
+            Ok(Ok(AudioAck::Stopped { had_audio: false })) => {
+                // No audio ever crossed the silence threshold (or nothing was
+                // captured at all) - drop the file and skip the database row
+                // rather than leaving a dangling empty/silent recording.
+                let _ = fs::remove_file(&recording_session.filepath);
+                println!("Discarded recording (no audio captured): {}", recording_session.filepath);
+                return Ok("Recording discarded (no audio captured)".to_string());
+            }
+            Ok(Ok(AudioAck::Stopped { had_audio: true })) => {}
+            Ok(Ok(_)) => return Err("Audio thread sent an unexpected acknowledgement".to_string()),
+            Ok(Err(e)) => return Err(e.into()),
+            Err(_) => return Err("Audio thread did not acknowledge stop command in time".to_string()),
+        }
     }
     drop(audio_sender_guard);
-    
+
     // Calculate actual duration
     let duration = (Utc::now() - recording_session.start_time).num_seconds() as i32;
-    
-    // Use recording session data and get proper relative path
-    let recordings_dir = state.path_resolver.get_recordings_dir();
-    let full_path = recordings_dir.join(&recording_session.filename);
-    let relative_path = state.path_resolver.to_relative_path(&full_path);
-    
-    // Save recording to database
+
+    // Save recording to database, using the stored path recorded at recording
+    // start time rather than re-deriving it from path_resolver - it already
+    // carries whichever storage root the file actually landed on.
     let mut db = state.db.lock().map_err(|e| e.to_string())?;
-    let _recording = db.save_recording(&recording_session.card_id, &recording_session.filename, &relative_path, Some(duration))
+    let recording = db.save_recording(&recording_session.card_id, &recording_session.filename, &recording_session.storage_path, Some(duration))
         .map_err(|e| e.to_string())?;
-    
+    if let Err(e) = db.enqueue_transcription(&recording.id) {
+        eprintln!("⚠️ Failed to enqueue transcription job for recording {}: {}", recording.id, e);
+    }
+
     println!("Stopped audio recording, duration: {}s", duration);
     
     Ok("Recording saved successfully".to_string())
@@ -525,10 +1012,17 @@ pub async fn pause_recording(state: State<'_, AppState>) -> Result<String, Strin
         // Send pause command to audio thread
         let audio_sender_guard = state.audio_thread_sender.lock().map_err(|e| e.to_string())?;
         if let Some(ref audio_sender) = *audio_sender_guard {
-            audio_sender.send(AudioCommand::PauseRecording)
+            let (ack_tx, ack_rx) = mpsc::channel();
+            audio_sender.send(AudioCommand::PauseRecording { ack_tx })
                 .map_err(|e| format!("Failed to send pause command to audio thread: {}", e))?;
+            match ack_rx.recv_timeout(AUDIO_COMMAND_TIMEOUT) {
+                Ok(Ok(AudioAck::Paused)) => {}
+                Ok(Ok(_)) => return Err("Audio thread sent an unexpected acknowledgement".to_string()),
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_) => return Err("Audio thread did not acknowledge pause command in time".to_string()),
+            }
         }
-        
+
         Ok("Recording paused".to_string())
     } else {
         Err("No active recording".to_string())
@@ -550,10 +1044,17 @@ pub async fn resume_recording(state: State<'_, AppState>) -> Result<String, Stri
         // Send resume command to audio thread
         let audio_sender_guard = state.audio_thread_sender.lock().map_err(|e| e.to_string())?;
         if let Some(ref audio_sender) = *audio_sender_guard {
-            audio_sender.send(AudioCommand::ResumeRecording)
+            let (ack_tx, ack_rx) = mpsc::channel();
+            audio_sender.send(AudioCommand::ResumeRecording { ack_tx })
                 .map_err(|e| format!("Failed to send resume command to audio thread: {}", e))?;
+            match ack_rx.recv_timeout(AUDIO_COMMAND_TIMEOUT) {
+                Ok(Ok(AudioAck::Resumed)) => {}
+                Ok(Ok(_)) => return Err("Audio thread sent an unexpected acknowledgement".to_string()),
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_) => return Err("Audio thread did not acknowledge resume command in time".to_string()),
+            }
         }
-        
+
         Ok("Recording resumed".to_string())
     } else {
         Err("No active recording".to_string())
@@ -597,22 +1098,97 @@ pub async fn get_card_recordings(state: State<'_, AppState>, card_id: String) ->
     db.get_recordings_for_card(&card_id).map_err(|e| e.to_string())
 }
 
+/// Reads the whole file and base64-encodes it in one shot - fine for short
+/// clips, but blocks and balloons memory for long recordings. Kept as-is for
+/// callers that just want a playable data URL; `get_audio_metadata` +
+/// `get_audio_chunk` below let a caller stream a long recording in ranges
+/// instead.
 #[tauri::command]
 pub async fn get_audio_data(state: State<'_, AppState>, filepath: String) -> Result<String, String> {
-    // Use the proper path resolver to convert relative path to absolute path
-    let absolute_path = state.path_resolver.resolve_relative_path(&filepath);
-    
+    // Resolve through the storage roots so a recording saved under any root
+    // (not just the app-data default) is found, mirroring card_images.rs.
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let absolute_path = db.resolve_media_path(&filepath).map_err(|e| e.to_string())?;
+    drop(db);
+
     // Read the audio file
     let audio_data = fs::read(&absolute_path)
         .map_err(|e| format!("Failed to read audio file '{}': {}", absolute_path.display(), e))?;
-    
+
     // Convert to base64 data URL
     let base64_data = general_purpose::STANDARD.encode(&audio_data);
     let data_url = format!("data:audio/wav;base64,{}", base64_data);
-    
+
     Ok(data_url)
 }
 
+/// Parses just the WAV header (`hound::WavReader::open` never reads the PCM
+/// body) so a caller can learn a long recording's shape - total byte length,
+/// sample rate, channel count, duration - before deciding how to stream it
+/// with `get_audio_chunk`.
+#[tauri::command]
+pub async fn get_audio_metadata(state: State<'_, AppState>, filepath: String) -> Result<AudioMetadata, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let absolute_path = db.resolve_media_path(&filepath).map_err(|e| e.to_string())?;
+    drop(db);
+
+    let reader = WavReader::open(&absolute_path)
+        .map_err(|e| format!("Failed to open audio file '{}': {}", absolute_path.display(), e))?;
+    let spec = reader.spec();
+    let total_samples = reader.len();
+    let duration_seconds = total_samples as f64 / (spec.sample_rate as f64 * spec.channels as f64);
+
+    let total_len = fs::metadata(&absolute_path)
+        .map_err(|e| format!("Failed to read file metadata for '{}': {}", absolute_path.display(), e))?
+        .len();
+
+    Ok(AudioMetadata {
+        total_len,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        duration_seconds,
+    })
+}
+
+/// Reads and base64-encodes at most `max_len` bytes starting at `byte_offset`,
+/// so a caller (e.g. a stream-loader controller fetching ranges around the
+/// current playback position) only ever blocks on the range it actually
+/// needs instead of the whole file. `next_offset` is `None` once the range
+/// reaches the end of the file.
+#[tauri::command]
+pub async fn get_audio_chunk(
+    state: State<'_, AppState>,
+    filepath: String,
+    byte_offset: u64,
+    max_len: u64,
+) -> Result<AudioChunk, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let absolute_path = db.resolve_media_path(&filepath).map_err(|e| e.to_string())?;
+    drop(db);
+
+    let mut file = fs::File::open(&absolute_path)
+        .map_err(|e| format!("Failed to open audio file '{}': {}", absolute_path.display(), e))?;
+    let total_len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file metadata for '{}': {}", absolute_path.display(), e))?
+        .len();
+
+    let offset = byte_offset.min(total_len);
+    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+
+    let read_len = (total_len - offset).min(max_len);
+    let mut buffer = vec![0u8; read_len as usize];
+    file.read_exact(&mut buffer)
+        .map_err(|e| format!("Failed to read audio chunk at offset {}: {}", offset, e))?;
+
+    let next_offset = offset + read_len;
+    Ok(AudioChunk {
+        bytes_base64: general_purpose::STANDARD.encode(&buffer),
+        total_len,
+        next_offset: if next_offset < total_len { Some(next_offset) } else { None },
+    })
+}
+
 #[tauri::command]
 pub async fn get_current_dir() -> Result<String, String> {
     let current_dir = std::env::current_dir().map_err(|e| e.to_string())?;
@@ -629,11 +1205,12 @@ pub async fn delete_recording(state: State<'_, AppState>, recording_id: String)
         recordings.into_iter().find(|r| r.id == recording_id)
             .ok_or("Recording not found")?
     };
+
+    // Convert the stored path to an absolute path for file deletion, resolving
+    // through whichever storage root the recording actually landed on.
+    let file_path = db.resolve_media_path(&recording.filepath).map_err(|e| e.to_string())?;
     drop(db);
     
-    // Convert relative path to absolute path for file deletion using proper path resolver
-    let file_path = state.path_resolver.resolve_relative_path(&recording.filepath);
-    
     // Attempt to delete the physical file (don't fail if file doesn't exist)
     let file_deleted = match fs::remove_file(&file_path) {
         Ok(()) => {
@@ -662,13 +1239,23 @@ pub async fn delete_recording(state: State<'_, AppState>, recording_id: String)
 #[tauri::command]
 pub async fn get_audio_devices(state: State<'_, AppState>) -> Result<AudioDeviceList, String> {
     let audio_sender = ensure_audio_thread_started(&state)?;
-    
+
     // Send refresh command to get current device list
-    audio_sender.send(AudioCommand::RefreshDevices)
+    let (ack_tx, ack_rx) = mpsc::channel();
+    audio_sender.send(AudioCommand::RefreshDevices { ack_tx })
         .map_err(|e| format!("Failed to send refresh devices command: {}", e))?;
-    
-    // For now, we'll return a basic device list since we can't easily get the response back
-    // In a more complex implementation, we'd use a synchronous channel or callback system
+
+    let cast_devices = crate::commands::cast::discover_cast_devices();
+
+    if let Ok(Ok(AudioAck::DevicesRefreshed(devices))) = ack_rx.recv_timeout(AUDIO_COMMAND_TIMEOUT) {
+        let current_device = devices.iter().find(|d| d.is_current).map(|d| d.name.clone())
+            .or_else(|| devices.iter().find(|d| d.is_default).map(|d| d.name.clone()));
+        return Ok(AudioDeviceList { devices, current_device, cast_devices });
+    }
+
+    // Fall back to a manual enumeration if the audio thread didn't ack in time
+    // (or acked with something unexpected) - a stale-but-present device list
+    // beats failing the whole command.
     let host = cpal::default_host();
     let mut devices = Vec::new();
     let default_device_name = host.default_input_device()
@@ -690,22 +1277,57 @@ pub async fn get_audio_devices(state: State<'_, AppState>) -> Result<AudioDevice
     Ok(AudioDeviceList {
         devices,
         current_device: Some(default_device_name),
+        cast_devices,
     })
 }
 
+/// Waits on `SwitchDevice`'s `ack_tx` for the audio thread's real confirmation
+/// before returning, so the message reflects a completed switch (or hot-swap
+/// of an in-progress recording) rather than assuming success the moment the
+/// command is sent.
 #[tauri::command]
 pub async fn switch_audio_device(state: State<'_, AppState>, device_name: String) -> Result<String, String> {
     let audio_sender = ensure_audio_thread_started(&state)?;
-    
+
     // Send switch device command to audio thread
-    audio_sender.send(AudioCommand::SwitchDevice { device_name: device_name.clone() })
+    let (ack_tx, ack_rx) = mpsc::channel();
+    audio_sender.send(AudioCommand::SwitchDevice { device_name: device_name.clone(), ack_tx })
         .map_err(|e| format!("Failed to send switch device command: {}", e))?;
-    
-    Ok(format!("Switched to device: {}", device_name))
+
+    match ack_rx.recv_timeout(AUDIO_COMMAND_TIMEOUT) {
+        Ok(Ok(AudioAck::DeviceSwitched(name))) => Ok(format!("Switched to device: {}", name)),
+        Ok(Ok(_)) => Err("Audio thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Audio thread did not acknowledge switch command in time".to_string()),
+    }
+}
+
+/// Drains every [`LevelFrame`] published by the capture callback since the
+/// last call, oldest first. Returns an empty list (not an error) whenever no
+/// recording is active yet, since the consumer is only created the first
+/// time the audio thread starts.
+#[tauri::command]
+pub async fn get_input_level(state: State<'_, AppState>) -> Result<Vec<LevelFrame>, String> {
+    let mut level_guard = state.level_consumer.lock().map_err(|e| e.to_string())?;
+    let Some(consumer) = level_guard.as_mut() else {
+        return Ok(Vec::new());
+    };
+
+    let mut frames = Vec::new();
+    while let Some(frame) = consumer.pop() {
+        frames.push(frame);
+    }
+    Ok(frames)
 }
 
 #[tauri::command]
-pub async fn refresh_audio_devices(state: State<'_, AppState>) -> Result<AudioDeviceList, String> {
+pub async fn refresh_audio_devices(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<AudioDeviceList, String> {
     // This is essentially the same as get_audio_devices but explicitly refreshes
-    get_audio_devices(state).await
+    let devices = get_audio_devices(state).await?;
+    crate::events::emit(&app_handle, crate::events::AppEvent::DeviceListChanged(AudioDeviceList {
+        devices: devices.devices.clone(),
+        current_device: devices.current_device.clone(),
+        cast_devices: devices.cast_devices.clone(),
+    }));
+    Ok(devices)
 }
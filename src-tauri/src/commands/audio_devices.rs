@@ -0,0 +1,142 @@
+// Input-device enumeration and selection, built on cpal's HostTrait/DeviceTrait.
+//
+// `debug::check_microphone_permission` only ever probes the default device; this
+// module is the real device-management surface for users with multiple mics
+// (USB interface, built-in, Bluetooth headset).
+
+use crate::models::AppState;
+use cpal::traits::{DeviceTrait, HostTrait};
+use tauri::State;
+
+/// One supported input configuration range reported by the device driver.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SupportedInputConfig {
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+}
+
+/// An enumerable input device and what it claims to support.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InputDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub supported_configs: Vec<SupportedInputConfig>,
+}
+
+/// Distinguishes a genuine hardware/driver error from a missing OS permission
+/// (most commonly macOS TCC, where `name()`/`supported_input_configs()` fail until
+/// the user grants microphone access) so the frontend can prompt the OS dialog
+/// instead of reporting a hardware failure.
+#[derive(Debug)]
+enum AudioDeviceError {
+    PermissionDenied(String),
+    DeviceError(String),
+}
+
+impl std::fmt::Display for AudioDeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioDeviceError::PermissionDenied(msg) => write!(f, "PermissionDenied: {}", msg),
+            AudioDeviceError::DeviceError(msg) => write!(f, "DeviceError: {}", msg),
+        }
+    }
+}
+
+impl From<AudioDeviceError> for String {
+    fn from(err: AudioDeviceError) -> Self {
+        err.to_string()
+    }
+}
+
+/// macOS reports device access failures as generic `BackendSpecific` errors with no
+/// distinct "permission" variant, so we recognize the TCC wording cpal/coreaudio
+/// surface. Any other enumeration failure is treated as a real device error.
+fn classify_device_error(context: &str, message: &str) -> AudioDeviceError {
+    let lower = message.to_lowercase();
+    if cfg!(target_os = "macos")
+        && (lower.contains("not authorized") || lower.contains("permission") || lower.contains("tcc"))
+    {
+        AudioDeviceError::PermissionDenied(format!("{}: {}", context, message))
+    } else {
+        AudioDeviceError::DeviceError(format!("{}: {}", context, message))
+    }
+}
+
+fn describe_device(device: &cpal::Device, default_name: &str) -> Result<InputDeviceInfo, AudioDeviceError> {
+    let name = device
+        .name()
+        .map_err(|e| classify_device_error("Failed to read device name", &e.to_string()))?;
+
+    let supported_configs = device
+        .supported_input_configs()
+        .map_err(|e| classify_device_error("Failed to query supported input configs", &e.to_string()))?
+        .map(|range| SupportedInputConfig {
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            channels: range.channels(),
+            sample_format: format!("{:?}", range.sample_format()),
+        })
+        .collect();
+
+    Ok(InputDeviceInfo {
+        is_default: name == default_name,
+        name,
+        supported_configs,
+    })
+}
+
+#[tauri::command]
+pub async fn list_input_devices() -> Result<Vec<InputDeviceInfo>, String> {
+    let host = cpal::default_host();
+    let default_name = host
+        .default_input_device()
+        .and_then(|d| d.name().ok())
+        .unwrap_or_default();
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| classify_device_error("Failed to enumerate input devices", &e.to_string()))?;
+
+    let mut infos = Vec::new();
+    for device in devices {
+        infos.push(describe_device(&device, &default_name)?);
+    }
+
+    Ok(infos)
+}
+
+#[tauri::command]
+pub async fn get_default_input_device() -> Result<Option<InputDeviceInfo>, String> {
+    let host = cpal::default_host();
+    let Some(device) = host.default_input_device() else {
+        return Ok(None);
+    };
+
+    let default_name = device.name().unwrap_or_default();
+    Ok(Some(describe_device(&device, &default_name)?))
+}
+
+#[tauri::command]
+pub async fn set_preferred_input_device(
+    state: State<'_, AppState>,
+    device_name: String,
+) -> Result<(), String> {
+    // Validate the device actually exists before persisting the preference so a
+    // typo'd name doesn't silently break the next recording attempt.
+    let devices = list_input_devices().await?;
+    if !devices.iter().any(|d| d.name == device_name) {
+        return Err(format!("Input device '{}' not found", device_name));
+    }
+
+    let mut preferred = state.preferred_input_device.lock().map_err(|e| e.to_string())?;
+    *preferred = Some(device_name);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_preferred_input_device(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let preferred = state.preferred_input_device.lock().map_err(|e| e.to_string())?;
+    Ok(preferred.clone())
+}
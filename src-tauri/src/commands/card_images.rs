@@ -1,25 +1,31 @@
-use crate::models::{AppState, CardImage, SaveCardImageRequest, DeleteCardImageRequest};
+// Content-addressed card images, mirroring `commands::images`' CAS storage
+// for `problem_images` (see migration 21): images are named by the BLAKE3
+// hash of their decoded bytes and written into the same shared `images/cas/`
+// directory, so a screenshot pasted onto several cards is stored once and
+// `image_blobs.ref_count` (now tracked for both tables, see
+// `database/triggers.rs`) tells `delete_card_image` whether it's still safe
+// to unlink the file.
+
+use crate::commands::images::{generate_thumbnail, thumbnail_path_for};
+use crate::database::jobs::{self, BulkImportItem};
+use crate::database::maintenance::blake3_hex;
+use crate::database::storage_roots::StorageRoots;
+use crate::database::DatabaseManager;
+use crate::models::{AppState, BulkImportCardImagesRequest, CardImage, SaveCardImageRequest, DeleteCardImageRequest};
 use base64::{Engine as _, engine::general_purpose};
-use std::path::PathBuf;
 use std::fs;
 use tauri::State;
-use uuid::Uuid;
-
-// Helper function to get the card images directory using PathResolver
-fn get_card_images_dir_with_resolver(path_resolver: &crate::path_resolver::PathResolver) -> anyhow::Result<PathBuf> {
-    let images_dir = path_resolver.get_images_dir();
-    let cards_dir = images_dir.join("cards");
-    fs::create_dir_all(&cards_dir)?;
-    Ok(cards_dir)
-}
 
-// Helper function to ensure card-specific directory exists using PathResolver
-fn ensure_card_dir_with_resolver(path_resolver: &crate::path_resolver::PathResolver, card_id: &str) -> anyhow::Result<PathBuf> {
-    let cards_dir = get_card_images_dir_with_resolver(path_resolver)?;
-    let card_dir = cards_dir.join(format!("card_{}", card_id));
-    fs::create_dir_all(&card_dir)?;
-    Ok(card_dir)
-}
+/// Long edge a card image's thumbnail is downscaled to - smaller than
+/// `commands::images::THUMBNAIL_MAX_EDGE` (320px), since card galleries show
+/// more images at once than a problem's image strip.
+const THUMBNAIL_MAX_EDGE: u32 = 256;
+
+/// Free space a root must report to be picked for a new card image - just
+/// enough headroom that a single large paste doesn't run a nearly-full
+/// secondary drive completely dry before the next `verify_storage_roots`/
+/// disk-space warning has a chance to surface.
+const MIN_FREE_BYTES_FOR_WRITE: u64 = 50 * 1024 * 1024;
 
 // Helper function to detect image format from base64 data
 fn detect_image_format(data: &str) -> Option<&str> {
@@ -39,55 +45,177 @@ fn detect_image_format(data: &str) -> Option<&str> {
     }
 }
 
-#[tauri::command]
-pub async fn save_card_image(
-    state: State<'_, AppState>,
-    request: SaveCardImageRequest,
+/// Maps `detect_image_format`'s sniffed tag to the codec `image` can decode,
+/// mirroring `commands::images::raster_image_format`. `svg` isn't in here -
+/// it's vector data with no raster form to thumbnail.
+fn raster_image_format(format: &str) -> Option<image::ImageFormat> {
+    match format {
+        "png" => Some(image::ImageFormat::Png),
+        "jpg" | "jpeg" => Some(image::ImageFormat::Jpeg),
+        "gif" => Some(image::ImageFormat::Gif),
+        "webp" => Some(image::ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// `save_card_image`'s CAS decode/hash/dedup/thumbnail/write path, pulled out
+/// so `run_bulk_import_card_images` can run the exact same per-image logic
+/// against an already-locked `DatabaseManager` instead of going through the
+/// `State<'_, AppState>` a `#[tauri::command]` needs.
+pub(crate) fn save_card_image_bytes(
+    db: &mut DatabaseManager,
+    card_id: &str,
+    image_data_field: &str,
+    caption: Option<String>,
+    position: Option<i32>,
 ) -> Result<CardImage, String> {
     // Extract base64 data (remove data URL prefix if present)
-    let image_data = if request.image_data.contains(',') {
-        request.image_data.split(',').nth(1).unwrap_or(&request.image_data)
+    let image_data = if image_data_field.contains(',') {
+        image_data_field.split(',').nth(1).unwrap_or(image_data_field)
     } else {
-        &request.image_data
+        image_data_field
     };
 
     // Detect image format
-    let format = detect_image_format(&request.image_data).unwrap_or("png");
+    let format = detect_image_format(image_data_field).unwrap_or("png");
 
     // Decode base64 data
     let decoded_data = general_purpose::STANDARD
         .decode(image_data)
         .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
 
-    // Generate unique filename
-    let filename = format!("{}.{}", Uuid::new_v4(), format);
-
-    // Ensure card directory exists
-    let card_dir = ensure_card_dir_with_resolver(&state.path_resolver, &request.card_id)
-        .map_err(|e| format!("Failed to create card directory: {}", e))?;
-
-    // Full path for file storage
-    let full_path = card_dir.join(&filename);
+    // Name the stored file after the BLAKE3 hash of its bytes instead of a
+    // fresh Uuid - pasting the same image onto another card then writes the
+    // content once.
+    let hash = blake3_hex(&decoded_data);
+    let filename = format!("{}.{}", hash, format);
+
+    // Pick the highest-priority root with room for this image instead of
+    // always writing under the single legacy `cas_dir()` - lets a user's
+    // image collection outgrow the drive the SQLite DB lives on.
+    let root = db.pick_storage_root_for_write(MIN_FREE_BYTES_FOR_WRITE).map_err(|e| e.to_string())?;
+    let cas_directory = root.path.join("images/cas");
+    fs::create_dir_all(&cas_directory)
+        .map_err(|e| format!("Failed to create cas directory: {}", e))?;
+
+    let full_path = cas_directory.join(&filename);
+    let already_stored = full_path.exists();
+    if !already_stored {
+        fs::write(&full_path, &decoded_data)
+            .map_err(|e| format!("Failed to save image file: {}", e))?;
+    }
 
-    // Save the image file
-    fs::write(&full_path, decoded_data)
-        .map_err(|e| format!("Failed to save image file: {}", e))?;
+    // Store which root this image lives in alongside its path relative to
+    // that root, so `resolve_media_path`/`verify_storage_roots` can find it
+    // again even if the root's own directory later moves.
+    let relative_path = StorageRoots::to_stored_path(&root.id, &format!("images/cas/{}", filename));
+
+    // Downscale raster formats to a `<hash>.thumb.webp` alongside the full
+    // file, so `get_card_image_thumbnail` can serve a small payload instead
+    // of base64-encoding the original on every request. A duplicate upload
+    // reuses whatever thumbnail the first save already generated.
+    let thumbnail_filename = format!("{}.thumb.webp", hash);
+    let thumbnail_full_path = cas_directory.join(&thumbnail_filename);
+    let relative_thumbnail_path = StorageRoots::to_stored_path(&root.id, &format!("images/cas/{}", thumbnail_filename));
+
+    let thumbnail_path = if already_stored && thumbnail_full_path.exists() {
+        Some(relative_thumbnail_path)
+    } else if let Some(image_format) = raster_image_format(format) {
+        match image::load_from_memory_with_format(&decoded_data, image_format) {
+            Ok(decoded) => match generate_thumbnail(&decoded, &thumbnail_full_path, THUMBNAIL_MAX_EDGE) {
+                Ok(()) => Some(relative_thumbnail_path),
+                Err(e) => {
+                    eprintln!("Failed to generate thumbnail for {}: {}", relative_path, e);
+                    None
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to decode {} as {} for thumbnailing: {}", relative_path, format, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
-    // Create relative path for database storage using PathResolver
-    let relative_path = state.path_resolver.to_relative_path(&full_path);
+    db.register_image_blob(&hash, format).map_err(|e| e.to_string())?;
 
-    // Save to database
-    let mut db = state.db.lock().map_err(|e| e.to_string())?;
     let image = db.save_card_image(
-        &request.card_id,
+        card_id,
         &relative_path,
-        request.caption,
-        request.position,
+        Some(&hash),
+        thumbnail_path.as_deref(),
+        caption,
+        position,
     ).map_err(|e| format!("Failed to save image to database: {}", e))?;
 
+    // Kick off auto-labeling in the background so pasted screenshots become
+    // searchable without blocking this command - only when the `ocr`
+    // feature is actually compiled in, since enqueuing a job the worker can
+    // never complete would just burn through `MAX_ATTEMPTS` retries for
+    // nothing.
+    #[cfg(feature = "ocr")]
+    if let Err(e) = db.enqueue_ocr_card_image(&image.id) {
+        eprintln!("⚠️ Rust: Failed to enqueue OCR job for image {}: {}", image.id, e);
+    }
+
     Ok(image)
 }
 
+#[tauri::command]
+pub async fn save_card_image(
+    state: State<'_, AppState>,
+    request: SaveCardImageRequest,
+) -> Result<CardImage, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    save_card_image_bytes(&mut db, &request.card_id, &request.image_data, request.caption, request.position)
+}
+
+/// Saves several images in one `db` lock instead of one round trip per
+/// image - the "apply to selection" counterpart of `save_card_image`. Each
+/// image still goes through the same CAS decode/hash/dedup/write path, so a
+/// duplicate within the batch (or already saved elsewhere) is still only
+/// written once; stops at the first failure and returns it, leaving
+/// whichever images already saved in place rather than trying to undo them -
+/// content-addressed writes are idempotent, so a retry of the same batch
+/// just resumes where it left off.
+#[tauri::command]
+pub async fn save_card_images(
+    state: State<'_, AppState>,
+    requests: Vec<SaveCardImageRequest>,
+) -> Result<Vec<CardImage>, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    requests
+        .into_iter()
+        .map(|request| save_card_image_bytes(&mut db, &request.card_id, &request.image_data, request.caption, request.position))
+        .collect()
+}
+
+/// Enqueues a `bulk_import_card_images` job and returns immediately - the
+/// actual imports run one at a time on the job-worker thread (see
+/// `commands::jobs::run_bulk_import_card_images`), so a batch of dozens of
+/// screenshots doesn't block the UI or risk losing everything if the app
+/// quits partway through. Poll progress with `get_job_progress`, or
+/// `pause_job`/`resume_job` to control it.
+#[tauri::command]
+pub async fn bulk_import_card_images(
+    state: State<'_, AppState>,
+    request: BulkImportCardImagesRequest,
+) -> Result<jobs::Job, String> {
+    let items: Vec<BulkImportItem> = request
+        .images
+        .into_iter()
+        .map(|image| BulkImportItem {
+            image_data: image.image_data,
+            caption: image.caption,
+        })
+        .collect();
+
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.enqueue_bulk_import_card_images(&request.card_id, items)
+        .map_err(|e| format!("Failed to enqueue bulk import: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_card_images(
     state: State<'_, AppState>,
@@ -98,51 +226,128 @@ pub async fn get_card_images(
         .map_err(|e| format!("Failed to get card images: {}", e))
 }
 
+/// Unlinks `image_path` (and `thumbnail_path`, if any) once `delete_card_image`/
+/// `delete_card_images` has confirmed `remaining_ref_count` reached 0 - no
+/// other card (or problem gallery) still references the same content.
+fn unlink_card_image_files(
+    db: &DatabaseManager,
+    image_path: &str,
+    thumbnail_path: Option<&str>,
+) -> Result<(), String> {
+    let full_path = db.resolve_media_path(image_path).map_err(|e| e.to_string())?;
+    if full_path.exists() {
+        fs::remove_file(full_path)
+            .map_err(|e| format!("Failed to delete image file: {}", e))?;
+    }
+
+    if let Some(thumbnail_path) = thumbnail_path {
+        let thumbnail_full_path = db.resolve_media_path(thumbnail_path).map_err(|e| e.to_string())?;
+        if thumbnail_full_path.exists() {
+            fs::remove_file(thumbnail_full_path)
+                .map_err(|e| format!("Failed to delete thumbnail file: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn delete_card_image(
     state: State<'_, AppState>,
     request: DeleteCardImageRequest,
 ) -> Result<(), String> {
-    println!("🗑️ Backend: Starting card image deletion for image_id: {}", request.image_id);
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
 
-    let mut db = state.db.lock().map_err(|e| {
-        let error_msg = format!("Failed to lock database: {}", e);
-        println!("❌ Backend: {}", error_msg);
-        error_msg
-    })?;
+    // `trg_card_image_blobs_ref_count_delete` has already decremented
+    // `image_blobs.ref_count` by the time this returns - only unlink the file
+    // once `remaining_ref_count` reaches 0, since another card (or a
+    // problem's gallery) may still reference the same content.
+    let (image_path, thumbnail_path, content_hash, remaining_ref_count) =
+        db.delete_card_image(&request.image_id)
+            .map_err(|e| format!("Failed to delete image from database: {}", e))?;
+
+    let still_referenced = content_hash.is_some() && remaining_ref_count.unwrap_or(0) > 0;
+    if still_referenced {
+        return Ok(());
+    }
 
-    // Get the image path from database and delete the record
-    println!("🔄 Backend: Querying database for image path and deleting record...");
-    let image_path = db.delete_card_image(&request.image_id)
-        .map_err(|e| {
-            let error_msg = format!("Failed to delete image from database: {}", e);
-            println!("❌ Backend: {}", error_msg);
-            error_msg
-        })?;
+    if let Some(hash) = &content_hash {
+        db.forget_image_blob(hash).map_err(|e| e.to_string())?;
+    }
 
-    println!("✅ Backend: Database record deleted, image_path: {}", image_path);
+    unlink_card_image_files(&db, &image_path, thumbnail_path.as_deref())
+}
 
-    // Delete the actual file using PathResolver
-    let full_path = state.path_resolver.resolve_relative_path(&image_path);
-    println!("🔄 Backend: Resolving file path: {} -> {}", image_path, full_path.display());
+/// Deletes several images in one transaction - the "apply to selection"
+/// counterpart of `delete_card_image` - so a partial failure (a stale id in
+/// the selection) rolls every row in the batch back instead of leaving the
+/// gallery half-pruned. Files are unlinked afterward, same as the
+/// single-image command.
+#[tauri::command]
+pub async fn delete_card_images(
+    state: State<'_, AppState>,
+    image_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
 
-    if full_path.exists() {
-        println!("🔄 Backend: File exists, attempting to delete...");
-        fs::remove_file(&full_path)
-            .map_err(|e| {
-                let error_msg = format!("Failed to delete image file: {}", e);
-                println!("❌ Backend: {}", error_msg);
-                error_msg
-            })?;
-        println!("✅ Backend: File deleted successfully");
-    } else {
-        println!("⚠️ Backend: File doesn't exist at path: {}", full_path.display());
+    let deleted = db.delete_card_images(&image_ids)
+        .map_err(|e| format!("Failed to delete images from database: {}", e))?;
+
+    for (_, image_path, thumbnail_path, content_hash, remaining_ref_count) in deleted {
+        let still_referenced = content_hash.is_some() && remaining_ref_count.unwrap_or(0) > 0;
+        if still_referenced {
+            continue;
+        }
+
+        if let Some(hash) = &content_hash {
+            db.forget_image_blob(hash).map_err(|e| e.to_string())?;
+        }
+
+        unlink_card_image_files(&db, &image_path, thumbnail_path.as_deref())?;
     }
 
-    println!("✅ Backend: Card image deletion completed successfully");
     Ok(())
 }
 
+/// Reassigns several images to a different card in one transaction - the
+/// "apply to selection" counterpart of dragging one image between cards.
+/// See `DatabaseManager::move_card_images` for why no file is actually
+/// relocated.
+#[tauri::command]
+pub async fn move_card_images(
+    state: State<'_, AppState>,
+    image_ids: Vec<String>,
+    target_card_id: String,
+) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.move_card_images(&image_ids, &target_card_id)
+        .map_err(|e| format!("Failed to move images: {}", e))
+}
+
+/// Returns `relative_path`'s thumbnail as a base64 data URL, generating and
+/// caching it on disk first if it doesn't exist yet - e.g. a `card_images`
+/// row saved before thumbnailing existed, or whose thumbnail generation
+/// failed at save time. Mirrors `commands::images::get_problem_image_thumbnail`.
+#[tauri::command]
+pub async fn get_card_image_thumbnail(state: State<'_, AppState>, relative_path: String) -> Result<String, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let full_path = db.resolve_media_path(&relative_path).map_err(|e| e.to_string())?;
+    drop(db);
+    let thumbnail_full_path = thumbnail_path_for(&full_path);
+
+    if !thumbnail_full_path.exists() {
+        let decoded = image::open(&full_path)
+            .map_err(|e| format!("Failed to open image {}: {}", full_path.display(), e))?;
+        generate_thumbnail(&decoded, &thumbnail_full_path, THUMBNAIL_MAX_EDGE).map_err(|e| e.to_string())?;
+    }
+
+    let thumbnail_data = fs::read(&thumbnail_full_path)
+        .map_err(|e| format!("Failed to read thumbnail file: {}", e))?;
+    let base64_data = general_purpose::STANDARD.encode(&thumbnail_data);
+
+    Ok(format!("data:image/webp;base64,{}", base64_data))
+}
+
 #[tauri::command]
 pub async fn update_card_image_positions(
     state: State<'_, AppState>,
@@ -156,8 +361,11 @@ pub async fn update_card_image_positions(
 // Helper command to get the full path for a card image (for displaying in frontend)
 #[tauri::command]
 pub async fn get_card_image_path(state: State<'_, AppState>, relative_path: String) -> Result<String, String> {
-    // Use PathResolver to handle environment-aware path resolution
-    let full_path = state.path_resolver.resolve_relative_path(&relative_path);
+    // Root-aware resolution, since a card image's stored path may be
+    // prefixed with the storage root it lives under (see `save_card_image_bytes`).
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let full_path = db.resolve_media_path(&relative_path).map_err(|e| e.to_string())?;
+    drop(db);
 
     // Convert to string and use the asset protocol
     let path_str = full_path.to_string_lossy().to_string();
@@ -175,8 +383,11 @@ pub async fn get_card_image_path(state: State<'_, AppState>, relative_path: Stri
 // Alternative: Get card image as base64 data URL
 #[tauri::command]
 pub async fn get_card_image_data_url(state: State<'_, AppState>, relative_path: String) -> Result<String, String> {
-    // Use PathResolver to handle environment-aware path resolution
-    let full_path = state.path_resolver.resolve_relative_path(&relative_path);
+    // Root-aware resolution, since a card image's stored path may be
+    // prefixed with the storage root it lives under (see `save_card_image_bytes`).
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let full_path = db.resolve_media_path(&relative_path).map_err(|e| e.to_string())?;
+    drop(db);
 
     // Read the image file
     let image_data = fs::read(&full_path)
@@ -197,4 +408,4 @@ pub async fn get_card_image_data_url(state: State<'_, AppState>, relative_path:
 
     // Return as data URL
     Ok(format!("data:{};base64,{}", mime_type, base64_data))
-}
\ No newline at end of file
+}
@@ -0,0 +1,515 @@
+// Casts a recording to a Chromecast/Cast-enabled receiver on the LAN, so users
+// can play back their spoken solution notes on a smart speaker instead of
+// squinting at the in-app waveform.
+//
+// This follows the standard CASTV2 flow: discover receivers via mDNS, open a
+// TLS connection to the receiver's CASTV2 port, send a connection handshake,
+// start a heartbeat, then issue a media `LOAD` request pointing at the
+// recording served over a short-lived local HTTP URL. The returned
+// `mediaSessionId` is stashed in `AppState.cast_session` so later play/pause/stop
+// commands know what to control without keeping a socket open between calls.
+
+use crate::models::{AppState, CastDevice, CastPlaybackSession};
+use serde_json::json;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::State;
+
+/// CASTV2 receivers listen on this TLS port.
+const CAST_PORT: u16 = 8009;
+const NAMESPACE_CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+const NAMESPACE_HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const NAMESPACE_RECEIVER: &str = "urn:x-cast:com.google.cast.receiver";
+const NAMESPACE_MEDIA: &str = "urn:x-cast:com.google.cast.media";
+
+const SENDER_ID: &str = "sender-0";
+const RECEIVER_ID: &str = "receiver-0";
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Discovers Cast-enabled receivers on the LAN by sending a single mDNS query
+/// for `_googlecast._tcp.local` and collecting replies for a short window.
+///
+/// This is a minimal, single-pass parser rather than a full RFC 1035 decoder -
+/// it only pulls out the fields (name, address, port) the `LOAD` flow needs.
+/// Every reply is listed; one with no recognizable SRV record gets the
+/// default port 8009.
+pub fn discover_cast_devices() -> Vec<CastDevice> {
+    match mdns::query_googlecast(Duration::from_secs(2)) {
+        Ok(devices) => devices,
+        Err(e) => {
+            eprintln!("⚠️ Cast device discovery failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_cast_devices() -> Result<Vec<CastDevice>, String> {
+    Ok(discover_cast_devices())
+}
+
+#[tauri::command]
+pub async fn play_recording_on_device(
+    state: State<'_, AppState>,
+    recording_id: String,
+    device_name: String,
+) -> Result<String, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let recording = db
+        .get_recordings()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|r| r.id == recording_id)
+        .ok_or("Recording not found")?;
+    drop(db);
+
+    let absolute_path = state.path_resolver.resolve_relative_path(&recording.filepath)?;
+
+    let device = discover_cast_devices()
+        .into_iter()
+        .find(|d| d.name == device_name)
+        .ok_or_else(|| format!("Cast device '{}' not found", device_name))?;
+
+    let (content_url, local_addr) = serve_recording_once(&absolute_path, &device)?;
+
+    let conn = CastConnection::connect(&device.host, device.port)?;
+    conn.send(NAMESPACE_CONNECTION, RECEIVER_ID, &json!({ "type": "CONNECT" }))?;
+    conn.spawn_heartbeat();
+
+    let request_id = 1;
+    conn.send(
+        NAMESPACE_MEDIA,
+        RECEIVER_ID,
+        &json!({
+            "type": "LOAD",
+            "requestId": request_id,
+            "sessionId": RECEIVER_ID,
+            "media": {
+                "contentId": content_url,
+                "contentType": "audio/wav",
+                "streamType": "BUFFERED",
+            },
+            "autoplay": true,
+        }),
+    )?;
+
+    let media_session_id = conn
+        .receive_media_session_id(CONNECT_TIMEOUT)
+        .unwrap_or(0);
+
+    let mut cast_session = state.cast_session.lock().map_err(|e| e.to_string())?;
+    *cast_session = Some(CastPlaybackSession {
+        device: device.clone(),
+        media_session_id,
+        next_request_id: request_id + 1,
+    });
+
+    println!(
+        "Casting recording {} to {} via {}",
+        recording.id, device.name, local_addr
+    );
+
+    Ok(format!("Casting recording to {}", device.name))
+}
+
+#[tauri::command]
+pub async fn pause_cast_playback(state: State<'_, AppState>) -> Result<String, String> {
+    send_media_control(&state, "PAUSE")?;
+    Ok("Cast playback paused".to_string())
+}
+
+#[tauri::command]
+pub async fn resume_cast_playback(state: State<'_, AppState>) -> Result<String, String> {
+    send_media_control(&state, "PLAY")?;
+    Ok("Cast playback resumed".to_string())
+}
+
+#[tauri::command]
+pub async fn stop_cast_playback(state: State<'_, AppState>) -> Result<String, String> {
+    send_media_control(&state, "STOP")?;
+    let mut cast_session = state.cast_session.lock().map_err(|e| e.to_string())?;
+    *cast_session = None;
+    Ok("Cast playback stopped".to_string())
+}
+
+/// Sends a media-control request (`PAUSE`/`PLAY`/`STOP`) for the recording
+/// currently loaded on the receiver, reusing the `mediaSessionId` captured by
+/// `play_recording_on_device`.
+fn send_media_control(state: &State<'_, AppState>, control_type: &str) -> Result<(), String> {
+    let mut cast_session = state.cast_session.lock().map_err(|e| e.to_string())?;
+    let session = cast_session.as_mut().ok_or("No active Cast playback session")?;
+
+    let conn = CastConnection::connect(&session.device.host, session.device.port)?;
+    conn.send(NAMESPACE_CONNECTION, RECEIVER_ID, &json!({ "type": "CONNECT" }))?;
+
+    let request_id = session.next_request_id;
+    session.next_request_id += 1;
+
+    conn.send(
+        NAMESPACE_MEDIA,
+        RECEIVER_ID,
+        &json!({
+            "type": control_type,
+            "requestId": request_id,
+            "mediaSessionId": session.media_session_id,
+        }),
+    )
+}
+
+/// Serves `path` over a short-lived local HTTP server so the Cast receiver
+/// (which can't read our filesystem) can fetch it, and returns the URL the
+/// receiver should load plus the local address it's served from.
+fn serve_recording_once(path: &std::path::Path, device: &CastDevice) -> Result<(String, String), String> {
+    let local_ip = local_ip_facing(&device.host)?;
+    let listener = TcpListener::bind((local_ip.as_str(), 0))
+        .map_err(|e| format!("Failed to bind local HTTP server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let filename = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("recording.wav")
+        .to_string();
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read recording file: {}", e))?;
+
+    // The receiver fetches the file at most once (it buffers it), so a single
+    // accepted connection is enough; the thread then exits and drops the listener.
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut discard = [0u8; 1024];
+            let _ = stream.read(&mut discard);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: audio/wav\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                data.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+            let _ = stream.write_all(&data);
+        }
+    });
+
+    Ok((format!("http://{}:{}/{}", local_ip, port, filename), local_ip))
+}
+
+/// Picks the local interface address that would be used to reach `peer_host`,
+/// by opening a UDP socket "connected" to it - the usual trick for finding
+/// which local IP a given peer is routed through without parsing `ip route`.
+fn local_ip_facing(peer_host: &str) -> Result<String, String> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket
+        .connect((peer_host, CAST_PORT))
+        .map_err(|e| format!("Failed to reach Cast device at {}: {}", peer_host, e))?;
+    socket
+        .local_addr()
+        .map(|addr| addr.ip().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// A CASTV2 connection: a TLS socket plus the length-prefixed-protobuf framing
+/// every message is wrapped in.
+struct CastConnection {
+    // Shared with the heartbeat thread `spawn_heartbeat` starts, so it can
+    // write PING frames on the same socket `send`/`receive_media_session_id`
+    // use without racing them for the TLS session's write half.
+    stream: Arc<Mutex<native_tls::TlsStream<TcpStream>>>,
+    stop_heartbeat: Arc<AtomicBool>,
+}
+
+impl CastConnection {
+    fn connect(host: &str, port: u16) -> Result<Self, String> {
+        let tcp = TcpStream::connect((host, port))
+            .map_err(|e| format!("Failed to reach Cast device at {}:{}: {}", host, port, e))?;
+        tcp.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+
+        // Cast receivers use a self-signed certificate, so this (like every other
+        // CASTV2 client) trusts it on the LAN rather than validating against a CA.
+        let connector = native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(true)
+            .build()
+            .map_err(|e| format!("Failed to build TLS connector: {}", e))?;
+        let stream = connector
+            .connect(host, tcp)
+            .map_err(|e| format!("TLS handshake with Cast device failed: {}", e))?;
+
+        Ok(Self {
+            stream: Arc::new(Mutex::new(stream)),
+            stop_heartbeat: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Sends a CASTV2 message: a 4-byte big-endian length prefix followed by
+    /// the protobuf-encoded `CastMessage` wrapping `payload` as its JSON body.
+    fn send(&self, namespace: &str, destination_id: &str, payload: &serde_json::Value) -> Result<(), String> {
+        let frame = encode_cast_message(SENDER_ID, destination_id, namespace, &payload.to_string());
+        let mut stream = self.stream.lock().map_err(|e| format!("Cast connection mutex poisoned: {}", e))?;
+        stream
+            .write_all(&frame)
+            .map_err(|e| format!("Failed to send Cast message: {}", e))
+    }
+
+    /// Spawns the periodic `PING` heartbeat CASTV2 expects on an open
+    /// connection - receivers drop an idle socket after ~8s with none - so it
+    /// actually writes a PING frame on `NAMESPACE_HEARTBEAT` every
+    /// `HEARTBEAT_INTERVAL` rather than just sleeping; stops once this
+    /// `CastConnection` is dropped.
+    fn spawn_heartbeat(&self) {
+        let stream = Arc::clone(&self.stream);
+        let stop = Arc::clone(&self.stop_heartbeat);
+        thread::spawn(move || {
+            let ping = encode_cast_message(SENDER_ID, RECEIVER_ID, NAMESPACE_HEARTBEAT, &json!({ "type": "PING" }).to_string());
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(HEARTBEAT_INTERVAL);
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(mut stream) = stream.lock() else { break };
+                if stream.write_all(&ping).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Reads CASTV2 frames until a `MEDIA_STATUS` reply carrying a
+    /// `mediaSessionId` shows up, or `timeout` elapses.
+    fn receive_media_session_id(&self, timeout: Duration) -> Option<i32> {
+        let mut stream = self.stream.lock().ok()?;
+        stream.get_ref().set_read_timeout(Some(timeout)).ok();
+        let deadline = std::time::Instant::now() + timeout;
+
+        loop {
+            let mut len_buf = [0u8; 4];
+            stream.read_exact(&mut len_buf).ok()?;
+            let len = u32::from_be_bytes(len_buf) as usize;
+            let mut payload_buf = vec![0u8; len];
+            stream.read_exact(&mut payload_buf).ok()?;
+
+            let media_session_id = decode_cast_message(&payload_buf)
+                .and_then(|(_namespace, payload_json)| serde_json::from_str::<serde_json::Value>(&payload_json).ok())
+                .and_then(|value| {
+                    value
+                        .get("status")
+                        .and_then(|s| s.as_array())
+                        .and_then(|statuses| statuses.first())
+                        .and_then(|status| status.get("mediaSessionId"))
+                        .and_then(|id| id.as_i64())
+                })
+                .map(|id| id as i32);
+            if media_session_id.is_some() || std::time::Instant::now() >= deadline {
+                return media_session_id;
+            }
+        }
+    }
+}
+
+impl Drop for CastConnection {
+    fn drop(&mut self) {
+        self.stop_heartbeat.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Hand-rolled protobuf wire encoding for the handful of `CastMessage` fields
+/// the LOAD/PAUSE/PLAY/STOP flow needs (protocol_version=1, source_id=2,
+/// destination_id=3, namespace=4, payload_type=5 (STRING=0), payload_utf8=6),
+/// prefixed with the
+/// 4-byte big-endian length every CASTV2 frame requires.
+fn encode_cast_message(source_id: &str, destination_id: &str, namespace: &str, payload_utf8: &str) -> Vec<u8> {
+    let mut message = Vec::new();
+    write_varint_field(&mut message, 1, 0); // protocol_version = CASTV2_1_0
+    write_string_field(&mut message, 2, source_id);
+    write_string_field(&mut message, 3, destination_id);
+    write_string_field(&mut message, 4, namespace);
+    write_varint_field(&mut message, 5, 0); // payload_type = STRING
+    write_string_field(&mut message, 6, payload_utf8);
+
+    let mut frame = Vec::with_capacity(4 + message.len());
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&message);
+    frame
+}
+
+/// Inverse of `encode_cast_message`, pulling out just the namespace (field 4)
+/// and the JSON payload (field 6) a reply carries.
+fn decode_cast_message(buf: &[u8]) -> Option<(String, String)> {
+    let mut namespace = String::new();
+    let mut payload = String::new();
+    let mut pos = 0;
+
+    while pos < buf.len() {
+        let (tag, new_pos) = read_varint(buf, pos)?;
+        pos = new_pos;
+        let field_number = tag >> 3;
+        let wire_type = tag & 0x7;
+
+        match wire_type {
+            0 => {
+                let (_, new_pos) = read_varint(buf, pos)?;
+                pos = new_pos;
+            }
+            2 => {
+                let (len, new_pos) = read_varint(buf, pos)?;
+                pos = new_pos;
+                let len = len as usize;
+                let bytes = buf.get(pos..pos + len)?;
+                pos += len;
+                let text = String::from_utf8_lossy(bytes).to_string();
+                if field_number == 4 {
+                    namespace = text;
+                } else if field_number == 6 {
+                    payload = text;
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some((namespace, payload))
+}
+
+fn write_varint_field(out: &mut Vec<u8>, field_number: u32, value: u64) {
+    write_varint(out, ((field_number as u64) << 3) | 0);
+    write_varint(out, value);
+}
+
+fn write_string_field(out: &mut Vec<u8>, field_number: u32, value: &str) {
+    write_varint(out, ((field_number as u64) << 3) | 2);
+    write_varint(out, value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn read_varint(buf: &[u8], mut pos: usize) -> Option<(u64, usize)> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *buf.get(pos)?;
+        pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Some((result, pos))
+}
+
+/// Minimal mDNS client for `_googlecast._tcp.local` discovery.
+mod mdns {
+    use crate::models::CastDevice;
+    use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+    use std::time::{Duration, Instant};
+
+    const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+    const MDNS_PORT: u16 = 5353;
+    const SERVICE: &str = "_googlecast._tcp.local";
+
+    /// Sends one mDNS PTR query for `_googlecast._tcp.local` and collects
+    /// replies until `timeout` elapses. Only pulls the source address and the
+    /// service's advertised port out of each reply - good enough to connect,
+    /// not a general-purpose resolver.
+    pub fn query_googlecast(timeout: Duration) -> Result<Vec<CastDevice>, String> {
+        let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+        socket.set_read_timeout(Some(Duration::from_millis(300))).ok();
+
+        let query = build_ptr_query(SERVICE);
+        socket
+            .send_to(&query, SocketAddrV4::new(MDNS_ADDR, MDNS_PORT))
+            .map_err(|e| format!("Failed to send mDNS query: {}", e))?;
+
+        let mut devices = Vec::new();
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0u8; 4096];
+
+        while Instant::now() < deadline {
+            match socket.recv_from(&mut buf) {
+                Ok((len, src)) => {
+                    if let Some(port) = extract_srv_port(&buf[..len]) {
+                        devices.push(CastDevice {
+                            name: src.ip().to_string(),
+                            host: src.ip().to_string(),
+                            port,
+                        });
+                    }
+                }
+                Err(_) => continue,
+            }
+        }
+
+        devices.dedup_by(|a, b| a.host == b.host);
+        Ok(devices)
+    }
+
+    /// Builds a single-question PTR query packet for `name`.
+    fn build_ptr_query(name: &str) -> Vec<u8> {
+        let mut packet = Vec::new();
+        packet.extend_from_slice(&[0x00, 0x00]); // transaction id
+        packet.extend_from_slice(&[0x00, 0x00]); // flags (standard query)
+        packet.extend_from_slice(&[0x00, 0x01]); // qdcount = 1
+        packet.extend_from_slice(&[0x00, 0x00]); // ancount
+        packet.extend_from_slice(&[0x00, 0x00]); // nscount
+        packet.extend_from_slice(&[0x00, 0x00]); // arcount
+
+        for label in name.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0x00); // root label
+        packet.extend_from_slice(&[0x00, 0x0c]); // qtype = PTR
+        packet.extend_from_slice(&[0x00, 0x01]); // qclass = IN
+
+        packet
+    }
+
+    /// Scans a raw mDNS response for a `SRV` resource record (TYPE 0x0021)
+    /// and returns the port it advertises, falling back to the well-known
+    /// 8009 if none is found. This is a best-effort byte scan rather than a
+    /// full resource-record decoder - it doesn't walk DNS name compression
+    /// pointers to skip over the record's NAME field, it just looks for the
+    /// TYPE=SRV/CLASS=IN marker bytes directly and reads the RDATA that
+    /// follows as PRIORITY(2)/WEIGHT(2)/PORT(2), which is where a
+    /// Chromecast's own SRV record puts it.
+    fn extract_srv_port(packet: &[u8]) -> Option<u16> {
+        if packet.len() >= 10 {
+            for i in 0..=packet.len() - 10 {
+                let record_type = u16::from_be_bytes([packet[i], packet[i + 1]]);
+                // The cache-flush bit some responders set on the class is
+                // masked off before comparing against plain IN (1).
+                let record_class = u16::from_be_bytes([packet[i + 2], packet[i + 3]]) & 0x7fff;
+                if record_type != 0x0021 || record_class != 0x0001 {
+                    continue;
+                }
+
+                let rdlength = u16::from_be_bytes([packet[i + 8], packet[i + 9]]) as usize;
+                let rdata_offset = i + 10;
+                if rdlength < 6 || rdata_offset + 6 > packet.len() {
+                    continue;
+                }
+
+                let port = u16::from_be_bytes([packet[rdata_offset + 4], packet[rdata_offset + 5]]);
+                if port != 0 {
+                    return Some(port);
+                }
+            }
+        }
+
+        Some(8009)
+    }
+}
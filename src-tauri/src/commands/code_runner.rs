@@ -0,0 +1,192 @@
+// Test-case storage and local code execution, turning a card's code from a
+// note into something that can actually be checked against expected output.
+
+use crate::models::{AddTestCaseRequest, AppState, TestCase, TestResult};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::State;
+use uuid::Uuid;
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+/// Wall-clock budget given to a single test case run before it's killed and
+/// reported as a timeout - generous enough for slow interpreted languages on
+/// a cold start, short enough that one infinite loop doesn't hang the UI.
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How to turn a card's `language` into a runnable program: the source file
+/// extension to write the code to, and the shell command template that
+/// compiles/runs it, with `{file}` substituted for the temp file's path.
+fn runner_for_language(language: &str) -> Option<(&'static str, &'static str)> {
+    match language.to_lowercase().as_str() {
+        "python" | "python3" => Some(("py", "python3 {file}")),
+        "javascript" | "js" => Some(("js", "node {file}")),
+        "typescript" | "ts" => Some(("ts", "ts-node {file}")),
+        "rust" => Some(("rs", "rustc -O {file} -o {file}.out && {file}.out")),
+        "go" => Some(("go", "go run {file}")),
+        "c++" | "cpp" => Some(("cpp", "g++ -O2 -o {file}.out {file} && {file}.out")),
+        "java" => Some(("java", "java {file}")),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+pub async fn add_test_case(
+    state: State<'_, AppState>,
+    request: AddTestCaseRequest,
+) -> Result<TestCase, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.add_test_case(request).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_test_cases_for_problem(
+    state: State<'_, AppState>,
+    problem_id: String,
+) -> Result<Vec<TestCase>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_test_cases_for_problem(&problem_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_test_case(
+    state: State<'_, AppState>,
+    test_case_id: String,
+) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.delete_test_case(&test_case_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn run_card_tests(
+    state: State<'_, AppState>,
+    card_id: String,
+) -> Result<Vec<TestResult>, String> {
+    let (code, language, problem_id) = {
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        let card = db
+            .get_card_by_id(&card_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Card '{}' not found", card_id))?;
+        let code = card
+            .code
+            .ok_or_else(|| "Card has no code to run".to_string())?;
+        (code, card.language, card.problem_id)
+    };
+
+    let test_cases = {
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        db.get_test_cases_for_problem(&problem_id)
+            .map_err(|e| e.to_string())?
+    };
+
+    let (extension, run_template) = runner_for_language(&language)
+        .ok_or_else(|| format!("No local runner configured for language '{}'", language))?;
+
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("dsa_run_{}.{}", Uuid::new_v4(), extension));
+    std::fs::write(&file_path, &code).map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+    let command_line = run_template.replace("{file}", &file_path.to_string_lossy());
+
+    let mut results = Vec::with_capacity(test_cases.len());
+    for test_case in &test_cases {
+        let result = run_one_test(&command_line, test_case)?;
+        results.push(result);
+    }
+
+    let _ = std::fs::remove_file(&file_path);
+    let _ = std::fs::remove_file(format!("{}.out", file_path.to_string_lossy()));
+
+    Ok(results)
+}
+
+fn run_one_test(command_line: &str, test_case: &TestCase) -> Result<TestResult, String> {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(command_line)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // `sh -c` itself exits as soon as a compound template's (`rustc ... &&
+    // {file}.out`) real work finishes, but on timeout it's that real child -
+    // not `sh` - that's still running. Giving the shell its own process
+    // group (pgid = its own pid) means every descendant it forks inherits
+    // that group, so killing the group by its negative pid below reaches
+    // the whole tree instead of just the now-dead shell.
+    #[cfg(unix)]
+    {
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to spawn test runner: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(test_case.input.as_bytes());
+    }
+
+    let pid = child.id();
+    let (tx, rx) = mpsc::channel();
+    let start = Instant::now();
+    std::thread::spawn(move || {
+        let output = child.wait_with_output();
+        let _ = tx.send(output);
+    });
+
+    let recv_result = rx.recv_timeout(RUN_TIMEOUT);
+    if recv_result.is_err() {
+        // Timed out - the runner thread is still blocked on wait_with_output,
+        // so kill the whole process group (negative pid) to unblock it
+        // rather than leaking whatever `sh -c` actually forked.
+        #[cfg(unix)]
+        let _ = Command::new("kill").arg("-9").arg(format!("-{}", pid)).status();
+        #[cfg(windows)]
+        let _ = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .status();
+    }
+
+    match recv_result {
+        Ok(Ok(output)) => {
+            let runtime_ms = start.elapsed().as_millis() as u64;
+            let actual_output = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let expected = test_case.expected_output.trim();
+
+            Ok(TestResult {
+                test_case_id: test_case.id.clone(),
+                passed: actual_output == expected,
+                actual_output,
+                expected_output: test_case.expected_output.clone(),
+                runtime_ms,
+                stderr: if stderr.trim().is_empty() {
+                    None
+                } else {
+                    Some(stderr.trim().to_string())
+                },
+                is_hidden: test_case.is_hidden,
+            })
+        }
+        Ok(Err(e)) => Err(format!("Test runner failed: {}", e)),
+        Err(_) => Ok(TestResult {
+            test_case_id: test_case.id.clone(),
+            passed: false,
+            actual_output: String::new(),
+            expected_output: test_case.expected_output.clone(),
+            runtime_ms: RUN_TIMEOUT.as_millis() as u64,
+            stderr: Some(format!(
+                "Timed out after {} seconds",
+                RUN_TIMEOUT.as_secs()
+            )),
+            is_hidden: test_case.is_hidden,
+        }),
+    }
+}
@@ -0,0 +1,64 @@
+// Tauri command wrappers around `data_archive`'s whole-data-directory
+// export/import - the backup/restore counterpart to
+// `commands::library_bundle`'s JSON-only round trip.
+
+use crate::data_archive::{self, Compression};
+use crate::models::*;
+use tauri::State;
+
+/// Mirrors `data_archive::Compression`, minus the internal encoder types,
+/// so the frontend can pick a scheme without depending on this crate's
+/// internal representation.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "scheme", rename_all = "lowercase")]
+pub enum CompressionOption {
+    Xz { level: u32, window_mb: u32 },
+    Zstd { level: i32 },
+}
+
+impl From<CompressionOption> for Compression {
+    fn from(option: CompressionOption) -> Self {
+        match option {
+            CompressionOption::Xz { level, window_mb } => Compression::Xz { level, window_mb },
+            CompressionOption::Zstd { level } => Compression::Zstd { level },
+        }
+    }
+}
+
+/// Archives the current `database.db`, `recordings/`, and `images/` into
+/// `destination` (expected to end in `.tar.xz` or `.tar.zst` - see
+/// `data_archive::import_data_directory`'s note on inferring the
+/// decompressor from that extension). Defaults to a moderate xz window
+/// when `compression` isn't given.
+#[tauri::command]
+pub async fn export_data_archive(
+    state: State<'_, AppState>,
+    destination: String,
+    compression: Option<CompressionOption>,
+) -> Result<(), String> {
+    let resolver = state.path_resolver.clone();
+    let destination = std::path::PathBuf::from(destination);
+    let compression = compression.map(Compression::from).unwrap_or_default();
+
+    // Streams the whole data directory through a compressor, so run it on a
+    // blocking task instead of stalling the async command on that I/O.
+    tokio::task::spawn_blocking(move || data_archive::export_data_directory(&resolver, &destination, compression))
+        .await
+        .map_err(|e| format!("Archive export task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Restores `archive_path` into the current `app_data_dir`, rewriting any
+/// `dev-data/`/`app-data/` relative paths it carries to match this
+/// environment. Intended to be run against a fresh install whose data
+/// directory doesn't yet hold a library of its own.
+#[tauri::command]
+pub async fn import_data_archive(state: State<'_, AppState>, archive_path: String) -> Result<(), String> {
+    let resolver = state.path_resolver.clone();
+    let archive_path = std::path::PathBuf::from(archive_path);
+
+    tokio::task::spawn_blocking(move || data_archive::import_data_directory(&archive_path, &resolver))
+        .await
+        .map_err(|e| format!("Archive import task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
@@ -1,5 +1,14 @@
-use tauri::State;
+use tauri::{AppHandle, State};
+use crate::database::encryption::DatabaseError;
+use crate::database::maintenance::{CasMigrationReport, CheckOptions, CheckReport, PruneReport, ReconcileReport};
+use crate::database::schema_validation::SchemaDrift;
+use crate::database::DatabaseManager;
 use crate::models::*;
+use crate::import::segment;
+use crate::import::tokenizer;
+use crate::import::verbatim;
+use crate::import::ParseError;
+use std::ops::Range;
 
 #[tauri::command]
 pub async fn init_database(_state: State<'_, AppState>) -> Result<String, String> {
@@ -32,6 +41,22 @@ pub async fn create_problem(
     db.create_problem(request).map_err(|e| e.to_string())
 }
 
+/// Runs a list of problem/card/tag/image/recording operations atomically -
+/// all succeed together or none are persisted. If an op fails partway
+/// through, the transaction is rolled back but the returned
+/// `BatchRunResult` still reports the results of the ops that would have
+/// succeeded and the index/message of the one that didn't, so the caller
+/// doesn't have to bisect the list to find the offending op. See
+/// `DatabaseManager::apply_batch`.
+#[tauri::command]
+pub async fn apply_batch(
+    state: State<'_, AppState>,
+    ops: Vec<BatchOp>,
+) -> Result<BatchRunResult, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.apply_batch(ops).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_problems(state: State<'_, AppState>) -> Result<Vec<FrontendProblem>, String> {
     eprintln!("🔍 Rust: get_problems called, acquiring database lock...");
@@ -86,17 +111,17 @@ pub async fn update_problem(
 pub async fn delete_problem(
     state: State<'_, AppState>,
     id: String,
-) -> Result<String, String> {
+) -> Result<DeletedProblemPayload, String> {
     eprintln!("🗑️ Rust: delete_problem called for ID: {}", id);
     let mut db = state.db.lock().map_err(|e| {
         eprintln!("❌ Rust: Failed to acquire database lock in delete_problem: {}", e);
         e.to_string()
     })?;
-    
+
     match db.delete_problem(&id) {
-        Ok(()) => {
+        Ok(payload) => {
             eprintln!("✅ Rust: Successfully deleted problem with ID: {}", id);
-            Ok("Problem deleted successfully".to_string())
+            Ok(payload)
         },
         Err(e) => {
             eprintln!("❌ Rust: Failed to delete problem with ID {}: {}", id, e);
@@ -105,6 +130,40 @@ pub async fn delete_problem(
     }
 }
 
+/// Moves a problem into the recycle bin instead of deleting it outright -
+/// see `DatabaseManager::soft_delete_problem`.
+#[tauri::command]
+pub async fn soft_delete_problem(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.soft_delete_problem(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_problem(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.restore_problem(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_deleted_problems(state: State<'_, AppState>) -> Result<Vec<FrontendProblem>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.list_deleted_problems().map_err(|e| e.to_string())
+}
+
+/// Hard-deletes (files included) every recycle-bin problem soft-deleted
+/// before `cutoff_rfc3339`, returning what was purged.
+#[tauri::command]
+pub async fn purge_deleted_before(
+    state: State<'_, AppState>,
+    cutoff_rfc3339: String,
+) -> Result<Vec<DeletedProblemPayload>, String> {
+    let cutoff = cutoff_rfc3339
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .map_err(|e| format!("Invalid cutoff timestamp: {}", e))?;
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.purge_deleted_before(cutoff).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn create_card(
     state: State<'_, AppState>,
@@ -166,6 +225,44 @@ pub async fn delete_card(
     }
 }
 
+#[tauri::command]
+pub async fn get_problem_history(
+    state: State<'_, AppState>,
+    problem_id: String,
+) -> Result<Vec<ProblemHistoryEntry>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_problem_history(&problem_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_card_history(
+    state: State<'_, AppState>,
+    card_id: String,
+) -> Result<Vec<CardHistoryEntry>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_card_history(&card_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_card(
+    state: State<'_, AppState>,
+    history_id: i64,
+) -> Result<Card, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.restore_card(history_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_card_field(
+    state: State<'_, AppState>,
+    card_id: String,
+    field: String,
+    history_id: i64,
+) -> Result<Card, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.restore_card_field(&card_id, &field, history_id).map_err(|e| e.to_string())
+}
+
 // Database analysis commands
 #[tauri::command]
 pub async fn get_database_stats(state: State<'_, AppState>) -> Result<DatabaseStats, String> {
@@ -173,6 +270,276 @@ pub async fn get_database_stats(state: State<'_, AppState>) -> Result<DatabaseSt
     db.get_database_stats().map_err(|e| e.to_string())
 }
 
+// Administrative command to drop/recreate secondary indexes and run ANALYZE/VACUUM,
+// for when a large problem import has left the search/tag hot paths slow.
+#[tauri::command]
+pub async fn rebuild_indexes(state: State<'_, AppState>) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.rebuild_indexes().map_err(|e| e.to_string())
+}
+
+/// Creates a single ad-hoc index, e.g. for a maintenance script tuning a
+/// query pattern that isn't already covered by `ensure_indexes`.
+#[tauri::command]
+pub async fn create_index(
+    state: State<'_, AppState>,
+    table: String,
+    name: String,
+    columns: String,
+) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.create_index(&table, &name, &columns).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn drop_index(state: State<'_, AppState>, name: String) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.drop_index(&name).map_err(|e| e.to_string())
+}
+
+/// (Re-)installs every managed trigger (reciprocal `problem_relations`
+/// edges, `tags.usage_count`, `image_blobs.ref_count`, session durations and
+/// card totals among them), and backfills `usage_count` and `ref_count`.
+/// Already run automatically on every connect.
+#[tauri::command]
+pub async fn install_triggers(state: State<'_, AppState>) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.install_triggers().map_err(|e| e.to_string())
+}
+
+/// Drops the managed triggers, for inspecting/debugging the relation and
+/// tag-count invariants without the storage layer enforcing them.
+#[tauri::command]
+pub async fn drop_triggers(state: State<'_, AppState>) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.drop_triggers().map_err(|e| e.to_string())
+}
+
+/// Lists which managed triggers are currently installed.
+#[tauri::command]
+pub async fn list_triggers(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.list_triggers().map_err(|e| e.to_string())
+}
+
+// Fsck-style maintenance pass for orphaned recording/image rows and files,
+// stale cached card durations, and dangling connections. Pass a default
+// `CheckOptions` to only report; set individual flags to also repair.
+#[tauri::command]
+pub async fn check_and_repair_database(state: State<'_, AppState>, opts: CheckOptions) -> Result<CheckReport, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.check_and_repair(opts).map_err(|e| e.to_string())
+}
+
+/// Runs a dry-run (`CheckOptions::default()`) `check_and_repair` once in the
+/// background so orphaned/missing recordings surface in the log on every
+/// launch, not just when someone thinks to run `check_and_repair_database`
+/// by hand. Modeled on `commands::jobs::spawn_worker` - a detached thread
+/// given its own clone of the shared `db` - but one-shot rather than a
+/// polling loop, since this pass is already exhaustive and re-running it
+/// without anything having changed would just repeat the same report.
+pub fn spawn_startup_reconciliation_scan(db: std::sync::Arc<std::sync::Mutex<DatabaseManager>>) {
+    std::thread::spawn(move || {
+        let report = match db.lock() {
+            Ok(mut db) => db.check_and_repair(CheckOptions::default()),
+            Err(e) => {
+                eprintln!("⚠️ Startup reconciliation scan: database mutex poisoned: {}", e);
+                return;
+            }
+        };
+
+        match report {
+            Ok(report) if report.is_clean() => {
+                eprintln!("DSA Learning App: Startup reconciliation scan found nothing to report");
+            }
+            Ok(report) => {
+                eprintln!(
+                    "⚠️ DSA Learning App: Startup reconciliation scan found {} orphan recording row(s), {} orphan image row(s), {} orphan recording file(s), {} orphan image file(s), {} duration mismatch(es), {} dangling connection(s) - run check_and_repair_database to repair",
+                    report.orphan_recording_rows.len(),
+                    report.orphan_image_rows.len(),
+                    report.orphan_recording_files.len(),
+                    report.orphan_image_files.len(),
+                    report.duration_mismatches.len(),
+                    report.dangling_connections.len(),
+                );
+            }
+            Err(e) => eprintln!("⚠️ Startup reconciliation scan: check_and_repair failed: {}", e),
+        }
+    });
+}
+
+/// Replaces the in-memory storage roots recordings/images are resolved
+/// against - `roots` is a list of `(root_id, directory, priority)` triples;
+/// a stored path not already prefixed with a matching `root_id:` falls back
+/// to whichever configured root has it, tried highest-`priority` first. This
+/// only affects the current session - use `add_storage_root`/`remove_storage_root`
+/// for a change that survives a restart.
+#[tauri::command]
+pub async fn configure_storage_roots(state: State<'_, AppState>, roots: Vec<(String, String, i32)>) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    let roots = roots
+        .into_iter()
+        .map(|(id, path, priority)| crate::database::storage_roots::StorageRoot { id, path: std::path::PathBuf::from(path), priority })
+        .collect();
+    db.set_storage_roots(crate::database::storage_roots::StorageRoots::new(roots));
+    Ok(())
+}
+
+/// Registers a new storage root and persists it to the `storage_roots`
+/// table, so it's picked back up on the next restart without another
+/// `configure_storage_roots` call (see `DatabaseManager::reload_storage_roots`).
+#[tauri::command]
+pub async fn add_storage_root(state: State<'_, AppState>, id: String, path: String, priority: i32) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.add_storage_root(&id, &path, priority).map_err(|e| e.to_string())
+}
+
+/// Lists the persisted storage roots, highest priority first.
+#[tauri::command]
+pub async fn list_storage_roots(state: State<'_, AppState>) -> Result<Vec<crate::database::storage_roots::StorageRootRow>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.list_storage_roots().map_err(|e| e.to_string())
+}
+
+/// Unregisters a storage root. Refused if it still holds recordings or
+/// images unless `force` is true, since those rows' stored paths would
+/// otherwise become unresolvable - run `verify_storage_roots` afterward (or
+/// force the removal) to see what that left behind.
+#[tauri::command]
+pub async fn remove_storage_root(state: State<'_, AppState>, id: String, force: bool) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.remove_storage_root(&id, force).map_err(|e| e.to_string())
+}
+
+/// Walks every `card_images` row and confirms its backing file is actually
+/// present in the root it claims to live in, surfacing the silent breakage
+/// that used to just print "file doesn't exist" - once images can live on
+/// removable or secondary drives, the UI needs an explicit integrity check
+/// rather than discovering a missing file the next time someone opens a card.
+#[tauri::command]
+pub async fn verify_storage_roots(state: State<'_, AppState>) -> Result<Vec<StorageRootIntegrityIssue>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.verify_storage_roots().map_err(|e| e.to_string())
+}
+
+/// Sweeps recordings older than `default_expiration_days` (unless a problem
+/// overrides its own retention or the recording is marked `retain_forever`),
+/// deleting both the row and its file. See `DatabaseManager::prune_recordings`.
+#[tauri::command]
+pub async fn prune_recordings(state: State<'_, AppState>, default_expiration_days: i64) -> Result<PruneReport, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.prune_recordings(chrono::Duration::days(default_expiration_days)).map_err(|e| e.to_string())
+}
+
+/// Permanently deletes orphaned recording/image files under `roots` and
+/// reports how much disk space that reclaimed. Unlike
+/// `check_and_repair_database`'s `trash_orphan_files` option, there is no
+/// dry-run here - run that first if you want to see what this would remove.
+#[tauri::command]
+pub async fn reconcile_media(state: State<'_, AppState>, roots: Vec<String>) -> Result<ReconcileReport, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    let roots: Vec<std::path::PathBuf> = roots.into_iter().map(std::path::PathBuf::from).collect();
+    db.reconcile_media(&roots).map_err(|e| e.to_string())
+}
+
+/// One-time backfill that moves every `problem_images` row saved before
+/// content-addressed storage into the shared `images/cas/` directory under
+/// its BLAKE3 content hash, deduplicating identical files along the way. See
+/// `DatabaseManager::migrate_images_to_cas`. Not run at startup - this
+/// rewrites every legacy image file on disk, so it's triggered explicitly.
+#[tauri::command]
+pub async fn migrate_images_to_cas(state: State<'_, AppState>) -> Result<CasMigrationReport, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.migrate_images_to_cas().map_err(|e| e.to_string())
+}
+
+// Opt-in at-rest encryption (see `database::encryption`). Errors map
+// `DatabaseError::BadPassphrase` to the distinct "BAD_PASSPHRASE" string so
+// the UI can tell a wrong passphrase apart from any other failure and prompt
+// for re-entry instead of surfacing a raw error message.
+fn map_database_error(e: anyhow::Error) -> String {
+    match e.downcast_ref::<DatabaseError>() {
+        Some(DatabaseError::BadPassphrase) => "BAD_PASSPHRASE".to_string(),
+        None => e.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn open_encrypted_database(state: State<'_, AppState>, passphrase: String) -> Result<(), String> {
+    let app_data_dir = state.path_resolver.get_app_data_dir().clone();
+    let new_db = DatabaseManager::new_encrypted_with_path(app_data_dir, &passphrase)
+        .await
+        .map_err(map_database_error)?;
+
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    *db = new_db;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn change_database_passphrase(
+    state: State<'_, AppState>,
+    old_passphrase: Option<String>,
+    new_passphrase: String,
+) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.set_passphrase(old_passphrase.as_deref(), &new_passphrase)
+        .map_err(map_database_error)
+}
+
+#[tauri::command]
+pub async fn encrypt_database(state: State<'_, AppState>, passphrase: String) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.encrypt_in_place(&passphrase).map_err(map_database_error)
+}
+
+// Migration registry administrative commands
+
+#[tauri::command]
+pub async fn get_schema_version(state: State<'_, AppState>) -> Result<i64, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.current_version().map_err(|e| e.to_string())
+}
+
+/// The highest migration version this build knows how to run. The frontend
+/// can warn on downgrade by comparing this against `get_schema_version`: if
+/// the database's recorded version is higher, it was already migrated by a
+/// newer build than the one currently running.
+#[tauri::command]
+pub async fn get_latest_known_schema_version(state: State<'_, AppState>) -> Result<i64, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    Ok(db.latest_known_version())
+}
+
+// Runs the same pending-migration pass every connect already runs
+// automatically - useful for triggering it on demand without reopening the
+// database connection (e.g. after restoring an older backup).
+#[tauri::command]
+pub async fn migrate_database_to_latest(state: State<'_, AppState>) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.migrate_to_latest().map_err(|e| e.to_string())
+}
+
+// For developers testing a migration locally - steps the schema backward to
+// `target_version` without deleting `database.db`.
+#[tauri::command]
+pub async fn rollback_schema_to(state: State<'_, AppState>, target_version: i64) -> Result<(), String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.rollback_to(target_version).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_database_schema(state: State<'_, AppState>) -> Result<SchemaDrift, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.validate_schema().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repair_database_schema(state: State<'_, AppState>) -> Result<SchemaDrift, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.repair_schema().map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_card_hierarchy(state: State<'_, AppState>) -> Result<Vec<CardHierarchy>, String> {
     let db = state.db.lock().map_err(|e| e.to_string())?;
@@ -291,14 +658,50 @@ pub async fn remove_tag_from_problems(
     Ok(())
 }
 
-// Search commands for Name/Topic/Tags search system
+/// A problem matched by `search_problems`, paired with the relevance score
+/// it earned (lower is better - BM25 rank for FTS matches, edit distance
+/// for typo-tolerant fallback matches).
+#[derive(serde::Serialize)]
+pub struct ScoredProblemResponse {
+    pub problem: FrontendProblem,
+    pub score: f64,
+}
+
+// Unified full-text search, replacing ad-hoc LIKE queries with FTS5 BM25
+// ranking and a trigram+Levenshtein typo-tolerant fallback. See
+// `database::search` for the indexing/ranking implementation.
+#[tauri::command]
+pub async fn search_problems(
+    state: State<'_, AppState>,
+    query: String,
+    fields: Option<Vec<String>>,
+    limit: Option<i32>,
+) -> Result<Vec<ScoredProblemResponse>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let results = db
+        .search_problems(&query, &fields.unwrap_or_default(), limit.unwrap_or(50))
+        .map_err(|e| e.to_string())?;
+    Ok(results
+        .into_iter()
+        .map(|r| ScoredProblemResponse {
+            problem: r.problem,
+            score: r.score,
+        })
+        .collect())
+}
+
+// Search commands for Name/Topic/Tags search system - thin wrappers kept for
+// the existing frontend call sites, now backed by the FTS index above.
 #[tauri::command]
 pub async fn search_problems_by_name(
     state: State<'_, AppState>,
     query: String,
 ) -> Result<Vec<FrontendProblem>, String> {
     let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.search_problems_by_title(&query, 50, None).map_err(|e| e.to_string())
+    let fields = vec!["title".to_string()];
+    db.search_problems(&query, &fields, 50)
+        .map(|results| results.into_iter().map(|r| r.problem).collect())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -307,7 +710,10 @@ pub async fn search_problems_by_topic(
     query: String,
 ) -> Result<Vec<FrontendProblem>, String> {
     let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.search_problems_by_topic(&query).map_err(|e| e.to_string())
+    let fields = vec!["topic".to_string()];
+    db.search_problems(&query, &fields, 50)
+        .map(|results| results.into_iter().map(|r| r.problem).collect())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -316,7 +722,10 @@ pub async fn search_problems_by_tags(
     query: String,
 ) -> Result<Vec<FrontendProblem>, String> {
     let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.search_problems_by_tags(&query).map_err(|e| e.to_string())
+    let fields = vec!["tags".to_string()];
+    db.search_problems(&query, &fields, 50)
+        .map(|results| results.into_iter().map(|r| r.problem).collect())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -384,9 +793,58 @@ pub async fn get_related_problems(
     db.get_related_problems(&problem_id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_problems_referencing(
+    state: State<'_, AppState>,
+    problem_id: String,
+) -> Result<Vec<FrontendProblem>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_problems_referencing(&problem_id).map_err(|e| e.to_string())
+}
+
+/// Breadth-first expansion of the relation graph, e.g. "everything within 2
+/// hops of Two Sum", each candidate annotated with its hop distance.
+#[tauri::command]
+pub async fn get_related_problems_within(
+    state: State<'_, AppState>,
+    problem_id: String,
+    depth: i32,
+) -> Result<Vec<RelatedProblemHop>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_related_problems_within(&problem_id, depth, None)
+        .map_err(|e| e.to_string())
+}
+
+/// Shortest chain of relations connecting two problems, e.g. for "how do I
+/// get from A to B" learning-path prompts. Empty if disconnected.
+#[tauri::command]
+pub async fn shortest_relation_path(
+    state: State<'_, AppState>,
+    from_id: String,
+    to_id: String,
+) -> Result<Vec<FrontendProblem>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.shortest_relation_path(&from_id, &to_id).map_err(|e| e.to_string())
+}
+
+/// Recommends related problems ranked by how many distinct shortest paths
+/// reach them within `depth` hops, so strongly-connected topics surface first.
+#[tauri::command]
+pub async fn recommend_related_problems(
+    state: State<'_, AppState>,
+    problem_id: String,
+    depth: i32,
+    limit: i32,
+) -> Result<Vec<RecommendedProblem>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.recommend_related_problems(&problem_id, depth, limit)
+        .map_err(|e| e.to_string())
+}
+
 // TXT Import system
 #[tauri::command]
 pub async fn import_problems_from_txt(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     content: String,
 ) -> Result<ImportResult, String> {
@@ -402,36 +860,45 @@ pub async fn import_problems_from_txt(
         errors: Vec::new(),
     };
     
-    // Parse the TXT content
+    // Parse the TXT content. Parsing never bails outright - a malformed
+    // field or a failed validation only drops the one offending problem,
+    // surfaced as a diagnostic with its exact line/column below.
     eprintln!("🔍 Rust: Starting TXT content parsing...");
-    let problems = match parse_txt_content(&content) {
-        Ok(problems) => {
-            eprintln!("✅ Rust: Successfully parsed {} problems", problems.len());
-            for (i, problem) in problems.iter().enumerate() {
-                eprintln!("📋 Rust: Problem {}: '{}' ({})", i + 1, problem.title, problem.difficulty);
-            }
-            problems
-        },
-        Err(e) => {
-            eprintln!("❌ Rust: Failed to parse TXT content: {}", e);
-            result.success = false;
-            result.errors.push(ImportError {
-                line: 0,
-                field: None,
-                message: format!("Failed to parse TXT content: {}", e),
-                severity: "error".to_string(),
-            });
-            return Ok(result);
-        }
-    };
-    
+    let (problems, diagnostics) = parse_txt_content(&content);
+    eprintln!("✅ Rust: Parsed {} problems with {} diagnostics", problems.len(), diagnostics.len());
+    for (i, problem) in problems.iter().enumerate() {
+        eprintln!("📋 Rust: Problem {}: '{}' ({})", i + 1, problem.title, problem.difficulty);
+    }
+    for diagnostic in &diagnostics {
+        eprintln!("⚠️ Rust: {}:{}: {}", diagnostic.line, diagnostic.col, diagnostic.message);
+        result.error_count += 1;
+        result.errors.push(ImportError {
+            line: diagnostic.line as i32,
+            field: None,
+            message: diagnostic.message.clone(),
+            severity: "error".to_string(),
+        });
+    }
+
     eprintln!("🔄 Rust: Starting problem import process...");
     
-    // Check for duplicates and import problems
+    // Check for duplicates and import problems. A problem that failed
+    // validation was already reported as a diagnostic above - skip
+    // creating it here rather than handing the database an empty title
+    // or an invalid difficulty.
     for (index, problem) in problems.iter().enumerate() {
         let line_number = (index + 1) as i32;
         eprintln!("🔍 Rust: Processing problem {}: '{}'", line_number, problem.title);
-        
+
+        if problem.title.is_empty()
+            || problem.description.is_empty()
+            || !["Easy", "Medium", "Hard"].contains(&problem.difficulty.as_str())
+        {
+            eprintln!("⏭️ Rust: Skipping invalid problem {} (see diagnostics)", line_number);
+            result.skipped_count += 1;
+            continue;
+        }
+
         // Check if problem already exists by title
         match db.search_problems_by_title(&problem.title, 1, None) {
             Ok(existing) if !existing.is_empty() => {
@@ -487,8 +954,14 @@ pub async fn import_problems_from_txt(
                 });
             }
         }
+
+        crate::events::emit(&app_handle, crate::events::AppEvent::ImportProgress {
+            imported: result.imported_count,
+            skipped: result.skipped_count,
+            errors: result.error_count,
+        });
     }
-    
+
     // Update overall success status
     result.success = result.error_count == 0;
     
@@ -514,90 +987,286 @@ pub async fn import_problems_from_txt(
     Ok(result)
 }
 
-// Helper function to parse TXT content
-fn parse_txt_content(content: &str) -> Result<Vec<ParsedProblem>, String> {
-    eprintln!("🔄 Rust: Starting detailed TXT parsing...");
+// JSON bulk import system - accepts LeetCode's `stat_status_pairs` problem-list
+// export shape, so users can seed problem stubs from an exported list instead
+// of hand-writing TXT.
+#[derive(serde::Deserialize)]
+struct LeetCodeProblemList {
+    stat_status_pairs: Vec<LeetCodeStatStatusPair>,
+}
+
+#[derive(serde::Deserialize)]
+struct LeetCodeStatStatusPair {
+    stat: LeetCodeStat,
+    difficulty: LeetCodeDifficulty,
+    paid_only: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct LeetCodeStat {
+    #[serde(rename = "question__title")]
+    question_title: String,
+    #[serde(rename = "question__title_slug")]
+    question_title_slug: String,
+    #[allow(dead_code)]
+    frontend_question_id: Option<i32>,
+}
+
+#[derive(serde::Deserialize)]
+struct LeetCodeDifficulty {
+    level: i32,
+}
+
+fn map_difficulty_level(level: i32) -> String {
+    match level {
+        1 => "Easy",
+        2 => "Medium",
+        3 => "Hard",
+        _ => "Medium",
+    }
+    .to_string()
+}
+
+#[tauri::command]
+pub async fn import_problems_from_json(
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<ImportResult, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+
+    let mut result = ImportResult {
+        success: true,
+        imported_count: 0,
+        skipped_count: 0,
+        error_count: 0,
+        duplicates: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let problem_list: LeetCodeProblemList = match serde_json::from_str(&content) {
+        Ok(list) => list,
+        Err(e) => {
+            result.success = false;
+            result.errors.push(ImportError {
+                line: 0,
+                field: None,
+                message: format!("Failed to parse JSON content: {}", e),
+                severity: "error".to_string(),
+            });
+            return Ok(result);
+        }
+    };
+
+    for (index, pair) in problem_list.stat_status_pairs.iter().enumerate() {
+        let line_number = (index + 1) as i32;
+        let title = pair.stat.question_title.clone();
+
+        if pair.paid_only {
+            result.skipped_count += 1;
+            result.errors.push(ImportError {
+                line: line_number,
+                field: Some("paid_only".to_string()),
+                message: format!("'{}' is a paid-only problem, skipping", title),
+                severity: "skipped".to_string(),
+            });
+            continue;
+        }
+
+        match db.search_problems_by_title(&title, 1, None) {
+            Ok(existing) if !existing.is_empty() => {
+                result.skipped_count += 1;
+                result.duplicates.push(title);
+                continue;
+            }
+            Err(e) => {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    line: line_number,
+                    field: Some("title".to_string()),
+                    message: format!("Failed to check for duplicate: {}", e),
+                    severity: "error".to_string(),
+                });
+                continue;
+            }
+            _ => {}
+        }
+
+        let request = CreateProblemRequest {
+            title: title.clone(),
+            description: String::new(),
+            difficulty: map_difficulty_level(pair.difficulty.level),
+            topic: Vec::new(),
+            leetcode_url: Some(format!(
+                "https://leetcode.com/problems/{}/",
+                pair.stat.question_title_slug
+            )),
+            constraints: Vec::new(),
+            hints: Vec::new(),
+            related_problem_ids: None,
+        };
+
+        match db.create_problem(request) {
+            Ok(_) => {
+                result.imported_count += 1;
+            }
+            Err(e) => {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    line: line_number,
+                    field: None,
+                    message: format!("Failed to create problem: {}", e),
+                    severity: "error".to_string(),
+                });
+            }
+        }
+    }
+
+    result.success = result.error_count == 0;
+    Ok(result)
+}
+
+// Helper function to parse TXT content. A segmentation pass
+// (`import::segment`) first cuts the document into one byte range per
+// problem - at a `# Heading`, a `---` separator, or a `title:` field
+// reappearing - so a single file can hold a whole batch of problems.
+// Each segment is then field-parsed independently: a bad field or failed
+// validation in one segment only drops that problem, reported as a
+// `ParseError` anchored to the segment's own source range, rather than
+// aborting the whole batch.
+fn parse_txt_content(content: &str) -> (Vec<ParsedProblem>, Vec<ParseError>) {
     let mut problems = Vec::new();
-    let mut current_problem: Option<ParsedProblem> = None;
+    let mut diagnostics = Vec::new();
+
+    for range in segment::segment(content) {
+        let segment_source = &content[range.clone()];
+        let (parsed, mut errors) = parse_segment(segment_source, range.start, content);
+
+        if let Some(problem) = parsed {
+            let index = problems.len();
+            let mut validation_errors = validate_problem(&problem, range.clone(), content);
+            for error in &mut validation_errors {
+                error.message = format!("Problem {}: {}", index + 1, error.message);
+            }
+            errors.extend(validation_errors);
+            problems.push(problem);
+        }
+
+        diagnostics.extend(errors);
+    }
+
+    (problems, diagnostics)
+}
+
+// Field-parses a single segment (one problem's worth of TXT) produced by
+// `import::segment::segment`. `base_offset` is the segment's start within
+// `full_source`, so every `ParseError` this emits is anchored to the real
+// position in the original import text rather than the segment-local one.
+fn parse_segment(
+    segment_source: &str,
+    base_offset: usize,
+    full_source: &str,
+) -> (Option<ParsedProblem>, Vec<ParseError>) {
+    let mut diagnostics = Vec::new();
+    let mut problem = ParsedProblem::new();
+    let mut saw_any_field = false;
     let mut current_field: Option<String> = None;
+    let mut current_field_span: Range<usize> = base_offset..base_offset;
     let mut current_value = String::new();
-    
-    let total_lines = content.lines().count();
-    eprintln!("📝 Rust: Processing {} lines", total_lines);
-    
-    for (line_num, line) in content.lines().enumerate() {
-        let line = line.trim();
-        
-        // Skip empty lines
+    let mut open_block: Option<verbatim::BlockOpen> = None;
+    let mut block_content = String::new();
+
+    let mut offset = base_offset;
+    for raw_line in segment_source.split_inclusive('\n') {
+        let line_span = offset..offset + raw_line.trim_end_matches(['\n', '\r']).len();
+        let literal_line = raw_line.trim_end_matches(['\n', '\r']);
+        offset += raw_line.len();
+        let line = raw_line.trim();
+
+        if let Some(open) = &open_block {
+            if verbatim::is_close(line, open) {
+                let language = match open {
+                    verbatim::BlockOpen::Fence { language, .. } => language.clone(),
+                    verbatim::BlockOpen::OrgExample => None,
+                };
+                problem.code_blocks.push(CodeBlock {
+                    language,
+                    content: block_content.clone(),
+                });
+                open_block = None;
+            } else {
+                if !block_content.is_empty() {
+                    block_content.push('\n');
+                }
+                block_content.push_str(literal_line);
+            }
+            continue;
+        }
+
         if line.is_empty() {
             continue;
         }
-        
-        eprintln!("📄 Rust: Line {}: '{}'", line_num + 1, line);
-        
-        // Check if this is a field header
-        if let Some((field_name, immediate_value)) = parse_field_header(line) {
-            eprintln!("🏷️ Rust: Found field header: '{}' with immediate value: '{}'", field_name, immediate_value);
-            
-            // Save previous field if we have one
-            if let (Some(ref mut problem), Some(ref field)) = (&mut current_problem, &current_field) {
-                eprintln!("💾 Rust: Saving previous field '{}' with value: '{}'", field, current_value.trim());
-                set_problem_field(problem, field, &current_value.trim())?;
+
+        if current_field.is_some() {
+            if let Some(open) = verbatim::detect_open(line) {
+                open_block = Some(open);
+                block_content.clear();
+                continue;
             }
-            
-            // Start new problem if this is the title field
-            if field_name == "title" {
-                if let Some(problem) = current_problem.take() {
-                    eprintln!("✅ Rust: Completed problem: '{}'", problem.title);
-                    problems.push(problem);
+        }
+
+        if let Some((field_name, immediate_value)) = parse_field_header(line) {
+            if let Some(ref field) = current_field {
+                if let Err(e) = set_problem_field(
+                    &mut problem,
+                    field,
+                    current_value.trim(),
+                    current_field_span.clone(),
+                    full_source,
+                ) {
+                    diagnostics.push(e);
                 }
-                eprintln!("🆕 Rust: Starting new problem");
-                current_problem = Some(ParsedProblem::new());
             }
-            
+
+            saw_any_field = true;
             current_field = Some(field_name.clone());
-            
-            // Handle immediate value or start fresh for multi-line content
+            current_field_span = line_span;
+
             if !immediate_value.is_empty() {
-                eprintln!("📝 Rust: Using immediate value for field '{}': '{}'", field_name, immediate_value);
                 current_value = immediate_value;
             } else {
-                eprintln!("📝 Rust: Starting multi-line content for field '{}'", field_name);
                 current_value.clear();
             }
         } else {
-            eprintln!("📝 Rust: Content line for field '{:?}': '{}'", current_field, line);
-            // This is content for the current field
             if !current_value.is_empty() {
                 current_value.push('\n');
             }
             current_value.push_str(line);
+            current_field_span = current_field_span.start..line_span.end;
         }
     }
-    
-    // Save the last field and problem
-    if let (Some(ref mut problem), Some(ref field)) = (&mut current_problem, &current_field) {
-        eprintln!("💾 Rust: Saving final field '{}' with value: '{}'", field, current_value.trim());
-        set_problem_field(problem, field, &current_value.trim())?;
-    }
-    if let Some(problem) = current_problem {
-        eprintln!("✅ Rust: Completed final problem: '{}'", problem.title);
-        problems.push(problem);
-    }
-    
-    eprintln!("📋 Rust: Finished parsing, found {} problems total", problems.len());
-    
-    // Validate all problems
-    for (index, problem) in problems.iter().enumerate() {
-        eprintln!("🔍 Rust: Validating problem {}: '{}'", index + 1, problem.title);
-        if let Err(e) = validate_problem(problem) {
-            eprintln!("❌ Rust: Problem {} validation failed: {}", index + 1, e);
-            return Err(format!("Problem {} validation failed: {}", index + 1, e));
+
+    if let Some(ref field) = current_field {
+        if let Err(e) = set_problem_field(
+            &mut problem,
+            field,
+            current_value.trim(),
+            current_field_span.clone(),
+            full_source,
+        ) {
+            diagnostics.push(e);
         }
-        eprintln!("✅ Rust: Problem {} validation passed", index + 1);
     }
-    
-    Ok(problems)
+
+    if !saw_any_field {
+        diagnostics.push(ParseError::new(
+            full_source,
+            base_offset..offset,
+            "Segment has no recognizable fields",
+        ));
+        return (None, diagnostics);
+    }
+
+    (Some(problem), diagnostics)
 }
 
 // Helper function to parse field headers - now supports both "field:" and "field: value" formats
@@ -645,18 +1314,28 @@ fn parse_field_header(line: &str) -> Option<(String, String)> {
     }
 }
 
-// Helper function to set problem field
-fn set_problem_field(problem: &mut ParsedProblem, field: &str, value: &str) -> Result<(), String> {
+// Helper function to set problem field. `span` is the byte range from the
+// field header line that introduced `value` through the value's last
+// continuation line, used to anchor any resulting `ParseError` at the
+// field's position (starting at its header line) rather than just a bare
+// message.
+fn set_problem_field(
+    problem: &mut ParsedProblem,
+    field: &str,
+    value: &str,
+    span: Range<usize>,
+    source: &str,
+) -> Result<(), ParseError> {
     match field {
         "title" => {
             if value.is_empty() {
-                return Err("Title cannot be empty".to_string());
+                return Err(ParseError::new(source, span, "Title cannot be empty"));
             }
             problem.title = value.to_string();
         },
         "description" => {
             if value.is_empty() && problem.description.is_empty() {
-                return Err("Description cannot be empty".to_string());
+                return Err(ParseError::new(source, span, "Description cannot be empty"));
             }
             // Append to description if it already has content (for examples)
             if !problem.description.is_empty() && !value.is_empty() {
@@ -669,12 +1348,16 @@ fn set_problem_field(problem: &mut ParsedProblem, field: &str, value: &str) -> R
         "difficulty" => {
             let difficulty = value.to_string();
             if !["Easy", "Medium", "Hard"].contains(&difficulty.as_str()) {
-                return Err(format!("Invalid difficulty: {}. Must be Easy, Medium, or Hard", difficulty));
+                return Err(ParseError::new(
+                    source,
+                    span,
+                    format!("Invalid difficulty: {}. Must be Easy, Medium, or Hard", difficulty),
+                ));
             }
             problem.difficulty = difficulty;
         },
         "topics" => {
-            problem.topics = parse_list_field(value);
+            problem.topics = parse_list_field(value, span.clone(), source)?;
         },
         "leetcode_url" => {
             if !value.is_empty() {
@@ -682,43 +1365,32 @@ fn set_problem_field(problem: &mut ParsedProblem, field: &str, value: &str) -> R
             }
         },
         "constraints" => {
-            problem.constraints = parse_list_field(value);
+            problem.constraints = parse_list_field(value, span.clone(), source)?;
         },
         "hints" => {
-            problem.hints = parse_list_field(value);
+            problem.hints = parse_list_field(value, span.clone(), source)?;
         },
         "tags" => {
             // Handle tags as additional topics for now
-            problem.topics.extend(parse_list_field(value));
+            problem.topics.extend(parse_list_field(value, span.clone(), source)?);
         },
-        _ => return Err(format!("Unknown field: {}", field)),
+        _ => return Err(ParseError::new(source, span, format!("Unknown field: {}", field))),
     }
     Ok(())
 }
 
-// Helper function to parse list fields (comma-separated or line-separated)
-fn parse_list_field(value: &str) -> Vec<String> {
+// Helper function to parse list fields (comma-separated or line-separated).
+// Returns a diagnostic (rather than silently yielding an empty list) when a
+// non-empty value fails to produce any items, since that almost always
+// means the bullet/comma detection missed an unusual format.
+fn parse_list_field(value: &str, span: Range<usize>, source: &str) -> Result<Vec<String>, ParseError> {
     // Smart detection: check if it looks like bullet points vs comma-separated
     let has_bullet_indicators = value.lines().any(|line| {
         let trimmed = line.trim();
-        // Check for dash bullets or numbered items
-        let dash_chars = ['-', '–', '—', '−', '∙', '•', '◦', '▪', '▫', '*'];
-        for &dash in &dash_chars {
-            if trimmed.starts_with(&format!("{} ", dash)) || 
-               (trimmed.starts_with(dash) && trimmed.len() > 1) {
-                return true;
-            }
-        }
-        // Check for numbered items like "1. ", "2. "
-        if let Some(pos) = trimmed.find('.') {
-            if pos > 0 && trimmed[..pos].chars().all(|c| c.is_ascii_digit()) {
-                return true;
-            }
-        }
-        false
+        tokenizer::bullet_prefix_len(trimmed).is_some() || tokenizer::is_numbered_item(trimmed)
     });
-    
-    if has_bullet_indicators {
+
+    let items = if has_bullet_indicators {
         parse_bullet_list(value)
     } else if value.contains(',') && !value.contains('\n') {
         // Only treat as comma-separated if it's single-line AND contains commas
@@ -728,83 +1400,68 @@ fn parse_list_field(value: &str) -> Vec<String> {
             .collect()
     } else {
         parse_bullet_list(value)
+    };
+
+    if items.is_empty() && !value.trim().is_empty() {
+        return Err(ParseError::new(
+            source,
+            span,
+            format!("Could not parse any items from list value: '{}'", value.trim()),
+        ));
     }
+
+    Ok(items)
 }
 
-// Helper function to parse bullet list with proper dash removal
+// Helper function to parse bullet list with proper dash removal. Line
+// classification (bullet vs numbered vs continuation) is delegated to the
+// reusable `import::tokenizer` lexer rather than re-implemented here.
 fn parse_bullet_list(value: &str) -> Vec<String> {
-    let dash_chars = ['-', '–', '—', '−', '∙', '•', '◦', '▪', '▫', '*'];
-    let lines: Vec<&str> = value.lines().collect();
-    let mut items = Vec::new();
-    
-    
-    for (i, line) in lines.iter().enumerate() {
-        let trimmed = normalize_whitespace(line);
-        
-        
+    let tokens = tokenizer::Cursor::new(value).tokenize();
+    let mut items: Vec<String> = Vec::new();
+
+    for token in &tokens {
+        let raw_line = token.text(value);
+        let trimmed = normalize_whitespace(raw_line);
         if trimmed.is_empty() {
             continue;
         }
-        
-        // Check for bullet points
-        let mut found_bullet = false;
-        for &dash in &dash_chars {
-            let dash_with_space = format!("{} ", dash);
-            if trimmed.starts_with(&dash_with_space) {
-                let content = trimmed[2..].trim().to_string();
-                if !content.is_empty() {
-                    items.push(content);
-                }
-                found_bullet = true;
-                break;
-            } else if trimmed.starts_with(dash) && trimmed.len() > 1 {
-                let content = trimmed[1..].trim().to_string();
-                if !content.is_empty() {
-                    items.push(content);
+
+        match token.kind {
+            tokenizer::TokenKind::BulletItem => {
+                if let Some(prefix_len) = tokenizer::bullet_prefix_len(&trimmed) {
+                    let content = trimmed[prefix_len..].trim().to_string();
+                    if !content.is_empty() {
+                        items.push(content);
+                    }
                 }
-                found_bullet = true;
-                break;
             }
-        }
-        
-        // Check for numbered items
-        if !found_bullet && is_numbered_item(&trimmed) {
-            let content = remove_number_prefix(&trimmed);
-            if !content.is_empty() {
-                items.push(content);
+            tokenizer::TokenKind::NumberedItem => {
+                if let Some(prefix_len) = tokenizer::numbered_prefix_len(&trimmed) {
+                    let content = trimmed[prefix_len..].trim().to_string();
+                    if !content.is_empty() {
+                        items.push(content);
+                    }
+                }
             }
-            found_bullet = true;
-        }
-        
-        // If no bullet found and this is the first item, or if it looks like a continuation
-        if !found_bullet {
-            if items.is_empty() {
-                // First line without bullet, treat as first item
-                items.push(trimmed);
-            } else if should_treat_as_continuation(&trimmed, line) {
-                // Append to last item
+            tokenizer::TokenKind::ContinuationLine if !items.is_empty() => {
                 if let Some(last) = items.last_mut() {
                     last.push(' ');
                     last.push_str(&trimmed);
                 }
-            } else {
-                // Treat as separate item
+            }
+            _ => {
                 items.push(trimmed);
             }
         }
     }
-    
+
     // Filter out empty items, items that are too long, and dash-only entries
-    let result = items.into_iter()
+    items
+        .into_iter()
         .map(|item| item.trim().to_string())
-        .filter(|item| {
-            !item.is_empty() && 
-            item.len() <= 500 &&
-            !is_dash_only(item)
-        })
-        .collect::<Vec<String>>();
-        
-    result
+        .filter(|item| !item.is_empty() && item.len() <= 500 && !tokenizer::is_dash_only(item))
+        .collect::<Vec<String>>()
 }
 
 // Helper function to normalize whitespace
@@ -821,70 +1478,29 @@ fn normalize_whitespace(text: &str) -> String {
         .join(" ")
 }
 
-// Helper function to check if line is a numbered item
-fn is_numbered_item(text: &str) -> bool {
-    if let Some(pos) = text.find('.') {
-        if pos > 0 {
-            let number_part = &text[..pos];
-            return number_part.chars().all(|c| c.is_ascii_digit()) && text.len() > pos + 1;
-        }
-    }
-    false
-}
-
-// Helper function to remove number prefix from numbered item
-fn remove_number_prefix(text: &str) -> String {
-    if let Some(pos) = text.find('.') {
-        if pos > 0 && pos + 1 < text.len() {
-            return text[pos + 1..].trim().to_string();
-        }
-    }
-    text.to_string()
-}
+// Line classification (numbered items, continuation lines, dash-only
+// entries) now lives in `import::tokenizer`, shared with `parse_bullet_list`.
 
-// Helper function to determine if a line should be treated as continuation
-fn should_treat_as_continuation(trimmed: &str, original_line: &str) -> bool {
-    // Don't treat as continuation if line looks like it could be a bullet point that we failed to detect
-    if trimmed.len() > 50 && trimmed.contains(' ') && trimmed.chars().next().map_or(false, |c| c.is_ascii_alphabetic()) {
-        return false;
-    }
-    
-    // Don't treat as continuation if the original line had significant leading whitespace
-    let leading_whitespace = original_line.len() - original_line.trim_start().len();
-    if leading_whitespace > 2 {
-        return false;
-    }
-    
-    // Don't treat as continuation if line starts with common sentence starters
-    let sentence_starters = ["The", "This", "When", "If", "Use", "Keep", "Remember", "Consider", "Try", "Again", "Also", "Another"];
-    for starter in &sentence_starters {
-        if trimmed.starts_with(&format!("{} ", starter)) {
-            return false;
-        }
-    }
-    
-    true
-}
-
-// Helper function to check if a string contains only dashes
-fn is_dash_only(text: &str) -> bool {
-    let dash_chars = ['-', '–', '—', '−'];
-    !text.is_empty() && text.chars().all(|c| dash_chars.contains(&c) || c.is_whitespace())
-}
-
-// Helper function to validate a parsed problem
-fn validate_problem(problem: &ParsedProblem) -> Result<(), String> {
+// Helper function to validate a parsed problem. Accumulates every issue
+// instead of bailing on the first, so a single bad problem in a large
+// import reports all of its problems at once rather than one at a time
+// across repeated re-imports.
+fn validate_problem(problem: &ParsedProblem, span: Range<usize>, source: &str) -> Vec<ParseError> {
+    let mut errors = Vec::new();
     if problem.title.is_empty() {
-        return Err("Title is required".to_string());
+        errors.push(ParseError::new(source, span.clone(), "Title is required"));
     }
     if problem.description.is_empty() {
-        return Err("Description is required".to_string());
+        errors.push(ParseError::new(source, span.clone(), "Description is required"));
     }
     if problem.difficulty.is_empty() {
-        return Err("Difficulty is required".to_string());
-    }
-    if !["Easy", "Medium", "Hard"].contains(&problem.difficulty.as_str()) {
-        return Err(format!("Invalid difficulty: {}", problem.difficulty));
+        errors.push(ParseError::new(source, span.clone(), "Difficulty is required"));
+    } else if !["Easy", "Medium", "Hard"].contains(&problem.difficulty.as_str()) {
+        errors.push(ParseError::new(
+            source,
+            span,
+            format!("Invalid difficulty: {}", problem.difficulty),
+        ));
     }
-    Ok(())
+    errors
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use crate::models::AppState;
+use crate::path_resolver::Scope;
 use tauri::State;
 
 #[derive(serde::Serialize)]
@@ -9,25 +10,29 @@ pub struct PathDebugInfo {
     pub images_dir: String,
     pub sample_resolved_path: String,
     pub cfg_debug_assertions: bool,
+    pub logical_base_dir: String,
+    pub canonical_base_dir: String,
 }
 
 #[tauri::command]
 pub async fn debug_paths(state: State<'_, AppState>) -> Result<PathDebugInfo, String> {
     // Test path resolution with a sample relative path
     let sample_relative = "app-data/recordings/test.wav";
-    let resolved = state.path_resolver.resolve_relative_path(sample_relative);
-    
+    let resolved = state.path_resolver.resolve_relative_path(sample_relative)?;
+
     let recordings_dir = state.path_resolver.get_recordings_dir();
     let images_dir = state.path_resolver.get_images_dir();
     let base_dir = state.path_resolver.get_app_data_dir();
-    
+
     Ok(PathDebugInfo {
-        is_debug_mode: cfg!(debug_assertions),
+        is_debug_mode: state.path_resolver.is_debug_mode(),
         cfg_debug_assertions: cfg!(debug_assertions),
         path_resolver_base_dir: base_dir.display().to_string(),
         recordings_dir: recordings_dir.display().to_string(),
         images_dir: images_dir.display().to_string(),
         sample_resolved_path: resolved.display().to_string(),
+        logical_base_dir: state.path_resolver.logical_base_dir().display().to_string(),
+        canonical_base_dir: state.path_resolver.canonical_base_dir().display().to_string(),
     })
 }
 
@@ -36,15 +41,17 @@ pub async fn debug_recording_paths(
     state: State<'_, AppState>,
     relative_path: String,
 ) -> Result<String, String> {
-    let resolved = state.path_resolver.resolve_relative_path(&relative_path);
-    
+    let resolved = state
+        .path_resolver
+        .resolve_scoped(&relative_path, Scope::Recordings)?;
+
     // Check if file exists
     let exists = resolved.exists();
-    
+
     Ok(format!(
-        "Relative: {} → Resolved: {} (exists: {})", 
-        relative_path, 
-        resolved.display(), 
+        "Relative: {} → Resolved: {} (exists: {})",
+        relative_path,
+        resolved.display(),
         exists
     ))
 }
@@ -54,32 +61,39 @@ pub async fn debug_audio_loading(state: State<'_, AppState>, relative_path: Stri
     // Method 1: What frontend currently does (WRONG)
     let current_dir = std::env::current_dir().unwrap_or_default();
     let wrong_path = current_dir.join(&relative_path);
-    
-    // Method 2: What PathResolver does (CORRECT)
-    let correct_path = state.path_resolver.resolve_relative_path(&relative_path);
-    
+
+    // Method 2: What PathResolver does (CORRECT) - the logical path is what's
+    // persisted to the DB, but existence checks use the canonical (symlink-resolved)
+    // form so a data dir reached through a symlink doesn't produce a mismatch.
+    let logical_path = state.path_resolver.resolve_logical(&relative_path)?;
+    let canonical_path = state.path_resolver.resolve_canonical(&relative_path)?;
+
     let result = format!(
         "🔍 Audio Loading Debug:\n\n\
         1. Current Working Dir: {}\n\
         2. Relative Path: {}\n\
         3. Frontend Wrong Path: {} (exists: {})\n\
-        4. PathResolver Correct Path: {} (exists: {})\n\n\
+        4. PathResolver Logical Path: {}\n\
+        5. PathResolver Canonical Path: {} (exists: {})\n\n\
         ❌ Problem: Frontend uses current_dir + relative_path\n\
         ✅ Solution: Frontend should get absolute path from Rust",
         current_dir.display(),
         relative_path,
         wrong_path.display(),
         wrong_path.exists(),
-        correct_path.display(),
-        correct_path.exists()
+        logical_path.display(),
+        canonical_path.display(),
+        canonical_path.exists()
     );
-    
+
     Ok(result)
 }
 
 #[tauri::command]
 pub async fn get_absolute_path(state: State<'_, AppState>, relative_path: String) -> Result<String, String> {
-    let absolute_path = state.path_resolver.resolve_relative_path(&relative_path);
+    let absolute_path = state
+        .path_resolver
+        .resolve_scoped(&relative_path, Scope::AppData)?;
     Ok(absolute_path.to_string_lossy().to_string())
 }
 
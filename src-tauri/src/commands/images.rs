@@ -1,10 +1,33 @@
+use crate::database::maintenance::{blake3_hex, cas_dir, pending_dir, resolve_image_path};
+use crate::database::DatabaseManager;
 use crate::models::{AppState, ProblemImage, SaveImageRequest, DeleteImageRequest};
+use anyhow::Context;
 use base64::{Engine as _, engine::general_purpose};
-use std::path::PathBuf;
+use image::GenericImageView;
+use std::path::{Path, PathBuf};
 use std::fs;
 use tauri::State;
 use uuid::Uuid;
 
+/// Hard ceiling on a single upload, pict-rs-style - guards against a pasted
+/// screenshot silently eating disk before anything else in the pipeline
+/// notices.
+const MAX_UPLOAD_BYTES: usize = 20 * 1024 * 1024;
+
+/// Rejects any image whose long edge exceeds this - nothing in the app
+/// renders an image larger than this, and it keeps a hostile upload from
+/// forcing an enormous decode buffer.
+const MAX_IMAGE_DIMENSION: u32 = 8192;
+
+/// Oversized PNGs (screenshots especially) are re-encoded to WebP once their
+/// stripped size crosses this, since WebP reaches a similar-quality image at
+/// a fraction of PNG's size.
+const PNG_REENCODE_THRESHOLD_BYTES: usize = 2 * 1024 * 1024;
+
+/// Long edge a thumbnail is downscaled to, matching common grid/list
+/// thumbnail sizes (e.g. pict-rs's default thumbnail pipeline).
+const THUMBNAIL_MAX_EDGE: u32 = 320;
+
 /// Get the app data directory based on environment
 /// Development: uses project_root/dev-data/
 /// Production: would use app data directory (need app context for that)
@@ -35,23 +58,6 @@ fn get_app_data_dir() -> PathBuf {
     }
 }
 
-// Helper function to get the images directory with cross-platform support
-fn get_images_dir() -> anyhow::Result<PathBuf> {
-    let app_data_dir = get_app_data_dir().join("images");
-    
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&app_data_dir)?;
-    
-    Ok(app_data_dir)
-}
-
-// Helper function to ensure problem-specific directory exists
-fn ensure_problem_dir(problem_id: &str) -> anyhow::Result<PathBuf> {
-    let problem_dir = get_images_dir()?.join(format!("problem_{}", problem_id));
-    fs::create_dir_all(&problem_dir)?;
-    Ok(problem_dir)
-}
-
 // Helper function to detect image format from base64 data
 fn detect_image_format(data: &str) -> Option<&str> {
     if data.starts_with("data:image/png") {
@@ -70,6 +76,190 @@ fn detect_image_format(data: &str) -> Option<&str> {
     }
 }
 
+/// Maps `detect_image_format`'s sniffed tag to the codec the ingest step
+/// should validate/re-encode it as. `svg` isn't in here - the `image` crate
+/// can't decode vector formats at all, so it's passed through unvalidated.
+fn raster_image_format(format: &str) -> Option<image::ImageFormat> {
+    match format {
+        "png" => Some(image::ImageFormat::Png),
+        "jpg" | "jpeg" => Some(image::ImageFormat::Jpeg),
+        "gif" => Some(image::ImageFormat::Gif),
+        "webp" => Some(image::ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// Re-encodes `source` as `format` into an in-memory buffer. `image`'s
+/// encoders don't reproduce an input's EXIF/metadata, so writing straight
+/// back out through them is the strip step. JPEG has no alpha channel, so
+/// `source` is flattened to RGB8 first.
+fn reencode_stripped(source: &image::DynamicImage, format: image::ImageFormat) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut buf);
+    if format == image::ImageFormat::Jpeg {
+        image::DynamicImage::ImageRgb8(source.to_rgb8()).write_to(&mut cursor, format)?;
+    } else {
+        source.write_to(&mut cursor, format)?;
+    }
+    Ok(buf)
+}
+
+/// Default BlurHash component counts (see `blurhash::encode`): enough detail
+/// to read as "blurry version of the image" without the string getting long.
+const BLUR_HASH_COMP_X: u32 = 4;
+const BLUR_HASH_COMP_Y: u32 = 3;
+
+/// Downscales `source` to at most `max_edge` on its long edge and writes it
+/// as WebP to `thumbnail_path`. Shared with `commands::card_images`, which
+/// calls this with its own (smaller) edge constant rather than
+/// [`THUMBNAIL_MAX_EDGE`].
+pub(crate) fn generate_thumbnail(source: &image::DynamicImage, thumbnail_path: &Path, max_edge: u32) -> anyhow::Result<()> {
+    source
+        .thumbnail(max_edge, max_edge)
+        .save_with_format(thumbnail_path, image::ImageFormat::WebP)
+        .with_context(|| format!("Failed to write thumbnail {}", thumbnail_path.display()))?;
+    Ok(())
+}
+
+/// `<stem>.thumb.webp` next to `full_path`, reusing `full_path`'s own stem so
+/// the pair is easy to spot on disk. Shared with `commands::card_images`.
+pub(crate) fn thumbnail_path_for(full_path: &Path) -> PathBuf {
+    full_path.with_extension("thumb.webp")
+}
+
+/// BlurHash placeholder string for `source`, using the repo's default 4x3
+/// component counts.
+fn compute_blur_hash(source: &image::DynamicImage) -> String {
+    let rgb = source.to_rgb8();
+    let (width, height) = rgb.dimensions();
+    crate::blurhash::encode(rgb.as_raw(), width, height, BLUR_HASH_COMP_X, BLUR_HASH_COMP_Y)
+}
+
+/// Outcome of [`process_uploaded_image`] - everything a `problem_images` row
+/// needs once the ingest pipeline has finished with an upload's bytes.
+pub(crate) struct ProcessedImage {
+    pub relative_path: String,
+    pub thumbnail_path: Option<String>,
+    pub blur_hash: Option<String>,
+    pub content_hash: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub byte_size: i64,
+}
+
+/// Ingest step (inspired by pict-rs's magick+exiftool pipeline): for every
+/// raster format, decode with the `image` crate to confirm the bytes really
+/// are what `sniffed_format` claims, reject anything over
+/// `MAX_IMAGE_DIMENSION`, then re-encode through the same crate - whose
+/// encoders don't reproduce EXIF/metadata, so this is the strip step -
+/// capping oversized PNGs to WebP along the way. `svg` is vector data the
+/// `image` crate can't decode at all, so it passes through unvalidated and
+/// unstripped. Shared between the synchronous call `save_problem_image` used
+/// to make and the `process_image` job worker that replaced it (see
+/// `commands::jobs`).
+pub(crate) fn process_uploaded_image(
+    db: &mut DatabaseManager,
+    raw_data: Vec<u8>,
+    sniffed_format: &str,
+) -> Result<ProcessedImage, String> {
+    let (final_data, format, decoded, width, height) = match raster_image_format(sniffed_format) {
+        Some(image_format) => {
+            let decoded = image::load_from_memory_with_format(&raw_data, image_format).map_err(|e| {
+                format!("Uploaded data doesn't look like a valid {} image: {}", sniffed_format, e)
+            })?;
+            let (width, height) = decoded.dimensions();
+            if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+                return Err(format!(
+                    "Image is {}x{}, exceeding the {}px limit",
+                    width, height, MAX_IMAGE_DIMENSION
+                ));
+            }
+
+            let stripped = reencode_stripped(&decoded, image_format)
+                .map_err(|e| format!("Failed to re-encode image: {}", e))?;
+            if image_format == image::ImageFormat::Png && stripped.len() > PNG_REENCODE_THRESHOLD_BYTES {
+                let webp = reencode_stripped(&decoded, image::ImageFormat::WebP)
+                    .map_err(|e| format!("Failed to re-encode oversized PNG to WebP: {}", e))?;
+                (webp, "webp", Some(decoded), Some(width as i32), Some(height as i32))
+            } else {
+                (stripped, sniffed_format, Some(decoded), Some(width as i32), Some(height as i32))
+            }
+        }
+        None => (raw_data, sniffed_format, None, None, None),
+    };
+
+    // Name the stored file after the BLAKE3 hash of its final (stripped,
+    // possibly re-encoded) bytes rather than a fresh `Uuid`, following
+    // Spacedrive's CAS file-identifier approach: pasting the same screenshot
+    // into many cards then writes the content once, and `image_blobs.ref_count`
+    // (see `database/triggers.rs`) tracks how many `problem_images` rows
+    // still reference it.
+    let hash = blake3_hex(&final_data);
+    let filename = format!("{}.{}", hash, format);
+    let thumbnail_filename = format!("{}.thumb.webp", hash);
+    let byte_size = final_data.len() as i64;
+
+    let cas_directory = cas_dir().map_err(|e| format!("Failed to resolve cas directory: {}", e))?;
+    fs::create_dir_all(&cas_directory)
+        .map_err(|e| format!("Failed to create cas directory: {}", e))?;
+
+    let full_path = cas_directory.join(&filename);
+    let thumbnail_full_path = cas_directory.join(&thumbnail_filename);
+    let already_stored = full_path.exists();
+
+    if !already_stored {
+        fs::write(&full_path, &final_data)
+            .map_err(|e| format!("Failed to save image file: {}", e))?;
+    }
+
+    // Create relative path for database storage (environment-aware)
+    let relative_path = if cfg!(debug_assertions) {
+        format!("dev-data/images/cas/{}", filename)
+    } else {
+        format!("app-data/images/cas/{}", filename)
+    };
+    let relative_thumbnail_path = if cfg!(debug_assertions) {
+        format!("dev-data/images/cas/{}", thumbnail_filename)
+    } else {
+        format!("app-data/images/cas/{}", thumbnail_filename)
+    };
+
+    // A duplicate's thumbnail and BlurHash were already computed the first
+    // time this content was saved - reuse them instead of regenerating.
+    // Otherwise generate both from the `DynamicImage` the ingest step above
+    // already decoded; `svg` has none, so both stay unset -
+    // `get_problem_image_thumbnail` backfills the thumbnail lazily, and the
+    // frontend falls back to waiting for the full image if there's no
+    // BlurHash placeholder.
+    let (relative_thumbnail_path, blur_hash) = if already_stored && thumbnail_full_path.exists() {
+        let blur_hash = db.find_image_blur_hash_by_content_hash(&hash).map_err(|e| e.to_string())?;
+        (Some(relative_thumbnail_path), blur_hash)
+    } else if let Some(decoded) = &decoded {
+        let thumbnail_path = match generate_thumbnail(decoded, &thumbnail_full_path, THUMBNAIL_MAX_EDGE) {
+            Ok(()) => Some(relative_thumbnail_path),
+            Err(e) => {
+                eprintln!("Failed to generate thumbnail for {}: {}", relative_path, e);
+                None
+            }
+        };
+        (thumbnail_path, Some(compute_blur_hash(decoded)))
+    } else {
+        (None, None)
+    };
+
+    db.register_image_blob(&hash, format).map_err(|e| e.to_string())?;
+
+    Ok(ProcessedImage {
+        relative_path,
+        thumbnail_path: relative_thumbnail_path,
+        blur_hash,
+        content_hash: hash,
+        width,
+        height,
+        byte_size,
+    })
+}
+
 #[tauri::command]
 pub async fn save_problem_image(
     state: State<'_, AppState>,
@@ -81,48 +271,79 @@ pub async fn save_problem_image(
     } else {
         &request.image_data
     };
-    
+
     // Detect image format
-    let format = detect_image_format(&request.image_data).unwrap_or("png");
-    
+    let sniffed_format = detect_image_format(&request.image_data).unwrap_or("png");
+
     // Decode base64 data
-    let decoded_data = general_purpose::STANDARD
+    let raw_data = general_purpose::STANDARD
         .decode(image_data)
         .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
-    
-    // Generate unique filename
-    let filename = format!("{}.{}", Uuid::new_v4(), format);
-    
-    // Ensure problem directory exists
-    let problem_dir = ensure_problem_dir(&request.problem_id)
-        .map_err(|e| format!("Failed to create problem directory: {}", e))?;
-    
-    // Full path for file storage
-    let full_path = problem_dir.join(&filename);
-    
-    // Save the image file
-    fs::write(&full_path, decoded_data)
-        .map_err(|e| format!("Failed to save image file: {}", e))?;
-    
-    // Create relative path for database storage (environment-aware)
-    let relative_path = if cfg!(debug_assertions) {
-        format!("dev-data/images/problem_{}/{}", request.problem_id, filename)
+
+    if raw_data.len() > MAX_UPLOAD_BYTES {
+        return Err(format!(
+            "Image exceeds the {}MB upload limit",
+            MAX_UPLOAD_BYTES / (1024 * 1024)
+        ));
+    }
+
+    // Stash the original bytes under `pending_dir()` and enqueue a
+    // `process_image` job rather than running `process_uploaded_image`
+    // inline - decoding, stripping, re-encoding and thumbnailing can take
+    // long enough on a large screenshot that doing it on the Tauri command's
+    // thread would make every paste feel like it hangs. The returned row is
+    // `pending`; `commands::jobs::process_image` flips it to `ready` once the
+    // worker thread gets to it, and the frontend polls
+    // `get_image_processing_status` in the meantime.
+    let staging_id = Uuid::new_v4().to_string();
+    let staging_filename = format!("{}.{}", staging_id, sniffed_format);
+    let staging_directory = pending_dir().map_err(|e| format!("Failed to resolve pending directory: {}", e))?;
+    fs::create_dir_all(&staging_directory)
+        .map_err(|e| format!("Failed to create pending directory: {}", e))?;
+    fs::write(staging_directory.join(&staging_filename), &raw_data)
+        .map_err(|e| format!("Failed to stage uploaded image: {}", e))?;
+
+    let relative_staging_path = if cfg!(debug_assertions) {
+        format!("dev-data/images/pending/{}", staging_filename)
     } else {
-        format!("app-data/images/problem_{}/{}", request.problem_id, filename)
+        format!("app-data/images/pending/{}", staging_filename)
     };
-    
-    // Save to database
+
     let mut db = state.db.lock().map_err(|e| e.to_string())?;
+
     let image = db.save_problem_image(
         &request.problem_id,
-        &relative_path,
+        &relative_staging_path,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        "pending",
         request.caption,
         request.position,
     ).map_err(|e| format!("Failed to save image to database: {}", e))?;
-    
+
+    db.enqueue_image_processing(&image.id, &relative_staging_path, sniffed_format)
+        .map_err(|e| format!("Failed to enqueue image processing: {}", e))?;
+
     Ok(image)
 }
 
+/// Polls a `problem_images` row's `status` while `commands::jobs::process_image`
+/// works through the queue - the frontend calls this after `save_problem_image`
+/// returns a `pending` row, the same way it would poll `get_job_status` for a
+/// transcription job.
+#[tauri::command]
+pub async fn get_image_processing_status(
+    state: State<'_, AppState>,
+    image_id: String,
+) -> Result<Option<ProblemImage>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_problem_image(&image_id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_problem_images(
     state: State<'_, AppState>,
@@ -139,44 +360,41 @@ pub async fn delete_problem_image(
     request: DeleteImageRequest,
 ) -> Result<(), String> {
     let mut db = state.db.lock().map_err(|e| e.to_string())?;
-    
-    // Get the image path from database and delete the record
-    let image_path = db.delete_problem_image(&request.image_id)
-        .map_err(|e| format!("Failed to delete image from database: {}", e))?;
-    
-    // Delete the actual file - handle environment-aware path resolution
-    let full_path = if image_path.starts_with("dev-data/") || image_path.starts_with("app-data/") || image_path.starts_with("images/") {
-        // Convert relative path to absolute path based on environment
-        if image_path.starts_with("dev-data/") {
-            // Development path: project_root/dev-data/...
-            std::env::current_dir()
-                .map_err(|e| e.to_string())?
-                .join(&image_path)
-        } else if image_path.starts_with("app-data/") {
-            // Production path: resolve to actual app data directory
-            get_app_data_dir().join(&image_path[9..]) // Remove "app-data/" prefix
-        } else if image_path.starts_with("images/") {
-            // Legacy path: attachments/images/...
-            std::env::current_dir()
-                .map_err(|e| e.to_string())?
-                .join("attachments")
-                .join(&image_path)
-        } else {
-            std::env::current_dir()
-                .map_err(|e| e.to_string())?
-                .join(&image_path)
-        }
-    } else {
-        std::env::current_dir()
-            .map_err(|e| e.to_string())?
-            .join(&image_path)
-    };
-    
+
+    // Get the image/thumbnail paths from database and delete the record.
+    // `trg_image_blobs_ref_count_delete` has already decremented
+    // `image_blobs.ref_count` by the time this returns - a CAS-backed image
+    // (`content_hash` is `Some`) only has its files unlinked once
+    // `remaining_ref_count` reaches 0, since another row may still share
+    // them. An image saved before content-addressed storage existed has no
+    // `content_hash` and was never shared, so its file is always removed.
+    let (image_path, thumbnail_path, content_hash, remaining_ref_count) =
+        db.delete_problem_image(&request.image_id)
+            .map_err(|e| format!("Failed to delete image from database: {}", e))?;
+
+    let still_referenced = content_hash.is_some() && remaining_ref_count.unwrap_or(0) > 0;
+    if still_referenced {
+        return Ok(());
+    }
+
+    if let Some(hash) = &content_hash {
+        db.forget_image_blob(hash).map_err(|e| e.to_string())?;
+    }
+
+    let full_path = resolve_image_path(&image_path).map_err(|e| e.to_string())?;
     if full_path.exists() {
         fs::remove_file(full_path)
             .map_err(|e| format!("Failed to delete image file: {}", e))?;
     }
-    
+
+    if let Some(thumbnail_path) = thumbnail_path {
+        let thumbnail_full_path = resolve_image_path(&thumbnail_path).map_err(|e| e.to_string())?;
+        if thumbnail_full_path.exists() {
+            fs::remove_file(thumbnail_full_path)
+                .map_err(|e| format!("Failed to delete thumbnail file: {}", e))?;
+        }
+    }
+
     Ok(())
 }
 
@@ -284,4 +502,27 @@ pub async fn get_image_data_url(relative_path: String) -> Result<String, String>
     
     // Return as data URL
     Ok(format!("data:{};base64,{}", mime_type, base64_data))
+}
+
+/// Get a problem image's thumbnail as a base64 data URL, given the
+/// *original* image's `relative_path` (as stored in `ProblemImage.image_path`).
+/// If the image predates thumbnailing (or a previous generation attempt
+/// failed), the thumbnail is generated on the spot rather than failing.
+#[tauri::command]
+pub async fn get_problem_image_thumbnail(relative_path: String) -> Result<String, String> {
+    let full_path = resolve_image_path(&relative_path).map_err(|e| e.to_string())?;
+    let thumbnail_full_path = thumbnail_path_for(&full_path);
+
+    if !thumbnail_full_path.exists() {
+        let decoded = image::open(&full_path)
+            .with_context(|| format!("Failed to open image {}", full_path.display()))
+            .map_err(|e| e.to_string())?;
+        generate_thumbnail(&decoded, &thumbnail_full_path, THUMBNAIL_MAX_EDGE).map_err(|e| e.to_string())?;
+    }
+
+    let thumbnail_data = fs::read(&thumbnail_full_path)
+        .map_err(|e| format!("Failed to read thumbnail file: {}", e))?;
+    let base64_data = general_purpose::STANDARD.encode(&thumbnail_data);
+
+    Ok(format!("data:image/webp;base64,{}", base64_data))
 }
\ No newline at end of file
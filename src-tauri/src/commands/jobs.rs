@@ -0,0 +1,270 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use anyhow::Context;
+use tauri::State;
+use crate::database::jobs::{self, OcrCardImagePayload, ProcessImagePayload, TranscribeRecordingPayload};
+use crate::database::ocr;
+use crate::database::maintenance::resolve_image_path;
+use crate::database::DatabaseManager;
+use crate::models::AppState;
+
+/// How often the job-worker thread polls for the next `queued` job when the
+/// queue is empty.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[tauri::command]
+pub async fn enqueue_transcription(state: State<'_, AppState>, recording_id: String) -> Result<jobs::Job, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.enqueue_transcription(&recording_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_job_status(state: State<'_, AppState>, job_id: String) -> Result<Option<jobs::Job>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_job(&job_id).map_err(|e| e.to_string())
+}
+
+/// Requests that `job_id` stop after its current item rather than its next
+/// one. Only meaningful for `bulk_import_card_images` - other job types run
+/// to completion in one pass and have nothing to pause between. Returns
+/// `false` if the job was already paused, or already finished.
+#[tauri::command]
+pub async fn pause_job(state: State<'_, AppState>, job_id: String) -> Result<bool, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.pause_job(&job_id).map_err(|e| e.to_string())
+}
+
+/// Puts a paused job back to `queued` so the worker thread's next poll
+/// continues it from `progress.current_index`. Returns `false` if the job
+/// wasn't paused.
+#[tauri::command]
+pub async fn resume_job(state: State<'_, AppState>, job_id: String) -> Result<bool, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.resume_job(&job_id).map_err(|e| e.to_string())
+}
+
+/// Decodes `job_id`'s MessagePack `progress` column for a live progress
+/// indicator. Returns `None` if the job doesn't exist or hasn't persisted any
+/// progress yet.
+#[tauri::command]
+pub async fn get_job_progress(state: State<'_, AppState>, job_id: String) -> Result<Option<jobs::BulkImportProgress>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let job = db.get_job(&job_id).map_err(|e| e.to_string())?;
+
+    match job.and_then(|job| job.progress) {
+        Some(bytes) => rmp_serde::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| format!("Failed to decode job progress: {}", e)),
+        None => Ok(None),
+    }
+}
+
+/// Spawns the dedicated job-worker thread, polling for the oldest eligible
+/// `queued` job and running it to completion (or failure) one at a time.
+/// Modeled on `commands::audio::ensure_audio_thread_started`'s dedicated
+/// background thread, but simpler - the worker has no commands sent to it, it
+/// just polls the `jobs` table the same way a separate worker process would
+/// poll a real task queue.
+pub fn spawn_worker(db: Arc<Mutex<DatabaseManager>>) {
+    thread::spawn(move || loop {
+        let claimed = {
+            match db.lock() {
+                Ok(db) => db.claim_next_queued_job(),
+                Err(e) => {
+                    eprintln!("⚠️ Job worker: database mutex poisoned: {}", e);
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+            }
+        };
+
+        let job = match claimed {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("⚠️ Job worker: failed to claim next job: {}", e);
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+
+        let result = run_job(&db, &job);
+
+        let outcome = match &db.lock() {
+            Ok(db) => match result {
+                Ok(JobRunOutcome::Completed) => db.complete_job(&job.id),
+                // `pause_job` has already flipped the row to `paused` by the
+                // time `run_bulk_import_card_images` notices and returns -
+                // there's nothing left to record here.
+                Ok(JobRunOutcome::Paused) => Ok(()),
+                Err(e) => db.fail_job(&job.id, &e.to_string()),
+            },
+            Err(e) => {
+                eprintln!("⚠️ Job worker: database mutex poisoned while recording outcome: {}", e);
+                continue;
+            }
+        };
+
+        if let Err(e) = outcome {
+            eprintln!("⚠️ Job worker: failed to record outcome for job {}: {}", job.id, e);
+        }
+    });
+}
+
+/// What a job handler left the job in. Every handler besides
+/// `run_bulk_import_card_images` only ever produces `Completed` - they run to
+/// completion or fail in one pass, with nothing in between to pause.
+enum JobRunOutcome {
+    Completed,
+    Paused,
+}
+
+fn run_job(db: &Arc<Mutex<DatabaseManager>>, job: &jobs::Job) -> anyhow::Result<JobRunOutcome> {
+    match job.job_type.as_str() {
+        jobs::JOB_TYPE_TRANSCRIBE_RECORDING => transcribe_recording(db, job).map(|()| JobRunOutcome::Completed),
+        jobs::JOB_TYPE_PROCESS_IMAGE => process_image(db, job).map(|()| JobRunOutcome::Completed),
+        jobs::JOB_TYPE_BULK_IMPORT_CARD_IMAGES => run_bulk_import_card_images(db, job),
+        jobs::JOB_TYPE_OCR_CARD_IMAGE => run_ocr_card_image(db, job).map(|()| JobRunOutcome::Completed),
+        other => anyhow::bail!("Unknown job type: {}", other),
+    }
+}
+
+/// Imports one `BulkImportProgress` item at a time, re-reading the job row
+/// fresh before each one so a `pause_job` call from another thread is
+/// noticed between items rather than only at the very end. Each item goes
+/// through `save_card_image_bytes` - the exact same decode/hash/dedup/write
+/// path `save_card_image` uses for a single upload - so a bulk import can
+/// never leave a half-written or un-deduplicated image behind.
+fn run_bulk_import_card_images(db: &Arc<Mutex<DatabaseManager>>, job: &jobs::Job) -> anyhow::Result<JobRunOutcome> {
+    let payload: jobs::BulkImportCardImagesPayload = serde_json::from_str(&job.payload)?;
+
+    loop {
+        let (mut progress, state) = {
+            let db = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+            let current = db
+                .get_job(&job.id)?
+                .ok_or_else(|| anyhow::anyhow!("job {} disappeared mid-import", job.id))?;
+            let progress_bytes = current
+                .progress
+                .ok_or_else(|| anyhow::anyhow!("bulk import job {} has no progress state", job.id))?;
+            let progress: jobs::BulkImportProgress = rmp_serde::from_slice(&progress_bytes)
+                .context("Failed to decode bulk import progress")?;
+            (progress, current.state)
+        };
+
+        if state == "paused" {
+            return Ok(JobRunOutcome::Paused);
+        }
+
+        if progress.current_index >= progress.items.len() {
+            return Ok(JobRunOutcome::Completed);
+        }
+
+        let item = progress.items[progress.current_index].clone();
+        let item_result = {
+            let mut db = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+            crate::commands::card_images::save_card_image_bytes(&mut db, &payload.card_id, &item.image_data, item.caption.clone(), None)
+        };
+
+        match item_result {
+            Ok(image) => progress.imported_image_ids.push(image.id),
+            Err(e) => progress.failed.push(format!("item {}: {}", progress.current_index, e)),
+        }
+        progress.current_index += 1;
+
+        let encoded = rmp_serde::to_vec(&progress).context("Failed to encode bulk import progress")?;
+        let db = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+        db.update_job_progress(&job.id, &encoded)?;
+    }
+}
+
+/// `save_problem_image`'s background half: decode/validate/strip/reencode the
+/// bytes it staged under `pending_dir()`, move the result into `cas_dir()`,
+/// and flip the `problem_images` row to `ready`.
+fn process_image(db: &Arc<Mutex<DatabaseManager>>, job: &jobs::Job) -> anyhow::Result<()> {
+    let payload: ProcessImagePayload = serde_json::from_str(&job.payload)?;
+
+    let staging_path = resolve_image_path(&payload.staging_path)?;
+    let raw_data = std::fs::read(&staging_path)
+        .with_context(|| format!("Failed to read staged image {}", staging_path.display()))?;
+
+    let mut db_guard = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+
+    match crate::commands::images::process_uploaded_image(&mut db_guard, raw_data, &payload.sniffed_format) {
+        Ok(processed) => {
+            db_guard.finish_image_processing(
+                &payload.image_id,
+                &processed.relative_path,
+                processed.thumbnail_path.as_deref(),
+                processed.blur_hash.as_deref(),
+                Some(&processed.content_hash),
+                processed.width,
+                processed.height,
+                Some(processed.byte_size),
+            )?;
+            drop(db_guard);
+            if let Err(e) = std::fs::remove_file(&staging_path) {
+                eprintln!("⚠️ Job worker: failed to remove staged image {}: {}", staging_path.display(), e);
+            }
+            Ok(())
+        }
+        Err(validation_error) => {
+            // Unlike a transient `jobs` failure, bytes that don't decode as
+            // their declared format or exceed a size/dimension limit will
+            // never succeed on retry - mark the image `failed` directly and
+            // report the job itself as done, rather than letting the queue
+            // retry an upload that trying again can't fix.
+            db_guard.fail_image_processing(&payload.image_id)?;
+            drop(db_guard);
+            let _ = std::fs::remove_file(&staging_path);
+            eprintln!("⚠️ Job worker: image {} failed validation: {}", payload.image_id, validation_error);
+            Ok(())
+        }
+    }
+}
+
+/// `save_card_image`'s background half when the `ocr` feature is enabled:
+/// re-read the image's bytes off disk, run OCR, and persist whatever labels
+/// come back - `insert_image_labels` replaces any prior labels for this
+/// image, so re-running (e.g. via `regenerate_image_labels`) is idempotent.
+fn run_ocr_card_image(db: &Arc<Mutex<DatabaseManager>>, job: &jobs::Job) -> anyhow::Result<()> {
+    let payload: OcrCardImagePayload = serde_json::from_str(&job.payload)?;
+
+    let image_path = {
+        let db_guard = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+        let image = db_guard
+            .get_card_image_by_id(&payload.image_id)?
+            .ok_or_else(|| anyhow::anyhow!("Card image {} no longer exists", payload.image_id))?;
+        db_guard.resolve_media_path(&image.image_path)?
+    };
+
+    let image_bytes = std::fs::read(&image_path)
+        .with_context(|| format!("Failed to read card image {}", image_path.display()))?;
+    let labels = ocr::run_ocr(&image_bytes)?;
+
+    let mut db_guard = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+    db_guard.insert_image_labels(&payload.image_id, &labels)?;
+    Ok(())
+}
+
+// TODO: wire this up to a real speech-to-text backend. For now there is no
+// transcription engine available to this build, so the job queue's retry and
+// backoff machinery is exercised but every attempt fails, which is an honest
+// reflection of "not implemented yet" rather than silently marking the job
+// done without a transcript.
+fn transcribe_recording(db: &Arc<Mutex<DatabaseManager>>, job: &jobs::Job) -> anyhow::Result<()> {
+    let payload: TranscribeRecordingPayload = serde_json::from_str(&job.payload)?;
+
+    let db = db.lock().map_err(|e| anyhow::anyhow!("database mutex poisoned: {}", e))?;
+    let recording = db
+        .get_recording_by_id(&payload.recording_id)?
+        .ok_or_else(|| anyhow::anyhow!("Recording {} no longer exists", payload.recording_id))?;
+    drop(db);
+
+    let _ = recording.filepath;
+    anyhow::bail!("No transcription backend is configured")
+}
@@ -0,0 +1,329 @@
+// Imports a problem straight from LeetCode, removing the manual copy-paste step the
+// TXT format (`import_problems_from_txt`) forces on users.
+
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const LEETCODE_GRAPHQL_URL: &str = "https://leetcode.com/graphql";
+
+const QUESTION_DATA_QUERY: &str = r#"
+query questionData($titleSlug: String!) {
+  question(titleSlug: $titleSlug) {
+    questionFrontendId
+    title
+    content
+    difficulty
+    hints
+    topicTags {
+      name
+    }
+    codeSnippets {
+      lang
+      langSlug
+      code
+    }
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse {
+    data: Option<QuestionDataWrapper>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuestionDataWrapper {
+    question: Option<QuestionData>,
+}
+
+// Also the unit cached in `leetcode_problem_cache` (see `fetch_question_data`),
+// so repeated imports/syncs of the same problem work offline.
+#[derive(Debug, Serialize, Deserialize)]
+struct QuestionData {
+    #[serde(rename = "questionFrontendId")]
+    question_frontend_id: String,
+    title: String,
+    content: Option<String>,
+    difficulty: String,
+    #[serde(default)]
+    hints: Vec<String>,
+    #[serde(rename = "topicTags")]
+    topic_tags: Vec<TopicTag>,
+    #[serde(rename = "codeSnippets")]
+    code_snippets: Vec<CodeSnippet>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TopicTag {
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CodeSnippet {
+    #[serde(rename = "langSlug")]
+    lang_slug: String,
+    code: String,
+}
+
+/// Accepts either a bare slug ("two-sum") or a full problem URL
+/// ("https://leetcode.com/problems/two-sum/") and extracts the slug.
+fn extract_slug(slug_or_url: &str) -> Result<String, String> {
+    let trimmed = slug_or_url.trim();
+    if !trimmed.contains('/') {
+        if trimmed.is_empty() {
+            return Err("LeetCode slug or URL must not be empty".to_string());
+        }
+        return Ok(trimmed.to_string());
+    }
+
+    trimmed
+        .split("/problems/")
+        .nth(1)
+        .and_then(|tail| tail.split('/').next())
+        .filter(|slug| !slug.is_empty())
+        .map(|slug| slug.to_string())
+        .ok_or_else(|| format!("Could not extract a problem slug from '{}'", slug_or_url))
+}
+
+/// Strips LeetCode's HTML description down to plain text good enough for our
+/// description field - this app doesn't render rich HTML elsewhere either.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .trim()
+        .to_string()
+}
+
+fn map_difficulty(raw: &str) -> String {
+    match raw {
+        "Easy" | "Medium" | "Hard" => raw.to_string(),
+        other => {
+            eprintln!("⚠️ Unexpected LeetCode difficulty '{}', defaulting to Medium", other);
+            "Medium".to_string()
+        }
+    }
+}
+
+/// Extracts the bullet points under a "Constraints:" heading from LeetCode's
+/// raw HTML `content`, e.g. `<ul><li>1 <= n <= 10^5</li></ul>`. Returns an
+/// empty list if no such section is found - this app works fine without
+/// constraints filled in.
+fn extract_constraints(html: &str) -> Vec<String> {
+    let Some(marker) = html.find("Constraints:") else {
+        return Vec::new();
+    };
+    let Some(ul_offset) = html[marker..].find("<ul>") else {
+        return Vec::new();
+    };
+    let list_start = marker + ul_offset;
+    let Some(ul_end) = html[list_start..].find("</ul>") else {
+        return Vec::new();
+    };
+    let list = &html[list_start..list_start + ul_end];
+
+    let mut constraints = Vec::new();
+    let mut rest = list;
+    while let Some(li_start) = rest.find("<li>") {
+        let after = &rest[li_start + "<li>".len()..];
+        let Some(li_end) = after.find("</li>") else {
+            break;
+        };
+        let item = strip_html(&after[..li_end]);
+        if !item.is_empty() {
+            constraints.push(item);
+        }
+        rest = &after[li_end + "</li>".len()..];
+    }
+    constraints
+}
+
+async fn fetch_question_data_live(slug: &str) -> Result<QuestionData, String> {
+    let client = reqwest::Client::new();
+    let body = serde_json::json!({
+        "query": QUESTION_DATA_QUERY,
+        "variables": { "titleSlug": slug },
+        "operationName": "questionData",
+    });
+
+    let response = client
+        .post(LEETCODE_GRAPHQL_URL)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach LeetCode: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("LeetCode responded with status {}", response.status()));
+    }
+
+    let parsed: GraphQlResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse LeetCode response: {}", e))?;
+
+    parsed
+        .data
+        .and_then(|d| d.question)
+        .ok_or_else(|| format!("No question found for slug '{}'", slug))
+}
+
+/// Fetches `slug`'s question data, caching the raw payload in
+/// `leetcode_problem_cache` on success so that a later import/sync of the
+/// same problem can fall back to it when LeetCode is unreachable.
+async fn fetch_question_data(state: &State<'_, AppState>, slug: &str) -> Result<QuestionData, String> {
+    match fetch_question_data_live(slug).await {
+        Ok(question) => {
+            if let Ok(raw_json) = serde_json::to_string(&question) {
+                let mut db = state.db.lock().map_err(|e| e.to_string())?;
+                if let Err(e) = db.cache_leetcode_payload(slug, &raw_json) {
+                    eprintln!("⚠️ Rust: Failed to cache LeetCode payload for '{}': {}", slug, e);
+                }
+            }
+            Ok(question)
+        }
+        Err(live_err) => {
+            let cached = {
+                let db = state.db.lock().map_err(|e| e.to_string())?;
+                db.get_cached_leetcode_payload(slug).map_err(|e| e.to_string())?
+            };
+            match cached {
+                Some(raw_json) => serde_json::from_str(&raw_json)
+                    .map_err(|e| format!("Failed to parse cached LeetCode payload for '{}': {}", slug, e)),
+                None => Err(live_err),
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn import_problem_from_leetcode(
+    state: State<'_, AppState>,
+    slug_or_url: String,
+) -> Result<FrontendProblem, String> {
+    let slug = extract_slug(&slug_or_url)?;
+    let question = fetch_question_data(&state, &slug).await?;
+
+    let description = question
+        .content
+        .as_deref()
+        .map(strip_html)
+        .unwrap_or_default();
+    let constraints = question
+        .content
+        .as_deref()
+        .map(extract_constraints)
+        .unwrap_or_default();
+
+    let request = CreateProblemRequest {
+        title: question.title.clone(),
+        description,
+        difficulty: map_difficulty(&question.difficulty),
+        topic: question.topic_tags.iter().map(|t| t.name.clone()).collect(),
+        leetcode_url: Some(format!("https://leetcode.com/problems/{}/", slug)),
+        constraints,
+        hints: question.hints.clone(),
+        related_problem_ids: None,
+    };
+
+    eprintln!(
+        "🌐 Rust: Fetched LeetCode problem #{} '{}', creating problem record",
+        question.question_frontend_id, question.title
+    );
+
+    let created_problem = {
+        let mut db = state.db.lock().map_err(|e| e.to_string())?;
+        db.create_problem(request).map_err(|e| e.to_string())?
+    };
+
+    // Seed one starter card per official code stub so users begin from the real signature.
+    for snippet in &question.code_snippets {
+        let card_request = CreateCardRequest {
+            problem_id: created_problem.id.clone(),
+            language: Some(snippet.lang_slug.clone()),
+            parent_card_id: None,
+        };
+
+        let mut db = state.db.lock().map_err(|e| e.to_string())?;
+        match db.create_card(card_request) {
+            Ok(card) => {
+                let update = UpdateCardRequest {
+                    id: card.id,
+                    code: Some(snippet.code.clone()),
+                    language: None,
+                    notes: None,
+                    status: None,
+                };
+                if let Err(e) = db.update_card(update) {
+                    eprintln!("⚠️ Rust: Failed to seed stub code for '{}': {}", snippet.lang_slug, e);
+                }
+            }
+            Err(e) => {
+                eprintln!("⚠️ Rust: Failed to create starter card for '{}': {}", snippet.lang_slug, e);
+            }
+        }
+    }
+
+    Ok(created_problem)
+}
+
+/// Re-fetches `problem_id`'s LeetCode metadata and refreshes it in place.
+/// Only the fields LeetCode itself owns - difficulty, topic tags,
+/// constraints, hints - are overwritten; `title` and `description` are left
+/// alone since users commonly annotate or rewrite those after import.
+#[tauri::command]
+pub async fn sync_problem_metadata(
+    state: State<'_, AppState>,
+    problem_id: String,
+) -> Result<FrontendProblem, String> {
+    let leetcode_url = {
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        let problem = db
+            .get_problem_by_id(&problem_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Problem with id '{}' not found", problem_id))?;
+        problem
+            .leetcode_url
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| format!("Problem '{}' has no leetcode_url to sync from", problem_id))?
+    };
+
+    let slug = extract_slug(&leetcode_url)?;
+    let question = fetch_question_data(&state, &slug).await?;
+
+    let update = UpdateProblemRequest {
+        id: problem_id.clone(),
+        title: None,
+        description: None,
+        difficulty: Some(map_difficulty(&question.difficulty)),
+        topic: Some(question.topic_tags.iter().map(|t| t.name.clone()).collect()),
+        leetcode_url: None,
+        constraints: Some(
+            question
+                .content
+                .as_deref()
+                .map(extract_constraints)
+                .unwrap_or_default(),
+        ),
+        hints: Some(question.hints.clone()),
+        related_problem_ids: None,
+    };
+
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.update_problem(update)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Problem with id '{}' disappeared during sync", problem_id))
+}
@@ -0,0 +1,226 @@
+// Structured JSON import/export of a user's whole library - the TXT/JSON
+// importers in `database.rs` only ever create problem stubs; this round-trips
+// a `LibraryBundle` (problems with their cards, images and tags attached) so
+// a user can back up their library or move it to another machine in one file.
+
+use crate::models::*;
+use tauri::State;
+
+#[tauri::command]
+pub async fn export_library_bundle(state: State<'_, AppState>) -> Result<LibraryBundle, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+
+    let mut problems = Vec::new();
+    for mut problem in db.get_problems().map_err(|e| e.to_string())? {
+        problem.tags = db
+            .get_problem_tags(&problem.id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+
+        let cards = db.get_cards_for_problem(&problem.id).map_err(|e| e.to_string())?;
+        let images = db.get_problem_images(&problem.id).map_err(|e| e.to_string())?;
+
+        problems.push(ProblemBundle { problem, cards, images });
+    }
+
+    let tags = db.get_all_tags().map_err(|e| e.to_string())?;
+
+    Ok(LibraryBundle { problems, tags })
+}
+
+#[tauri::command]
+pub async fn import_library_bundle(
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<ImportResult, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+
+    let mut result = ImportResult {
+        success: true,
+        imported_count: 0,
+        skipped_count: 0,
+        error_count: 0,
+        duplicates: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    // `ImportError::line` has no meaning for a JSON document - we reuse its
+    // `field` slot to carry a JSON-pointer-style path to the offending entry
+    // instead, keeping `line` at 0 and `severity` unchanged.
+    let bundle: LibraryBundle = match serde_json::from_str(&content) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            result.success = false;
+            result.error_count += 1;
+            result.errors.push(ImportError {
+                line: 0,
+                field: Some("$".to_string()),
+                message: format!("Failed to parse library bundle: {}", e),
+                severity: "error".to_string(),
+            });
+            return Ok(result);
+        }
+    };
+
+    let tags_by_name: std::collections::HashMap<String, Tag> = bundle
+        .tags
+        .iter()
+        .map(|tag| (tag.name.clone(), tag.clone()))
+        .collect();
+
+    for (problem_index, problem_bundle) in bundle.problems.into_iter().enumerate() {
+        let pointer = format!("/problems/{}", problem_index);
+        let title = problem_bundle.problem.title.clone();
+
+        match db.search_problems_by_title(&title, 1, None) {
+            Ok(existing) if !existing.is_empty() => {
+                result.skipped_count += 1;
+                result.duplicates.push(title);
+                continue;
+            }
+            Err(e) => {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    line: 0,
+                    field: Some(format!("{}/problem/title", pointer)),
+                    message: format!("Failed to check for duplicate: {}", e),
+                    severity: "error".to_string(),
+                });
+                continue;
+            }
+            _ => {}
+        }
+
+        let request = CreateProblemRequest {
+            title: problem_bundle.problem.title.clone(),
+            description: problem_bundle.problem.description.clone(),
+            difficulty: problem_bundle.problem.difficulty.clone(),
+            topic: problem_bundle.problem.topic.clone(),
+            leetcode_url: problem_bundle.problem.leetcode_url.clone(),
+            constraints: problem_bundle.problem.constraints.clone(),
+            hints: problem_bundle.problem.hints.clone(),
+            related_problem_ids: Some(problem_bundle.problem.related_problem_ids.clone()),
+        };
+
+        let created_problem = match db.create_problem(request) {
+            Ok(created) => created,
+            Err(e) => {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    line: 0,
+                    field: Some(format!("{}/problem", pointer)),
+                    message: format!("Failed to create problem: {}", e),
+                    severity: "error".to_string(),
+                });
+                continue;
+            }
+        };
+        result.imported_count += 1;
+
+        for tag_name in &problem_bundle.problem.tags {
+            let tag = tags_by_name.get(tag_name);
+            if let Err(e) = db.add_problem_tag(AddProblemTagRequest {
+                problem_id: created_problem.id.clone(),
+                tag_name: tag_name.clone(),
+                color: tag.and_then(|t| t.color.clone()),
+                category: tag.map(|t| t.category.clone()),
+            }) {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    line: 0,
+                    field: Some(format!("{}/problem/tags", pointer)),
+                    message: format!("Failed to attach tag '{}': {}", tag_name, e),
+                    severity: "error".to_string(),
+                });
+            }
+        }
+
+        for (card_index, card) in problem_bundle.cards.iter().enumerate() {
+            let card_request = CreateCardRequest {
+                problem_id: created_problem.id.clone(),
+                language: Some(card.language.clone()),
+                parent_card_id: None,
+            };
+            match db.create_card(card_request) {
+                Ok(created_card) => {
+                    if card.code.is_some() || card.notes.is_some() {
+                        if let Err(e) = db.update_card(UpdateCardRequest {
+                            id: created_card.id.clone(),
+                            code: card.code.clone(),
+                            language: Some(card.language.clone()),
+                            notes: card.notes.clone(),
+                            status: Some(card.status.clone()),
+                        }) {
+                            result.error_count += 1;
+                            result.errors.push(ImportError {
+                                line: 0,
+                                field: Some(format!("{}/cards/{}", pointer, card_index)),
+                                message: format!("Failed to restore card contents: {}", e),
+                                severity: "error".to_string(),
+                            });
+                        }
+                    }
+                }
+                Err(e) => {
+                    result.error_count += 1;
+                    result.errors.push(ImportError {
+                        line: 0,
+                        field: Some(format!("{}/cards/{}", pointer, card_index)),
+                        message: format!("Failed to create card: {}", e),
+                        severity: "error".to_string(),
+                    });
+                }
+            }
+        }
+
+        for (image_index, image) in problem_bundle.images.iter().enumerate() {
+            // The bundle doesn't embed file bytes, only path/metadata, so no
+            // new content file is written here - but a `content_hash` still
+            // needs an `image_blobs` row registered before the insert so
+            // `trg_image_blobs_ref_count_insert` has something to increment.
+            if let Some(hash) = &image.content_hash {
+                let extension = std::path::Path::new(&image.image_path)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("png");
+                if let Err(e) = db.register_image_blob(hash, extension) {
+                    result.error_count += 1;
+                    result.errors.push(ImportError {
+                        line: 0,
+                        field: Some(format!("{}/images/{}", pointer, image_index)),
+                        message: format!("Failed to register image blob: {}", e),
+                        severity: "error".to_string(),
+                    });
+                    continue;
+                }
+            }
+
+            if let Err(e) = db.save_problem_image(
+                &created_problem.id,
+                &image.image_path,
+                image.thumbnail_path.as_deref(),
+                image.blur_hash.as_deref(),
+                image.content_hash.as_deref(),
+                image.width,
+                image.height,
+                image.byte_size,
+                "ready",
+                image.caption.clone(),
+                Some(image.position),
+            ) {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    line: 0,
+                    field: Some(format!("{}/images/{}", pointer, image_index)),
+                    message: format!("Failed to restore image: {}", e),
+                    severity: "error".to_string(),
+                });
+            }
+        }
+    }
+
+    result.success = result.error_count == 0;
+    Ok(result)
+}
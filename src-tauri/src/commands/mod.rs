@@ -0,0 +1,24 @@
+pub mod analytics;
+pub mod analytics_export;
+pub mod audio;
+pub mod audio_devices;
+pub mod card_images;
+pub mod cast;
+pub mod code_runner;
+pub mod data_archive;
+pub mod database;
+pub mod debug;
+pub mod images;
+pub mod jobs;
+pub mod leetcode;
+pub mod library_bundle;
+pub mod ocr;
+pub mod playback;
+pub mod problem_bundle;
+pub mod response;
+pub mod review_timer;
+pub mod solution_card;
+pub mod stats;
+pub mod storage;
+pub mod timer;
+pub mod work_sessions;
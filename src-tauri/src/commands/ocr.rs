@@ -0,0 +1,68 @@
+// Commands for the optional on-device OCR/auto-labeling subsystem (see
+// `database::ocr`). These stay available in every build - downloading,
+// status-checking, and reading labels don't need the `ocr` cargo feature
+// themselves - only `database::ocr::run_ocr` (invoked by the
+// `ocr_card_image` job, never directly from here) requires it.
+
+use crate::models::*;
+use tauri::State;
+
+/// Downloads the OCR model's weights from `model_url` into
+/// `database::ocr::model_path()`, overwriting any previous download for the
+/// same `MODEL_VERSION`. `model_url` is supplied by the caller rather than
+/// hardcoded, since there's no single official host for the weights and
+/// self-hosted/offline setups need to point this at their own mirror.
+#[tauri::command]
+pub async fn download_ocr_model(model_url: String) -> Result<(), String> {
+    let response = reqwest::get(&model_url)
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", model_url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Model download responded with status {}", response.status()));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read model download body: {}", e))?;
+
+    let dir = crate::database::ocr::model_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let path = crate::database::ocr::model_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, &bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(())
+}
+
+/// Whether the `ocr` feature was compiled in, whether its model has been
+/// downloaded, and which `MODEL_VERSION` that download corresponds to - so
+/// the UI can tell "not supported in this build" apart from "supported,
+/// just needs `download_ocr_model` run first".
+#[tauri::command]
+pub async fn get_ocr_model_status() -> Result<OcrModelStatus, String> {
+    Ok(OcrModelStatus {
+        feature_enabled: cfg!(feature = "ocr"),
+        model_downloaded: crate::database::ocr::is_model_downloaded().map_err(|e| e.to_string())?,
+        model_version: crate::database::ocr::MODEL_VERSION.to_string(),
+    })
+}
+
+/// Every label `run_ocr` found for a card image, highest confidence first.
+#[tauri::command]
+pub async fn get_image_labels(state: State<'_, AppState>, image_id: String) -> Result<Vec<ImageLabelRow>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_image_labels(&image_id).map_err(|e| e.to_string())
+}
+
+/// Re-enqueues an `ocr_card_image` job for an image that was already
+/// labeled - for rerunning after `download_ocr_model` fetches a newer
+/// `MODEL_VERSION`, since `insert_image_labels` replaces rather than
+/// appends, re-running is safe to call as often as needed.
+#[tauri::command]
+pub async fn regenerate_image_labels(state: State<'_, AppState>, image_id: String) -> Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.enqueue_ocr_card_image(&image_id).map_err(|e| e.to_string())?;
+    Ok(())
+}
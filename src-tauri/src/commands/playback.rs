@@ -0,0 +1,570 @@
+// Local WAV playback through a dedicated cpal output stream, mirroring
+// `commands::audio`'s thread-based input recording: a dedicated thread owns
+// the (non-Send) `cpal::Stream` and is driven by `PlaybackCommand`s sent over
+// an mpsc channel, each carrying its own `ack_tx` reply channel so the Tauri
+// commands below can block on a real result.
+
+use crate::commands::audio::LinearResampler;
+use crate::models::*;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use hound::WavReader;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::State;
+
+/// How long a command-issuing Tauri command waits for the playback thread's
+/// ack before giving up and reporting it as unreachable.
+const PLAYBACK_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+enum PlaybackStreamState {
+    Playing,
+    Paused,
+    Stopped,
+}
+
+/// Initialize the playback thread, mirroring `commands::audio::ensure_audio_thread_started`.
+fn ensure_playback_thread_started(state: &AppState) -> Result<mpsc::Sender<PlaybackCommand>, String> {
+    let sender_guard = state.playback_thread_sender.lock().map_err(|e| e.to_string())?;
+
+    if let Some(ref sender) = *sender_guard {
+        return Ok(sender.clone());
+    }
+
+    drop(sender_guard);
+
+    let (command_sender, command_receiver) = mpsc::channel::<PlaybackCommand>();
+    let position = Arc::clone(&state.playback_position);
+    let app_handle = state.app_handle.clone();
+
+    thread::spawn(move || {
+        playback_thread(command_receiver, position, app_handle);
+    });
+
+    let mut sender_guard = state.playback_thread_sender.lock().map_err(|e| e.to_string())?;
+    *sender_guard = Some(command_sender.clone());
+
+    Ok(command_sender)
+}
+
+/// The dedicated playback thread. Runs in its own thread so the non-Send
+/// `cpal::Stream` never has to cross a thread boundary, exactly like
+/// `commands::audio::audio_recording_thread`.
+fn playback_thread(
+    command_receiver: mpsc::Receiver<PlaybackCommand>,
+    position: Arc<Mutex<Option<PlaybackPosition>>>,
+    app_handle: tauri::AppHandle,
+) {
+    let mut current_stream: Option<cpal::Stream> = None;
+    let mut current_state: Option<Arc<Mutex<PlaybackStreamState>>> = None;
+    let mut current_idx: Option<Arc<Mutex<usize>>> = None;
+    let mut current_device_rate: u32 = 0;
+    let mut current_len: usize = 0;
+    // Persists across tracks (like a real volume knob) rather than resetting
+    // on every `PlayRecording`, so picking a new recording doesn't surprise
+    // the listener with full volume.
+    let volume = Arc::new(Mutex::new(Volume::default()));
+
+    while let Ok(command) = command_receiver.recv() {
+        match command {
+            PlaybackCommand::PlayRecording { recording_id, filepath, start_offset_secs, ack_tx } => {
+                // Dropping the old stream stops it; starting a new one always replaces whatever was playing.
+                current_stream = None;
+
+                match start_playback_stream(&recording_id, &filepath, start_offset_secs, Arc::clone(&volume), Arc::clone(&position), app_handle.clone()) {
+                    Ok((stream, state_arc, idx_arc, device_rate, total_len, duration_seconds)) => {
+                        current_stream = Some(stream);
+                        current_state = Some(state_arc);
+                        current_idx = Some(idx_arc);
+                        current_device_rate = device_rate;
+                        current_len = total_len;
+                        let _ = ack_tx.send(Ok(PlaybackAck::Started { duration_seconds }));
+                    }
+                    Err(e) => {
+                        current_state = None;
+                        current_idx = None;
+                        current_device_rate = 0;
+                        current_len = 0;
+                        let _ = ack_tx.send(Err(e));
+                    }
+                }
+            }
+            PlaybackCommand::PausePlayback { ack_tx } => {
+                if let Some(ref state_arc) = current_state {
+                    if let Ok(mut state_guard) = state_arc.lock() {
+                        *state_guard = PlaybackStreamState::Paused;
+                    }
+                    let _ = ack_tx.send(Ok(PlaybackAck::Paused));
+                } else {
+                    let _ = ack_tx.send(Err(PlaybackError("No active playback".to_string())));
+                }
+            }
+            PlaybackCommand::ResumePlayback { ack_tx } => {
+                if let Some(ref state_arc) = current_state {
+                    if let Ok(mut state_guard) = state_arc.lock() {
+                        *state_guard = PlaybackStreamState::Playing;
+                    }
+                    let _ = ack_tx.send(Ok(PlaybackAck::Resumed));
+                } else {
+                    let _ = ack_tx.send(Err(PlaybackError("No active playback".to_string())));
+                }
+            }
+            PlaybackCommand::StopPlayback { ack_tx } => {
+                current_stream = None;
+                current_state = None;
+                current_idx = None;
+                current_device_rate = 0;
+                current_len = 0;
+                if let Ok(mut position_guard) = position.lock() {
+                    *position_guard = None;
+                }
+                let _ = ack_tx.send(Ok(PlaybackAck::Stopped));
+            }
+            PlaybackCommand::Seek { seconds, ack_tx } => {
+                if let (Some(ref idx_arc), true) = (&current_idx, current_device_rate > 0) {
+                    let target_idx = (seconds.max(0.0) * current_device_rate as f64) as usize;
+                    let clamped_idx = target_idx.min(current_len);
+                    if let Ok(mut idx_guard) = idx_arc.lock() {
+                        *idx_guard = clamped_idx;
+                    }
+                    let position_seconds = clamped_idx as f64 / current_device_rate as f64;
+                    let _ = ack_tx.send(Ok(PlaybackAck::Sought { position_seconds }));
+                } else {
+                    let _ = ack_tx.send(Err(PlaybackError("No active playback".to_string())));
+                }
+            }
+            PlaybackCommand::SetVolume { volume: new_volume, ack_tx } => {
+                if let Ok(mut volume_guard) = volume.lock() {
+                    *volume_guard = new_volume;
+                }
+                let _ = ack_tx.send(Ok(PlaybackAck::VolumeSet { volume: new_volume.as_f32() }));
+            }
+        }
+    }
+}
+
+/// Loads `filepath` as 16-bit integer PCM WAV (files of any other format,
+/// including the 24-bit and 32-bit float recordings `commands::audio` can
+/// produce, are rejected), resamples it once up front to the default output device's
+/// native rate, and starts a `cpal` output stream that drains the resampled
+/// buffer. Returns the shared play/pause state and read cursor so later
+/// commands (pause/resume/seek) can drive the same stream.
+fn start_playback_stream(
+    recording_id: &str,
+    filepath: &str,
+    start_offset_secs: f64,
+    volume: Arc<Mutex<Volume>>,
+    position: Arc<Mutex<Option<PlaybackPosition>>>,
+    app_handle: tauri::AppHandle,
+) -> Result<(cpal::Stream, Arc<Mutex<PlaybackStreamState>>, Arc<Mutex<usize>>, u32, usize, f64), PlaybackError> {
+    let mut reader = WavReader::open(filepath)
+        .map_err(|e| PlaybackError(format!("Failed to open recording '{}': {}", filepath, e)))?;
+    let spec = reader.spec();
+
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err(PlaybackError(format!(
+            "Unsupported recording format: {:?} {}-bit (expected 16-bit PCM)",
+            spec.sample_format, spec.bits_per_sample
+        )));
+    }
+
+    let file_channels = spec.channels as usize;
+    let file_sample_rate = spec.sample_rate;
+
+    let raw_samples: Vec<i16> = reader
+        .samples::<i16>()
+        .collect::<Result<_, _>>()
+        .map_err(|e| PlaybackError(format!("Failed to read recording samples: {}", e)))?;
+
+    // Downmix to mono, normalized to -1.0..=1.0, mirroring the recorder's own downmix.
+    let mono: Vec<f32> = if file_channels <= 1 {
+        raw_samples.iter().map(|&s| s as f32 / 32768.0).collect()
+    } else {
+        raw_samples
+            .chunks(file_channels)
+            .map(|chunk| (chunk.iter().map(|&s| s as i32).sum::<i32>() / file_channels as i32) as f32 / 32768.0)
+            .collect()
+    };
+
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| PlaybackError("No audio output device available".to_string()))?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| PlaybackError(format!("Failed to get default output config: {}", e)))?;
+
+    let device_sample_rate = config.sample_rate().0;
+    let out_channels = config.channels() as usize;
+
+    let mut resampler = LinearResampler::new(file_sample_rate, device_sample_rate);
+    let mut resampled = Vec::with_capacity(mono.len());
+    resampler.process(&mono, &mut resampled);
+    let resampled = Arc::new(resampled);
+    let total_len = resampled.len();
+    let duration_seconds = total_len as f64 / device_sample_rate as f64;
+
+    let initial_idx = ((start_offset_secs.max(0.0)) * device_sample_rate as f64) as usize;
+    let initial_idx = initial_idx.min(total_len);
+
+    let state_arc = Arc::new(Mutex::new(PlaybackStreamState::Playing));
+    let idx_arc = Arc::new(Mutex::new(initial_idx));
+
+    let stream_config = cpal::StreamConfig {
+        channels: out_channels as u16,
+        sample_rate: cpal::SampleRate(device_sample_rate),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    let filepath_owned = filepath.to_string();
+    let recording_id_owned = recording_id.to_string();
+
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => {
+            let state_clone = Arc::clone(&state_arc);
+            let idx_clone = Arc::clone(&idx_arc);
+            let resampled_clone = Arc::clone(&resampled);
+            let position_clone = Arc::clone(&position);
+            let volume_clone = Arc::clone(&volume);
+            let app_handle_clone = app_handle.clone();
+            let filepath_clone = filepath_owned.clone();
+            let recording_id_clone = recording_id_owned.clone();
+            let mut already_finished = false;
+            device.build_output_stream(
+                &stream_config,
+                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    fill_output_block(
+                        data,
+                        out_channels,
+                        &state_clone,
+                        &idx_clone,
+                        &resampled_clone,
+                        device_sample_rate,
+                        duration_seconds,
+                        &position_clone,
+                        &volume_clone,
+                        &app_handle_clone,
+                        &filepath_clone,
+                        &recording_id_clone,
+                        &mut already_finished,
+                        |sample| sample,
+                    );
+                },
+                |err| eprintln!("Playback stream error: {}", err),
+                None,
+            )
+        }
+        cpal::SampleFormat::I16 => {
+            let state_clone = Arc::clone(&state_arc);
+            let idx_clone = Arc::clone(&idx_arc);
+            let resampled_clone = Arc::clone(&resampled);
+            let position_clone = Arc::clone(&position);
+            let volume_clone = Arc::clone(&volume);
+            let app_handle_clone = app_handle.clone();
+            let filepath_clone = filepath_owned.clone();
+            let recording_id_clone = recording_id_owned.clone();
+            let mut already_finished = false;
+            device.build_output_stream(
+                &stream_config,
+                move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
+                    fill_output_block(
+                        data,
+                        out_channels,
+                        &state_clone,
+                        &idx_clone,
+                        &resampled_clone,
+                        device_sample_rate,
+                        duration_seconds,
+                        &position_clone,
+                        &volume_clone,
+                        &app_handle_clone,
+                        &filepath_clone,
+                        &recording_id_clone,
+                        &mut already_finished,
+                        |sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16,
+                    );
+                },
+                |err| eprintln!("Playback stream error: {}", err),
+                None,
+            )
+        }
+        cpal::SampleFormat::U16 => {
+            let state_clone = Arc::clone(&state_arc);
+            let idx_clone = Arc::clone(&idx_arc);
+            let resampled_clone = Arc::clone(&resampled);
+            let position_clone = Arc::clone(&position);
+            let volume_clone = Arc::clone(&volume);
+            let app_handle_clone = app_handle.clone();
+            let filepath_clone = filepath_owned.clone();
+            let recording_id_clone = recording_id_owned.clone();
+            let mut already_finished = false;
+            device.build_output_stream(
+                &stream_config,
+                move |data: &mut [u16], _: &cpal::OutputCallbackInfo| {
+                    fill_output_block(
+                        data,
+                        out_channels,
+                        &state_clone,
+                        &idx_clone,
+                        &resampled_clone,
+                        device_sample_rate,
+                        duration_seconds,
+                        &position_clone,
+                        &volume_clone,
+                        &app_handle_clone,
+                        &filepath_clone,
+                        &recording_id_clone,
+                        &mut already_finished,
+                        |sample| ((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i32 + 32768) as u16,
+                    );
+                },
+                |err| eprintln!("Playback stream error: {}", err),
+                None,
+            )
+        }
+        _ => return Err(PlaybackError("Unsupported output sample format".to_string())),
+    }
+    .map_err(|e| PlaybackError(format!("Failed to build output stream: {}", e)))?;
+
+    stream
+        .play()
+        .map_err(|e| PlaybackError(format!("Failed to start playback stream: {}", e)))?;
+
+    Ok((stream, state_arc, idx_arc, device_sample_rate, total_len, duration_seconds))
+}
+
+/// Shared body for the F32/I16/U16 output callbacks: advances the read
+/// cursor over `resampled` (never blocking - a contended lock just emits
+/// silence for this block), converts each mono sample to the device's
+/// sample type via `convert`, and publishes the resulting position. Emits
+/// `AppEvent::PlaybackFinished` exactly once, the first block after the
+/// cursor runs out of samples.
+#[allow(clippy::too_many_arguments)]
+fn fill_output_block<T: Copy + Default>(
+    data: &mut [T],
+    out_channels: usize,
+    state: &Arc<Mutex<PlaybackStreamState>>,
+    idx: &Arc<Mutex<usize>>,
+    resampled: &Arc<Vec<f32>>,
+    device_sample_rate: u32,
+    duration_seconds: f64,
+    position: &Arc<Mutex<Option<PlaybackPosition>>>,
+    volume: &Arc<Mutex<Volume>>,
+    app_handle: &tauri::AppHandle,
+    filepath: &str,
+    recording_id: &str,
+    already_finished: &mut bool,
+    convert: impl Fn(f32) -> T,
+) {
+    let state_snapshot = state.try_lock().map(|guard| guard.clone()).unwrap_or(PlaybackStreamState::Playing);
+    let playing = state_snapshot == PlaybackStreamState::Playing;
+    let paused = state_snapshot == PlaybackStreamState::Paused;
+    let gain = volume.try_lock().map(|guard| guard.as_f32()).unwrap_or(1.0);
+
+    let mut idx_guard = match idx.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            data.fill(T::default());
+            return;
+        }
+    };
+
+    let mut just_finished = false;
+    for frame in data.chunks_mut(out_channels.max(1)) {
+        let sample = if playing {
+            if *idx_guard < resampled.len() {
+                let s = resampled[*idx_guard];
+                *idx_guard += 1;
+                s * gain
+            } else {
+                just_finished = true;
+                0.0
+            }
+        } else {
+            0.0
+        };
+        let converted = convert(sample);
+        for ch in frame.iter_mut() {
+            *ch = converted;
+        }
+    }
+
+    let position_seconds = *idx_guard as f64 / device_sample_rate as f64;
+    let is_finished = just_finished;
+    drop(idx_guard);
+
+    if let Ok(mut position_guard) = position.try_lock() {
+        *position_guard = Some(PlaybackPosition {
+            recording_id: Some(recording_id.to_string()),
+            position_seconds,
+            duration_seconds,
+            is_paused: paused,
+            is_finished,
+        });
+    }
+
+    if is_finished && !*already_finished {
+        *already_finished = true;
+        crate::events::emit(app_handle, crate::events::AppEvent::PlaybackFinished {
+            filepath: filepath.to_string(),
+        });
+    }
+}
+
+#[tauri::command]
+pub async fn play_recording(
+    state: State<'_, AppState>,
+    recording_id: String,
+    start_offset_secs: Option<f64>,
+) -> Result<f64, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let recording = db
+        .get_recordings()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|r| r.id == recording_id)
+        .ok_or("Recording not found")?;
+
+    // Resolve through the storage roots so a recording saved under any root
+    // is found, matching `commands::audio::get_audio_data`'s resolution.
+    let absolute_path = db.resolve_media_path(&recording.filepath).map_err(|e| e.to_string())?;
+    drop(db);
+
+    let playback_sender = ensure_playback_thread_started(&state)?;
+    let (ack_tx, ack_rx) = mpsc::channel();
+    playback_sender
+        .send(PlaybackCommand::PlayRecording {
+            recording_id: recording_id.clone(),
+            filepath: absolute_path.to_string_lossy().to_string(),
+            start_offset_secs: start_offset_secs.unwrap_or(0.0),
+            ack_tx,
+        })
+        .map_err(|e| format!("Failed to send play command to playback thread: {}", e))?;
+
+    match ack_rx.recv_timeout(PLAYBACK_COMMAND_TIMEOUT) {
+        Ok(Ok(PlaybackAck::Started { duration_seconds })) => Ok(duration_seconds),
+        Ok(Ok(_)) => Err("Playback thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Playback thread did not acknowledge play command in time".to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn pause_playback(state: State<'_, AppState>) -> Result<String, String> {
+    let sender_guard = state.playback_thread_sender.lock().map_err(|e| e.to_string())?;
+    let sender = sender_guard.as_ref().ok_or("No active playback")?.clone();
+    drop(sender_guard);
+
+    let (ack_tx, ack_rx) = mpsc::channel();
+    sender
+        .send(PlaybackCommand::PausePlayback { ack_tx })
+        .map_err(|e| format!("Failed to send pause command to playback thread: {}", e))?;
+
+    match ack_rx.recv_timeout(PLAYBACK_COMMAND_TIMEOUT) {
+        Ok(Ok(PlaybackAck::Paused)) => Ok("Playback paused".to_string()),
+        Ok(Ok(_)) => Err("Playback thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Playback thread did not acknowledge pause command in time".to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn resume_playback(state: State<'_, AppState>) -> Result<String, String> {
+    let sender_guard = state.playback_thread_sender.lock().map_err(|e| e.to_string())?;
+    let sender = sender_guard.as_ref().ok_or("No active playback")?.clone();
+    drop(sender_guard);
+
+    let (ack_tx, ack_rx) = mpsc::channel();
+    sender
+        .send(PlaybackCommand::ResumePlayback { ack_tx })
+        .map_err(|e| format!("Failed to send resume command to playback thread: {}", e))?;
+
+    match ack_rx.recv_timeout(PLAYBACK_COMMAND_TIMEOUT) {
+        Ok(Ok(PlaybackAck::Resumed)) => Ok("Playback resumed".to_string()),
+        Ok(Ok(_)) => Err("Playback thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Playback thread did not acknowledge resume command in time".to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_playback(state: State<'_, AppState>) -> Result<String, String> {
+    let sender_guard = state.playback_thread_sender.lock().map_err(|e| e.to_string())?;
+    let sender = sender_guard.as_ref().ok_or("No active playback")?.clone();
+    drop(sender_guard);
+
+    let (ack_tx, ack_rx) = mpsc::channel();
+    sender
+        .send(PlaybackCommand::StopPlayback { ack_tx })
+        .map_err(|e| format!("Failed to send stop command to playback thread: {}", e))?;
+
+    match ack_rx.recv_timeout(PLAYBACK_COMMAND_TIMEOUT) {
+        Ok(Ok(PlaybackAck::Stopped)) => Ok("Playback stopped".to_string()),
+        Ok(Ok(_)) => Err("Playback thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Playback thread did not acknowledge stop command in time".to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn seek_playback(state: State<'_, AppState>, seconds: f64) -> Result<f64, String> {
+    let sender_guard = state.playback_thread_sender.lock().map_err(|e| e.to_string())?;
+    let sender = sender_guard.as_ref().ok_or("No active playback")?.clone();
+    drop(sender_guard);
+
+    let (ack_tx, ack_rx) = mpsc::channel();
+    sender
+        .send(PlaybackCommand::Seek { seconds, ack_tx })
+        .map_err(|e| format!("Failed to send seek command to playback thread: {}", e))?;
+
+    match ack_rx.recv_timeout(PLAYBACK_COMMAND_TIMEOUT) {
+        Ok(Ok(PlaybackAck::Sought { position_seconds })) => Ok(position_seconds),
+        Ok(Ok(_)) => Err("Playback thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Playback thread did not acknowledge seek command in time".to_string()),
+    }
+}
+
+/// Polled by the UI to drive a scrubber - returns `None` when nothing is playing.
+#[tauri::command]
+pub async fn get_playback_position(state: State<'_, AppState>) -> Result<Option<PlaybackPosition>, String> {
+    let position_guard = state.playback_position.lock().map_err(|e| e.to_string())?;
+    Ok(position_guard.clone())
+}
+
+/// Sets the gain applied to every sample the output callback writes, clamped
+/// to `0.0..=1.0` by [`Volume::new`]. Starts the playback thread if it isn't
+/// running yet, like `play_recording`, so a volume set before the first play
+/// is already in effect when playback starts.
+#[tauri::command]
+pub async fn set_playback_volume(state: State<'_, AppState>, volume: f32) -> Result<f32, String> {
+    let volume = Volume::new(volume);
+    let playback_sender = ensure_playback_thread_started(&state)?;
+
+    let (ack_tx, ack_rx) = mpsc::channel();
+    playback_sender
+        .send(PlaybackCommand::SetVolume { volume, ack_tx })
+        .map_err(|e| format!("Failed to send volume command to playback thread: {}", e))?;
+
+    match ack_rx.recv_timeout(PLAYBACK_COMMAND_TIMEOUT) {
+        Ok(Ok(PlaybackAck::VolumeSet { volume })) => Ok(volume),
+        Ok(Ok(_)) => Err("Playback thread sent an unexpected acknowledgement".to_string()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("Playback thread did not acknowledge volume command in time".to_string()),
+    }
+}
+
+/// Single-call transport snapshot for the UI - recording id, position,
+/// duration and paused flag - so it doesn't need to separately track which
+/// recording `get_playback_position` is reporting on.
+#[tauri::command]
+pub async fn get_playback_state(state: State<'_, AppState>) -> Result<Option<PlaybackState>, String> {
+    let position_guard = state.playback_position.lock().map_err(|e| e.to_string())?;
+    Ok(position_guard.as_ref().map(|p| PlaybackState {
+        recording_id: p.recording_id.clone(),
+        position_seconds: p.position_seconds,
+        duration_seconds: p.duration_seconds,
+        is_paused: p.is_paused,
+    }))
+}
@@ -0,0 +1,51 @@
+// Encrypted single-problem export/import (see `database::problem_bundle`).
+// Unlike `library_bundle`'s plain, file-less JSON round-trip of the whole
+// library, this moves or backs up one problem - cards, time sessions,
+// recordings, images and connections included - as an opaque encrypted blob.
+// Binary transfer over the Tauri IPC boundary uses base64, same as
+// `commands::audio`/`commands::card_images`.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use tauri::State;
+
+use crate::database::problem_bundle::BundleError;
+use crate::models::AppState;
+
+// Maps `BundleError::BadPassphrase` to a distinct sentinel so the UI can
+// prompt for re-entry instead of showing a raw error, same as
+// `commands::database::map_database_error` does for `DatabaseError`.
+fn map_bundle_error(e: anyhow::Error) -> String {
+    match e.downcast_ref::<BundleError>() {
+        Some(BundleError::BadPassphrase) => "BAD_PASSPHRASE".to_string(),
+        Some(BundleError::UnsupportedVersion(_)) => "UNSUPPORTED_BUNDLE_VERSION".to_string(),
+        None => e.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn export_problem_bundle(
+    state: State<'_, AppState>,
+    problem_id: String,
+    passphrase: String,
+) -> Result<String, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let bundle = db
+        .export_problem_bundle(&problem_id, &passphrase)
+        .map_err(map_bundle_error)?;
+    Ok(BASE64.encode(bundle))
+}
+
+#[tauri::command]
+pub async fn import_problem_bundle(
+    state: State<'_, AppState>,
+    bundle_base64: String,
+    passphrase: String,
+) -> Result<String, String> {
+    let bytes = BASE64
+        .decode(&bundle_base64)
+        .map_err(|e| format!("Failed to decode problem bundle: {}", e))?;
+
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    db.import_problem_bundle(&bytes, &passphrase).map_err(map_bundle_error)
+}
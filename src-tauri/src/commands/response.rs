@@ -0,0 +1,48 @@
+// Generic tri-state response type for Tauri commands.
+//
+// Hand-rolled `{ success, card, error }` structs don't let the frontend
+// distinguish "operation legitimately found nothing" (e.g. no solution card
+// yet) from "the app is broken" (a poisoned mutex, a SQL error). `Response<A>`
+// gives every command the same three-way shape to `switch` on instead.
+
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum Response<A> {
+    /// The operation completed and produced a value.
+    Success { content: A },
+    /// The operation completed but found nothing / couldn't proceed for an
+    /// expected reason (e.g. "no solution card for this problem").
+    Failure { content: String },
+    /// The operation failed unexpectedly (poisoned mutex, I/O, SQL error).
+    Fatal { content: String },
+}
+
+impl<A> Response<A> {
+    pub fn ok(content: A) -> Self {
+        Response::Success { content }
+    }
+
+    pub fn failure(message: impl Into<String>) -> Self {
+        Response::Failure { content: message.into() }
+    }
+
+    pub fn fatal(message: impl Into<String>) -> Self {
+        Response::Fatal { content: message.into() }
+    }
+}
+
+/// Maps a `Result<T, E>` into a `Response<T>`, treating the error as fatal.
+/// Use this for the common "just forward the DB error" case; build a
+/// `Response::failure` by hand when an `Err`/`None` is an expected outcome
+/// rather than a broken app.
+#[macro_export]
+macro_rules! result {
+    ($expr:expr) => {
+        match $expr {
+            Ok(value) => $crate::commands::response::Response::ok(value),
+            Err(e) => $crate::commands::response::Response::fatal(e.to_string()),
+        }
+    };
+}
@@ -1,6 +1,7 @@
 use tauri::State;
 use chrono::Utc;
 use crate::models::*;
+use crate::commands::response::Response;
 
 // Review Timer State - similar to TimerState but for review mode
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -16,19 +17,22 @@ pub struct ReviewTimerState {
 pub async fn start_review_timer_session(
     state: State<'_, AppState>,
     card_id: String,
-) -> Result<ReviewTimerState, String> {
+) -> Result<Response<ReviewTimerState>, String> {
     // Stop any existing review timer session
     if let Ok(mut current_review_timer) = state.current_review_timer.lock() {
         if let Some(review_timer_session) = current_review_timer.take() {
             // End the previous review session
-            let db = state.db.lock().map_err(|e| e.to_string())?;
+            let mut db = state.db.lock().map_err(|e| e.to_string())?;
             let _ = db.end_review_timer_session(&review_timer_session.id, review_timer_session.review_work_session_id.as_deref());
         }
     }
 
     // Start new review timer session
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let (session, review_work_session_id) = db.start_review_timer_session(&card_id).map_err(|e| e.to_string())?;
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    let (session, review_work_session_id) = match db.start_review_timer_session(&card_id) {
+        Ok(result) => result,
+        Err(e) => return Ok(Response::fatal(e.to_string())),
+    };
 
     // Store in current review timer state
     let review_timer_session = ReviewTimerSession {
@@ -37,6 +41,7 @@ pub async fn start_review_timer_session(
         start_time: session.start_time,
         is_paused: false,
         pause_duration: 0,
+        pause_started_at: None,
         review_work_session_id: Some(review_work_session_id),
         original_session_id: session.original_session_id,
     };
@@ -46,79 +51,83 @@ pub async fn start_review_timer_session(
         *current_review_timer = Some(review_timer_session);
     }
 
-    Ok(ReviewTimerState {
+    Ok(Response::ok(ReviewTimerState {
         is_running: true,
         is_paused: false,
         current_session_id: Some(session.id),
         session_start_time: Some(session.start_time),
         elapsed_time: 0,
-    })
+    }))
 }
 
 #[tauri::command]
-pub async fn stop_review_timer_session(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn stop_review_timer_session(state: State<'_, AppState>) -> Result<Response<()>, String> {
     let mut current_review_timer = state.current_review_timer.lock().map_err(|e| e.to_string())?;
 
     if let Some(review_timer_session) = current_review_timer.take() {
-        let db = state.db.lock().map_err(|e| e.to_string())?;
-        db.end_review_timer_session(&review_timer_session.id, review_timer_session.review_work_session_id.as_deref()).map_err(|e| e.to_string())?;
-        Ok("Review timer session stopped successfully".to_string())
+        let mut db = state.db.lock().map_err(|e| e.to_string())?;
+        Ok(match db.end_review_timer_session(&review_timer_session.id, review_timer_session.review_work_session_id.as_deref()) {
+            Ok(()) => Response::ok(()),
+            Err(e) => Response::fatal(e.to_string()),
+        })
     } else {
-        Err("No active review timer session".to_string())
+        Ok(Response::failure("No active review timer session"))
     }
 }
 
 #[tauri::command]
-pub async fn pause_review_timer_session(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn pause_review_timer_session(state: State<'_, AppState>) -> Result<Response<()>, String> {
     let mut current_review_timer = state.current_review_timer.lock().map_err(|e| e.to_string())?;
 
     if let Some(ref mut review_timer_session) = *current_review_timer {
         review_timer_session.is_paused = true;
-        Ok("Review timer session paused".to_string())
+        review_timer_session.pause_started_at = Some(state.clock.now());
+        Ok(Response::ok(()))
     } else {
-        Err("No active review timer session".to_string())
+        Ok(Response::failure("No active review timer session"))
     }
 }
 
 #[tauri::command]
-pub async fn resume_review_timer_session(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn resume_review_timer_session(state: State<'_, AppState>) -> Result<Response<()>, String> {
     let mut current_review_timer = state.current_review_timer.lock().map_err(|e| e.to_string())?;
 
     if let Some(ref mut review_timer_session) = *current_review_timer {
+        if let Some(pause_started_at) = review_timer_session.pause_started_at.take() {
+            review_timer_session.pause_duration += (state.clock.now() - pause_started_at).num_seconds() as i32;
+        }
         review_timer_session.is_paused = false;
-        Ok("Review timer session resumed".to_string())
+        Ok(Response::ok(()))
     } else {
-        Err("No active review timer session".to_string())
+        Ok(Response::failure("No active review timer session"))
     }
 }
 
 #[tauri::command]
-pub async fn get_review_timer_state(state: State<'_, AppState>) -> Result<ReviewTimerState, String> {
+pub async fn get_review_timer_state(state: State<'_, AppState>) -> Result<Response<ReviewTimerState>, String> {
     let current_review_timer = state.current_review_timer.lock().map_err(|e| e.to_string())?;
 
     if let Some(ref review_timer_session) = *current_review_timer {
-        let now = Utc::now();
-        let elapsed_time = if review_timer_session.is_paused {
-            review_timer_session.pause_duration
-        } else {
-            (now - review_timer_session.start_time).num_seconds() as i32 - review_timer_session.pause_duration
-        };
-
-        Ok(ReviewTimerState {
+        // While paused, freeze elapsed at the moment the pause began instead
+        // of continuing to advance with `now`.
+        let now = review_timer_session.pause_started_at.unwrap_or_else(|| state.clock.now());
+        let elapsed_time = (now - review_timer_session.start_time).num_seconds() as i32 - review_timer_session.pause_duration;
+
+        Ok(Response::ok(ReviewTimerState {
             is_running: true,
             is_paused: review_timer_session.is_paused,
             current_session_id: Some(review_timer_session.id.clone()),
             session_start_time: Some(review_timer_session.start_time),
             elapsed_time: elapsed_time.max(0),
-        })
+        }))
     } else {
-        Ok(ReviewTimerState {
+        Ok(Response::ok(ReviewTimerState {
             is_running: false,
             is_paused: false,
             current_session_id: None,
             session_start_time: None,
             elapsed_time: 0,
-        })
+        }))
     }
 }
 
@@ -126,17 +135,16 @@ pub async fn get_review_timer_state(state: State<'_, AppState>) -> Result<Review
 pub async fn get_card_review_sessions(
     state: State<'_, AppState>,
     card_id: String,
-) -> Result<Vec<ReviewSession>, String> {
+) -> Result<Response<Vec<ReviewSession>>, String> {
     let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_card_review_sessions(&card_id).map_err(|e| e.to_string())
+    Ok(crate::result!(db.get_card_review_sessions(&card_id)))
 }
 
 #[tauri::command]
 pub async fn delete_review_session(
     state: State<'_, AppState>,
     session_id: String,
-) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.delete_review_session(&session_id).map_err(|e| e.to_string())?;
-    Ok("Review session deleted successfully".to_string())
+) -> Result<Response<()>, String> {
+    let mut db = state.db.lock().map_err(|e| e.to_string())?;
+    Ok(crate::result!(db.delete_review_session(&session_id)))
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use crate::models::AppState;
+use crate::storage::{self, StorageUsageReport};
+use tauri::State;
+
+#[tauri::command]
+pub async fn compute_storage_usage(state: State<'_, AppState>) -> Result<StorageUsageReport, String> {
+    let recordings_dir = state.path_resolver.get_recordings_dir();
+    let images_dir = state.path_resolver.get_images_dir();
+
+    // The scan spawns its own worker threads and joins them before returning, so run
+    // it on a blocking task instead of stalling the async command on a long walk.
+    tokio::task::spawn_blocking(move || storage::compute_storage_usage(&recordings_dir, &images_dir))
+        .await
+        .map_err(|e| format!("Storage scan task failed: {}", e))
+}
@@ -1,9 +1,10 @@
-use tauri::State;
-use chrono::Utc;
+use tauri::{AppHandle, State};
 use crate::models::*;
+use crate::events::{self, AppEvent};
 
 #[tauri::command]
 pub async fn start_timer_session(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     card_id: String,
 ) -> Result<TimerState, String> {
@@ -13,13 +14,14 @@ pub async fn start_timer_session(
             // End the previous session
             let mut db = state.db.lock().map_err(|e| e.to_string())?;
             let _ = db.end_timer_session(&timer_session.id, timer_session.work_session_id.as_deref());
+            let _ = db.clear_timer_session_snapshot();
         }
     }
 
     // Start new timer session
     let mut db = state.db.lock().map_err(|e| e.to_string())?;
     let (session, work_session_id) = db.start_timer_session(&card_id).map_err(|e| e.to_string())?;
-    
+
     // Store in current timer state
     let timer_session = TimerSession {
         id: session.id.clone(),
@@ -27,14 +29,22 @@ pub async fn start_timer_session(
         start_time: session.start_time,
         is_paused: false,
         pause_duration: 0,
+        pause_started_at: None,
         work_session_id: Some(work_session_id),
     };
-    
+
+    db.save_timer_session_snapshot(&timer_session).map_err(|e| e.to_string())?;
+
     {
         let mut current_timer = state.current_timer.lock().map_err(|e| e.to_string())?;
         *current_timer = Some(timer_session);
     }
-    
+
+    events::emit(&app_handle, AppEvent::TimerTick {
+        session_id: session.id.clone(),
+        elapsed_time: 0,
+    });
+
     Ok(TimerState {
         is_running: true,
         is_paused: false,
@@ -47,10 +57,11 @@ pub async fn start_timer_session(
 #[tauri::command]
 pub async fn stop_timer_session(state: State<'_, AppState>) -> Result<String, String> {
     let mut current_timer = state.current_timer.lock().map_err(|e| e.to_string())?;
-    
+
     if let Some(timer_session) = current_timer.take() {
         let mut db = state.db.lock().map_err(|e| e.to_string())?;
         db.end_timer_session(&timer_session.id, timer_session.work_session_id.as_deref()).map_err(|e| e.to_string())?;
+        db.clear_timer_session_snapshot().map_err(|e| e.to_string())?;
         Ok("Timer session stopped successfully".to_string())
     } else {
         Err("No active timer session".to_string())
@@ -58,11 +69,22 @@ pub async fn stop_timer_session(state: State<'_, AppState>) -> Result<String, St
 }
 
 #[tauri::command]
-pub async fn pause_timer_session(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn pause_timer_session(app_handle: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
     let mut current_timer = state.current_timer.lock().map_err(|e| e.to_string())?;
-    
+
     if let Some(ref mut timer_session) = *current_timer {
+        let pause_started_at = state.clock.now();
         timer_session.is_paused = true;
+        timer_session.pause_started_at = Some(pause_started_at);
+
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        db.save_timer_session_snapshot(timer_session).map_err(|e| e.to_string())?;
+
+        let elapsed_time = (pause_started_at - timer_session.start_time).num_seconds() as i32 - timer_session.pause_duration;
+        events::emit(&app_handle, AppEvent::TimerTick {
+            session_id: timer_session.id.clone(),
+            elapsed_time,
+        });
         Ok("Timer session paused".to_string())
     } else {
         Err("No active timer session".to_string())
@@ -70,11 +92,23 @@ pub async fn pause_timer_session(state: State<'_, AppState>) -> Result<String, S
 }
 
 #[tauri::command]
-pub async fn resume_timer_session(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn resume_timer_session(app_handle: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
     let mut current_timer = state.current_timer.lock().map_err(|e| e.to_string())?;
-    
+
     if let Some(ref mut timer_session) = *current_timer {
+        if let Some(pause_started_at) = timer_session.pause_started_at.take() {
+            timer_session.pause_duration += (state.clock.now() - pause_started_at).num_seconds() as i32;
+        }
         timer_session.is_paused = false;
+
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        db.save_timer_session_snapshot(timer_session).map_err(|e| e.to_string())?;
+
+        let elapsed_time = (state.clock.now() - timer_session.start_time).num_seconds() as i32 - timer_session.pause_duration;
+        events::emit(&app_handle, AppEvent::TimerTick {
+            session_id: timer_session.id.clone(),
+            elapsed_time,
+        });
         Ok("Timer session resumed".to_string())
     } else {
         Err("No active timer session".to_string())
@@ -84,21 +118,19 @@ pub async fn resume_timer_session(state: State<'_, AppState>) -> Result<String,
 #[tauri::command]
 pub async fn get_timer_state(state: State<'_, AppState>) -> Result<TimerState, String> {
     let current_timer = state.current_timer.lock().map_err(|e| e.to_string())?;
-    
+
     if let Some(ref timer_session) = *current_timer {
-        let now = Utc::now();
-        let elapsed_time = if timer_session.is_paused {
-            timer_session.pause_duration
-        } else {
-            (now - timer_session.start_time).num_seconds() as i32 - timer_session.pause_duration
-        };
-        
+        // While paused, freeze elapsed at the moment the pause began instead
+        // of continuing to advance with `now`.
+        let now = timer_session.pause_started_at.unwrap_or_else(|| state.clock.now());
+        let elapsed_time = (now - timer_session.start_time).num_seconds() as i32 - timer_session.pause_duration;
+
         Ok(TimerState {
             is_running: true,
             is_paused: timer_session.is_paused,
             current_session_id: Some(timer_session.id.clone()),
             session_start_time: Some(timer_session.start_time),
-            elapsed_time,
+            elapsed_time: elapsed_time.max(0),
         })
     } else {
         Ok(TimerState {
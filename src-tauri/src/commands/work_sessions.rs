@@ -12,7 +12,7 @@ pub async fn get_work_sessions_date_range(
     
     let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
-    match db.get_work_sessions_by_date_range(&request.start_date, &request.end_date) {
+    match db.get_work_sessions_by_date_range(&request.start_date, &request.end_date, &request.filter) {
         Ok(sessions) => {
             println!("✅ [API] Retrieved {} work sessions", sessions.len());
             Ok(sessions)
@@ -144,7 +144,7 @@ pub async fn get_daily_aggregates(
     
     let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
-    match db.get_daily_aggregates(&request.start_date, &request.end_date) {
+    match db.get_daily_aggregates(&request.start_date, &request.end_date, &request.filter) {
         Ok(aggregates) => {
             println!("✅ [API] Retrieved {} daily aggregates", aggregates.len());
             Ok(aggregates)
@@ -167,7 +167,7 @@ pub async fn get_productivity_by_hour(
     
     let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
-    match db.get_productivity_by_hour(days) {
+    match db.get_productivity_by_hour(days, &request.filter) {
         Ok(breakdown) => {
             println!("✅ [API] Retrieved productivity breakdown with {} hour slots", breakdown.len());
             Ok(breakdown)
@@ -214,7 +214,7 @@ pub async fn get_most_worked_problem(
     
     let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
-    match db.get_most_worked_problem(&request.start_date, &request.end_date) {
+    match db.get_most_worked_problem(&request.start_date, &request.end_date, &request.filter) {
         Ok(problem) => {
             match &problem {
                 Some(p) => println!("✅ [API] Most worked problem: '{}' with {} seconds", p.problem_title, p.total_duration_seconds),
@@ -229,6 +229,137 @@ pub async fn get_most_worked_problem(
     }
 }
 
+/// Retroactively correct a work session's start/end timestamps and/or the
+/// card it's attributed to (e.g. a timer left running overnight, or started
+/// on the wrong card).
+#[tauri::command]
+pub async fn edit_work_session(
+    app_state: State<'_, AppState>,
+    request: EditWorkSessionRequest,
+) -> Result<WorkSession, String> {
+    println!("🔍 [API] Editing work session {}", request.session_id);
+
+    let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    match db.edit_work_session(&request) {
+        Ok(session) => {
+            println!("✅ [API] Updated work session {}", session.id);
+            Ok(session)
+        }
+        Err(e) => {
+            println!("❌ [API] Error editing work session: {}", e);
+            Err(format!("Failed to edit work session: {}", e))
+        }
+    }
+}
+
+/// Delete a work session, e.g. one started by accident.
+#[tauri::command]
+pub async fn delete_work_session(
+    app_state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), String> {
+    println!("🔍 [API] Deleting work session {}", session_id);
+
+    let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    match db.delete_work_session(&session_id) {
+        Ok(()) => {
+            println!("✅ [API] Deleted work session {}", session_id);
+            Ok(())
+        }
+        Err(e) => {
+            println!("❌ [API] Error deleting work session: {}", e);
+            Err(format!("Failed to delete work session: {}", e))
+        }
+    }
+}
+
+/// Break one long work session into two at a given timestamp.
+#[tauri::command]
+pub async fn split_work_session(
+    app_state: State<'_, AppState>,
+    request: SplitWorkSessionRequest,
+) -> Result<(WorkSession, WorkSession), String> {
+    println!("🔍 [API] Splitting work session {} at {}", request.session_id, request.split_at);
+
+    let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    match db.split_work_session(&request) {
+        Ok(sessions) => {
+            println!("✅ [API] Split work session {} into {} and {}", request.session_id, sessions.0.id, sessions.1.id);
+            Ok(sessions)
+        }
+        Err(e) => {
+            println!("❌ [API] Error splitting work session: {}", e);
+            Err(format!("Failed to split work session: {}", e))
+        }
+    }
+}
+
+/// Save a reusable `WorkSessionFilter` preset under `request.name`.
+#[tauri::command]
+pub async fn save_filter(
+    app_state: State<'_, AppState>,
+    request: SaveFilterRequest,
+) -> Result<SavedFilter, String> {
+    println!("🔍 [API] Saving filter preset '{}'", request.name);
+
+    let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    match db.save_filter(&request.name, &request.filter) {
+        Ok(filter) => {
+            println!("✅ [API] Saved filter preset '{}'", filter.name);
+            Ok(filter)
+        }
+        Err(e) => {
+            println!("❌ [API] Error saving filter: {}", e);
+            Err(format!("Failed to save filter: {}", e))
+        }
+    }
+}
+
+/// List saved filter presets, most recently created first.
+#[tauri::command]
+pub async fn list_filters(app_state: State<'_, AppState>) -> Result<Vec<SavedFilter>, String> {
+    println!("🔍 [API] Listing filter presets");
+
+    let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    match db.list_filters() {
+        Ok(filters) => {
+            println!("✅ [API] Retrieved {} filter presets", filters.len());
+            Ok(filters)
+        }
+        Err(e) => {
+            println!("❌ [API] Error listing filters: {}", e);
+            Err(format!("Failed to list filters: {}", e))
+        }
+    }
+}
+
+/// Delete a saved filter preset.
+#[tauri::command]
+pub async fn delete_filter(
+    app_state: State<'_, AppState>,
+    filter_id: String,
+) -> Result<(), String> {
+    println!("🔍 [API] Deleting filter preset {}", filter_id);
+
+    let db = app_state.db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    match db.delete_filter(&filter_id) {
+        Ok(()) => {
+            println!("✅ [API] Deleted filter preset {}", filter_id);
+            Ok(())
+        }
+        Err(e) => {
+            println!("❌ [API] Error deleting filter: {}", e);
+            Err(format!("Failed to delete filter: {}", e))
+        }
+    }
+}
+
 // Internal functions for session management (called by timer commands)
 
 /// Create a new work session (internal function)
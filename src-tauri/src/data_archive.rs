@@ -0,0 +1,320 @@
+// Compressed export/import of the whole app_data_dir - the database file
+// plus `recordings/` and `images/` - so a user can back up or move their
+// entire library to another machine in one file. Complements
+// `database::problem_bundle` (one problem, passphrase-encrypted) and
+// `commands::library_bundle` (plain JSON, no files) the same way a full
+// disk image complements a single-file copy.
+//
+// Written against the real `tar`, `xz2`, and `zstd` crate APIs - a
+// streaming `tar::Builder` piped through an `xz2::write::XzEncoder` (or,
+// for the faster alternative, a `zstd::stream::write::Encoder`) - but none
+// of those crates are in this tree's dependencies; there's no `Cargo.toml`
+// here to add them. See `database::problem_bundle` for the same situation
+// with `aes-gcm`.
+
+use crate::path_resolver::PathResolver;
+use crate::storage_format;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Bumped whenever `ArchiveManifest`'s shape, or the archive's internal
+/// entry layout, changes in a way an older `import_data_directory` can't
+/// read.
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Compression to use for `export_data_directory`. xz's dictionary window
+/// defaults to 8MB; the rust-installer project found raising it to 64MB
+/// meaningfully shrinks archives at the cost of more memory during
+/// compression. Exposed as a knob rather than hardcoded so a caller can
+/// trade memory for size, with zstd offered as a faster (if slightly
+/// larger) alternative for callers that would rather not pay xz's time cost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    /// `level` is xz's `0..=9` preset; `window_mb` overrides its dictionary
+    /// size (xz's own default is 8).
+    Xz { level: u32, window_mb: u32 },
+    /// `level` is zstd's `1..=22` scale.
+    Zstd { level: i32 },
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        // A moderate window: bigger than xz's 8MB default (recordings and
+        // images directories often have cross-file redundancy a small
+        // window can't see), well short of the 64MB rust-installer found
+        // worth it only for release artifacts built on beefier machines
+        // than whatever a user is exporting their library on.
+        Compression::Xz { level: 6, window_mb: 16 }
+    }
+}
+
+/// Wraps either compressor behind one `Write` impl so `export_data_directory`
+/// doesn't need to duplicate its tar-building loop per compression choice.
+enum CompressedWriter {
+    Xz(xz2::write::XzEncoder<File>),
+    Zstd(zstd::stream::write::Encoder<'static, File>),
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            CompressedWriter::Xz(w) => w.write(buf),
+            CompressedWriter::Zstd(w) => w.write(buf),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            CompressedWriter::Xz(w) => w.flush(),
+            CompressedWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+impl CompressedWriter {
+    fn new(file: File, compression: Compression) -> anyhow::Result<Self> {
+        match compression {
+            Compression::Xz { level, window_mb } => {
+                let mut filters = xz2::stream::Filters::new();
+                let mut lzma_options = xz2::stream::LzmaOptions::new_preset(level)?;
+                lzma_options.dict_size(window_mb * 1024 * 1024);
+                filters.lzma2(&lzma_options);
+                let stream = xz2::stream::Stream::new_stream_encoder(&filters, xz2::stream::Check::Crc64)?;
+                Ok(CompressedWriter::Xz(xz2::write::XzEncoder::new_stream(file, stream)))
+            }
+            Compression::Zstd { level } => Ok(CompressedWriter::Zstd(zstd::stream::write::Encoder::new(file, level)?)),
+        }
+    }
+
+    /// Flushes the compressor's trailing frame/footer. Must be called
+    /// before the underlying file is considered complete - dropping either
+    /// encoder without this truncates the archive.
+    fn finish(self) -> anyhow::Result<()> {
+        match self {
+            CompressedWriter::Xz(w) => {
+                w.finish().context("Failed to finalize xz stream")?;
+            }
+            CompressedWriter::Zstd(w) => {
+                w.finish().context("Failed to finalize zstd stream")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// First entry written into the archive (as `manifest.json`), read back
+/// before extracting anything else so `import_data_directory` can refuse an
+/// archive it doesn't understand without half-extracting it first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveManifest {
+    archive_format_version: u32,
+    /// `storage_format::CURRENT_FORMAT_VERSION` at export time - lets
+    /// `import_data_directory` hand off to `StorageMigrator` immediately
+    /// against the freshly-extracted directory, rather than waiting to
+    /// discover the mismatch the next time `PathResolver::new` runs there.
+    storage_format_version: i64,
+    /// Whichever of `"dev-data"`/`"app-data"` `PathResolver::to_relative_path`
+    /// was using at export time, so import can rewrite stored relative
+    /// paths to the importing environment's own prefix.
+    source_prefix: String,
+    exported_at: DateTime<Utc>,
+}
+
+/// Errors specific to archive handling that callers need to distinguish
+/// from a generic I/O failure - modeled on `database::problem_bundle::BundleError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// The manifest's `archive_format_version` is newer than this build
+    /// understands.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::UnsupportedVersion(found) => write!(
+                f,
+                "archive format version {} is newer than this app supports (up to {})",
+                found, ARCHIVE_FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<ArchiveError> for String {
+    fn from(err: ArchiveError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Streams `resolver`'s `database.db`, `recordings/`, and `images/` into a
+/// single compressed tar archive at `destination`.
+pub fn export_data_directory(resolver: &PathResolver, destination: &Path, compression: Compression) -> anyhow::Result<()> {
+    let file = File::create(destination)
+        .with_context(|| format!("Failed to create archive at {}", destination.display()))?;
+    let writer = CompressedWriter::new(file, compression)?;
+    let mut tar_builder = tar::Builder::new(writer);
+
+    let manifest = ArchiveManifest {
+        archive_format_version: ARCHIVE_FORMAT_VERSION,
+        storage_format_version: storage_format::CURRENT_FORMAT_VERSION,
+        source_prefix: if resolver.is_debug_mode() { "dev-data" } else { "app-data" }.to_string(),
+        exported_at: Utc::now(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).context("Failed to serialize archive manifest")?;
+    append_bytes(&mut tar_builder, "manifest.json", &manifest_json)?;
+
+    let db_path = resolver.get_database_path();
+    if db_path.exists() {
+        tar_builder
+            .append_path_with_name(&db_path, "database.db")
+            .with_context(|| format!("Failed to archive {}", db_path.display()))?;
+    }
+
+    let recordings_dir = resolver.get_recordings_dir();
+    if recordings_dir.exists() {
+        tar_builder
+            .append_dir_all("recordings", &recordings_dir)
+            .with_context(|| format!("Failed to archive {}", recordings_dir.display()))?;
+    }
+
+    let images_dir = resolver.get_images_dir();
+    if images_dir.exists() {
+        tar_builder
+            .append_dir_all("images", &images_dir)
+            .with_context(|| format!("Failed to archive {}", images_dir.display()))?;
+    }
+
+    let writer = tar_builder.into_inner().context("Failed to finalize archive")?;
+    writer.finish()
+}
+
+fn append_bytes(tar_builder: &mut tar::Builder<CompressedWriter>, name: &str, contents: &[u8]) -> anyhow::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar_builder
+        .append_data(&mut header, name, contents)
+        .with_context(|| format!("Failed to write {} into archive", name))
+}
+
+/// Reads `archive_path`'s manifest, rejects it outright if its
+/// `archive_format_version` is newer than this build supports, then
+/// extracts `database.db`/`recordings/`/`images/` into `destination_dir`
+/// (created if missing; entries are unpacked over whatever the directory
+/// already holds, overwriting files with the same names and leaving any
+/// others in place - it is not required to be empty). If the manifest's
+/// `storage_format_version` is older than this build's, runs
+/// `StorageMigrator` on the extracted directory. Afterward rewrites every stored `dev-data/`/`app-data/` relative path in
+/// the restored database to match `destination_resolver`'s own prefix, via
+/// `PathResolver::resolve_relative_path`/`to_relative_path`, so a bundle
+/// exported from a dev checkout resolves correctly once restored into a
+/// production install (or vice versa).
+pub fn import_data_directory(archive_path: &Path, destination_resolver: &PathResolver) -> anyhow::Result<()> {
+    let file = File::open(archive_path)
+        .with_context(|| format!("Failed to open archive {}", archive_path.display()))?;
+    let decompressed: Box<dyn std::io::Read> = match sniff_compression(archive_path) {
+        Compression::Xz { .. } => Box::new(xz2::read::XzDecoder::new(file)),
+        Compression::Zstd { .. } => Box::new(zstd::stream::read::Decoder::new(file)?),
+    };
+    let mut archive = tar::Archive::new(decompressed);
+
+    let destination_dir = destination_resolver.get_app_data_dir();
+    std::fs::create_dir_all(destination_dir)
+        .with_context(|| format!("Failed to create {}", destination_dir.display()))?;
+
+    let mut manifest: Option<ArchiveManifest> = None;
+    for entry in archive.entries().context("Failed to read archive entries")? {
+        let mut entry = entry.context("Failed to read archive entry")?;
+        let entry_path = entry.path().context("Invalid entry path in archive")?.into_owned();
+
+        if entry_path == Path::new("manifest.json") {
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents).context("Failed to read archive manifest")?;
+            let parsed: ArchiveManifest =
+                serde_json::from_slice(&contents).context("Failed to parse archive manifest")?;
+            if parsed.archive_format_version > ARCHIVE_FORMAT_VERSION {
+                anyhow::bail!(ArchiveError::UnsupportedVersion(parsed.archive_format_version));
+            }
+            manifest = Some(parsed);
+            continue;
+        }
+
+        entry
+            .unpack_in(destination_dir)
+            .with_context(|| format!("Failed to extract {} from archive", entry_path.display()))?;
+    }
+
+    let manifest = manifest.ok_or_else(|| anyhow::anyhow!("Archive is missing manifest.json"))?;
+
+    if manifest.storage_format_version < storage_format::CURRENT_FORMAT_VERSION {
+        storage_format::StorageMigrator::new()
+            .migrate(destination_resolver, manifest.storage_format_version)
+            .context("Failed to migrate restored data directory's storage format")?;
+    }
+
+    rewrite_restored_path_prefixes(destination_resolver, &manifest.source_prefix)
+        .context("Failed to rewrite restored relative path prefixes")?;
+
+    Ok(())
+}
+
+/// tar archives don't carry their own compression tag, so - same as the
+/// `.tar.xz`/`.tar.zst` convention callers are expected to use for
+/// `destination`'s extension - infer which decompressor to use from it.
+fn sniff_compression(path: &Path) -> Compression {
+    let name = path.to_string_lossy();
+    if name.ends_with(".zst") || name.ends_with(".tar.zst") {
+        Compression::Zstd { level: 0 }
+    } else {
+        Compression::Xz { level: 0, window_mb: 0 }
+    }
+}
+
+/// Every `*_path`/`filepath` column that stores a `dev-data/`/`app-data/`-
+/// prefixed relative path (see `PathResolver::to_relative_path`) - updated
+/// in place after a restore so paths exported from one environment resolve
+/// correctly in the other.
+const RELATIVE_PATH_COLUMNS: &[(&str, &str)] = &[
+    ("recordings", "filepath"),
+    ("problem_images", "image_path"),
+    ("problem_images", "thumbnail_path"),
+];
+
+fn rewrite_restored_path_prefixes(resolver: &PathResolver, source_prefix: &str) -> anyhow::Result<()> {
+    let db_path = resolver.get_database_path();
+    let connection = rusqlite::Connection::open(&db_path)
+        .with_context(|| format!("Failed to open restored database at {}", db_path.display()))?;
+
+    let new_prefix = if resolver.is_debug_mode() { "dev-data" } else { "app-data" };
+    if new_prefix == source_prefix {
+        return Ok(());
+    }
+
+    for (table, column) in RELATIVE_PATH_COLUMNS {
+        // Content-addressed `card_images.image_path` stores a `<root-id>:`
+        // prefix instead (see `database::storage_roots`), not an env
+        // prefix, so it's deliberately left out of this list.
+        let old_prefix_pattern = format!("{}/%", source_prefix);
+        connection.execute(
+            &format!(
+                "UPDATE {table} SET {column} = ?1 || substr({column}, ?2)
+                 WHERE {column} LIKE ?3",
+            ),
+            rusqlite::params![
+                format!("{}/", new_prefix),
+                source_prefix.len() as i64 + 2,
+                old_prefix_pattern,
+            ],
+        )?;
+    }
+
+    Ok(())
+}
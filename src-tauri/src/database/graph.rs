@@ -0,0 +1,188 @@
+// Breadth-first traversal over the `problem_relations` edge set, for
+// "explore a cluster" and "shortest learning path" queries that
+// `get_related_problems`'s one-hop join can't answer. The relation table is
+// small enough to load wholesale, so every function here builds the
+// adjacency map in a single query rather than re-querying per visited node.
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Hard ceiling on how many nodes a single traversal will visit, independent
+/// of whatever `max_nodes` a caller passes in - a safety net against a
+/// pathological relation graph turning one request into an unbounded scan.
+pub const MAX_TRAVERSAL_NODES: usize = 5000;
+
+/// Loads every `problem_relations` edge into an adjacency map keyed by
+/// problem id. `add_problem_relation` (and the reciprocal-edge trigger it
+/// relies on) already store both directions of a relation as separate rows,
+/// so this is a direct edge list - no symmetrizing needed here.
+fn load_adjacency(conn: &Connection) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let mut stmt = conn.prepare("SELECT problem_id, related_problem_id FROM problem_relations")?;
+    let edges = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut adjacency: HashMap<String, Vec<String>> = HashMap::new();
+    for (from, to) in edges {
+        adjacency.entry(from).or_default().push(to);
+    }
+    Ok(adjacency)
+}
+
+/// Breadth-first expansion from `problem_id` out to `depth` hops, capped at
+/// `max_nodes` visited problems (and always at `MAX_TRAVERSAL_NODES`).
+/// Returns `(problem_id, hop_distance)` pairs in discovery order (closest
+/// first); the starting problem itself is not included.
+pub fn related_within(
+    conn: &Connection,
+    problem_id: &str,
+    depth: i32,
+    max_nodes: usize,
+) -> anyhow::Result<Vec<(String, i32)>> {
+    let adjacency = load_adjacency(conn)?;
+    let node_cap = max_nodes.min(MAX_TRAVERSAL_NODES);
+
+    let mut visited: HashSet<String> = HashSet::new();
+    visited.insert(problem_id.to_string());
+
+    let mut queue: VecDeque<(String, i32)> = VecDeque::new();
+    queue.push_back((problem_id.to_string(), 0));
+
+    let mut result = Vec::new();
+    while let Some((current, hop)) = queue.pop_front() {
+        if hop >= depth || result.len() >= node_cap {
+            continue;
+        }
+        let Some(neighbors) = adjacency.get(&current) else {
+            continue;
+        };
+        for neighbor in neighbors {
+            if result.len() >= node_cap {
+                break;
+            }
+            if visited.insert(neighbor.clone()) {
+                result.push((neighbor.clone(), hop + 1));
+                queue.push_back((neighbor.clone(), hop + 1));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Shortest path (by edge count) from `from_id` to `to_id`, as an ordered
+/// list of problem ids including both endpoints. Empty if the two problems
+/// are not connected by any chain of relations.
+pub fn shortest_path(conn: &Connection, from_id: &str, to_id: &str) -> anyhow::Result<Vec<String>> {
+    if from_id == to_id {
+        return Ok(vec![from_id.to_string()]);
+    }
+
+    let adjacency = load_adjacency(conn)?;
+
+    let mut visited: HashSet<String> = HashSet::new();
+    visited.insert(from_id.to_string());
+    let mut predecessor: HashMap<String, String> = HashMap::new();
+
+    let mut queue: VecDeque<String> = VecDeque::new();
+    queue.push_back(from_id.to_string());
+
+    let mut found = false;
+    'bfs: while let Some(current) = queue.pop_front() {
+        let Some(neighbors) = adjacency.get(&current) else {
+            continue;
+        };
+        for neighbor in neighbors {
+            if visited.insert(neighbor.clone()) {
+                predecessor.insert(neighbor.clone(), current.clone());
+                if neighbor == to_id {
+                    found = true;
+                    break 'bfs;
+                }
+                queue.push_back(neighbor.clone());
+            }
+        }
+    }
+
+    if !found {
+        return Ok(Vec::new());
+    }
+
+    let mut path = vec![to_id.to_string()];
+    let mut node = to_id.to_string();
+    while let Some(prev) = predecessor.get(&node) {
+        path.push(prev.clone());
+        if prev == from_id {
+            break;
+        }
+        node = prev.clone();
+    }
+    path.reverse();
+    Ok(path)
+}
+
+/// Recommendation scoring: within `depth` hops of `problem_id`, ranks
+/// candidates by how many distinct shortest paths reach them (the classic
+/// BFS "count of shortest paths" computation) - a problem reachable via
+/// several short relation chains is more central to the cluster than one
+/// hanging off a single edge, so it surfaces first. Returns
+/// `(problem_id, hop_distance, path_count)`, sorted by `path_count`
+/// descending then `hop_distance` ascending.
+pub fn recommend_related(
+    conn: &Connection,
+    problem_id: &str,
+    depth: i32,
+    max_nodes: usize,
+) -> anyhow::Result<Vec<(String, i32, i64)>> {
+    let adjacency = load_adjacency(conn)?;
+    let node_cap = max_nodes.min(MAX_TRAVERSAL_NODES);
+
+    let mut distance: HashMap<String, i32> = HashMap::new();
+    let mut path_count: HashMap<String, i64> = HashMap::new();
+    distance.insert(problem_id.to_string(), 0);
+    path_count.insert(problem_id.to_string(), 1);
+
+    let mut queue: VecDeque<String> = VecDeque::new();
+    queue.push_back(problem_id.to_string());
+    let mut visited_count = 1usize;
+
+    while let Some(current) = queue.pop_front() {
+        let current_distance = distance[&current];
+        if current_distance >= depth {
+            continue;
+        }
+        let Some(neighbors) = adjacency.get(&current) else {
+            continue;
+        };
+        let current_paths = path_count[&current];
+        for neighbor in neighbors {
+            match distance.get(neighbor).copied() {
+                None => {
+                    if visited_count >= node_cap {
+                        continue;
+                    }
+                    distance.insert(neighbor.clone(), current_distance + 1);
+                    path_count.insert(neighbor.clone(), current_paths);
+                    visited_count += 1;
+                    queue.push_back(neighbor.clone());
+                }
+                Some(existing_distance) if existing_distance == current_distance + 1 => {
+                    *path_count.get_mut(neighbor).unwrap() += current_paths;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut ranked: Vec<(String, i32, i64)> = distance
+        .into_iter()
+        .filter(|(id, _)| id != problem_id)
+        .map(|(id, hop)| {
+            let count = path_count[&id];
+            (id, hop, count)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.1.cmp(&b.1)).then_with(|| a.0.cmp(&b.0)));
+    Ok(ranked)
+}
@@ -0,0 +1,78 @@
+// Secondary-index management beyond what the migration registry owns.
+// `database/migrations.rs` creates the indexes every install needs from day
+// one, as part of the schema itself. This module is for indexes that exist
+// to make a *specific query shape* sargable - expression indexes mirroring a
+// `WHERE LOWER(col) LIKE ?` predicate, for instance - plus a small
+// create/drop API so those can be provisioned or torn down for maintenance
+// without a full `rebuild_indexes()` pass.
+
+use rusqlite::Connection;
+
+/// One index `ensure_indexes` is responsible for keeping present. `columns`
+/// is the raw expression inside the index's parentheses, so it can be a
+/// plain column list (`"card_id, is_solution"`) or an expression
+/// (`"LOWER(title)"`).
+pub struct ManagedIndex {
+    pub name: &'static str,
+    pub table: &'static str,
+    pub columns: &'static str,
+}
+
+// `search_problems_by_title`/`search_problems_by_topic` wrap their column in
+// `LOWER(...)`, which an ordinary index on that column can't serve - SQLite
+// needs an index on the expression itself to avoid a full table scan.
+// `idx_cards_problem_is_solution` speeds `get_solution_card`/
+// `solution_card_exists`, which both filter on exactly that pair.
+pub const MANAGED_INDEXES: &[ManagedIndex] = &[
+    ManagedIndex {
+        name: "idx_problems_title_lower",
+        table: "problems",
+        columns: "LOWER(title)",
+    },
+    ManagedIndex {
+        name: "idx_problems_topic_lower",
+        table: "problems",
+        columns: "LOWER(topic)",
+    },
+    ManagedIndex {
+        name: "idx_cards_problem_is_solution",
+        table: "cards",
+        columns: "problem_id, is_solution",
+    },
+    // Every listing/search query now filters on `deleted_at IS NULL`, so this
+    // keeps the recycle bin's soft-delete filter from degrading into a scan.
+    ManagedIndex {
+        name: "idx_problems_deleted_at",
+        table: "problems",
+        columns: "deleted_at",
+    },
+];
+
+/// Idempotently provisions every index in `MANAGED_INDEXES`. Safe to call on
+/// every startup - each one is `CREATE INDEX IF NOT EXISTS`.
+pub fn ensure_indexes(conn: &Connection) -> anyhow::Result<()> {
+    for index in MANAGED_INDEXES {
+        create_index(conn, index.table, index.name, index.columns)?;
+    }
+    Ok(())
+}
+
+/// Creates `name` on `table(columns)` if it doesn't already exist. `columns`
+/// is interpolated as-is (it may be an expression, not just a column list),
+/// so callers must only pass trusted, internally-defined strings - never
+/// user input.
+pub fn create_index(conn: &Connection, table: &str, name: &str, columns: &str) -> anyhow::Result<()> {
+    conn.execute_batch(&format!(
+        "CREATE INDEX IF NOT EXISTS {} ON {}({})",
+        name, table, columns
+    ))?;
+    Ok(())
+}
+
+/// Drops `name` if it exists. Works on any index, not just ones in
+/// `MANAGED_INDEXES` - this is the same "any index, by name" contract
+/// `rebuild_indexes` already uses when it tears everything down.
+pub fn drop_index(conn: &Connection, name: &str) -> anyhow::Result<()> {
+    conn.execute(&format!("DROP INDEX IF EXISTS {}", name), [])?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+// Durable background job queue, modeled on the same "survives a restart"
+// requirement as the `recordings`/`time_sessions` tables themselves: a job
+// enqueued by a command and picked up by the worker thread in `main.rs` is
+// tracked entirely in the `jobs` table, so a crash or update between enqueue
+// and execution just leaves it `queued` for the next run to find, rather than
+// losing it the way an in-memory channel or task handle would.
+
+use chrono::{DateTime, Utc};
+
+/// A unit of background work. `payload` is job-type-specific JSON, the same
+/// way `problems.examples`/`problems.hints` are JSON blobs interpreted by
+/// whoever reads them rather than normalized columns.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub job_type: String,
+    pub payload: String,
+    pub state: String,
+    /// MessagePack-encoded [`BulkImportProgress`] for a
+    /// `bulk_import_card_images` job (migration 26); `None` for every other
+    /// job type, and for a bulk-import job that hasn't run its first item yet.
+    pub progress: Option<Vec<u8>>,
+    pub error_message: Option<String>,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Job type for transcribing a recording's audio into `recordings.transcript`.
+pub const JOB_TYPE_TRANSCRIBE_RECORDING: &str = "transcribe_recording";
+
+/// Job type for `commands::images::save_problem_image`'s background
+/// decode/validate/strip/reencode pass (see `commands::jobs::process_image`).
+pub const JOB_TYPE_PROCESS_IMAGE: &str = "process_image";
+
+/// Job type for running `database::ocr::run_ocr` against a card image after
+/// `save_card_image`, persisting the result via `insert_image_labels` (see
+/// `commands::jobs::run_ocr_card_image`).
+pub const JOB_TYPE_OCR_CARD_IMAGE: &str = "ocr_card_image";
+
+/// A job is given up on (left `failed`) after this many attempts, rather than
+/// being requeued forever.
+pub const MAX_ATTEMPTS: i32 = 5;
+
+/// Capped exponential backoff before a failed job is eligible to be claimed
+/// again: doubles per attempt, capped at an hour so a job that's been
+/// struggling for a while doesn't end up waiting longer than the whole app
+/// is likely to run between restarts.
+pub fn backoff_delay(attempts: i32) -> chrono::Duration {
+    let capped_attempts = attempts.clamp(0, 10) as u32;
+    let seconds = 2u64.saturating_pow(capped_attempts).min(3600);
+    chrono::Duration::seconds(seconds as i64)
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TranscribeRecordingPayload {
+    pub recording_id: String,
+}
+
+/// `staging_path` is the `problem_images.image_path`-style relative path
+/// `save_problem_image` wrote the upload's original bytes to before
+/// returning, under `maintenance::pending_dir()` rather than `cas_dir()` -
+/// its final name depends on the hash of the *processed* bytes, which aren't
+/// known until the worker runs. `sniffed_format` is the `data:image/...`
+/// prefix tag `detect_image_format` read off the original upload.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProcessImagePayload {
+    pub image_id: String,
+    pub staging_path: String,
+    pub sniffed_format: String,
+}
+
+/// `payload` for an `ocr_card_image` job - just the `card_images.id` to
+/// label; the image's bytes are re-read from disk via
+/// `DatabaseManager::resolve_media_path` when the job runs, the same way
+/// `ProcessImagePayload` only carries a path rather than the bytes themselves.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct OcrCardImagePayload {
+    pub image_id: String,
+}
+
+/// Job type for `bulk_import_card_images`'s item-by-item import loop (see
+/// `commands::jobs::run_bulk_import_card_images`). Unlike the other job
+/// types, this one can sit `paused` indefinitely rather than only ever
+/// passing through `running` on its way to `completed`/`failed`.
+pub const JOB_TYPE_BULK_IMPORT_CARD_IMAGES: &str = "bulk_import_card_images";
+
+/// `payload` for a `bulk_import_card_images` job - just enough to identify
+/// which card the batch belongs to. The actual items and how far the import
+/// has gotten live in `progress`, not here, since that's rewritten after
+/// every item and `payload` is meant to be written once at enqueue time.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BulkImportCardImagesPayload {
+    pub card_id: String,
+}
+
+/// One image queued for `bulk_import_card_images` - the same shape
+/// `SaveCardImageRequest` uses for a single upload, minus `card_id` (shared
+/// by the whole batch) and `position` (appended in upload order).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BulkImportItem {
+    pub image_data: String,
+    pub caption: Option<String>,
+}
+
+/// The job's resume point, serialized with MessagePack into `jobs.progress`
+/// and re-read/rewritten by `commands::jobs::run_bulk_import_card_images`
+/// after every item - so a crash, quit, or user-requested pause never loses
+/// more than the one item that was in flight, and `resume_job` picks back up
+/// at `current_index` instead of reimporting the whole batch.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BulkImportProgress {
+    pub items: Vec<BulkImportItem>,
+    pub current_index: usize,
+    pub imported_image_ids: Vec<String>,
+    pub failed: Vec<String>,
+}
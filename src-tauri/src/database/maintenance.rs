@@ -0,0 +1,258 @@
+// Offline "fsck"-style consistency pass for the things nothing else in the
+// codebase reconciles: `recordings`/`problem_images` rows whose backing file has
+// disappeared, files sitting in the recordings/images directories that no row
+// references, `cards.total_duration` drifting from the `time_sessions` it's
+// supposed to sum, and `connections` left pointing at a deleted card.
+// `DatabaseManager::check_and_repair` runs the read-only scan this module
+// implements, then applies whichever repairs `CheckOptions` opts into.
+
+use super::storage_roots::StorageRoots;
+use anyhow::Context;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Controls which findings `check_and_repair` also fixes, rather than just
+/// reports. Every flag defaults to `false`, so `CheckOptions::default()` is a
+/// pure dry run.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct CheckOptions {
+    pub delete_orphan_rows: bool,
+    pub trash_orphan_files: bool,
+    pub fix_durations: bool,
+    pub delete_dangling_connections: bool,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DurationMismatch {
+    pub card_id: String,
+    pub stored_total_duration: i32,
+    pub computed_total_duration: i32,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DanglingConnection {
+    pub connection_id: String,
+    pub source_card_id: String,
+    pub target_card_id: String,
+}
+
+/// Result of `DatabaseManager::check_and_repair` - what the pass found, whether
+/// or not any of it was also fixed.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CheckReport {
+    pub orphan_recording_rows: Vec<String>,
+    pub orphan_image_rows: Vec<String>,
+    pub orphan_recording_files: Vec<String>,
+    pub orphan_image_files: Vec<String>,
+    pub duration_mismatches: Vec<DurationMismatch>,
+    pub dangling_connections: Vec<DanglingConnection>,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphan_recording_rows.is_empty()
+            && self.orphan_image_rows.is_empty()
+            && self.orphan_recording_files.is_empty()
+            && self.orphan_image_files.is_empty()
+            && self.duration_mismatches.is_empty()
+            && self.dangling_connections.is_empty()
+    }
+}
+
+/// Result of `DatabaseManager::reconcile_media` - unlike `check_and_repair`'s
+/// `trash_orphan_files` (which only ever moves files aside), this pass
+/// permanently deletes files it finds orphaned, so it reports what it
+/// actually reclaimed rather than what it found.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ReconcileReport {
+    pub dangling_rows_found: i64,
+    pub orphan_files_deleted: i64,
+    pub bytes_reclaimed: i64,
+}
+
+/// Result of `DatabaseManager::prune_recordings` - how much old audio its
+/// retention sweep actually cleared out, per-problem transactions and all.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct PruneReport {
+    pub problems_affected: i64,
+    pub recordings_deleted: i64,
+    pub bytes_reclaimed: i64,
+}
+
+/// Base app-data directory, resolved the same way `DatabaseManager::save_recording`
+/// resolves its `app-data/` prefix. Kept separate from `PathResolver` since the
+/// database layer has no `tauri::AppHandle` to build one from.
+pub(crate) fn app_data_dir() -> anyhow::Result<PathBuf> {
+    if cfg!(debug_assertions) {
+        std::env::current_dir()
+            .context("Failed to get current directory")
+            .map(|dir| dir.join("dev-data"))
+    } else if cfg!(target_os = "macos") {
+        Ok(dirs::home_dir()
+            .context("Failed to get home directory")?
+            .join("Library")
+            .join("Application Support")
+            .join("com.dsalearning.app"))
+    } else if cfg!(target_os = "windows") {
+        Ok(dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join("com.dsalearning.app"))
+    } else {
+        Ok(dirs::data_local_dir()
+            .context("Failed to get local data directory")?
+            .join("com.dsalearning.app"))
+    }
+}
+
+pub(crate) fn recordings_dir() -> anyhow::Result<PathBuf> {
+    Ok(app_data_dir()?.join("recordings"))
+}
+
+/// Every configured storage root's `recordings` subdirectory, so
+/// `check_and_repair`'s orphan-file walk covers recordings saved under any
+/// root rather than only the legacy single-root [`recordings_dir`]. A root
+/// that has never had a recording written to it simply yields an empty dir
+/// to [`list_files_recursive`], same as a fresh install.
+pub(crate) fn recording_dirs_for_roots(storage_roots: &StorageRoots) -> Vec<PathBuf> {
+    storage_roots
+        .roots()
+        .iter()
+        .map(|root| root.path.join("recordings"))
+        .collect()
+}
+
+pub(crate) fn images_dir() -> anyhow::Result<PathBuf> {
+    Ok(app_data_dir()?.join("images"))
+}
+
+/// Resolves a `recordings.filepath` value to an absolute path, mirroring
+/// `DatabaseManager::save_recording`'s inline handling of `dev-data/`, `app-data/`,
+/// legacy `attachments/`, and already-absolute paths.
+pub(crate) fn resolve_recording_path(filepath: &str) -> anyhow::Result<PathBuf> {
+    if filepath.starts_with("dev-data/") || filepath.starts_with("attachments/") {
+        let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+        Ok(current_dir.join(filepath))
+    } else if filepath.starts_with("app-data/") {
+        if cfg!(debug_assertions) {
+            let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+            Ok(current_dir.join("dev-data").join(&filepath[9..]))
+        } else {
+            Ok(app_data_dir()?.join(&filepath[9..]))
+        }
+    } else {
+        Ok(PathBuf::from(filepath))
+    }
+}
+
+/// Resolves a `problem_images.image_path` value to an absolute path, mirroring
+/// `delete_problem_image`'s inline handling in `commands/images.rs`. Note its
+/// legacy `images/` prefix is rooted under `attachments/`, unlike recordings'
+/// legacy `attachments/` prefix which is already baked into the stored path.
+pub(crate) fn resolve_image_path(image_path: &str) -> anyhow::Result<PathBuf> {
+    if image_path.starts_with("dev-data/") {
+        let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+        Ok(current_dir.join(image_path))
+    } else if image_path.starts_with("app-data/") {
+        if cfg!(debug_assertions) {
+            let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+            Ok(current_dir.join("dev-data").join(&image_path[9..]))
+        } else {
+            Ok(app_data_dir()?.join(&image_path[9..]))
+        }
+    } else if image_path.starts_with("images/") {
+        let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+        Ok(current_dir.join("attachments").join(image_path))
+    } else {
+        Ok(PathBuf::from(image_path))
+    }
+}
+
+/// Recursively collects every regular file under `root` as an absolute path.
+/// Returns an empty list if `root` doesn't exist yet, rather than treating a
+/// fresh install (nothing saved there) as an error.
+pub(crate) fn list_files_recursive(root: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).with_context(|| format!("Failed to read directory {}", dir.display()))? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Canonicalizes `path` for set-membership comparisons against other resolved
+/// paths, falling back to the path as-is if it doesn't (or no longer) exist.
+pub(crate) fn canonical_or_self(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Files in `files_on_disk` whose canonicalized form isn't in `referenced`.
+pub(crate) fn orphan_files(files_on_disk: Vec<PathBuf>, referenced: &HashSet<PathBuf>) -> Vec<String> {
+    files_on_disk
+        .into_iter()
+        .filter(|file| !referenced.contains(&canonical_or_self(file)))
+        .map(|file| file.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Moves `file` (an absolute path somewhere under `base_dir`) to the mirrored
+/// location under `base_dir/trash`, preserving its `recordings/...`/`images/...`
+/// sub-path so repeated runs don't collide on bare filenames.
+pub(crate) fn trash_file(base_dir: &Path, file: &Path) -> anyhow::Result<()> {
+    let relative = file.strip_prefix(base_dir).unwrap_or(file);
+    let destination = base_dir.join("trash").join(relative);
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create trash directory {}", parent.display()))?;
+    }
+    std::fs::rename(file, &destination)
+        .with_context(|| format!("Failed to move {} to {}", file.display(), destination.display()))?;
+    Ok(())
+}
+
+/// Shared directory content-addressed images are stored under, unlike the
+/// per-problem `images/problem_<id>/` directories `save_problem_image` used
+/// before migration 21 - the whole point of hashing by content is that the
+/// same file can be shared across problems, so it no longer lives under any
+/// one problem's folder.
+pub(crate) fn cas_dir() -> anyhow::Result<PathBuf> {
+    Ok(images_dir()?.join("cas"))
+}
+
+/// Staging directory for an upload's original bytes between `save_problem_image`
+/// returning a `pending` row and the `jobs`-queue worker finishing its
+/// decode/validate/strip/reencode pass (see `commands/jobs.rs`) - kept
+/// separate from [`cas_dir`] since a staged file isn't named by its content
+/// hash yet and may never become a CAS entry if it fails validation.
+pub(crate) fn pending_dir() -> anyhow::Result<PathBuf> {
+    Ok(images_dir()?.join("pending"))
+}
+
+/// BLAKE3 hex digest of `data`, used to name content-addressed image files
+/// and as their `image_blobs.hash`/`problem_images.content_hash` key.
+pub(crate) fn blake3_hex(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
+
+/// Result of `DatabaseManager::migrate_images_to_cas` - a one-time backfill
+/// for `problem_images` rows saved before content-addressed storage (see
+/// migration 21), run as an explicit admin command rather than at startup
+/// since it touches every legacy image file on disk.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CasMigrationReport {
+    pub images_migrated: i64,
+    pub images_deduplicated: i64,
+    pub images_skipped: i64,
+}
@@ -0,0 +1,1053 @@
+// Versioned migration registry, modeled on tools like `migra`: each entry is an
+// ordered, numbered step that takes the schema from `version - 1` to `version`.
+// `DatabaseManager` tracks how far a given database has progressed in a
+// `schema_migrations` table and replays every migration past that point, in
+// order, on every connect. This replaces the old ad-hoc "does this table/column
+// exist yet?" checks that used to live directly in `database/mod.rs`.
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up_sql: &'static str,
+    /// Undoes `up_sql`, taking the schema back from `version` to `version - 1`.
+    /// Used by `DatabaseManager::rollback_to`.
+    pub down_sql: &'static str,
+}
+
+// Every migration's SQL is written to be safe to run on its own, in order,
+// starting from whatever version a database is bootstrapped to - it is not
+// meant to be re-run out of order or skipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS problems (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT,
+                difficulty TEXT CHECK(difficulty IN ('Easy', 'Medium', 'Hard')),
+                category TEXT,
+                leetcode_url TEXT,
+                constraints TEXT,
+                examples TEXT,
+                hints TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS cards (
+                id TEXT PRIMARY KEY,
+                problem_id TEXT NOT NULL,
+                card_number INTEGER NOT NULL,
+                code TEXT,
+                language TEXT DEFAULT 'javascript',
+                notes TEXT,
+                status TEXT CHECK(status IN ('In Progress', 'Completed', 'Paused')),
+                total_duration INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_modified DATETIME DEFAULT CURRENT_TIMESTAMP,
+                parent_card_id TEXT,
+                FOREIGN KEY (problem_id) REFERENCES problems(id),
+                FOREIGN KEY (parent_card_id) REFERENCES cards(id)
+            );
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS cards;
+            DROP TABLE IF EXISTS problems;
+        "#,
+    },
+    Migration {
+        version: 2,
+        name: "category_to_topic",
+        up_sql: r#"
+            PRAGMA foreign_keys = OFF;
+
+            CREATE TABLE problems_new (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT,
+                difficulty TEXT CHECK(difficulty IN ('Easy', 'Medium', 'Hard')),
+                topic TEXT,
+                leetcode_url TEXT,
+                constraints TEXT,
+                examples TEXT,
+                hints TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            INSERT INTO problems_new (id, title, description, difficulty, topic, leetcode_url, constraints, examples, hints, created_at)
+            SELECT id, title, description, difficulty, category, leetcode_url, constraints, examples, hints, created_at
+            FROM problems;
+
+            DROP TABLE problems;
+            ALTER TABLE problems_new RENAME TO problems;
+
+            PRAGMA foreign_keys = ON;
+        "#,
+        down_sql: r#"
+            PRAGMA foreign_keys = OFF;
+
+            CREATE TABLE problems_new (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT,
+                difficulty TEXT CHECK(difficulty IN ('Easy', 'Medium', 'Hard')),
+                category TEXT,
+                leetcode_url TEXT,
+                constraints TEXT,
+                examples TEXT,
+                hints TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            INSERT INTO problems_new (id, title, description, difficulty, category, leetcode_url, constraints, examples, hints, created_at)
+            SELECT id, title, description, difficulty, topic, leetcode_url, constraints, examples, hints, created_at
+            FROM problems;
+
+            DROP TABLE problems;
+            ALTER TABLE problems_new RENAME TO problems;
+
+            PRAGMA foreign_keys = ON;
+        "#,
+    },
+    Migration {
+        version: 3,
+        name: "add_related_problem_ids",
+        up_sql: "ALTER TABLE problems ADD COLUMN related_problem_ids TEXT;",
+        // Requires SQLite 3.35+ (bundled by rusqlite's `bundled` feature), which
+        // added `ALTER TABLE ... DROP COLUMN`.
+        down_sql: "ALTER TABLE problems DROP COLUMN related_problem_ids;",
+    },
+    Migration {
+        version: 4,
+        name: "add_missing_core_tables",
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS time_sessions (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                start_time DATETIME NOT NULL,
+                end_time DATETIME,
+                duration INTEGER,
+                date DATE,
+                is_active INTEGER DEFAULT 0,
+                notes TEXT,
+                FOREIGN KEY (card_id) REFERENCES cards(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS recordings (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                time_session_id TEXT,
+                audio_url TEXT NOT NULL,
+                duration INTEGER,
+                transcript TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                filename TEXT NOT NULL,
+                filepath TEXT NOT NULL,
+                file_size INTEGER,
+                FOREIGN KEY (card_id) REFERENCES cards(id),
+                FOREIGN KEY (time_session_id) REFERENCES time_sessions(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS connections (
+                id TEXT PRIMARY KEY,
+                source_card_id TEXT NOT NULL,
+                target_card_id TEXT NOT NULL,
+                connection_type TEXT CHECK(connection_type IN ('related', 'prerequisite', 'similar', 'builds-upon')),
+                notes TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_card_id) REFERENCES cards(id),
+                FOREIGN KEY (target_card_id) REFERENCES cards(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS tags (
+                id TEXT PRIMARY KEY,
+                name TEXT UNIQUE NOT NULL,
+                color TEXT,
+                category TEXT CHECK(category IN ('algorithm', 'data-structure', 'pattern', 'custom'))
+            );
+
+            CREATE TABLE IF NOT EXISTS problem_tags (
+                problem_id TEXT,
+                tag_id TEXT,
+                PRIMARY KEY (problem_id, tag_id),
+                FOREIGN KEY (problem_id) REFERENCES problems(id),
+                FOREIGN KEY (tag_id) REFERENCES tags(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS card_tags (
+                card_id TEXT,
+                tag_id TEXT,
+                PRIMARY KEY (card_id, tag_id),
+                FOREIGN KEY (card_id) REFERENCES cards(id),
+                FOREIGN KEY (tag_id) REFERENCES tags(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS test_cases (
+                id TEXT PRIMARY KEY,
+                problem_id TEXT NOT NULL,
+                input TEXT NOT NULL,
+                expected_output TEXT NOT NULL,
+                is_hidden INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (problem_id) REFERENCES problems(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_time_sessions_card_id ON time_sessions(card_id);
+            CREATE INDEX IF NOT EXISTS idx_time_sessions_date ON time_sessions(date);
+            CREATE INDEX IF NOT EXISTS idx_recordings_card_id ON recordings(card_id);
+            CREATE INDEX IF NOT EXISTS idx_connections_source ON connections(source_card_id);
+            CREATE INDEX IF NOT EXISTS idx_connections_target ON connections(target_card_id);
+            CREATE INDEX IF NOT EXISTS idx_test_cases_problem_id ON test_cases(problem_id);
+            CREATE INDEX IF NOT EXISTS idx_problems_title ON problems(title);
+            CREATE INDEX IF NOT EXISTS idx_problems_topic ON problems(topic);
+            CREATE INDEX IF NOT EXISTS idx_problem_tags_problem_id ON problem_tags(problem_id);
+            CREATE INDEX IF NOT EXISTS idx_problem_tags_tag_id ON problem_tags(tag_id);
+            CREATE INDEX IF NOT EXISTS idx_problems_related_problem_ids ON problems(related_problem_ids);
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS test_cases;
+            DROP TABLE IF EXISTS card_tags;
+            DROP TABLE IF EXISTS problem_tags;
+            DROP TABLE IF EXISTS tags;
+            DROP TABLE IF EXISTS connections;
+            DROP TABLE IF EXISTS recordings;
+            DROP TABLE IF EXISTS time_sessions;
+        "#,
+    },
+    Migration {
+        version: 5,
+        name: "add_image_and_work_session_tables",
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS recording_highlights (
+                recording_id TEXT PRIMARY KEY,
+                color TEXT NOT NULL CHECK(color IN ('green', 'blue', 'purple')),
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS problem_images (
+                id TEXT PRIMARY KEY,
+                problem_id TEXT NOT NULL,
+                image_path TEXT NOT NULL,
+                caption TEXT,
+                position INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (problem_id) REFERENCES problems(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS card_images (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                image_path TEXT NOT NULL,
+                caption TEXT,
+                position INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS work_sessions (
+                id TEXT PRIMARY KEY,
+                problem_id TEXT NOT NULL,
+                card_id TEXT NOT NULL,
+                session_date DATE NOT NULL,
+                start_timestamp DATETIME NOT NULL,
+                end_timestamp DATETIME,
+                duration_seconds INTEGER DEFAULT 0,
+                hour_slot INTEGER NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (problem_id) REFERENCES problems(id) ON DELETE CASCADE,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_recording_highlights_color ON recording_highlights(color);
+            CREATE INDEX IF NOT EXISTS idx_problem_images_problem_id ON problem_images(problem_id);
+            CREATE INDEX IF NOT EXISTS idx_problem_images_position ON problem_images(position);
+            CREATE INDEX IF NOT EXISTS idx_card_images_card_id ON card_images(card_id);
+            CREATE INDEX IF NOT EXISTS idx_card_images_position ON card_images(position);
+            CREATE INDEX IF NOT EXISTS idx_work_sessions_session_date ON work_sessions(session_date);
+            CREATE INDEX IF NOT EXISTS idx_work_sessions_problem_id ON work_sessions(problem_id);
+            CREATE INDEX IF NOT EXISTS idx_work_sessions_card_id ON work_sessions(card_id);
+            CREATE INDEX IF NOT EXISTS idx_work_sessions_date_hour ON work_sessions(session_date, hour_slot);
+            CREATE INDEX IF NOT EXISTS idx_work_sessions_problem_date ON work_sessions(problem_id, session_date);
+            CREATE INDEX IF NOT EXISTS idx_work_sessions_start_time ON work_sessions(start_timestamp);
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS work_sessions;
+            DROP TABLE IF EXISTS card_images;
+            DROP TABLE IF EXISTS problem_images;
+            DROP TABLE IF EXISTS recording_highlights;
+        "#,
+    },
+    Migration {
+        version: 6,
+        name: "add_problem_and_card_history",
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS problems_history (
+                history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                row_id TEXT NOT NULL,
+                title TEXT,
+                description TEXT,
+                difficulty TEXT,
+                topic TEXT,
+                leetcode_url TEXT,
+                constraints TEXT,
+                examples TEXT,
+                hints TEXT,
+                related_problem_ids TEXT,
+                created_at DATETIME,
+                operation TEXT NOT NULL CHECK(operation IN ('update', 'delete')),
+                changed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_problems_history_row_id ON problems_history(row_id);
+
+            CREATE TRIGGER IF NOT EXISTS problems_after_update AFTER UPDATE ON problems
+            BEGIN
+                INSERT INTO problems_history (row_id, title, description, difficulty, topic, leetcode_url, constraints, examples, hints, related_problem_ids, created_at, operation, changed_at)
+                VALUES (OLD.id, OLD.title, OLD.description, OLD.difficulty, OLD.topic, OLD.leetcode_url, OLD.constraints, OLD.examples, OLD.hints, OLD.related_problem_ids, OLD.created_at, 'update', CURRENT_TIMESTAMP);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS problems_after_delete AFTER DELETE ON problems
+            BEGIN
+                INSERT INTO problems_history (row_id, title, description, difficulty, topic, leetcode_url, constraints, examples, hints, related_problem_ids, created_at, operation, changed_at)
+                VALUES (OLD.id, OLD.title, OLD.description, OLD.difficulty, OLD.topic, OLD.leetcode_url, OLD.constraints, OLD.examples, OLD.hints, OLD.related_problem_ids, OLD.created_at, 'delete', CURRENT_TIMESTAMP);
+            END;
+
+            CREATE TABLE IF NOT EXISTS cards_history (
+                history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                row_id TEXT NOT NULL,
+                problem_id TEXT,
+                card_number INTEGER,
+                code TEXT,
+                language TEXT,
+                notes TEXT,
+                status TEXT,
+                total_duration INTEGER,
+                created_at DATETIME,
+                last_modified DATETIME,
+                parent_card_id TEXT,
+                operation TEXT NOT NULL CHECK(operation IN ('update', 'delete')),
+                changed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_cards_history_row_id ON cards_history(row_id);
+
+            CREATE TRIGGER IF NOT EXISTS cards_after_update AFTER UPDATE ON cards
+            BEGIN
+                INSERT INTO cards_history (row_id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id, operation, changed_at)
+                VALUES (OLD.id, OLD.problem_id, OLD.card_number, OLD.code, OLD.language, OLD.notes, OLD.status, OLD.total_duration, OLD.created_at, OLD.last_modified, OLD.parent_card_id, 'update', CURRENT_TIMESTAMP);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS cards_after_delete AFTER DELETE ON cards
+            BEGIN
+                INSERT INTO cards_history (row_id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id, operation, changed_at)
+                VALUES (OLD.id, OLD.problem_id, OLD.card_number, OLD.code, OLD.language, OLD.notes, OLD.status, OLD.total_duration, OLD.created_at, OLD.last_modified, OLD.parent_card_id, 'delete', CURRENT_TIMESTAMP);
+            END;
+        "#,
+        down_sql: r#"
+            DROP TRIGGER IF EXISTS cards_after_delete;
+            DROP TRIGGER IF EXISTS cards_after_update;
+            DROP TABLE IF EXISTS cards_history;
+            DROP TRIGGER IF EXISTS problems_after_delete;
+            DROP TRIGGER IF EXISTS problems_after_update;
+            DROP TABLE IF EXISTS problems_history;
+        "#,
+    },
+    Migration {
+        version: 7,
+        name: "normalize_related_problem_ids",
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS problem_relations (
+                problem_id TEXT NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+                related_problem_id TEXT NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+                PRIMARY KEY (problem_id, related_problem_id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_problem_relations_related_problem_id ON problem_relations(related_problem_id);
+
+            INSERT OR IGNORE INTO problem_relations (problem_id, related_problem_id)
+            SELECT p.id, je.value
+            FROM problems p
+            JOIN json_each(p.related_problem_ids) je
+            JOIN problems p2 ON p2.id = je.value
+            WHERE p.related_problem_ids IS NOT NULL
+              AND p.related_problem_ids != ''
+              AND p.related_problem_ids != 'null'
+              AND je.value != p.id;
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS problem_relations;
+        "#,
+    },
+    Migration {
+        version: 8,
+        name: "add_leetcode_cache",
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS leetcode_problem_cache (
+                slug TEXT PRIMARY KEY,
+                raw_json TEXT NOT NULL,
+                fetched_at DATETIME NOT NULL
+            );
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS leetcode_problem_cache;
+        "#,
+    },
+    Migration {
+        version: 9,
+        name: "backfill_card_durations_from_recordings",
+        // Before `time_sessions` existed, a card's only record of time spent was
+        // however long its recordings ran. For any card that still has a zero
+        // `total_duration` and no time_sessions of its own, treat the sum of its
+        // recordings' `duration` as the best available estimate. Guarded by the
+        // `total_duration = 0` check so this is safe to run more than once - once
+        // applied, a card's total_duration is no longer 0 and is skipped on any
+        // later pass.
+        up_sql: r#"
+            UPDATE cards
+            SET total_duration = (
+                SELECT COALESCE(SUM(r.duration), 0)
+                FROM recordings r
+                WHERE r.card_id = cards.id
+            )
+            WHERE total_duration = 0
+              AND EXISTS (SELECT 1 FROM recordings r WHERE r.card_id = cards.id AND r.duration IS NOT NULL)
+              AND NOT EXISTS (SELECT 1 FROM time_sessions ts WHERE ts.card_id = cards.id);
+        "#,
+        // One-way data repair for legacy rows that predate time_sessions
+        // tracking - there's no prior value to restore to.
+        down_sql: "",
+    },
+    Migration {
+        version: 10,
+        name: "add_is_solution_column",
+        // `get_solution_card`/`solution_card_exists` already query this column,
+        // but nothing had ever formally added it - codifying it here so it's
+        // part of every database's recorded schema instead of an implicit
+        // assumption downstream query code had to guard with `COALESCE`.
+        up_sql: "ALTER TABLE cards ADD COLUMN is_solution BOOLEAN DEFAULT 0;",
+        down_sql: "ALTER TABLE cards DROP COLUMN is_solution;",
+    },
+    Migration {
+        version: 11,
+        name: "add_problems_deleted_at",
+        // Backs the soft-delete recycle bin: a problem with `deleted_at` set
+        // is hidden from every normal listing/search query but its rows (and
+        // its cards/recordings/etc.) are left untouched until `purge_deleted_before`
+        // runs the real cascading delete on it.
+        up_sql: "ALTER TABLE problems ADD COLUMN deleted_at DATETIME;",
+        down_sql: "ALTER TABLE problems DROP COLUMN deleted_at;",
+    },
+    Migration {
+        version: 12,
+        name: "add_tags_usage_count",
+        // Maintained by the triggers `database::triggers::install_triggers`
+        // installs on `problem_tags`, so tag suggestions can rank popular
+        // tags first without a COUNT(*) join on every keystroke.
+        up_sql: r#"
+            ALTER TABLE tags ADD COLUMN usage_count INTEGER NOT NULL DEFAULT 0;
+            UPDATE tags SET usage_count = (
+                SELECT COUNT(*) FROM problem_tags WHERE problem_tags.tag_id = tags.id
+            );
+        "#,
+        down_sql: "ALTER TABLE tags DROP COLUMN usage_count;",
+    },
+    Migration {
+        version: 13,
+        name: "add_cascading_deleted_at",
+        // `soft_delete_problem`/`restore_problem` stamp these alongside
+        // `problems.deleted_at`, so a trashed problem's cards/recordings/
+        // images carry their own recycle-bin timestamp too instead of only
+        // being inferred from their parent row.
+        up_sql: r#"
+            ALTER TABLE cards ADD COLUMN deleted_at DATETIME;
+            ALTER TABLE recordings ADD COLUMN deleted_at DATETIME;
+            ALTER TABLE problem_images ADD COLUMN deleted_at DATETIME;
+        "#,
+        down_sql: r#"
+            ALTER TABLE cards DROP COLUMN deleted_at;
+            ALTER TABLE recordings DROP COLUMN deleted_at;
+            ALTER TABLE problem_images DROP COLUMN deleted_at;
+        "#,
+    },
+    Migration {
+        version: 14,
+        name: "add_cascade_on_delete",
+        // `cards`, `time_sessions`, `recordings`, `connections`, `problem_tags`,
+        // and `card_tags` were declared with plain `FOREIGN KEY` references and
+        // no `ON DELETE` action, so `delete_problem` had to hand-delete each of
+        // them in dependency order before it could delete the `problems` row
+        // itself. SQLite has no `ALTER TABLE ... ADD CONSTRAINT`, so adding
+        // `ON DELETE CASCADE` to an existing table means rebuilding it: create
+        // the table under a new name with the constraint, copy the rows across,
+        // drop the original, and rename the copy into its place. `problem_images`,
+        // `card_images`, `work_sessions`, `recording_highlights`, and
+        // `problem_relations` already cascade this way (see their own
+        // migrations); this does the same for the remaining child tables so a
+        // plain `DELETE FROM problems WHERE id = ?` cascades the whole tree.
+        //
+        // `DROP TABLE cards` below implicitly deletes every row in it, which
+        // would violate the still-`NO ACTION` `time_sessions`/`recordings`/
+        // `connections`/`card_tags` FKs on any populated database if foreign
+        // keys were actually enforced here. The in-SQL `PRAGMA foreign_keys`
+        // toggles below are a no-op on their own (the pragma can't change
+        // mid-transaction) - this migration is only safe because
+        // `migrate_to_latest` disables foreign keys on the connection before
+        // opening the migration transaction and re-enables them after commit.
+        up_sql: r#"
+            PRAGMA foreign_keys = OFF;
+
+            CREATE TABLE cards_new (
+                id TEXT PRIMARY KEY,
+                problem_id TEXT NOT NULL,
+                card_number INTEGER NOT NULL,
+                code TEXT,
+                language TEXT DEFAULT 'javascript',
+                notes TEXT,
+                status TEXT CHECK(status IN ('In Progress', 'Completed', 'Paused')),
+                total_duration INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_modified DATETIME DEFAULT CURRENT_TIMESTAMP,
+                parent_card_id TEXT,
+                is_solution BOOLEAN DEFAULT 0,
+                deleted_at DATETIME,
+                FOREIGN KEY (problem_id) REFERENCES problems(id) ON DELETE CASCADE,
+                FOREIGN KEY (parent_card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+            INSERT INTO cards_new SELECT id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id, is_solution, deleted_at FROM cards;
+            DROP TABLE cards;
+            ALTER TABLE cards_new RENAME TO cards;
+
+            CREATE TABLE time_sessions_new (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                start_time DATETIME NOT NULL,
+                end_time DATETIME,
+                duration INTEGER,
+                date DATE,
+                is_active INTEGER DEFAULT 0,
+                notes TEXT,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+            INSERT INTO time_sessions_new SELECT id, card_id, start_time, end_time, duration, date, is_active, notes FROM time_sessions;
+            DROP TABLE time_sessions;
+            ALTER TABLE time_sessions_new RENAME TO time_sessions;
+
+            CREATE TABLE recordings_new (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                time_session_id TEXT,
+                audio_url TEXT NOT NULL,
+                duration INTEGER,
+                transcript TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                filename TEXT NOT NULL,
+                filepath TEXT NOT NULL,
+                file_size INTEGER,
+                deleted_at DATETIME,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE,
+                FOREIGN KEY (time_session_id) REFERENCES time_sessions(id)
+            );
+            INSERT INTO recordings_new SELECT id, card_id, time_session_id, audio_url, duration, transcript, created_at, filename, filepath, file_size, deleted_at FROM recordings;
+            DROP TABLE recordings;
+            ALTER TABLE recordings_new RENAME TO recordings;
+
+            CREATE TABLE connections_new (
+                id TEXT PRIMARY KEY,
+                source_card_id TEXT NOT NULL,
+                target_card_id TEXT NOT NULL,
+                connection_type TEXT CHECK(connection_type IN ('related', 'prerequisite', 'similar', 'builds-upon')),
+                notes TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_card_id) REFERENCES cards(id) ON DELETE CASCADE,
+                FOREIGN KEY (target_card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+            INSERT INTO connections_new SELECT id, source_card_id, target_card_id, connection_type, notes, created_at FROM connections;
+            DROP TABLE connections;
+            ALTER TABLE connections_new RENAME TO connections;
+
+            CREATE TABLE problem_tags_new (
+                problem_id TEXT,
+                tag_id TEXT,
+                PRIMARY KEY (problem_id, tag_id),
+                FOREIGN KEY (problem_id) REFERENCES problems(id) ON DELETE CASCADE,
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            );
+            INSERT INTO problem_tags_new SELECT problem_id, tag_id FROM problem_tags;
+            DROP TABLE problem_tags;
+            ALTER TABLE problem_tags_new RENAME TO problem_tags;
+
+            CREATE TABLE card_tags_new (
+                card_id TEXT,
+                tag_id TEXT,
+                PRIMARY KEY (card_id, tag_id),
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE,
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            );
+            INSERT INTO card_tags_new SELECT card_id, tag_id FROM card_tags;
+            DROP TABLE card_tags;
+            ALTER TABLE card_tags_new RENAME TO card_tags;
+
+            CREATE INDEX IF NOT EXISTS idx_time_sessions_card_id ON time_sessions(card_id);
+            CREATE INDEX IF NOT EXISTS idx_time_sessions_date ON time_sessions(date);
+            CREATE INDEX IF NOT EXISTS idx_recordings_card_id ON recordings(card_id);
+            CREATE INDEX IF NOT EXISTS idx_connections_source ON connections(source_card_id);
+            CREATE INDEX IF NOT EXISTS idx_connections_target ON connections(target_card_id);
+            CREATE INDEX IF NOT EXISTS idx_problem_tags_problem_id ON problem_tags(problem_id);
+            CREATE INDEX IF NOT EXISTS idx_problem_tags_tag_id ON problem_tags(tag_id);
+
+            PRAGMA foreign_keys = ON;
+        "#,
+        // The rebuilt tables are a strict superset of the old behavior (the
+        // only change is the `ON DELETE` action on existing foreign keys), so
+        // there's no data to lose on the way back down - just drop the
+        // cascade by rebuilding once more without it.
+        down_sql: r#"
+            PRAGMA foreign_keys = OFF;
+
+            CREATE TABLE cards_old (
+                id TEXT PRIMARY KEY,
+                problem_id TEXT NOT NULL,
+                card_number INTEGER NOT NULL,
+                code TEXT,
+                language TEXT DEFAULT 'javascript',
+                notes TEXT,
+                status TEXT CHECK(status IN ('In Progress', 'Completed', 'Paused')),
+                total_duration INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_modified DATETIME DEFAULT CURRENT_TIMESTAMP,
+                parent_card_id TEXT,
+                is_solution BOOLEAN DEFAULT 0,
+                deleted_at DATETIME,
+                FOREIGN KEY (problem_id) REFERENCES problems(id),
+                FOREIGN KEY (parent_card_id) REFERENCES cards(id)
+            );
+            INSERT INTO cards_old SELECT id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id, is_solution, deleted_at FROM cards;
+            DROP TABLE cards;
+            ALTER TABLE cards_old RENAME TO cards;
+
+            CREATE TABLE time_sessions_old (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                start_time DATETIME NOT NULL,
+                end_time DATETIME,
+                duration INTEGER,
+                date DATE,
+                is_active INTEGER DEFAULT 0,
+                notes TEXT,
+                FOREIGN KEY (card_id) REFERENCES cards(id)
+            );
+            INSERT INTO time_sessions_old SELECT id, card_id, start_time, end_time, duration, date, is_active, notes FROM time_sessions;
+            DROP TABLE time_sessions;
+            ALTER TABLE time_sessions_old RENAME TO time_sessions;
+
+            CREATE TABLE recordings_old (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                time_session_id TEXT,
+                audio_url TEXT NOT NULL,
+                duration INTEGER,
+                transcript TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                filename TEXT NOT NULL,
+                filepath TEXT NOT NULL,
+                file_size INTEGER,
+                deleted_at DATETIME,
+                FOREIGN KEY (card_id) REFERENCES cards(id),
+                FOREIGN KEY (time_session_id) REFERENCES time_sessions(id)
+            );
+            INSERT INTO recordings_old SELECT id, card_id, time_session_id, audio_url, duration, transcript, created_at, filename, filepath, file_size, deleted_at FROM recordings;
+            DROP TABLE recordings;
+            ALTER TABLE recordings_old RENAME TO recordings;
+
+            CREATE TABLE connections_old (
+                id TEXT PRIMARY KEY,
+                source_card_id TEXT NOT NULL,
+                target_card_id TEXT NOT NULL,
+                connection_type TEXT CHECK(connection_type IN ('related', 'prerequisite', 'similar', 'builds-upon')),
+                notes TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_card_id) REFERENCES cards(id),
+                FOREIGN KEY (target_card_id) REFERENCES cards(id)
+            );
+            INSERT INTO connections_old SELECT id, source_card_id, target_card_id, connection_type, notes, created_at FROM connections;
+            DROP TABLE connections;
+            ALTER TABLE connections_old RENAME TO connections;
+
+            CREATE TABLE problem_tags_old (
+                problem_id TEXT,
+                tag_id TEXT,
+                PRIMARY KEY (problem_id, tag_id),
+                FOREIGN KEY (problem_id) REFERENCES problems(id),
+                FOREIGN KEY (tag_id) REFERENCES tags(id)
+            );
+            INSERT INTO problem_tags_old SELECT problem_id, tag_id FROM problem_tags;
+            DROP TABLE problem_tags;
+            ALTER TABLE problem_tags_old RENAME TO problem_tags;
+
+            CREATE TABLE card_tags_old (
+                card_id TEXT,
+                tag_id TEXT,
+                PRIMARY KEY (card_id, tag_id),
+                FOREIGN KEY (card_id) REFERENCES cards(id),
+                FOREIGN KEY (tag_id) REFERENCES tags(id)
+            );
+            INSERT INTO card_tags_old SELECT card_id, tag_id FROM card_tags;
+            DROP TABLE card_tags;
+            ALTER TABLE card_tags_old RENAME TO card_tags;
+
+            CREATE INDEX IF NOT EXISTS idx_time_sessions_card_id ON time_sessions(card_id);
+            CREATE INDEX IF NOT EXISTS idx_time_sessions_date ON time_sessions(date);
+            CREATE INDEX IF NOT EXISTS idx_recordings_card_id ON recordings(card_id);
+            CREATE INDEX IF NOT EXISTS idx_connections_source ON connections(source_card_id);
+            CREATE INDEX IF NOT EXISTS idx_connections_target ON connections(target_card_id);
+            CREATE INDEX IF NOT EXISTS idx_problem_tags_problem_id ON problem_tags(problem_id);
+            CREATE INDEX IF NOT EXISTS idx_problem_tags_tag_id ON problem_tags(tag_id);
+
+            PRAGMA foreign_keys = ON;
+        "#,
+    },
+    Migration {
+        version: 15,
+        name: "add_recording_retention_columns",
+        // Back `DatabaseManager::prune_recordings`'s retention policy:
+        // `recordings.retain_forever` pins an individual recording (like a
+        // starred/pinned file) so it's never swept regardless of age, and
+        // `problems.recording_retention_days` lets one problem keep its audio
+        // longer (or shorter) than `prune_recordings`'s default expiration.
+        up_sql: r#"
+            ALTER TABLE recordings ADD COLUMN retain_forever BOOLEAN NOT NULL DEFAULT 0;
+            ALTER TABLE problems ADD COLUMN recording_retention_days INTEGER;
+        "#,
+        down_sql: r#"
+            ALTER TABLE recordings DROP COLUMN retain_forever;
+            ALTER TABLE problems DROP COLUMN recording_retention_days;
+        "#,
+    },
+    Migration {
+        version: 16,
+        name: "add_jobs_table",
+        // Backs `DatabaseManager::enqueue_job`/`claim_next_queued_job`: a
+        // durable work queue so long-running background work (starting with
+        // `transcribe_recording`) survives an app restart instead of being
+        // lost if it was only ever tracked in memory. `available_at` is when
+        // the job becomes eligible to be claimed again - set to now on
+        // enqueue, and pushed into the future on failure so retries back off
+        // instead of spinning.
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS jobs (
+                id TEXT PRIMARY KEY,
+                job_type TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                state TEXT NOT NULL CHECK(state IN ('queued', 'running', 'completed', 'failed')) DEFAULT 'queued',
+                error_message TEXT,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                available_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_jobs_state_available_at ON jobs(state, available_at);
+        "#,
+        down_sql: r#"
+            DROP INDEX IF EXISTS idx_jobs_state_available_at;
+            DROP TABLE IF EXISTS jobs;
+        "#,
+    },
+    Migration {
+        version: 17,
+        name: "add_saved_filters_table",
+        // Backs `save_filter`/`list_filters`/`delete_filter`: reusable
+        // `WorkSessionFilter` presets (e.g. "Hard dynamic-programming, last
+        // 30 days"). The filter itself is stored as JSON rather than its own
+        // columns, since `WorkSessionFilter` is expected to grow facets over
+        // time and a new column per facet would mean a migration each time.
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS saved_filters (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                filter_json TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS saved_filters;
+        "#,
+    },
+    Migration {
+        version: 18,
+        name: "add_timer_session_snapshot_table",
+        // Backs `DatabaseManager::save_timer_session_snapshot`/
+        // `load_timer_session_snapshot`: a durable copy of the one active
+        // `TimerSession`, written on every start/pause/resume so a crash or
+        // quit doesn't lose the in-progress session - `main`'s setup
+        // rehydrates `AppState.current_timer` from this row if one exists.
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS timer_session_snapshot (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                start_time DATETIME NOT NULL,
+                is_paused INTEGER NOT NULL DEFAULT 0,
+                pause_duration INTEGER NOT NULL DEFAULT 0,
+                pause_started_at DATETIME,
+                work_session_id TEXT,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS timer_session_snapshot;
+        "#,
+    },
+    Migration {
+        version: 19,
+        name: "add_problem_image_thumbnail_path",
+        // Backs the thumbnailer in `commands/images.rs`: `save_problem_image`
+        // now also writes a downscaled `<uuid>.thumb.webp` next to the
+        // original, and this column records where it landed so
+        // `get_problem_image_thumbnail` doesn't have to guess the filename
+        // from `image_path`. Left `NULL` for images saved before this
+        // feature - `get_problem_image_thumbnail` falls back to generating
+        // one lazily when it finds a row without one.
+        up_sql: r#"
+            ALTER TABLE problem_images ADD COLUMN thumbnail_path TEXT;
+        "#,
+        down_sql: r#"
+            ALTER TABLE problem_images DROP COLUMN thumbnail_path;
+        "#,
+    },
+    Migration {
+        version: 20,
+        name: "add_problem_image_blur_hash",
+        // Backs `blurhash::encode`, called from `save_problem_image`: a
+        // ~20-30 character placeholder the frontend can decode into a
+        // blurred preview and paint before the thumbnail (let alone the
+        // full image) has loaded. `NULL` for images saved before this
+        // feature, same as `thumbnail_path`.
+        up_sql: r#"
+            ALTER TABLE problem_images ADD COLUMN blur_hash TEXT;
+        "#,
+        down_sql: r#"
+            ALTER TABLE problem_images DROP COLUMN blur_hash;
+        "#,
+    },
+    Migration {
+        version: 21,
+        name: "add_image_blobs_table",
+        // Backs content-addressed image storage (Spacedrive's CAS file
+        // identifier approach): `save_problem_image` now names the stored
+        // file after the BLAKE3 hash of its decoded bytes instead of a
+        // fresh `Uuid`, so pasting the same screenshot into many cards
+        // writes it once. `image_blobs.ref_count` is maintained by
+        // `trg_image_blobs_ref_count_insert/delete` (see
+        // `database/triggers.rs`) the same way `trg_tags_usage_count_*`
+        // maintains `tags.usage_count`; `delete_problem_image` only unlinks
+        // the physical file once it reaches zero.
+        // `problem_images.content_hash` is left NULL for images saved
+        // before this feature - `migrate_images_to_cas` backfills them.
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS image_blobs (
+                hash TEXT PRIMARY KEY,
+                extension TEXT NOT NULL,
+                ref_count INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+
+            ALTER TABLE problem_images ADD COLUMN content_hash TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_problem_images_content_hash ON problem_images(content_hash);
+        "#,
+        down_sql: r#"
+            DROP INDEX IF EXISTS idx_problem_images_content_hash;
+            ALTER TABLE problem_images DROP COLUMN content_hash;
+            DROP TABLE IF EXISTS image_blobs;
+        "#,
+    },
+    Migration {
+        version: 22,
+        name: "add_problem_image_dimensions",
+        // `save_problem_image`'s new ingest step (decode-validate-strip-reencode,
+        // see `commands/images.rs`) already has the decoded `DynamicImage` in
+        // hand, so it records its pixel dimensions and final encoded size
+        // here rather than making the frontend load the image just to lay
+        // out a gallery. `NULL` for images saved before this feature and for
+        // `svg` uploads, which are never decoded through the `image` crate.
+        up_sql: r#"
+            ALTER TABLE problem_images ADD COLUMN width INTEGER;
+            ALTER TABLE problem_images ADD COLUMN height INTEGER;
+            ALTER TABLE problem_images ADD COLUMN byte_size INTEGER;
+        "#,
+        down_sql: r#"
+            ALTER TABLE problem_images DROP COLUMN byte_size;
+            ALTER TABLE problem_images DROP COLUMN height;
+            ALTER TABLE problem_images DROP COLUMN width;
+        "#,
+    },
+    Migration {
+        version: 23,
+        name: "add_problem_image_processing_status",
+        // `save_problem_image` now returns as soon as the original bytes are
+        // written, before the `jobs`-queue worker has generated a thumbnail,
+        // BlurHash and stripped re-encode (see `commands/jobs.rs`). `pending`
+        // rows exist only for the gap between those two points; every row
+        // backfills to `ready` since it was already fully processed under the
+        // old synchronous path.
+        up_sql: r#"
+            ALTER TABLE problem_images ADD COLUMN status TEXT NOT NULL DEFAULT 'ready';
+        "#,
+        down_sql: r#"
+            ALTER TABLE problem_images DROP COLUMN status;
+        "#,
+    },
+    Migration {
+        version: 24,
+        name: "add_card_images_content_hash",
+        // `save_card_image` used to write every pasted image under a fresh
+        // `Uuid`-named file in its own `card_<id>/` folder (migration 5), so
+        // the same diagram pasted onto ten cards was stored ten times.
+        // `card_images` becomes content-addressed the same way
+        // `problem_images` did in migration 21: `content_hash` is the BLAKE3
+        // digest of the saved bytes, shared with `image_blobs.hash`
+        // (extended by this migration's triggers, see `database/triggers.rs`,
+        // to also track `card_images` rows) and `image_path` now points into
+        // the shared `images/cas/` directory instead of a per-card one.
+        // `NULL` for rows saved before this feature.
+        up_sql: r#"
+            ALTER TABLE card_images ADD COLUMN content_hash TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_card_images_content_hash ON card_images(content_hash);
+        "#,
+        down_sql: r#"
+            DROP INDEX IF EXISTS idx_card_images_content_hash;
+            ALTER TABLE card_images DROP COLUMN content_hash;
+        "#,
+    },
+    Migration {
+        version: 25,
+        name: "add_card_images_thumbnail_path",
+        // `save_card_image` now downscales every raster upload to a
+        // `<hash>.thumb.webp` alongside the full file, the same way
+        // `problem_images.thumbnail_path` works (migration 19) - so
+        // `get_card_image_thumbnail` can serve a small payload instead of
+        // `get_card_image_data_url` base64-encoding the whole original on
+        // every request. `NULL` for rows saved before this feature and for
+        // `svg` uploads, which have no raster form to downscale;
+        // `get_card_image_thumbnail` generates one lazily in both cases.
+        up_sql: r#"
+            ALTER TABLE card_images ADD COLUMN thumbnail_path TEXT;
+        "#,
+        down_sql: r#"
+            ALTER TABLE card_images DROP COLUMN thumbnail_path;
+        "#,
+    },
+    Migration {
+        version: 26,
+        name: "add_job_pause_resume",
+        // `bulk_import_card_images` needs more than what every other job type
+        // already gets from the `jobs` table (surviving a crash between
+        // enqueue and execution) - it needs to survive the user deliberately
+        // stopping a long import partway through and picking it back up
+        // later. SQLite has no `ALTER TABLE ... ADD CONSTRAINT`, so widening
+        // the `state` CHECK to admit `paused` means rebuilding the table the
+        // same way migration 14 did for `cards`/`time_sessions`/etc.
+        // `progress` is the job's resume point - item count, current index,
+        // results so far - serialized with MessagePack (`rmp-serde`) rather
+        // than JSON like `payload`, since it's rewritten after every single
+        // item instead of once at enqueue time and a denser binary format
+        // keeps that per-item write cheap. `NULL` for every job type besides
+        // `bulk_import_card_images`.
+        up_sql: r#"
+            CREATE TABLE jobs_new (
+                id TEXT PRIMARY KEY,
+                job_type TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                state TEXT NOT NULL CHECK(state IN ('queued', 'running', 'paused', 'completed', 'failed')) DEFAULT 'queued',
+                progress BLOB,
+                error_message TEXT,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                available_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO jobs_new (id, job_type, payload, state, error_message, attempts, created_at, updated_at, available_at)
+                SELECT id, job_type, payload, state, error_message, attempts, created_at, updated_at, available_at FROM jobs;
+            DROP TABLE jobs;
+            ALTER TABLE jobs_new RENAME TO jobs;
+
+            CREATE INDEX IF NOT EXISTS idx_jobs_state_available_at ON jobs(state, available_at);
+        "#,
+        down_sql: r#"
+            CREATE TABLE jobs_new (
+                id TEXT PRIMARY KEY,
+                job_type TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                state TEXT NOT NULL CHECK(state IN ('queued', 'running', 'completed', 'failed')) DEFAULT 'queued',
+                error_message TEXT,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                available_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO jobs_new (id, job_type, payload, state, error_message, attempts, created_at, updated_at, available_at)
+                SELECT id, job_type, payload, CASE WHEN state = 'paused' THEN 'queued' ELSE state END, error_message, attempts, created_at, updated_at, available_at FROM jobs;
+            DROP TABLE jobs;
+            ALTER TABLE jobs_new RENAME TO jobs;
+
+            CREATE INDEX IF NOT EXISTS idx_jobs_state_available_at ON jobs(state, available_at);
+        "#,
+    },
+    Migration {
+        version: 27,
+        name: "add_storage_roots_table",
+        // Persists `database::storage_roots::StorageRoots` across restarts -
+        // until now `configure_storage_roots` only ever replaced the
+        // in-memory list for the current run, so a user who pointed images
+        // at a secondary drive had to redo it every launch. `priority`
+        // breaks ties for `save_card_image`'s free-space search (see
+        // `DatabaseManager::pick_storage_root_for_write`): the
+        // highest-priority root that still has room wins. `reload_storage_roots`
+        // seeds this with a single `"default"` row (the `app_data_dir` the
+        // connection was opened with) the first time it runs against an
+        // empty table, so every existing single-root install keeps working
+        // unchanged.
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS storage_roots (
+                id TEXT PRIMARY KEY,
+                path TEXT NOT NULL,
+                priority INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS storage_roots;
+        "#,
+    },
+    Migration {
+        version: 28,
+        name: "add_image_labels_table",
+        // Backs the optional on-device OCR/auto-labeling pipeline (see
+        // `database::ocr`): one row per label/OCR line an inference pass
+        // found in a `card_images` row, so `search_problems`/`_by_topic` can
+        // match text that's only visible inside a pasted screenshot.
+        // `ON DELETE CASCADE` keeps this in sync when the image itself is
+        // deleted; `regenerate_image_labels` clears and re-inserts a row's
+        // labels itself rather than relying on the cascade for a re-run.
+        up_sql: r#"
+            CREATE TABLE IF NOT EXISTS image_labels (
+                id TEXT PRIMARY KEY,
+                image_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (image_id) REFERENCES card_images(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_image_labels_image_id ON image_labels(image_id);
+        "#,
+        down_sql: r#"
+            DROP TABLE IF EXISTS image_labels;
+        "#,
+    },
+];
+
+pub const LATEST_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
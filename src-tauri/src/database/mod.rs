@@ -1,11 +1,24 @@
+pub mod encryption;
+mod graph;
+mod indexes;
+pub mod jobs;
+pub mod maintenance;
+mod migrations;
+pub mod ocr;
+pub mod problem_bundle;
 mod schema;
+pub mod schema_validation;
+pub(crate) mod search;
+pub mod storage_roots;
+mod triggers;
 
 use rusqlite::{Connection, params, OptionalExtension};
 use anyhow::Context;
 use chrono::{Utc, DateTime, NaiveDateTime};
+use std::path::Path;
 use uuid::Uuid;
 use crate::models::*;
-use schema::{CREATE_TABLES_SQL, CREATE_INDEXES_SQL};
+use schema::CREATE_INDEXES_SQL;
 
 // Helper functions for JSON parsing
 fn parse_json_array(json_str: &str) -> Vec<String> {
@@ -28,12 +41,10 @@ fn parse_json_array(json_str: &str) -> Vec<String> {
     }
 }
 
-fn convert_problem_to_frontend(db_problem: Problem) -> FrontendProblem {
-    let related_problem_ids = match db_problem.related_problem_ids {
-        Some(ids_json) => parse_json_array(&ids_json),
-        None => Vec::new()
-    };
-    
+// `related_problem_ids` is passed in separately rather than parsed off
+// `db_problem` - it now lives in the normalized `problem_relations` table
+// (see `DatabaseManager::related_problem_ids_for`), not as JSON on the row.
+fn convert_problem_to_frontend(db_problem: Problem, related_problem_ids: Vec<String>) -> FrontendProblem {
     FrontendProblem {
         id: db_problem.id,
         title: db_problem.title,
@@ -67,8 +78,461 @@ fn parse_datetime_flexible(datetime_str: &str) -> DateTime<Utc> {
     Utc::now()
 }
 
+fn related_problem_ids_for_conn(conn: &Connection, problem_id: &str) -> anyhow::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT related_problem_id FROM problem_relations WHERE problem_id = ?1 ORDER BY related_problem_id"
+    )?;
+    let ids = stmt
+        .query_map(params![problem_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+fn set_problem_relations_conn(conn: &Connection, problem_id: &str, related_ids: &[String]) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM problem_relations WHERE problem_id = ?1", params![problem_id])?;
+    for related_id in related_ids {
+        if related_id == problem_id {
+            continue;
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO problem_relations (problem_id, related_problem_id) VALUES (?1, ?2)",
+            params![problem_id, related_id],
+        )?;
+    }
+    Ok(())
+}
+
+// Batch-operation core implementations (see `DatabaseManager::apply_batch`).
+// Unlike the single-op `DatabaseManager` methods, none of these opens its own
+// transaction - they're meant to run inside a transaction the caller already
+// holds open, so several of them can be rolled back together atomically.
+
+fn batch_create_problem(conn: &Connection, req: CreateProblemRequest) -> anyhow::Result<FrontendProblem> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let topic_json = serde_json::to_string(&req.topic)?;
+    let constraints_json = serde_json::to_string(&req.constraints)?;
+    let hints_json = serde_json::to_string(&req.hints)?;
+    let leetcode_url = req.leetcode_url.as_ref().map(|s| s.as_str()).unwrap_or("");
+
+    conn.execute(
+        "INSERT INTO problems (id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            &id,
+            &req.title,
+            &req.description,
+            &req.difficulty,
+            &topic_json,
+            leetcode_url,
+            &constraints_json,
+            &hints_json,
+            &now.to_rfc3339(),
+            &now.to_rfc3339(),
+        ],
+    )?;
+
+    let related_problem_ids = req.related_problem_ids.clone().unwrap_or_default();
+    set_problem_relations_conn(conn, &id, &related_problem_ids)?;
+
+    Ok(FrontendProblem {
+        id,
+        title: req.title,
+        description: req.description,
+        difficulty: req.difficulty,
+        topic: req.topic,
+        leetcode_url: req.leetcode_url,
+        constraints: req.constraints,
+        hints: req.hints,
+        related_problem_ids,
+        created_at: now,
+        updated_at: now,
+        tags: Vec::new(),
+    })
+}
+
+fn batch_get_problem_by_id(conn: &Connection, id: &str) -> anyhow::Result<Option<FrontendProblem>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at FROM problems WHERE id = ?1"
+    )?;
+
+    let mut problem_iter = stmt.query_map([id], |row| {
+        Ok(Problem {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            difficulty: row.get(3)?,
+            topic: row.get(4)?,
+            leetcode_url: row.get(5)?,
+            constraints: row.get(6)?,
+            hints: row.get(7)?,
+            related_problem_ids: None,
+            created_at: parse_datetime_flexible(&row.get::<_, String>(8)?),
+            updated_at: parse_datetime_flexible(&row.get::<_, String>(9)?),
+        })
+    })?;
+
+    match problem_iter.next() {
+        Some(problem) => {
+            let db_problem = problem?;
+            let related_ids = related_problem_ids_for_conn(conn, &db_problem.id).unwrap_or_default();
+            Ok(Some(convert_problem_to_frontend(db_problem, related_ids)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn batch_update_problem(conn: &Connection, req: UpdateProblemRequest) -> anyhow::Result<Option<FrontendProblem>> {
+    let existing_problem = batch_get_problem_by_id(conn, &req.id)?;
+    if existing_problem.is_none() {
+        return Err(anyhow::anyhow!("Problem with id '{}' not found", req.id));
+    }
+
+    let mut update_fields = Vec::new();
+    let mut update_values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(ref title) = req.title {
+        update_fields.push("title = ?");
+        update_values.push(Box::new(title.clone()));
+    }
+    if let Some(ref description) = req.description {
+        update_fields.push("description = ?");
+        update_values.push(Box::new(description.clone()));
+    }
+    if let Some(ref difficulty) = req.difficulty {
+        update_fields.push("difficulty = ?");
+        update_values.push(Box::new(difficulty.clone()));
+    }
+    if let Some(ref topic) = req.topic {
+        let topic_json = serde_json::to_string(topic)?;
+        update_fields.push("topic = ?");
+        update_values.push(Box::new(topic_json));
+    }
+    if let Some(ref leetcode_url) = req.leetcode_url {
+        update_fields.push("leetcode_url = ?");
+        update_values.push(Box::new(leetcode_url.clone()));
+    }
+    if let Some(ref constraints) = req.constraints {
+        let constraints_json = serde_json::to_string(constraints)?;
+        update_fields.push("constraints = ?");
+        update_values.push(Box::new(constraints_json));
+    }
+    if let Some(ref hints) = req.hints {
+        let hints_json = serde_json::to_string(hints)?;
+        update_fields.push("hints = ?");
+        update_values.push(Box::new(hints_json));
+    }
+
+    let relations_changed = req.related_problem_ids.is_some();
+
+    if update_fields.is_empty() && !relations_changed {
+        return Ok(existing_problem);
+    }
+
+    if !update_fields.is_empty() {
+        let now = Utc::now();
+        update_fields.push("updated_at = ?");
+        update_values.push(Box::new(now.to_rfc3339()));
+
+        let sql = format!("UPDATE problems SET {} WHERE id = ?", update_fields.join(", "));
+        update_values.push(Box::new(req.id.clone()));
+
+        let rows_affected = conn.execute(
+            &sql,
+            rusqlite::params_from_iter(update_values.iter().map(|v| v.as_ref())),
+        )?;
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Failed to update problem - no rows affected"));
+        }
+    }
+
+    if let Some(related_problem_ids) = req.related_problem_ids {
+        set_problem_relations_conn(conn, &req.id, &related_problem_ids)?;
+    }
+
+    batch_get_problem_by_id(conn, &req.id)
+}
+
+fn batch_get_card_by_id(conn: &Connection, card_id: &str) -> anyhow::Result<Option<Card>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id
+         FROM cards WHERE id = ?1"
+    )?;
+
+    let mut cards = stmt.query_map([card_id], |row| {
+        Ok(Card {
+            id: row.get(0)?,
+            problem_id: row.get(1)?,
+            card_number: row.get(2)?,
+            code: row.get(3)?,
+            language: row.get(4)?,
+            notes: row.get(5)?,
+            status: row.get(6)?,
+            total_duration: row.get(7)?,
+            created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+            last_modified: row.get::<_, String>(9)?.parse().unwrap_or_else(|_| Utc::now()),
+            parent_card_id: row.get(10)?,
+        })
+    })?;
+
+    Ok(cards.next().transpose()?)
+}
+
+fn batch_create_card(conn: &Connection, req: CreateCardRequest) -> anyhow::Result<Card> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let card_number: i32 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(card_number), 0) + 1 FROM cards WHERE problem_id = ?1",
+            [&req.problem_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(1);
+
+    let language = req.language.as_ref().map(|s| s.as_str()).unwrap_or("javascript");
+
+    match req.parent_card_id.as_ref() {
+        Some(parent_id) if !parent_id.is_empty() => {
+            conn.execute(
+                "INSERT INTO cards (id, problem_id, card_number, language, status, total_duration, created_at, last_modified, parent_card_id)
+                 VALUES (?1, ?2, ?3, ?4, 'In Progress', 0, ?5, ?6, ?7)",
+                params![&id, &req.problem_id, card_number, language, &now.to_rfc3339(), &now.to_rfc3339(), parent_id],
+            )?;
+        }
+        _ => {
+            conn.execute(
+                "INSERT INTO cards (id, problem_id, card_number, language, status, total_duration, created_at, last_modified, parent_card_id)
+                 VALUES (?1, ?2, ?3, ?4, 'In Progress', 0, ?5, ?6, NULL)",
+                params![&id, &req.problem_id, card_number, language, &now.to_rfc3339(), &now.to_rfc3339()],
+            )?;
+        }
+    }
+
+    Ok(Card {
+        id,
+        problem_id: req.problem_id,
+        card_number,
+        code: None,
+        language: language.to_string(),
+        notes: None,
+        status: "In Progress".to_string(),
+        total_duration: 0,
+        created_at: now,
+        last_modified: now,
+        parent_card_id: req.parent_card_id,
+    })
+}
+
+fn batch_update_card(conn: &Connection, req: UpdateCardRequest) -> anyhow::Result<Option<Card>> {
+    // `trg_cards_stamp_last_modified` stamps `last_modified` on any of these
+    // updates, so it's no longer set by hand here.
+    if let Some(ref code) = req.code {
+        conn.execute("UPDATE cards SET code = ?1 WHERE id = ?2", params![code, &req.id])?;
+    }
+    if let Some(ref notes) = req.notes {
+        conn.execute("UPDATE cards SET notes = ?1 WHERE id = ?2", params![notes, &req.id])?;
+    }
+    if let Some(ref language) = req.language {
+        conn.execute("UPDATE cards SET language = ?1 WHERE id = ?2", params![language, &req.id])?;
+    }
+    if let Some(ref status) = req.status {
+        conn.execute("UPDATE cards SET status = ?1 WHERE id = ?2", params![status, &req.id])?;
+    }
+
+    batch_get_card_by_id(conn, &req.id)
+}
+
+fn batch_delete_card(conn: &Connection, card_id: &str) -> anyhow::Result<()> {
+    let card = batch_get_card_by_id(conn, card_id)?;
+    if card.is_none() {
+        return Err(anyhow::anyhow!("Card with id '{}' not found", card_id));
+    }
+
+    conn.execute("DELETE FROM time_sessions WHERE card_id = ?1", [card_id]).unwrap_or(0);
+    conn.execute("DELETE FROM recordings WHERE card_id = ?1", [card_id]).unwrap_or(0);
+    conn.execute("DELETE FROM connections WHERE source_card_id = ?1 OR target_card_id = ?1", [card_id]).unwrap_or(0);
+
+    let rows_affected = conn.execute("DELETE FROM cards WHERE id = ?1", [card_id])?;
+    if rows_affected == 0 {
+        return Err(anyhow::anyhow!("Failed to delete card - no rows affected"));
+    }
+    Ok(())
+}
+
+fn batch_add_tag(conn: &Connection, req: AddProblemTagRequest) -> anyhow::Result<Tag> {
+    let existing_tag: Option<Tag> = conn
+        .query_row(
+            "SELECT id, name, color, category FROM tags WHERE name = ?1",
+            [&req.tag_name],
+            |row| {
+                Ok(Tag {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    color: row.get(2)?,
+                    category: row.get(3)?,
+                })
+            },
+        )
+        .optional()?;
+
+    let tag = match existing_tag {
+        Some(tag) => tag,
+        None => {
+            let tag_id = Uuid::new_v4().to_string();
+            let category = req.category.unwrap_or_else(|| "custom".to_string());
+            conn.execute(
+                "INSERT INTO tags (id, name, color, category) VALUES (?1, ?2, ?3, ?4)",
+                params![&tag_id, &req.tag_name, &req.color, &category],
+            )?;
+            Tag {
+                id: tag_id,
+                name: req.tag_name.clone(),
+                color: req.color.clone(),
+                category,
+            }
+        }
+    };
+
+    let exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM problem_tags WHERE problem_id = ?1 AND tag_id = ?2",
+        params![&req.problem_id, &tag.id],
+        |row| row.get(0),
+    )?;
+    if exists == 0 {
+        conn.execute(
+            "INSERT INTO problem_tags (problem_id, tag_id) VALUES (?1, ?2)",
+            params![&req.problem_id, &tag.id],
+        )?;
+    }
+
+    Ok(tag)
+}
+
+fn batch_remove_tag(conn: &Connection, req: RemoveProblemTagRequest) -> anyhow::Result<()> {
+    conn.execute(
+        "DELETE FROM problem_tags WHERE problem_id = ?1 AND tag_id = ?2",
+        params![&req.problem_id, &req.tag_id],
+    )?;
+    Ok(())
+}
+
+fn batch_reorder_images(conn: &Connection, updates: &[(String, i32)]) -> anyhow::Result<()> {
+    for (image_id, position) in updates {
+        conn.execute(
+            "UPDATE problem_images SET position = ?1 WHERE id = ?2",
+            params![position, image_id],
+        )?;
+    }
+    Ok(())
+}
+
+fn batch_save_recording(
+    conn: &Connection,
+    card_id: &str,
+    filename: &str,
+    filepath: &str,
+    duration: Option<i32>,
+) -> anyhow::Result<Recording> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let file_size = maintenance::resolve_recording_path(filepath)
+        .ok()
+        .and_then(|path| std::fs::metadata(&path).ok())
+        .map(|metadata| metadata.len() as i64);
+
+    conn.execute(
+        "INSERT INTO recordings (id, card_id, audio_url, filename, filepath, duration, file_size, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![&id, card_id, filepath, filename, filepath, duration, file_size, &now.to_rfc3339()],
+    )?;
+
+    Ok(Recording {
+        id,
+        card_id: card_id.to_string(),
+        time_session_id: None,
+        audio_url: filepath.to_string(),
+        duration,
+        transcript: None,
+        created_at: now,
+        filename: filename.to_string(),
+        filepath: filepath.to_string(),
+        file_size,
+    })
+}
+
+fn batch_delete_recording(conn: &Connection, recording_id: &str) -> anyhow::Result<()> {
+    let rows_affected = conn.execute("DELETE FROM recordings WHERE id = ?1", [recording_id])?;
+    if rows_affected == 0 {
+        return Err(anyhow::anyhow!("Recording with id '{}' not found", recording_id));
+    }
+    Ok(())
+}
+
+/// Dispatches one [`BatchOp`] against `conn`, the transaction `apply_batch`
+/// holds open for the whole run. Kept as a single op -> single result
+/// function (rather than inlined in `apply_batch`'s loop) so a failing op's
+/// error can be caught per-iteration instead of aborting the loop via `?`.
+fn apply_one_batch_op(conn: &Connection, op: BatchOp) -> anyhow::Result<BatchOpResult> {
+    Ok(match op {
+        BatchOp::CreateProblem(req) => BatchOpResult::ProblemCreated(batch_create_problem(conn, req)?),
+        BatchOp::UpdateProblem(req) => BatchOpResult::ProblemUpdated(batch_update_problem(conn, req)?),
+        BatchOp::CreateCard(req) => BatchOpResult::CardCreated(batch_create_card(conn, req)?),
+        BatchOp::UpdateCard(req) => BatchOpResult::CardUpdated(batch_update_card(conn, req)?),
+        BatchOp::DeleteCard(card_id) => {
+            batch_delete_card(conn, &card_id)?;
+            BatchOpResult::CardDeleted(card_id)
+        }
+        BatchOp::AddTag(req) => BatchOpResult::TagAdded(batch_add_tag(conn, req)?),
+        BatchOp::RemoveTag(req) => {
+            batch_remove_tag(conn, req)?;
+            BatchOpResult::TagRemoved
+        }
+        BatchOp::ReorderImages(updates) => {
+            batch_reorder_images(conn, &updates)?;
+            BatchOpResult::ImagesReordered
+        }
+        BatchOp::SaveRecording(req) => BatchOpResult::RecordingSaved(batch_save_recording(
+            conn,
+            &req.card_id,
+            &req.filename,
+            &req.filepath,
+            req.duration,
+        )?),
+        BatchOp::DeleteRecording(recording_id) => {
+            batch_delete_recording(conn, &recording_id)?;
+            BatchOpResult::RecordingDeleted(recording_id)
+        }
+    })
+}
+
+// Records a migration as applied against whatever connection/transaction handle
+// is driving the current migration run (`rusqlite::Transaction` derefs to
+// `Connection`, so this takes either).
+fn record_migration_applied_on(conn: &Connection, migration: &migrations::Migration) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (?1, ?2, ?3, ?4)",
+        params![migration.version, migration.name, migration_checksum(migration), Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// A stable content hash of a migration's `up_sql`, recorded alongside its
+/// version and name so a future audit can tell if a migration's body was
+/// edited after it had already been applied somewhere.
+fn migration_checksum(migration: &migrations::Migration) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    migration.up_sql.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
 pub struct DatabaseManager {
     connection: Connection,
+    db_path: std::path::PathBuf,
+    storage_roots: storage_roots::StorageRoots,
 }
 
 impl DatabaseManager {
@@ -108,16 +572,257 @@ impl DatabaseManager {
         let _: i32 = connection.query_row("PRAGMA foreign_keys = ON", [], |row| row.get(0)).unwrap_or(0);
         let _: String = connection.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0)).unwrap_or_else(|_| "delete".to_string());
         
-        let mut db_manager = Self { connection };
-        
+        let storage_roots = storage_roots::StorageRoots::single(app_data_dir);
+        let mut db_manager = Self { connection, db_path, storage_roots };
+
         // Initialize database schema
         db_manager.init_schema()
             .await
             .context("Failed to initialize database schema")?;
-        
+        db_manager.reload_storage_roots()
+            .context("Failed to load storage roots")?;
+
         Ok(db_manager)
     }
-    
+
+    /// Like [`Self::new_with_path`], but opens (or creates) `database.db`
+    /// encrypted under `passphrase` via SQLCipher's `PRAGMA key`, instead of a
+    /// plaintext connection. See [`encryption`] for what that requires of the
+    /// `rusqlite` build. Opening an existing encrypted file with the wrong
+    /// passphrase fails with [`encryption::DatabaseError::BadPassphrase`].
+    pub async fn new_encrypted_with_path(app_data_dir: std::path::PathBuf, passphrase: &str) -> anyhow::Result<Self> {
+        println!("🔧 [Database] Initializing encrypted database with path: {}", app_data_dir.display());
+
+        std::fs::create_dir_all(&app_data_dir)
+            .context("Failed to create app data directory")?;
+
+        let db_path = app_data_dir.join("database.db");
+        let connection = encryption::open_encrypted(&db_path, passphrase)?;
+
+        let _: i32 = connection.query_row("PRAGMA foreign_keys = ON", [], |row| row.get(0)).unwrap_or(0);
+        let _: String = connection.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0)).unwrap_or_else(|_| "delete".to_string());
+
+        let storage_roots = storage_roots::StorageRoots::single(app_data_dir);
+        let mut db_manager = Self { connection, db_path, storage_roots };
+
+        db_manager.init_schema()
+            .await
+            .context("Failed to initialize database schema")?;
+        db_manager.reload_storage_roots()
+            .context("Failed to load storage roots")?;
+
+        Ok(db_manager)
+    }
+
+    /// Changes (`old` is `Some`) or sets for the first time (`old` is `None`)
+    /// the passphrase protecting this database, via `PRAGMA rekey`. See
+    /// [`encryption::rekey`].
+    pub fn set_passphrase(&mut self, old: Option<&str>, new: &str) -> anyhow::Result<()> {
+        encryption::rekey(&self.connection, old, new)
+    }
+
+    /// Replaces the configured [`storage_roots::StorageRoots`], e.g. to add a
+    /// large secondary drive for recordings/images while the database file
+    /// stays on the default root. Defaults to a single `"default"` root at
+    /// the `app_data_dir` passed to whichever constructor opened this
+    /// connection.
+    pub fn set_storage_roots(&mut self, roots: storage_roots::StorageRoots) {
+        self.storage_roots = roots;
+    }
+
+    /// Resolves a `recordings.filepath`/`problem_images.image_path` value to
+    /// an absolute filesystem path by trying each configured storage root in
+    /// turn. See [`storage_roots::StorageRoots::resolve`].
+    pub fn resolve_media_path(&self, stored: &str) -> anyhow::Result<std::path::PathBuf> {
+        self.storage_roots.resolve(stored)
+    }
+
+    /// Seeds a `"default"` row into the (possibly empty, on first run) `storage_roots`
+    /// table from whichever root the constructor built in-memory, then loads
+    /// `self.storage_roots` from the table ordered by `priority DESC, id ASC` -
+    /// called once after `init_schema()` so roots added via `add_storage_root`
+    /// in a previous run are picked back up on every restart.
+    fn reload_storage_roots(&mut self) -> anyhow::Result<()> {
+        let existing: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM storage_roots",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if existing == 0 {
+            for root in self.storage_roots.roots() {
+                self.connection.execute(
+                    "INSERT INTO storage_roots (id, path, priority) VALUES (?1, ?2, ?3)",
+                    params![root.id, root.path.to_string_lossy(), root.priority],
+                )?;
+            }
+        }
+
+        let mut stmt = self.connection.prepare(
+            "SELECT id, path, priority FROM storage_roots ORDER BY priority DESC, id ASC",
+        )?;
+        let roots = stmt
+            .query_map([], |row| {
+                Ok(storage_roots::StorageRoot {
+                    id: row.get(0)?,
+                    path: std::path::PathBuf::from(row.get::<_, String>(1)?),
+                    priority: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        self.storage_roots = storage_roots::StorageRoots::new(roots);
+        Ok(())
+    }
+
+    /// Registers a new storage root and persists it immediately, so it
+    /// survives the next restart without a `configure_storage_roots` call.
+    pub fn add_storage_root(&mut self, id: &str, path: &str, priority: i32) -> anyhow::Result<()> {
+        self.connection.execute(
+            "INSERT INTO storage_roots (id, path, priority) VALUES (?1, ?2, ?3)",
+            params![id, path, priority],
+        ).context("Failed to insert storage root")?;
+        self.reload_storage_roots()
+    }
+
+    /// Lists all configured storage roots, highest priority first, for the
+    /// frontend's storage-settings view.
+    pub fn list_storage_roots(&self) -> anyhow::Result<Vec<storage_roots::StorageRootRow>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, path, priority, created_at FROM storage_roots ORDER BY priority DESC, id ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(storage_roots::StorageRootRow {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    priority: row.get(2)?,
+                    created_at: row.get::<_, String>(3)?.parse().unwrap_or_else(|_| Utc::now()),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Number of `recordings`/`card_images` rows whose stored path is
+    /// prefixed with `id:` - i.e. rows that would become unresolvable if `id`
+    /// were removed. Used by `remove_storage_root` to refuse a removal that
+    /// would strand media rather than only rejecting the last-root case.
+    fn count_rows_referencing_root(&self, id: &str) -> anyhow::Result<i64> {
+        let prefix = format!("{}:%", id);
+        let recordings: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM recordings WHERE filepath LIKE ?1",
+            params![prefix],
+            |row| row.get(0),
+        )?;
+        let images: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM card_images WHERE image_path LIKE ?1",
+            params![prefix],
+            |row| row.get(0),
+        )?;
+        Ok(recordings + images)
+    }
+
+    /// Removes a configured storage root. Refuses to remove the last
+    /// remaining root, since `save_card_image`/`start_recording` always need
+    /// somewhere to write. Also refuses - unless `force` is set - to remove a
+    /// root that still holds recordings or images, since doing so strands
+    /// those rows' stored paths; `force` removes it anyway, leaving the
+    /// affected rows for `verify_storage_roots`/a recording scan to surface.
+    pub fn remove_storage_root(&mut self, id: &str, force: bool) -> anyhow::Result<()> {
+        let count: i64 = self.connection.query_row("SELECT COUNT(*) FROM storage_roots", [], |row| row.get(0))?;
+        if count <= 1 {
+            anyhow::bail!("Cannot remove the last remaining storage root");
+        }
+
+        if !force {
+            let referencing = self.count_rows_referencing_root(id)?;
+            if referencing > 0 {
+                anyhow::bail!(
+                    "Storage root '{}' still holds {} recording(s)/image(s); pass force to remove it anyway",
+                    id, referencing
+                );
+            }
+        }
+
+        self.connection.execute("DELETE FROM storage_roots WHERE id = ?1", params![id])
+            .context("Failed to delete storage root")?;
+        self.reload_storage_roots()
+    }
+
+    /// Picks the highest-priority root with at least `min_free_bytes` free
+    /// for `save_card_image_bytes` to write a new image (and its thumbnail)
+    /// under.
+    pub fn pick_storage_root_for_write(&self, min_free_bytes: u64) -> anyhow::Result<storage_roots::StorageRoot> {
+        self.storage_roots
+            .pick_for_write(min_free_bytes)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No configured storage root has enough free space"))
+    }
+
+    /// Walks every `card_images` row and confirms its backing file exists in
+    /// the root it claims to live in, surfacing the kind of silent breakage
+    /// `delete_card_image`'s old "file doesn't exist" log used to hide -
+    /// once images can live on removable or secondary drives, the UI needs an
+    /// explicit way to ask "is everything actually still there?"
+    pub fn verify_storage_roots(&self) -> anyhow::Result<Vec<crate::models::StorageRootIntegrityIssue>> {
+        let mut stmt = self.connection.prepare("SELECT id, image_path FROM card_images")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut issues = Vec::new();
+        for (card_image_id, stored_path) in rows {
+            if let Some((root_id, _)) = stored_path.split_once(':') {
+                if !self.storage_roots.contains(root_id) {
+                    issues.push(crate::models::StorageRootIntegrityIssue {
+                        card_image_id,
+                        stored_path,
+                        kind: "misplaced".to_string(),
+                    });
+                    continue;
+                }
+            }
+
+            let exists = self.resolve_media_path(&stored_path).map(|p| p.exists()).unwrap_or(false);
+            if !exists {
+                issues.push(crate::models::StorageRootIntegrityIssue {
+                    card_image_id,
+                    stored_path,
+                    kind: "missing".to_string(),
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Encrypts this (plaintext) database file in place under `passphrase`:
+    /// exports to a sibling encrypted copy, backs up the plaintext original
+    /// the same way [`Self::migrate_to_latest`] does before a migration, then
+    /// swaps the encrypted copy into `db_path` and reopens the connection
+    /// against it.
+    pub fn encrypt_in_place(&mut self, passphrase: &str) -> anyhow::Result<()> {
+        let encrypted_path = self.db_path.with_extension("db.encrypting");
+        if encrypted_path.exists() {
+            std::fs::remove_file(&encrypted_path)
+                .context("Failed to clear a previous incomplete encryption attempt")?;
+        }
+
+        encryption::export_to_encrypted_copy(&self.db_path, &encrypted_path, passphrase)?;
+
+        self.backup_database_file()
+            .context("Failed to back up the plaintext database before swapping in the encrypted copy")?;
+
+        std::fs::rename(&encrypted_path, &self.db_path)
+            .context("Failed to swap the encrypted copy into place")?;
+
+        self.connection = encryption::open_encrypted(&self.db_path, passphrase)?;
+        Ok(())
+    }
+
     pub async fn connect_existing() -> anyhow::Result<Self> {
         println!("🔧 [Database] Attempting to connect to existing database...");
         
@@ -156,513 +861,412 @@ impl DatabaseManager {
         let _: i32 = connection.query_row("PRAGMA foreign_keys = ON", [], |row| row.get(0)).unwrap_or(0);
         let _: String = connection.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0)).unwrap_or_else(|_| "delete".to_string());
         
-        let mut db_manager = Self { connection };
-        
+        let storage_roots = storage_roots::StorageRoots::single(app_data_dir);
+        let mut db_manager = Self { connection, db_path, storage_roots };
+
         // CRITICAL: Always check and run migration for existing databases
         println!("🔧 [Database] Checking if migration is needed...");
         db_manager.init_schema()
             .await
             .context("Failed to initialize/migrate database schema")?;
-        
+        db_manager.reload_storage_roots()
+            .context("Failed to load storage roots")?;
+
         println!("🔧 [Database] Connected to existing database with schema validation complete");
         Ok(db_manager)
     }
     
     async fn init_schema(&mut self) -> anyhow::Result<()> {
         println!("🔧 [Database] Initializing comprehensive database schema...");
-        
-        // Check if this is a migration from old schema
-        let needs_migration = self.check_migration_needed().await?;
-        println!("🔧 [Database] Migration needed: {}", needs_migration);
-        
-        if needs_migration {
-            println!("🔧 [Database] Existing database detected - performing safe migration...");
-            self.migrate_database().await?;
-        } else {
-            println!("🔧 [Database] Creating fresh database with complete schema...");
-            self.create_fresh_schema().await?;
-        }
-        
-        // Verify time_sessions table exists after migration/creation
+
+        self.migrate_to_latest()?;
+
+        // Belt-and-suspenders check against the committed connection.
         let time_sessions_exists = self.verify_time_sessions_table().await?;
         println!("🔧 [Database] time_sessions table exists: {}", time_sessions_exists);
-        
         if !time_sessions_exists {
-            println!("❌ [Database] CRITICAL: time_sessions table missing after migration!");
             return Err(anyhow::anyhow!("time_sessions table was not created successfully"));
         }
-        
+
+        search::ensure_fts_index(&self.connection)?;
+        indexes::ensure_indexes(&self.connection)?;
+        triggers::install_triggers(&self.connection)?;
+
         println!("✅ [Database] Schema initialization completed successfully!");
         Ok(())
     }
-    
-    async fn verify_time_sessions_table(&self) -> anyhow::Result<bool> {
-        let count: i64 = self.connection.query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='time_sessions'",
-            [],
-            |row| row.get(0)
-        )?;
-        Ok(count > 0)
-    }
-    
-    async fn check_migration_needed(&self) -> anyhow::Result<bool> {
-        println!("🔍 [Database] Checking migration status...");
-        
-        // Check if problems table exists (indicates existing database)
-        let problems_exists: i64 = self.connection.query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='problems'",
-            [],
-            |row| row.get(0)
-        )?;
-        println!("🔍 [Database] problems table exists: {}", problems_exists > 0);
-        
-        // Check if time_sessions table exists (indicates complete schema)
-        let time_sessions_exists: i64 = self.connection.query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='time_sessions'",
+
+    /// Applies every migration newer than the database's current recorded
+    /// version, in order, inside a single transaction - bootstrapping
+    /// `schema_migrations` first if this database predates the registry.
+    /// This is the same work `init_schema` runs automatically on every
+    /// connect; exposed publicly so it can also be triggered explicitly
+    /// (e.g. from a maintenance command) without reopening the connection.
+    pub fn migrate_to_latest(&mut self) -> anyhow::Result<()> {
+        self.ensure_schema_migrations_table()?;
+
+        let recorded_version: Option<i64> = self.connection.query_row(
+            "SELECT MAX(version) FROM schema_migrations",
             [],
-            |row| row.get(0)
+            |row| row.get(0),
         )?;
-        println!("🔍 [Database] time_sessions table exists: {}", time_sessions_exists > 0);
-        
-        // Check if problems table has old 'category' column but no 'topic' column
-        let has_category_column = if problems_exists > 0 {
-            let column_info: Result<Vec<String>, _> = self.connection.prepare("PRAGMA table_info(problems)")?
-                .query_map([], |row| Ok(row.get::<_, String>(1)?))?
-                .collect();
-            
-            match column_info {
-                Ok(columns) => {
-                    println!("🔍 [Database] problems table columns: {:?}", columns);
-                    let has_category = columns.contains(&"category".to_string());
-                    let has_topic = columns.contains(&"topic".to_string());
-                    println!("🔍 [Database] has_category: {}, has_topic: {}", has_category, has_topic);
-                    has_category && !has_topic
-                }
-                Err(e) => {
-                    println!("🔍 [Database] Failed to get column info: {}", e);
-                    false
+        let starting_version = match recorded_version {
+            Some(v) => v,
+            None => self.detect_existing_schema_version()?,
+        };
+
+        let pending: Vec<&migrations::Migration> = migrations::MIGRATIONS
+            .iter()
+            .filter(|m| m.version > starting_version)
+            .collect();
+
+        // Only touch the database file/schema when there's actually bootstrap
+        // bookkeeping or a migration to run - an already-current database is
+        // a no-op.
+        if recorded_version.is_none() || !pending.is_empty() {
+            self.backup_database_file()
+                .context("Failed to create pre-migration backup")?;
+
+            // `PRAGMA foreign_keys` is a documented no-op once a transaction is
+            // open, so the `PRAGMA foreign_keys = OFF` every table-rebuild
+            // migration's `up_sql` starts with does nothing here - it has to be
+            // toggled on the connection itself, outside the transaction, the
+            // same way `rebuild_table` does it.
+            self.connection.execute("PRAGMA foreign_keys = OFF", [])?;
+
+            println!("🔧 [Database] Running {} pending migration(s) in a single transaction...", pending.len());
+            let tx = self.connection.unchecked_transaction()?;
+
+            if recorded_version.is_none() {
+                println!("🔧 [Database] Bootstrapping un-tracked database at schema version {}", starting_version);
+                for migration in migrations::MIGRATIONS.iter().filter(|m| m.version <= starting_version) {
+                    record_migration_applied_on(&tx, migration)?;
                 }
             }
-        } else {
-            false
-        };
-        
-        // List all existing tables for debugging
-        let tables: Vec<String> = self.connection.prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")?
-            .query_map([], |row| Ok(row.get::<_, String>(0)?))?
-            .collect::<Result<Vec<String>, _>>()?;
-        println!("🔍 [Database] Existing tables: {:?}", tables);
-        
-        // Migration needed if:
-        // 1. Problems exist but time_sessions don't (original migration)
-        // 2. Problems table has 'category' column but no 'topic' column (new migration)
-        let needs_table_migration = problems_exists > 0 && time_sessions_exists == 0;
-        let needs_column_migration = has_category_column;
-        let migration_needed = needs_table_migration || needs_column_migration;
-        
-        println!("🔍 [Database] Migration logic: table_migration={}, column_migration={}, total_needed={}", 
-                 needs_table_migration,
-                 needs_column_migration,
-                 migration_needed);
-        
-        Ok(migration_needed)
-    }
-    
-    async fn create_fresh_schema(&mut self) -> anyhow::Result<()> {
-        println!("🏗️ [Database] Executing complete table creation...");
-        
-        // Execute all table creation statements
-        match self.connection.execute_batch(CREATE_TABLES_SQL) {
-            Ok(_) => println!("✅ [Database] Tables created successfully"),
-            Err(e) => {
-                println!("❌ [Database] Failed to create tables: {}", e);
-                return Err(anyhow::anyhow!("Failed to create tables: {}", e));
+
+            for migration in &pending {
+                println!("🔄 [Database Migration] Applying migration {} ({})...", migration.version, migration.name);
+                tx.execute_batch(migration.up_sql)
+                    .with_context(|| format!("Failed to apply migration {} ({})", migration.version, migration.name))?;
+                record_migration_applied_on(&tx, migration)?;
+                println!("✅ [Database Migration] Applied migration {} ({})", migration.version, migration.name);
             }
-        }
-        
-        println!("🏗️ [Database] Creating performance indexes...");
-        
-        // Execute all index creation statements
-        match self.connection.execute_batch(CREATE_INDEXES_SQL) {
-            Ok(_) => println!("✅ [Database] Indexes created successfully"),
-            Err(e) => {
-                println!("❌ [Database] Failed to create indexes: {}", e);
-                return Err(anyhow::anyhow!("Failed to create indexes: {}", e));
+
+            // Checked inside the transaction so a failure here rolls the whole
+            // migration run back instead of committing a half-migrated schema.
+            let time_sessions_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='time_sessions'",
+                [],
+                |row| row.get(0),
+            )?;
+            if time_sessions_exists == 0 {
+                println!("❌ [Database] CRITICAL: time_sessions table missing after migration - rolling back!");
+                return Err(anyhow::anyhow!("time_sessions table was not created successfully"));
             }
+
+            tx.commit()?;
+
+            self.connection.execute("PRAGMA foreign_keys = ON", [])?;
         }
-        
-        println!("✅ [Database] Fresh schema creation completed");
+
         Ok(())
     }
-    
-    async fn migrate_database(&mut self) -> anyhow::Result<()> {
-        println!("🔄 [Database Migration] Starting comprehensive migration process...");
-        
-        // First, verify what tables currently exist
-        let existing_tables: Vec<String> = self.connection.prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")?
-            .query_map([], |row| Ok(row.get::<_, String>(0)?))
-            .context("Failed to query existing tables")?
-            .collect::<Result<Vec<String>, _>>()?;
-        println!("🔄 [Database Migration] Current tables before migration: {:?}", existing_tables);
-        
-        // Check if we need to migrate category -> topic column
-        let needs_column_migration = if existing_tables.contains(&"problems".to_string()) {
-            let column_info: Result<Vec<String>, _> = self.connection.prepare("PRAGMA table_info(problems)")?
-                .query_map([], |row| Ok(row.get::<_, String>(1)?))?
-                .collect();
-                
-            match column_info {
-                Ok(columns) => {
-                    let has_category = columns.contains(&"category".to_string());
-                    let has_topic = columns.contains(&"topic".to_string());
-                    has_category && !has_topic
-                }
-                Err(_) => false
-            }
-        } else {
-            false
-        };
-        
-        // Perform category -> topic migration if needed
-        if needs_column_migration {
-            println!("🔄 [Database Migration] Migrating category column to topic...");
-            self.migrate_category_to_topic().await?;
-        }
-        
-        // Check if we need to add related_problem_ids column
-        println!("🔍 [Database Migration] Checking if related_problem_ids column migration is needed...");
-        let needs_related_problems_migration = if existing_tables.contains(&"problems".to_string()) {
-            println!("🔍 [Database Migration] Problems table exists, checking columns...");
-            let column_info: Result<Vec<String>, _> = self.connection.prepare("PRAGMA table_info(problems)")?
-                .query_map([], |row| Ok(row.get::<_, String>(1)?))?
-                .collect();
-                
-            match column_info {
-                Ok(columns) => {
-                    println!("🔍 [Database Migration] Current problems table columns: {:?}", columns);
-                    let has_related_problem_ids = columns.contains(&"related_problem_ids".to_string());
-                    println!("🔍 [Database Migration] Has related_problem_ids column: {}", has_related_problem_ids);
-                    let needs_migration = !has_related_problem_ids;
-                    println!("🔍 [Database Migration] Needs related_problem_ids migration: {}", needs_migration);
-                    needs_migration
-                }
-                Err(e) => {
-                    println!("⚠️ [Database Migration] Failed to get column info: {}", e);
-                    false
-                }
-            }
-        } else {
-            println!("🔍 [Database Migration] Problems table does not exist, no related_problem_ids migration needed");
-            false
-        };
-        
-        // Perform related_problem_ids migration if needed
-        if needs_related_problems_migration {
-            println!("🔄 [Database Migration] Related problems migration required - executing...");
-            match self.migrate_add_related_problems_column().await {
-                Ok(()) => {
-                    println!("✅ [Database Migration] Related problems migration completed successfully!");
-                },
-                Err(e) => {
-                    let error_msg = format!("Related problems migration failed: {}", e);
-                    println!("❌ [Database Migration] {}", error_msg);
-                    return Err(anyhow::anyhow!(error_msg));
-                }
-            }
-        } else {
-            println!("ℹ️ [Database Migration] Related problems migration not needed, skipping...");
-        }
-        
-        // Add missing tables one by one with error handling
-        let missing_tables = [
-            ("time_sessions", "CREATE TABLE IF NOT EXISTS time_sessions (
-                id TEXT PRIMARY KEY,
-                card_id TEXT NOT NULL,
-                start_time DATETIME NOT NULL,
-                end_time DATETIME,
-                duration INTEGER,
-                date DATE,
-                is_active INTEGER DEFAULT 0,
-                notes TEXT,
-                FOREIGN KEY (card_id) REFERENCES cards(id)
-            )"),
-            ("recordings", "CREATE TABLE IF NOT EXISTS recordings (
-                id TEXT PRIMARY KEY,
-                card_id TEXT NOT NULL,
-                time_session_id TEXT,
-                audio_url TEXT NOT NULL,
-                duration INTEGER,
-                transcript TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                filename TEXT NOT NULL,
-                filepath TEXT NOT NULL,
-                file_size INTEGER,
-                FOREIGN KEY (card_id) REFERENCES cards(id),
-                FOREIGN KEY (time_session_id) REFERENCES time_sessions(id)
-            )"),
-            ("connections", "CREATE TABLE IF NOT EXISTS connections (
-                id TEXT PRIMARY KEY,
-                source_card_id TEXT NOT NULL,
-                target_card_id TEXT NOT NULL,
-                connection_type TEXT CHECK(connection_type IN ('related', 'prerequisite', 'similar', 'builds-upon')),
-                notes TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (source_card_id) REFERENCES cards(id),
-                FOREIGN KEY (target_card_id) REFERENCES cards(id)
-            )"),
-            ("tags", "CREATE TABLE IF NOT EXISTS tags (
-                id TEXT PRIMARY KEY,
-                name TEXT UNIQUE NOT NULL,
-                color TEXT,
-                category TEXT CHECK(category IN ('algorithm', 'data-structure', 'pattern', 'custom'))
-            )"),
-            ("problem_tags", "CREATE TABLE IF NOT EXISTS problem_tags (
-                problem_id TEXT,
-                tag_id TEXT,
-                PRIMARY KEY (problem_id, tag_id),
-                FOREIGN KEY (problem_id) REFERENCES problems(id),
-                FOREIGN KEY (tag_id) REFERENCES tags(id)
-            )"),
-            ("card_tags", "CREATE TABLE IF NOT EXISTS card_tags (
-                card_id TEXT,
-                tag_id TEXT,
-                PRIMARY KEY (card_id, tag_id),
-                FOREIGN KEY (card_id) REFERENCES cards(id),
-                FOREIGN KEY (tag_id) REFERENCES tags(id)
-            )")
-        ];
-        
-        for (table_name, create_sql) in missing_tables.iter() {
-            println!("🔄 [Database Migration] Processing table: {}", table_name);
-            println!("🔄 [Database Migration] SQL: {}", create_sql.chars().take(100).collect::<String>() + "...");
-            
-            match self.connection.execute(create_sql, []) {
-                Ok(rows_affected) => {
-                    println!("✅ [Database Migration] Successfully processed table: {} (rows affected: {})", table_name, rows_affected);
-                },
-                Err(e) => {
-                    println!("❌ [Database Migration] Failed to create table {}: {}", table_name, e);
-                    return Err(anyhow::anyhow!("Migration failed for table {}: {}", table_name, e));
-                }
-            }
-        }
-        
-        // Add missing indexes
-        println!("🔄 [Database Migration] Adding performance indexes...");
-        let missing_indexes = [
-            "CREATE INDEX IF NOT EXISTS idx_time_sessions_card_id ON time_sessions(card_id)",
-            "CREATE INDEX IF NOT EXISTS idx_time_sessions_date ON time_sessions(date)",
-            "CREATE INDEX IF NOT EXISTS idx_recordings_card_id ON recordings(card_id)",
-            "CREATE INDEX IF NOT EXISTS idx_connections_source ON connections(source_card_id)",
-            "CREATE INDEX IF NOT EXISTS idx_connections_target ON connections(target_card_id)"
-        ];
-        
-        for (i, index_sql) in missing_indexes.iter().enumerate() {
-            println!("🔄 [Database Migration] Adding index {}/{}: {}", i+1, missing_indexes.len(), index_sql.chars().take(80).collect::<String>() + "...");
-            match self.connection.execute(index_sql, []) {
-                Ok(rows_affected) => println!("✅ [Database Migration] Index added successfully (rows affected: {})", rows_affected),
-                Err(e) => {
-                    println!("⚠️ [Database Migration] Index creation warning: {}", e);
-                    // Don't fail on index errors, they might already exist
-                }
-            }
+
+    /// Copies the database file to `<path>.bak-<unix-timestamp>` before a
+    /// migration run touches it, so a catastrophic failure - or a DDL
+    /// statement SQLite can't roll back cleanly - is still recoverable.
+    fn backup_database_file(&self) -> anyhow::Result<()> {
+        if !self.db_path.exists() {
+            return Ok(());
         }
-        
-        // Verify tables exist after migration
-        let final_tables: Vec<String> = self.connection.prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")?
-            .query_map([], |row| Ok(row.get::<_, String>(0)?))
-            .context("Failed to verify tables after migration")?
-            .collect::<Result<Vec<String>, _>>()?;
-        println!("🔄 [Database Migration] Tables after migration: {:?}", final_tables);
-        
-        // Check specifically for time_sessions table
-        let time_sessions_count: i64 = self.connection.query_row(
+
+        let timestamp = Utc::now().timestamp();
+        let backup_path = self.db_path.with_file_name(format!(
+            "{}.bak-{}",
+            self.db_path.file_name().and_then(|n| n.to_str()).unwrap_or("database.db"),
+            timestamp
+        ));
+
+        std::fs::copy(&self.db_path, &backup_path)
+            .with_context(|| format!("Failed to copy {} to {}", self.db_path.display(), backup_path.display()))?;
+        println!("🗄️ [Database] Backed up database to {}", backup_path.display());
+        Ok(())
+    }
+
+    async fn verify_time_sessions_table(&self) -> anyhow::Result<bool> {
+        let count: i64 = self.connection.query_row(
             "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='time_sessions'",
             [],
             |row| row.get(0)
         )?;
-        println!("🔄 [Database Migration] time_sessions table check: {} (should be 1)", time_sessions_count);
-        
-        if time_sessions_count == 0 {
-            return Err(anyhow::anyhow!("CRITICAL: time_sessions table was not created during migration!"));
+        Ok(count > 0)
+    }
+
+    fn ensure_schema_migrations_table(&self) -> anyhow::Result<()> {
+        self.connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT,
+                applied_at DATETIME NOT NULL
+            )"
+        )?;
+
+        // `checksum` was added after `schema_migrations` itself - backfill it
+        // for databases that created the table before this column existed.
+        let columns = self.table_columns("schema_migrations")?;
+        if !columns.iter().any(|c| c == "checksum") {
+            self.connection.execute("ALTER TABLE schema_migrations ADD COLUMN checksum TEXT", [])?;
         }
-        
-        println!("✅ [Database Migration] Migration completed successfully!");
+
         Ok(())
     }
-    
-    async fn migrate_category_to_topic(&mut self) -> anyhow::Result<()> {
-        println!("🔄 [Database Migration] Starting category -> topic column migration...");
-        
-        // SQLite doesn't support ALTER TABLE DROP COLUMN until version 3.35.0
-        // So we use the standard SQLite approach: create new table, copy data, drop old, rename
-        
-        // Temporarily disable foreign key constraints for migration
-        println!("🔄 [Database Migration] Disabling foreign key constraints for migration...");
-        self.connection.execute("PRAGMA foreign_keys = OFF", [])?;
-        
-        // Begin transaction for atomic migration
-        let tx = self.connection.unchecked_transaction()?;
-        
-        // Step 0: Check if problems_new already exists and drop it (cleanup from failed migration)
-        let problems_new_exists: i64 = tx.query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='problems_new'",
+
+    fn table_exists(&self, table: &str) -> anyhow::Result<bool> {
+        let count: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?1",
+            params![table],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    fn table_columns(&self, table: &str) -> anyhow::Result<Vec<String>> {
+        self.connection
+            .prepare(&format!("PRAGMA table_info({})", table))?
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<String>, _>>()
+            .context("Failed to read table_info")
+    }
+
+    /// Maps an existing, pre-registry database to the migration version whose
+    /// end state it already matches, by inspecting `problems`' columns and
+    /// which of the later tables exist - mirroring the detection signals the
+    /// old ad-hoc `check_migration_needed`/`migrate_database` logic used to
+    /// check by hand.
+    fn detect_existing_schema_version(&self) -> anyhow::Result<i64> {
+        if !self.table_exists("problems")? {
+            return Ok(0);
+        }
+
+        let columns = self.table_columns("problems")?;
+        let has_category = columns.iter().any(|c| c == "category");
+        let has_topic = columns.iter().any(|c| c == "topic");
+        let has_related_problem_ids = columns.iter().any(|c| c == "related_problem_ids");
+
+        if has_category && !has_topic {
+            return Ok(1);
+        }
+
+        let mut version = 2;
+
+        if has_related_problem_ids {
+            version = 3;
+        }
+
+        let has_core_tables = ["time_sessions", "recordings", "connections", "tags", "problem_tags", "card_tags", "test_cases"]
+            .iter()
+            .all(|t| self.table_exists(t).unwrap_or(false));
+        if version >= 3 && has_core_tables {
+            version = 4;
+        }
+
+        let has_image_and_work_session_tables = ["recording_highlights", "problem_images", "card_images", "work_sessions"]
+            .iter()
+            .all(|t| self.table_exists(t).unwrap_or(false));
+        if version >= 4 && has_image_and_work_session_tables {
+            version = 5;
+        }
+
+        Ok(version)
+    }
+
+    /// The highest migration version this database has recorded as applied.
+    pub fn current_version(&self) -> anyhow::Result<i64> {
+        let version: Option<i64> = self.connection.query_row(
+            "SELECT MAX(version) FROM schema_migrations",
             [],
-            |row| row.get(0)
+            |row| row.get(0),
         )?;
-        
-        if problems_new_exists > 0 {
-            println!("🔄 [Database Migration] Found existing problems_new table from previous migration, dropping it...");
-            tx.execute("DROP TABLE problems_new", [])?;
+        Ok(version.unwrap_or(0))
+    }
+
+    /// The highest migration version this build of the app knows how to run.
+    /// Compared against [`Self::current_version`], lets a caller detect a
+    /// downgrade - a database `current_version` is ahead of what this binary's
+    /// own `MIGRATIONS` registry goes up to means an older build was just
+    /// pointed at a database a newer build already migrated.
+    pub fn latest_known_version(&self) -> i64 {
+        migrations::LATEST_VERSION
+    }
+
+    /// Steps the schema backward to `target_version` by running `down_sql` for
+    /// every applied migration above it, in descending order, inside a single
+    /// transaction - so a partial downgrade never leaves a corrupt schema.
+    /// Lets developers testing a migration locally (or a failed rollout of one
+    /// like `add_related_problem_ids`) undo it without deleting `database.db`.
+    pub fn rollback_to(&mut self, target_version: i64) -> anyhow::Result<()> {
+        let current = self.current_version()?;
+        if target_version >= current {
+            return Ok(());
         }
-        
-        // Step 1: Create a new problems table with the correct schema
-        println!("🔄 [Database Migration] Creating new problems table with topic column...");
-        tx.execute(
-            "CREATE TABLE problems_new (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                description TEXT,
-                difficulty TEXT CHECK(difficulty IN ('Easy', 'Medium', 'Hard')),
-                topic TEXT,
-                leetcode_url TEXT,
-                constraints TEXT,
-                examples TEXT,
-                hints TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            []
-        )?;
-        
-        // Step 2: Copy all data from old table to new table, mapping category -> topic
-        println!("🔄 [Database Migration] Copying data from old table to new table...");
-        let rows_copied = tx.execute(
-            "INSERT INTO problems_new (id, title, description, difficulty, topic, leetcode_url, constraints, examples, hints, created_at)
-             SELECT id, title, description, difficulty, category, leetcode_url, constraints, examples, hints, created_at
-             FROM problems",
-            []
-        )?;
-        println!("🔄 [Database Migration] Copied {} rows from old table to new table", rows_copied);
-        
-        // Step 3: Drop the old table
-        println!("🔄 [Database Migration] Dropping old problems table...");
-        tx.execute("DROP TABLE problems", [])?;
-        
-        // Step 4: Rename the new table to the original name
-        println!("🔄 [Database Migration] Renaming new table to problems...");
-        tx.execute("ALTER TABLE problems_new RENAME TO problems", [])?;
-        
-        // Commit the transaction
+
+        let tx = self.connection.unchecked_transaction()?;
+
+        for migration in migrations::MIGRATIONS.iter().rev().filter(|m| m.version > target_version && m.version <= current) {
+            println!("⏪ [Database Migration] Rolling back migration {} ({})...", migration.version, migration.name);
+            tx.execute_batch(migration.down_sql)
+                .with_context(|| format!("Failed to roll back migration {} ({})", migration.version, migration.name))?;
+            tx.execute("DELETE FROM schema_migrations WHERE version = ?1", params![migration.version])?;
+        }
+
         tx.commit()?;
-        
-        // Re-enable foreign key constraints
-        println!("🔄 [Database Migration] Re-enabling foreign key constraints...");
-        self.connection.execute("PRAGMA foreign_keys = ON", [])?;
-        
-        println!("✅ [Database Migration] Category -> Topic migration completed successfully!");
+        println!("✅ [Database Migration] Rolled back to schema version {}", target_version);
         Ok(())
     }
-    
-    async fn migrate_add_related_problems_column(&mut self) -> anyhow::Result<()> {
-        println!("🔄 [Database Migration] Adding related_problem_ids column to problems table...");
-        
-        // First, verify the problems table exists
-        let problems_exists: i64 = self.connection.query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='problems'",
+
+    /// Generic "rebuild table with a new schema" helper for structural
+    /// changes SQLite's `ALTER TABLE` can't express directly (retyping or
+    /// dropping a column, changing a primary key, etc.): creates
+    /// `<table>_new` from `new_create_sql`, copies rows across via
+    /// `column_map` (`(new_column, old_column_or_expression)` pairs),
+    /// verifies no rows were lost, drops the old table and renames the new
+    /// one into place - all inside one transaction. Foreign keys are
+    /// disabled on the connection before that transaction opens and
+    /// re-enabled after it commits.
+    /// This is the same recipe the `category_to_topic` migration hand-writes
+    /// for the `problems` table (its SQL predates this helper, since a
+    /// migration's `up_sql`/`down_sql` must stay static data); new ad-hoc
+    /// rebuilds, like the ones `repair_schema` can't safely do with a plain
+    /// `ALTER TABLE ADD COLUMN`, should go through here instead.
+    pub fn rebuild_table(&mut self, table: &str, new_create_sql: &str, column_map: &[(&str, &str)]) -> anyhow::Result<()> {
+        let new_table = format!("{}_new", table);
+
+        self.connection.execute("PRAGMA foreign_keys = OFF", [])?;
+
+        let tx = self.connection.unchecked_transaction()?;
+
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", new_table), [])?;
+        tx.execute_batch(new_create_sql)
+            .with_context(|| format!("Failed to create {} from new_create_sql", new_table))?;
+
+        let before_count: i64 = tx.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))?;
+
+        let new_columns = column_map.iter().map(|(new, _)| *new).collect::<Vec<_>>().join(", ");
+        let old_columns = column_map.iter().map(|(_, old)| *old).collect::<Vec<_>>().join(", ");
+        tx.execute(
+            &format!("INSERT INTO {} ({}) SELECT {} FROM {}", new_table, new_columns, old_columns, table),
             [],
-            |row| row.get(0)
-        ).context("Failed to check if problems table exists")?;
-        
-        if problems_exists == 0 {
-            return Err(anyhow::anyhow!("Problems table does not exist - cannot add related_problem_ids column"));
+        )
+        .with_context(|| format!("Failed to copy rows from {} into {}", table, new_table))?;
+
+        let after_count: i64 = tx.query_row(&format!("SELECT COUNT(*) FROM {}", new_table), [], |row| row.get(0))?;
+        if after_count != before_count {
+            return Err(anyhow::anyhow!(
+                "rebuild_table: row count mismatch for {} (had {} rows, copied {})",
+                table, before_count, after_count
+            ));
         }
-        
-        // Check if column already exists (double check)
-        let columns_result: Result<Vec<String>, _> = self.connection.prepare("PRAGMA table_info(problems)")
-            .context("Failed to prepare PRAGMA table_info")?
-            .query_map([], |row| Ok(row.get::<_, String>(1)?))
-            .context("Failed to query table info")?
-            .collect();
-            
-        match columns_result {
-            Ok(columns) => {
-                if columns.contains(&"related_problem_ids".to_string()) {
-                    println!("✅ [Database Migration] related_problem_ids column already exists, skipping...");
-                    return Ok(());
-                }
-            },
-            Err(e) => {
-                println!("⚠️ [Database Migration] Warning: Could not check existing columns: {}", e);
-                // Continue with migration attempt anyway
+
+        tx.execute(&format!("DROP TABLE {}", table), [])?;
+        tx.execute(&format!("ALTER TABLE {} RENAME TO {}", new_table, table), [])?;
+
+        tx.commit()?;
+
+        self.connection.execute("PRAGMA foreign_keys = ON", [])?;
+
+        Ok(())
+    }
+
+    /// Compares the live database against [`schema_validation::EXPECTED_SCHEMA`]
+    /// via `sqlite_master`/`PRAGMA table_info`, returning every missing table,
+    /// missing column and unexpected column found.
+    pub fn validate_schema(&self) -> anyhow::Result<schema_validation::SchemaDrift> {
+        let mut drift = schema_validation::SchemaDrift::default();
+
+        for table in schema_validation::EXPECTED_SCHEMA {
+            if !self.table_exists(table.name)? {
+                drift.missing_tables.push(table.name.to_string());
+                continue;
             }
-        }
-        
-        println!("🔄 [Database Migration] Executing ALTER TABLE to add related_problem_ids column...");
-        
-        match self.connection.execute(
-            "ALTER TABLE problems ADD COLUMN related_problem_ids TEXT",
-            []
-        ) {
-            Ok(rows_affected) => {
-                println!("✅ [Database Migration] ALTER TABLE executed successfully (rows affected: {})", rows_affected);
-                
-                // Verify the column was actually added
-                let verification_result: Result<Vec<String>, _> = self.connection.prepare("PRAGMA table_info(problems)")
-                    .context("Failed to prepare verification PRAGMA")?
-                    .query_map([], |row| Ok(row.get::<_, String>(1)?))
-                    .context("Failed to query verification table info")?
-                    .collect();
-                    
-                match verification_result {
-                    Ok(columns) => {
-                        if columns.contains(&"related_problem_ids".to_string()) {
-                            println!("✅ [Database Migration] Verified: related_problem_ids column was successfully added!");
-                            Ok(())
-                        } else {
-                            let error_msg = format!("Column addition failed verification. Current columns: {:?}", columns);
-                            println!("❌ [Database Migration] {}", error_msg);
-                            Err(anyhow::anyhow!(error_msg))
-                        }
-                    },
-                    Err(e) => {
-                        let error_msg = format!("Failed to verify column addition: {}", e);
-                        println!("❌ [Database Migration] {}", error_msg);
-                        Err(anyhow::anyhow!(error_msg))
-                    }
+
+            let actual_columns = self.table_columns(table.name)?;
+
+            for expected_column in table.columns {
+                if !actual_columns.iter().any(|c| c == expected_column.name) {
+                    drift.missing_columns.push((table.name.to_string(), expected_column.name.to_string()));
                 }
-            },
-            Err(e) => {
-                let error_msg = format!("Failed to add related_problem_ids column: {} (Error type: {})", e, std::any::type_name_of_val(&e));
-                println!("❌ [Database Migration] {}", error_msg);
-                
-                // Check if it's a "duplicate column" error (which means it already exists)
-                let error_str = e.to_string().to_lowercase();
-                if error_str.contains("duplicate column") || error_str.contains("already exists") {
-                    println!("ℹ️ [Database Migration] Column already exists, treating as success");
-                    Ok(())
-                } else {
-                    Err(anyhow::anyhow!("{}", error_msg))
+            }
+
+            for actual_column in &actual_columns {
+                if !table.columns.iter().any(|c| c.name == actual_column) {
+                    drift.unexpected_columns.push((table.name.to_string(), actual_column.clone()));
                 }
             }
         }
+
+        Ok(drift)
     }
 
-    // Helper function to check if related_problem_ids column exists
-    fn has_related_problem_ids_column(&self) -> bool {
-        let column_info: Result<Vec<String>, _> = self.connection.prepare("PRAGMA table_info(problems)")
-            .and_then(|mut stmt| {
-                stmt.query_map([], |row| Ok(row.get::<_, String>(1)?))
-                    .map(|rows| rows.collect::<Result<Vec<String>, _>>())
-            })
-            .and_then(|result| result);
-            
-        match column_info {
-            Ok(columns) => columns.contains(&"related_problem_ids".to_string()),
-            Err(_) => false
+    /// Opt-in self-healing pass: for every missing, nullable, non-primary-key
+    /// column `validate_schema` found, issues `ALTER TABLE ADD COLUMN` -
+    /// mirroring how `related_problem_ids` used to be patched onto `problems`
+    /// by hand. Missing tables and missing `NOT NULL`/primary-key columns
+    /// can't be added this way (SQLite can't backfill a `NOT NULL` column
+    /// without a default, and can't add a table's primary key after the
+    /// fact), so those are only logged; a full rebuild is left to the
+    /// migration registry.
+    pub fn repair_schema(&mut self) -> anyhow::Result<schema_validation::SchemaDrift> {
+        let drift = self.validate_schema()?;
+
+        if !drift.missing_tables.is_empty() {
+            println!("⚠️ [Database] repair_schema: missing tables require a full migration, not a repair: {:?}", drift.missing_tables);
+        }
+
+        for (table, column) in &drift.missing_columns {
+            let Some(expected_table) = schema_validation::EXPECTED_SCHEMA.iter().find(|t| t.name == table.as_str()) else {
+                continue;
+            };
+            let Some(expected_column) = expected_table.columns.iter().find(|c| c.name == column.as_str()) else {
+                continue;
+            };
+
+            if expected_column.pk || expected_column.not_null {
+                println!(
+                    "⚠️ [Database] repair_schema: {}.{} needs a full table rebuild, skipping (not_null/pk column can't be ALTER-ADDed)",
+                    table, column
+                );
+                continue;
+            }
+
+            println!("🔧 [Database] repair_schema: adding {}.{} ({})", table, column, expected_column.sql_type);
+            self.connection.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, expected_column.sql_type),
+                [],
+            )?;
         }
+
+        self.validate_schema()
     }
 
     // Problem operations
+
+    /// Reads the related-problem ids for `problem_id` out of the normalized
+    /// `problem_relations` table (see migration v7).
+    fn related_problem_ids_for(&self, problem_id: &str) -> anyhow::Result<Vec<String>> {
+        related_problem_ids_for_conn(&self.connection, problem_id)
+    }
+
+    /// Replaces `problem_id`'s rows in `problem_relations` with `related_ids`,
+    /// atomically. Self-references are dropped; `ON DELETE CASCADE` keeps this
+    /// table in sync when either problem is deleted.
+    fn set_problem_relations(&mut self, problem_id: &str, related_ids: &[String]) -> anyhow::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        set_problem_relations_conn(&tx, problem_id, related_ids)?;
+        tx.commit()?;
+        Ok(())
+    }
+
     pub fn create_problem(&mut self, req: CreateProblemRequest) -> anyhow::Result<FrontendProblem> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
@@ -671,54 +1275,27 @@ impl DatabaseManager {
         let constraints_json = serde_json::to_string(&req.constraints)?;
         let hints_json = serde_json::to_string(&req.hints)?;
         let leetcode_url = req.leetcode_url.as_ref().map(|s| s.as_str()).unwrap_or("");
-        
-        // Check if related_problem_ids column exists for backward compatibility
-        let has_related_column = self.has_related_problem_ids_column();
-        
-        if has_related_column {
-            // Use new schema with related_problem_ids column
-            let related_problem_ids_json = req.related_problem_ids
-                .as_ref()
-                .map(|ids| serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string()))
-                .unwrap_or_else(|| "[]".to_string());
-                
-            self.connection.execute(
-                "INSERT INTO problems (id, title, description, difficulty, topic, leetcode_url, constraints, hints, related_problem_ids, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-                params![
-                    &id,
-                    &req.title,
-                    &req.description,
-                    &req.difficulty,
-                    &topic_json,
-                    leetcode_url,
-                    &constraints_json,
-                    &hints_json,
-                    &related_problem_ids_json,
-                    &now.to_rfc3339(),
-                    &now.to_rfc3339(),
-                ],
-            )?;
-        } else {
-            // Use old schema without related_problem_ids column
-            self.connection.execute(
-                "INSERT INTO problems (id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-                params![
-                    &id,
-                    &req.title,
-                    &req.description,
-                    &req.difficulty,
-                    &topic_json,
-                    leetcode_url,
-                    &constraints_json,
-                    &hints_json,
-                    &now.to_rfc3339(),
-                    &now.to_rfc3339(),
-                ],
-            )?;
-        }
-        
+
+        self.connection.execute(
+            "INSERT INTO problems (id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                &id,
+                &req.title,
+                &req.description,
+                &req.difficulty,
+                &topic_json,
+                leetcode_url,
+                &constraints_json,
+                &hints_json,
+                &now.to_rfc3339(),
+                &now.to_rfc3339(),
+            ],
+        )?;
+
+        let related_problem_ids = req.related_problem_ids.clone().unwrap_or_default();
+        self.set_problem_relations(&id, &related_problem_ids)?;
+
         // Return the frontend-compatible version
         Ok(FrontendProblem {
             id,
@@ -729,7 +1306,7 @@ impl DatabaseManager {
             leetcode_url: req.leetcode_url,
             constraints: req.constraints,
             hints: req.hints,
-            related_problem_ids: req.related_problem_ids.unwrap_or_default(),
+            related_problem_ids,
             created_at: now,
             updated_at: now,
             tags: Vec::new(), // Empty for newly created problems
@@ -737,17 +1314,10 @@ impl DatabaseManager {
     }
     
     pub fn get_problems(&self) -> anyhow::Result<Vec<FrontendProblem>> {
-        // Check if related_problem_ids column exists for backward compatibility
-        let has_related_column = self.has_related_problem_ids_column();
-        
-        let sql = if has_related_column {
-            "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, related_problem_ids, created_at, updated_at FROM problems ORDER BY created_at DESC"
-        } else {
-            "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, NULL as related_problem_ids, created_at, updated_at FROM problems ORDER BY created_at DESC"
-        };
-        
+        let sql = "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at FROM problems WHERE deleted_at IS NULL ORDER BY created_at DESC";
+
         let mut stmt = self.connection.prepare(sql)?;
-        
+
         let problem_iter = stmt.query_map([], |row| {
             let db_problem = Problem {
                 id: row.get(0)?,
@@ -758,33 +1328,44 @@ impl DatabaseManager {
                 leetcode_url: row.get(5)?,
                 constraints: row.get(6)?,
                 hints: row.get(7)?,
-                related_problem_ids: row.get(8).ok(), // Use .ok() to handle NULL gracefully
-                created_at: parse_datetime_flexible(&row.get::<_, String>(9)?),
-                updated_at: parse_datetime_flexible(&row.get::<_, String>(10)?),
+                related_problem_ids: None,
+                created_at: parse_datetime_flexible(&row.get::<_, String>(8)?),
+                updated_at: parse_datetime_flexible(&row.get::<_, String>(9)?),
             };
-            Ok(convert_problem_to_frontend(db_problem))
+            Ok(db_problem)
         })?;
-        
+
         let mut problems = Vec::new();
         for problem in problem_iter {
-            problems.push(problem?);
+            let db_problem = problem?;
+            let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+            problems.push(convert_problem_to_frontend(db_problem, related_ids));
         }
-        
+
         Ok(problems)
     }
-    
+
     pub fn get_problem_by_id(&self, id: &str) -> anyhow::Result<Option<FrontendProblem>> {
-        // Check if related_problem_ids column exists for backward compatibility
-        let has_related_column = self.has_related_problem_ids_column();
-        
-        let sql = if has_related_column {
-            "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, related_problem_ids, created_at, updated_at FROM problems WHERE id = ?1"
+        self.get_problem_by_id_filtered(id, true)
+    }
+
+    /// Like [`Self::get_problem_by_id`], but also returns soft-deleted
+    /// problems when `only_not_deleted` is `false`. Used internally by the
+    /// hard-delete path (`delete_problem`/`delete_problem_with_files`) and
+    /// `purge_deleted_before`, which operate on rows that are expected to
+    /// already be in the recycle bin - unlike every other caller, they must
+    /// not be fooled by `deleted_at` into thinking the problem is gone.
+    fn get_problem_by_id_filtered(&self, id: &str, only_not_deleted: bool) -> anyhow::Result<Option<FrontendProblem>> {
+        let sql = if only_not_deleted {
+            "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at
+             FROM problems WHERE id = ?1 AND deleted_at IS NULL"
         } else {
-            "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, NULL as related_problem_ids, created_at, updated_at FROM problems WHERE id = ?1"
+            "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at
+             FROM problems WHERE id = ?1"
         };
-        
+
         let mut stmt = self.connection.prepare(sql)?;
-        
+
         let mut problem_iter = stmt.query_map([id], |row| {
             let db_problem = Problem {
                 id: row.get(0)?,
@@ -795,15 +1376,19 @@ impl DatabaseManager {
                 leetcode_url: row.get(5)?,
                 constraints: row.get(6)?,
                 hints: row.get(7)?,
-                related_problem_ids: row.get(8).ok(), // Use .ok() to handle NULL gracefully
-                created_at: parse_datetime_flexible(&row.get::<_, String>(9)?),
-                updated_at: parse_datetime_flexible(&row.get::<_, String>(10)?),
+                related_problem_ids: None,
+                created_at: parse_datetime_flexible(&row.get::<_, String>(8)?),
+                updated_at: parse_datetime_flexible(&row.get::<_, String>(9)?),
             };
-            Ok(convert_problem_to_frontend(db_problem))
+            Ok(db_problem)
         })?;
-        
+
         match problem_iter.next() {
-            Some(problem) => Ok(Some(problem?)),
+            Some(problem) => {
+                let db_problem = problem?;
+                let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+                Ok(Some(convert_problem_to_frontend(db_problem, related_ids)))
+            }
             None => Ok(None),
         }
     }
@@ -857,49 +1442,102 @@ impl DatabaseManager {
             update_values.push(Box::new(hints_json));
         }
 
-        // Only update related_problem_ids if the column exists (backward compatibility)
-        if let Some(ref related_problem_ids) = req.related_problem_ids {
-            if self.has_related_problem_ids_column() {
-                let related_ids_json = serde_json::to_string(related_problem_ids)?;
-                update_fields.push("related_problem_ids = ?");
-                update_values.push(Box::new(related_ids_json));
-            }
-            // If column doesn't exist, silently ignore the related_problem_ids update
-        }
+        // `related_problem_ids` now lives in the normalized `problem_relations`
+        // table (see `set_problem_relations`), not as a column push here.
+        let relations_changed = req.related_problem_ids.is_some();
 
         // If no fields to update, return the existing problem
-        if update_fields.is_empty() {
+        if update_fields.is_empty() && !relations_changed {
             return Ok(existing_problem);
         }
 
-        // Always update the updated_at timestamp when any field is modified
-        let now = Utc::now();
-        update_fields.push("updated_at = ?");
-        update_values.push(Box::new(now.to_rfc3339()));
+        if !update_fields.is_empty() {
+            // Always update the updated_at timestamp when any field is modified
+            let now = Utc::now();
+            update_fields.push("updated_at = ?");
+            update_values.push(Box::new(now.to_rfc3339()));
 
-        // Build the SQL query
-        let sql = format!(
-            "UPDATE problems SET {} WHERE id = ?",
-            update_fields.join(", ")
-        );
-        
-        // Add the id to the end of the values
-        update_values.push(Box::new(req.id.clone()));
+            // Build the SQL query
+            let sql = format!(
+                "UPDATE problems SET {} WHERE id = ?",
+                update_fields.join(", ")
+            );
 
-        // Execute the update
-        let rows_affected = self.connection.execute(
-            &sql,
-            rusqlite::params_from_iter(update_values.iter().map(|v| v.as_ref()))
-        )?;
+            // Add the id to the end of the values
+            update_values.push(Box::new(req.id.clone()));
 
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!("Failed to update problem - no rows affected"));
-        }
+            // Execute the update
+            let rows_affected = self.connection.execute(
+                &sql,
+                rusqlite::params_from_iter(update_values.iter().map(|v| v.as_ref()))
+            )?;
+
+            if rows_affected == 0 {
+                return Err(anyhow::anyhow!("Failed to update problem - no rows affected"));
+            }
+        }
+
+        if let Some(related_problem_ids) = req.related_problem_ids {
+            self.set_problem_relations(&req.id, &related_problem_ids)?;
+        }
 
         // Return the updated problem
         self.get_problem_by_id(&req.id)
     }
 
+    /// Returns the cached raw LeetCode GraphQL payload for `slug`, if any was
+    /// ever fetched, so repeated imports/syncs of the same problem work offline.
+    pub fn get_cached_leetcode_payload(&self, slug: &str) -> anyhow::Result<Option<String>> {
+        self.connection
+            .query_row(
+                "SELECT raw_json FROM leetcode_problem_cache WHERE slug = ?1",
+                params![slug],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.into())
+    }
+
+    /// Stores (or refreshes) the raw LeetCode GraphQL payload for `slug`.
+    pub fn cache_leetcode_payload(&mut self, slug: &str, raw_json: &str) -> anyhow::Result<()> {
+        self.connection.execute(
+            "INSERT INTO leetcode_problem_cache (slug, raw_json, fetched_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(slug) DO UPDATE SET raw_json = excluded.raw_json, fetched_at = excluded.fetched_at",
+            params![slug, raw_json, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Runs a list of problem/card operations inside a single transaction,
+    /// returning one [`BatchOpResult`] per op in order. If any op fails the
+    /// whole batch is rolled back - callers get all-or-nothing semantics for
+    /// bulk imports or multi-card edits instead of N independent round trips.
+    /// Runs `ops` inside a single transaction, stopping at the first op that
+    /// fails and rolling everything back - but unlike a plain `Result`, the
+    /// returned [`BatchRunResult`] still reports which ops up to that point
+    /// *would* have succeeded, and the index/message of the one that didn't,
+    /// so the caller can fix just that step instead of bisecting the list.
+    pub fn apply_batch(&mut self, ops: Vec<BatchOp>) -> anyhow::Result<BatchRunResult> {
+        let tx = self.connection.unchecked_transaction()?;
+        let mut results = Vec::with_capacity(ops.len());
+
+        for (index, op) in ops.into_iter().enumerate() {
+            match apply_one_batch_op(&tx, op) {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    return Ok(BatchRunResult {
+                        results,
+                        failed_at: Some(BatchOpError { index, message: e.to_string() }),
+                    });
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(BatchRunResult { results, failed_at: None })
+    }
+
     // Card operations
     pub fn create_card(&mut self, req: CreateCardRequest) -> anyhow::Result<Card> {
         let id = Uuid::new_v4().to_string();
@@ -1025,38 +1663,36 @@ impl DatabaseManager {
     }
     
     pub fn update_card(&mut self, req: UpdateCardRequest) -> anyhow::Result<Option<Card>> {
-        let now = Utc::now();
-        let now_str = now.to_rfc3339();
-        
-        // Build query to update only provided fields
+        // Build query to update only provided fields. `trg_cards_stamp_last_modified`
+        // stamps `last_modified` itself, so none of these set it by hand.
         if let Some(ref code) = req.code {
             self.connection.execute(
-                "UPDATE cards SET code = ?1, last_modified = ?2 WHERE id = ?3",
-                params![code, &now_str, &req.id],
+                "UPDATE cards SET code = ?1 WHERE id = ?2",
+                params![code, &req.id],
             )?;
         }
-        
+
         if let Some(ref notes) = req.notes {
             self.connection.execute(
-                "UPDATE cards SET notes = ?1, last_modified = ?2 WHERE id = ?3",
-                params![notes, &now_str, &req.id],
+                "UPDATE cards SET notes = ?1 WHERE id = ?2",
+                params![notes, &req.id],
             )?;
         }
-        
+
         if let Some(ref language) = req.language {
             self.connection.execute(
-                "UPDATE cards SET language = ?1, last_modified = ?2 WHERE id = ?3",
-                params![language, &now_str, &req.id],
+                "UPDATE cards SET language = ?1 WHERE id = ?2",
+                params![language, &req.id],
             )?;
         }
-        
+
         if let Some(ref status) = req.status {
             self.connection.execute(
-                "UPDATE cards SET status = ?1, last_modified = ?2 WHERE id = ?3",
-                params![status, &now_str, &req.id],
+                "UPDATE cards SET status = ?1 WHERE id = ?2",
+                params![status, &req.id],
             )?;
         }
-        
+
         // Return the updated card
         self.get_card_by_id(&req.id)
     }
@@ -1068,15 +1704,10 @@ impl DatabaseManager {
             return Err(anyhow::anyhow!("Card with id '{}' not found", card_id));
         }
 
-        let card = card.unwrap();
-
-        // Safety check: Only allow deletion of child cards for now
-        // (Main cards should be kept to preserve problem structure)
-        if card.parent_card_id.is_none() || card.parent_card_id.as_ref().map_or(true, |s| s.is_empty()) {
-            return Err(anyhow::anyhow!(
-                "Cannot delete main cards. Only child cards can be deleted for safety."
-            ));
-        }
+        // Main cards used to be off-limits here because deletion was
+        // unrecoverable. The `cards_after_delete` trigger now snapshots every
+        // deleted row into `cards_history`, so any card - main or child - can
+        // be restored afterwards via `restore_card`.
 
         // Begin transaction for atomic deletion
         let tx = self.connection.unchecked_transaction()?;
@@ -1119,68 +1750,632 @@ impl DatabaseManager {
         Ok(())
     }
 
-    // Timer session operations (disabled until time_sessions table is added)
-    #[allow(dead_code)]
-    pub fn start_timer_session(&mut self, card_id: &str) -> anyhow::Result<TimeSession> {
+    /// Prior versions of a problem, newest first, captured by the
+    /// `problems_after_update`/`problems_after_delete` triggers.
+    pub fn get_problem_history(&self, problem_id: &str) -> anyhow::Result<Vec<ProblemHistoryEntry>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT history_id, row_id, title, description, difficulty, topic, leetcode_url, constraints, examples, hints, related_problem_ids, created_at, operation, changed_at
+             FROM problems_history
+             WHERE row_id = ?1
+             ORDER BY history_id DESC"
+        )?;
+
+        let entries = stmt.query_map(params![problem_id], |row| {
+            Ok(ProblemHistoryEntry {
+                history_id: row.get(0)?,
+                row_id: row.get(1)?,
+                title: row.get(2)?,
+                description: row.get(3)?,
+                difficulty: row.get(4)?,
+                topic: row.get(5)?,
+                leetcode_url: row.get(6)?,
+                constraints: row.get(7)?,
+                examples: row.get(8)?,
+                hints: row.get(9)?,
+                related_problem_ids: row.get(10)?,
+                created_at: row.get::<_, Option<String>>(11)?.map(|s| parse_datetime_flexible(&s)),
+                operation: row.get(12)?,
+                changed_at: parse_datetime_flexible(&row.get::<_, String>(13)?),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Prior versions of a card, newest first, captured by the
+    /// `cards_after_update`/`cards_after_delete` triggers.
+    pub fn get_card_history(&self, card_id: &str) -> anyhow::Result<Vec<CardHistoryEntry>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT history_id, row_id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id, operation, changed_at
+             FROM cards_history
+             WHERE row_id = ?1
+             ORDER BY history_id DESC"
+        )?;
+
+        let entries = stmt.query_map(params![card_id], |row| {
+            Ok(CardHistoryEntry {
+                history_id: row.get(0)?,
+                row_id: row.get(1)?,
+                problem_id: row.get(2)?,
+                card_number: row.get(3)?,
+                code: row.get(4)?,
+                language: row.get(5)?,
+                notes: row.get(6)?,
+                status: row.get(7)?,
+                total_duration: row.get(8)?,
+                created_at: row.get::<_, Option<String>>(9)?.map(|s| parse_datetime_flexible(&s)),
+                last_modified: row.get::<_, Option<String>>(10)?.map(|s| parse_datetime_flexible(&s)),
+                parent_card_id: row.get(11)?,
+                operation: row.get(12)?,
+                changed_at: parse_datetime_flexible(&row.get::<_, String>(13)?),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Re-inserts a card from a `cards_history` snapshot, undoing whatever
+    /// update or delete produced it. Conflicts with a still-existing row
+    /// (restoring an `update` entry) are resolved by replacing it outright.
+    pub fn restore_card(&mut self, history_id: i64) -> anyhow::Result<Card> {
+        let snapshot = self.connection.query_row(
+            "SELECT row_id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id
+             FROM cards_history
+             WHERE history_id = ?1",
+            params![history_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i32>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, i32>(7)?,
+                    row.get::<_, String>(8)?,
+                    row.get::<_, String>(9)?,
+                    row.get::<_, Option<String>>(10)?,
+                ))
+            },
+        ).optional()?;
+
+        let (row_id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id) =
+            snapshot.ok_or_else(|| anyhow::anyhow!("History entry '{}' not found", history_id))?;
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO cards (id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![&row_id, &problem_id, card_number, &code, &language, &notes, &status, total_duration, &created_at, &last_modified, &parent_card_id],
+        )?;
+
+        self.get_card_by_id(&row_id)?
+            .ok_or_else(|| anyhow::anyhow!("Failed to reload restored card '{}'", row_id))
+    }
+
+    /// Rolls back a single `code`/`notes` edit without touching the rest of
+    /// the row, unlike `restore_card`'s whole-row replace - useful for the
+    /// long-lived solution card, where an old snapshot's `total_duration`
+    /// (which `time_sessions` triggers own, see `database/triggers.rs`) or
+    /// `status` shouldn't be clobbered just to undo one bad edit.
+    pub fn restore_card_field(&mut self, card_id: &str, field: &str, history_id: i64) -> anyhow::Result<Card> {
+        if field != "code" && field != "notes" {
+            return Err(anyhow::anyhow!("Unsupported field '{}': expected 'code' or 'notes'", field));
+        }
+
+        let value: Option<String> = self.connection.query_row(
+            &format!(
+                "SELECT {} FROM cards_history WHERE history_id = ?1 AND row_id = ?2",
+                field
+            ),
+            params![history_id, card_id],
+            |row| row.get(0),
+        ).optional()?.ok_or_else(|| anyhow::anyhow!("History entry '{}' not found for card '{}'", history_id, card_id))?;
+
+        self.connection.execute(
+            &format!("UPDATE cards SET {} = ?1 WHERE id = ?2", field),
+            params![value, card_id],
+        )?;
+
+        self.get_card_by_id(card_id)?
+            .ok_or_else(|| anyhow::anyhow!("Card '{}' not found", card_id))
+    }
+
+    // Timer session operations, backed by the `time_sessions` table added in
+    // migration 4. Review-mode timers (below) are thin wrappers around these.
+    // Alongside the `time_sessions` row that actually drives the stopwatch UI
+    // and `cards.total_duration`, a parallel `work_sessions` row is logged for
+    // the `get_daily_aggregates`/`get_most_worked_problem`-style analytics
+    // queries, which key off `problem_id` rather than `card_id`.
+    pub fn start_timer_session(&mut self, card_id: &str) -> anyhow::Result<(TimeSession, String)> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
         let date = now.format("%Y-%m-%d").to_string();
-        
+
         self.connection.execute(
             "INSERT INTO time_sessions (id, card_id, start_time, date, is_active)
              VALUES (?1, ?2, ?3, ?4, 1)",
             params![&id, card_id, &now.to_rfc3339(), &date],
         )?;
-        
-        Ok(TimeSession {
-            id,
-            card_id: card_id.to_string(),
-            start_time: now,
-            end_time: None,
-            duration: None,
-            date,
-            is_active: true,
-            notes: None,
+
+        let problem_id: String = self.connection.query_row(
+            "SELECT problem_id FROM cards WHERE id = ?1",
+            [card_id],
+            |row| row.get(0),
+        )?;
+        let work_session_id = self.create_work_session(&problem_id, card_id, now)?;
+
+        Ok((
+            TimeSession {
+                id,
+                card_id: card_id.to_string(),
+                start_time: now,
+                end_time: None,
+                duration: None,
+                date,
+                is_active: true,
+                notes: None,
+            },
+            work_session_id,
+        ))
+    }
+
+    /// Inserts a `work_sessions` row for the analytics dashboards. Unlike
+    /// `time_sessions`, `hour_slot`/`session_date` have a `NOT NULL`
+    /// constraint, so they're seeded from `start_timestamp` here;
+    /// `trg_work_sessions_derive_from_end_timestamp` re-derives them (along
+    /// with `duration_seconds`) once `complete_work_session` stamps
+    /// `end_timestamp`, which is a no-op in practice since `start_timestamp`
+    /// never changes in between.
+    pub fn create_work_session(&self, problem_id: &str, card_id: &str, start_timestamp: DateTime<Utc>) -> anyhow::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        let session_date = start_timestamp.format("%Y-%m-%d").to_string();
+        let hour_slot = start_timestamp.format("%H").to_string().parse::<i32>().unwrap_or(0);
+
+        self.connection.execute(
+            "INSERT INTO work_sessions (id, problem_id, card_id, session_date, start_timestamp, hour_slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![&id, problem_id, card_id, &session_date, &start_timestamp.to_rfc3339(), hour_slot],
+        )?;
+
+        Ok(id)
+    }
+
+    /// Stamps `end_timestamp`; `trg_work_sessions_derive_from_end_timestamp`
+    /// fills in `duration_seconds`/`session_date`/`hour_slot` from there.
+    pub fn complete_work_session(&self, session_id: &str, end_timestamp: DateTime<Utc>) -> anyhow::Result<()> {
+        self.connection.execute(
+            "UPDATE work_sessions SET end_timestamp = ?1 WHERE id = ?2",
+            params![&end_timestamp.to_rfc3339(), session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Looks up a single `work_sessions` row, e.g. so `edit_work_session` can
+    /// fall back to a session's current values for whatever fields a request
+    /// leaves unset.
+    pub fn get_work_session(&self, session_id: &str) -> anyhow::Result<Option<WorkSession>> {
+        self.connection
+            .query_row(
+                "SELECT id, problem_id, card_id, session_date, start_timestamp, end_timestamp, duration_seconds, hour_slot, created_at
+                 FROM work_sessions WHERE id = ?1",
+                [session_id],
+                Self::row_to_work_session,
+            )
+            .optional()
+            .context("Failed to fetch work session")
+    }
+
+    fn row_to_work_session(row: &rusqlite::Row) -> rusqlite::Result<WorkSession> {
+        let end_timestamp: Option<String> = row.get(5)?;
+        Ok(WorkSession {
+            id: row.get(0)?,
+            problem_id: row.get(1)?,
+            card_id: row.get(2)?,
+            session_date: row.get(3)?,
+            start_timestamp: parse_datetime_flexible(&row.get::<_, String>(4)?),
+            end_timestamp: end_timestamp.map(|s| parse_datetime_flexible(&s)),
+            duration_seconds: row.get(6)?,
+            hour_slot: row.get(7)?,
+            created_at: parse_datetime_flexible(&row.get::<_, String>(8)?),
         })
     }
-    
-    #[allow(dead_code)]
-    pub fn end_timer_session(&mut self, session_id: &str) -> anyhow::Result<()> {
-        let now = Utc::now();
-        
-        // Get session start time
-        let start_time: String = self.connection.query_row(
-            "SELECT start_time FROM time_sessions WHERE id = ?1",
+
+    /// Retroactively corrects a session's start/end timestamps and/or the
+    /// card it's attributed to, e.g. a timer left running overnight. Fields
+    /// left `None` in `req` keep their current value. Always writes
+    /// `end_timestamp` (even when it didn't change) so
+    /// `trg_work_sessions_derive_from_end_timestamp` fires and recomputes
+    /// `duration_seconds`/`session_date`/`hour_slot` from the final values.
+    pub fn edit_work_session(&self, req: &EditWorkSessionRequest) -> anyhow::Result<WorkSession> {
+        let current = self.get_work_session(&req.session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Work session with id '{}' not found", req.session_id))?;
+
+        let start_timestamp = req.start_timestamp.unwrap_or(current.start_timestamp);
+        let end_timestamp = req.end_timestamp.or(current.end_timestamp);
+
+        if let Some(end_timestamp) = end_timestamp {
+            if end_timestamp < start_timestamp {
+                return Err(anyhow::anyhow!("end_timestamp cannot be before start_timestamp"));
+            }
+        }
+
+        let (problem_id, card_id) = match &req.card_id {
+            Some(card_id) => {
+                let problem_id: String = self.connection.query_row(
+                    "SELECT problem_id FROM cards WHERE id = ?1",
+                    [card_id],
+                    |row| row.get(0),
+                ).context("Failed to look up problem for the new card")?;
+                (problem_id, card_id.clone())
+            }
+            None => (current.problem_id, current.card_id),
+        };
+
+        self.connection.execute(
+            "UPDATE work_sessions SET problem_id = ?1, card_id = ?2, start_timestamp = ?3, end_timestamp = ?4 WHERE id = ?5",
+            params![
+                &problem_id,
+                &card_id,
+                &start_timestamp.to_rfc3339(),
+                end_timestamp.map(|t| t.to_rfc3339()),
+                &req.session_id,
+            ],
+        )?;
+
+        self.get_work_session(&req.session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Work session with id '{}' not found", req.session_id))
+    }
+
+    /// Deletes a `work_sessions` row. Unlike `time_sessions`, no other
+    /// table's cached total is derived from `work_sessions`, so nothing else
+    /// needs recomputing afterward.
+    pub fn delete_work_session(&self, session_id: &str) -> anyhow::Result<()> {
+        let rows_affected = self.connection.execute(
+            "DELETE FROM work_sessions WHERE id = ?1",
             [session_id],
-            |row| row.get(0),
         )?;
-        
-        let start_time = start_time.parse::<chrono::DateTime<Utc>>()?;
-        let duration = (now - start_time).num_seconds() as i32;
-        
-        // Update session
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Work session with id '{}' not found", session_id));
+        }
+
+        Ok(())
+    }
+
+    /// Breaks one long session into two at `split_at`: the original session
+    /// is shortened to end there, and a new session covering the remainder
+    /// is created, inheriting the original's `end_timestamp`.
+    pub fn split_work_session(&self, req: &SplitWorkSessionRequest) -> anyhow::Result<(WorkSession, WorkSession)> {
+        let current = self.get_work_session(&req.session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Work session with id '{}' not found", req.session_id))?;
+
+        let original_end = current.end_timestamp
+            .ok_or_else(|| anyhow::anyhow!("Cannot split a work session that hasn't ended yet"))?;
+
+        if req.split_at <= current.start_timestamp || req.split_at >= original_end {
+            return Err(anyhow::anyhow!("split_at must fall strictly between the session's start and end"));
+        }
+
         self.connection.execute(
-            "UPDATE time_sessions SET end_time = ?1, duration = ?2, is_active = 0 WHERE id = ?3",
-            params![&now.to_rfc3339(), duration, session_id],
+            "UPDATE work_sessions SET end_timestamp = ?1 WHERE id = ?2",
+            params![req.split_at.to_rfc3339(), &current.id],
         )?;
-        
-        // Update card total duration
-        let card_id: String = self.connection.query_row(
-            "SELECT card_id FROM time_sessions WHERE id = ?1",
-            [session_id],
-            |row| row.get(0),
+
+        let new_id = self.create_work_session(&current.problem_id, &current.card_id, req.split_at)?;
+        self.complete_work_session(&new_id, original_end)?;
+
+        let first = self.get_work_session(&current.id)?
+            .ok_or_else(|| anyhow::anyhow!("Work session with id '{}' not found", current.id))?;
+        let second = self.get_work_session(&new_id)?
+            .ok_or_else(|| anyhow::anyhow!("Work session with id '{}' not found", new_id))?;
+
+        Ok((first, second))
+    }
+
+    /// Builds the `AND ...` fragment (and its bound params, in the order
+    /// they appear in the fragment) for `filter`. Assumes the caller's query
+    /// aliases `work_sessions` as `ws` and joins in `problems p ON p.id =
+    /// ws.problem_id`. Returns an empty string/vec when `filter` is `None`
+    /// or every facet is empty, so it's always safe to splice the result
+    /// straight after a query's existing `WHERE ...` clause.
+    fn work_session_filter_clause(filter: &Option<WorkSessionFilter>) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        let Some(filter) = filter else {
+            return (String::new(), params);
+        };
+
+        if !filter.difficulties.is_empty() {
+            let placeholders = vec!["?"; filter.difficulties.len()].join(", ");
+            clauses.push(format!("p.difficulty IN ({})", placeholders));
+            for difficulty in &filter.difficulties {
+                params.push(Box::new(difficulty.clone()));
+            }
+        }
+
+        if !filter.topics.is_empty() {
+            let placeholders = vec!["?"; filter.topics.len()].join(", ");
+            clauses.push(format!("p.topic IN ({})", placeholders));
+            for topic in &filter.topics {
+                params.push(Box::new(topic.clone()));
+            }
+        }
+
+        if !filter.problem_ids.is_empty() {
+            let placeholders = vec!["?"; filter.problem_ids.len()].join(", ");
+            clauses.push(format!("ws.problem_id IN ({})", placeholders));
+            for problem_id in &filter.problem_ids {
+                params.push(Box::new(problem_id.clone()));
+            }
+        }
+
+        if !filter.tag_ids.is_empty() {
+            let placeholders = vec!["?"; filter.tag_ids.len()].join(", ");
+            clauses.push(format!(
+                "(EXISTS (SELECT 1 FROM problem_tags pt WHERE pt.problem_id = ws.problem_id AND pt.tag_id IN ({0}))
+                  OR EXISTS (SELECT 1 FROM card_tags ct WHERE ct.card_id = ws.card_id AND ct.tag_id IN ({0})))",
+                placeholders
+            ));
+            // The fragment references `tag_ids` twice (once per EXISTS), so
+            // the params need to be bound twice too.
+            for tag_id in filter.tag_ids.iter().chain(filter.tag_ids.iter()) {
+                params.push(Box::new(tag_id.clone()));
+            }
+        }
+
+        if let Some(min_duration_seconds) = filter.min_duration_seconds {
+            clauses.push("COALESCE(ws.duration_seconds, 0) >= ?".to_string());
+            params.push(Box::new(min_duration_seconds));
+        }
+
+        if clauses.is_empty() {
+            (String::new(), params)
+        } else {
+            (format!(" AND {}", clauses.join(" AND ")), params)
+        }
+    }
+
+    /// Work sessions in `[start_date, end_date]`, optionally narrowed by
+    /// `filter`, joined with their problem's title for display.
+    pub fn get_work_sessions_by_date_range(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        filter: &Option<WorkSessionFilter>,
+    ) -> anyhow::Result<Vec<WorkSessionWithProblem>> {
+        let (filter_sql, filter_params) = Self::work_session_filter_clause(filter);
+
+        let sql = format!(
+            "SELECT ws.id, ws.problem_id, p.title, ws.card_id, ws.session_date, ws.start_timestamp, ws.end_timestamp, ws.duration_seconds, ws.hour_slot
+             FROM work_sessions ws
+             JOIN problems p ON p.id = ws.problem_id
+             WHERE ws.session_date BETWEEN ?1 AND ?2{}
+             ORDER BY ws.start_timestamp DESC",
+            filter_sql
+        );
+
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start_date.to_string()), Box::new(end_date.to_string())];
+        params.extend(filter_params);
+
+        let mut stmt = self.connection.prepare(&sql)?;
+        let session_iter = stmt.query_map(rusqlite::params_from_iter(params.iter().map(|v| v.as_ref())), |row| {
+            let end_timestamp: Option<String> = row.get(6)?;
+            Ok(WorkSessionWithProblem {
+                id: row.get(0)?,
+                problem_id: row.get(1)?,
+                problem_title: row.get(2)?,
+                card_id: row.get(3)?,
+                session_date: row.get(4)?,
+                start_timestamp: parse_datetime_flexible(&row.get::<_, String>(5)?),
+                end_timestamp: end_timestamp.map(|s| parse_datetime_flexible(&s)),
+                duration_seconds: row.get(7)?,
+                hour_slot: row.get(8)?,
+            })
+        })?;
+
+        let mut sessions = Vec::new();
+        for session in session_iter {
+            sessions.push(session?);
+        }
+        Ok(sessions)
+    }
+
+    /// Per-day totals in `[start_date, end_date]`, optionally narrowed by
+    /// `filter`, for the dashboard's daily chart.
+    pub fn get_daily_aggregates(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        filter: &Option<WorkSessionFilter>,
+    ) -> anyhow::Result<Vec<DailyWorkSummary>> {
+        let (filter_sql, filter_params) = Self::work_session_filter_clause(filter);
+
+        let sql = format!(
+            "SELECT ws.session_date,
+                    COALESCE(SUM(ws.duration_seconds), 0) AS total_duration_seconds,
+                    COUNT(*) AS session_count,
+                    COUNT(DISTINCT ws.problem_id) AS unique_problems_count
+             FROM work_sessions ws
+             JOIN problems p ON p.id = ws.problem_id
+             WHERE ws.session_date BETWEEN ?1 AND ?2{}
+             GROUP BY ws.session_date
+             ORDER BY ws.session_date",
+            filter_sql
+        );
+
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start_date.to_string()), Box::new(end_date.to_string())];
+        params.extend(filter_params);
+
+        let mut stmt = self.connection.prepare(&sql)?;
+        let summary_iter = stmt.query_map(rusqlite::params_from_iter(params.iter().map(|v| v.as_ref())), |row| {
+            Ok(DailyWorkSummary {
+                session_date: row.get(0)?,
+                total_duration_seconds: row.get(1)?,
+                session_count: row.get(2)?,
+                unique_problems_count: row.get(3)?,
+            })
+        })?;
+
+        let mut summaries = Vec::new();
+        for summary in summary_iter {
+            summaries.push(summary?);
+        }
+        Ok(summaries)
+    }
+
+    /// The problem with the most total time logged in `[start_date,
+    /// end_date]`, optionally narrowed by `filter`.
+    pub fn get_most_worked_problem(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        filter: &Option<WorkSessionFilter>,
+    ) -> anyhow::Result<Option<ProblemWorkBreakdown>> {
+        let (filter_sql, filter_params) = Self::work_session_filter_clause(filter);
+
+        let sql = format!(
+            "SELECT ws.problem_id, p.title,
+                    COALESCE(SUM(ws.duration_seconds), 0) AS total_duration_seconds,
+                    COUNT(*) AS session_count
+             FROM work_sessions ws
+             JOIN problems p ON p.id = ws.problem_id
+             WHERE ws.session_date BETWEEN ?1 AND ?2{}
+             GROUP BY ws.problem_id
+             ORDER BY total_duration_seconds DESC
+             LIMIT 1",
+            filter_sql
+        );
+
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start_date.to_string()), Box::new(end_date.to_string())];
+        params.extend(filter_params);
+
+        self.connection
+            .query_row(&sql, rusqlite::params_from_iter(params.iter().map(|v| v.as_ref())), |row| {
+                Ok(ProblemWorkBreakdown {
+                    problem_id: row.get(0)?,
+                    problem_title: row.get(1)?,
+                    total_duration_seconds: row.get(2)?,
+                    session_count: row.get(3)?,
+                })
+            })
+            .optional()
+            .context("Failed to fetch most worked problem")
+    }
+
+    /// Total time logged per hour-of-day over the last `days` days,
+    /// optionally narrowed by `filter`, for spotting a user's most
+    /// productive hours.
+    pub fn get_productivity_by_hour(&self, days: i32, filter: &Option<WorkSessionFilter>) -> anyhow::Result<Vec<HourlyWorkBreakdown>> {
+        let cutoff = (Utc::now() - chrono::Duration::days(days as i64)).format("%Y-%m-%d").to_string();
+        let (filter_sql, filter_params) = Self::work_session_filter_clause(filter);
+
+        let sql = format!(
+            "SELECT ws.hour_slot,
+                    COALESCE(SUM(ws.duration_seconds), 0) AS total_duration_seconds,
+                    COUNT(*) AS session_count
+             FROM work_sessions ws
+             JOIN problems p ON p.id = ws.problem_id
+             WHERE ws.session_date >= ?1{}
+             GROUP BY ws.hour_slot
+             ORDER BY ws.hour_slot",
+            filter_sql
+        );
+
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(cutoff)];
+        params.extend(filter_params);
+
+        let mut stmt = self.connection.prepare(&sql)?;
+        let breakdown_iter = stmt.query_map(rusqlite::params_from_iter(params.iter().map(|v| v.as_ref())), |row| {
+            Ok(HourlyWorkBreakdown {
+                hour_slot: row.get(0)?,
+                total_duration_seconds: row.get(1)?,
+                session_count: row.get(2)?,
+            })
+        })?;
+
+        let mut breakdown = Vec::new();
+        for entry in breakdown_iter {
+            breakdown.push(entry?);
+        }
+        Ok(breakdown)
+    }
+
+    /// Persists a `WorkSessionFilter` preset under `name`.
+    pub fn save_filter(&self, name: &str, filter: &WorkSessionFilter) -> anyhow::Result<SavedFilter> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let filter_json = serde_json::to_string(filter).context("Failed to serialize filter")?;
+
+        self.connection.execute(
+            "INSERT INTO saved_filters (id, name, filter_json, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![&id, name, &filter_json, now.to_rfc3339()],
         )?;
-        
+
+        Ok(SavedFilter {
+            id,
+            name: name.to_string(),
+            filter_json,
+            created_at: now,
+        })
+    }
+
+    /// Lists saved filter presets, most recently created first.
+    pub fn list_filters(&self) -> anyhow::Result<Vec<SavedFilter>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, name, filter_json, created_at FROM saved_filters ORDER BY created_at DESC",
+        )?;
+
+        let filter_iter = stmt.query_map([], |row| {
+            Ok(SavedFilter {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                filter_json: row.get(2)?,
+                created_at: parse_datetime_flexible(&row.get::<_, String>(3)?),
+            })
+        })?;
+
+        let mut filters = Vec::new();
+        for filter in filter_iter {
+            filters.push(filter?);
+        }
+        Ok(filters)
+    }
+
+    /// Deletes a saved filter preset.
+    pub fn delete_filter(&self, filter_id: &str) -> anyhow::Result<()> {
+        let rows_affected = self.connection.execute("DELETE FROM saved_filters WHERE id = ?1", [filter_id])?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Saved filter with id '{}' not found", filter_id));
+        }
+
+        Ok(())
+    }
+
+    /// Ends the session by stamping `end_time`. `trg_time_sessions_compute_duration`
+    /// derives `duration` from `start_time`/`end_time`, and
+    /// `trg_time_sessions_resum_card_total_duration` re-sums the parent card's
+    /// `total_duration` from every one of its `time_sessions` - neither is
+    /// computed by hand here anymore, so this can never drift out of sync
+    /// with whatever else touches these rows. `work_session_id`, if present,
+    /// is completed the same way.
+    pub fn end_timer_session(&mut self, session_id: &str, work_session_id: Option<&str>) -> anyhow::Result<()> {
         let now = Utc::now();
+
+        if let Some(work_session_id) = work_session_id {
+            self.complete_work_session(work_session_id, now)?;
+        }
+
         self.connection.execute(
-            "UPDATE cards SET total_duration = total_duration + ?1, last_modified = ?2 WHERE id = ?3",
-            params![duration, &now.to_rfc3339(), &card_id],
+            "UPDATE time_sessions SET end_time = ?1, is_active = 0 WHERE id = ?2",
+            params![&now.to_rfc3339(), session_id],
         )?;
-        
+
         Ok(())
     }
     
-    #[allow(dead_code)]
     pub fn get_sessions_for_card(&self, card_id: &str) -> anyhow::Result<Vec<TimeSession>> {
         let mut stmt = self.connection.prepare(
             "SELECT id, card_id, start_time, end_time, duration, date, is_active, notes 
@@ -1211,108 +2406,321 @@ impl DatabaseManager {
         Ok(sessions)
     }
     
-    #[allow(dead_code)]
+    /// Deletes a session. `trg_time_sessions_resum_card_total_duration`
+    /// re-sums the parent card's `total_duration` from the remaining
+    /// `time_sessions` rows, so it's not adjusted by hand here.
     pub fn delete_time_session(&mut self, session_id: &str) -> anyhow::Result<()> {
-        // First, get the session details to update the card's total duration
-        let session: Option<(String, i32)> = self.connection.query_row(
-            "SELECT card_id, duration FROM time_sessions WHERE id = ?1",
+        let rows_affected = self.connection.execute(
+            "DELETE FROM time_sessions WHERE id = ?1",
             [session_id],
-            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
-        ).optional()?;
-        
-        match session {
-            Some((card_id, duration)) => {
-                // Begin transaction for atomic operation
-                let tx = self.connection.unchecked_transaction()?;
-                
-                // Delete the session
-                let rows_affected = tx.execute(
-                    "DELETE FROM time_sessions WHERE id = ?1",
-                    [session_id]
-                )?;
-                
-                if rows_affected == 0 {
-                    return Err(anyhow::anyhow!("Session not found"));
-                }
-                
-                // Update card's total duration (subtract the deleted session duration)
-                let now = Utc::now();
-                tx.execute(
-                    "UPDATE cards SET total_duration = total_duration - ?1, last_modified = ?2 WHERE id = ?3",
-                    params![duration, &now.to_rfc3339(), &card_id]
-                )?;
-                
-                // Commit the transaction
-                tx.commit()?;
-                
-                println!("Successfully deleted session '{}' and updated card total duration", session_id);
-                Ok(())
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Session with id '{}' not found", session_id));
+        }
+
+        println!("Successfully deleted session '{}' and updated card total duration", session_id);
+        Ok(())
+    }
+
+    /// Durable snapshot of the one active timer, written on every `start`/
+    /// `pause`/`resume` so `AppState.current_timer` can be rehydrated after a
+    /// crash or quit instead of silently losing the in-progress session.
+    /// There is only ever one active timer, so writing a new snapshot
+    /// replaces whatever was there before.
+    pub fn save_timer_session_snapshot(&self, session: &TimerSession) -> anyhow::Result<()> {
+        self.connection.execute("DELETE FROM timer_session_snapshot", [])?;
+        self.connection.execute(
+            "INSERT INTO timer_session_snapshot (id, card_id, start_time, is_paused, pause_duration, pause_started_at, work_session_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                &session.id,
+                &session.card_id,
+                session.start_time.to_rfc3339(),
+                session.is_paused as i32,
+                session.pause_duration,
+                session.pause_started_at.map(|t| t.to_rfc3339()),
+                &session.work_session_id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Clears the snapshot once `end_timer_session` has recorded the
+    /// session, so there's nothing left to (incorrectly) resume.
+    pub fn clear_timer_session_snapshot(&self) -> anyhow::Result<()> {
+        self.connection.execute("DELETE FROM timer_session_snapshot", [])?;
+        Ok(())
+    }
+
+    /// Loads the snapshot left by a previous run, if any, so `main`'s setup
+    /// can rehydrate `AppState.current_timer` from it.
+    pub fn load_timer_session_snapshot(&self) -> anyhow::Result<Option<TimerSession>> {
+        self.connection
+            .query_row(
+                "SELECT id, card_id, start_time, is_paused, pause_duration, pause_started_at, work_session_id
+                 FROM timer_session_snapshot LIMIT 1",
+                [],
+                |row| {
+                    let pause_started_at: Option<String> = row.get(5)?;
+                    Ok(TimerSession {
+                        id: row.get(0)?,
+                        card_id: row.get(1)?,
+                        start_time: parse_datetime_flexible(&row.get::<_, String>(2)?),
+                        is_paused: row.get::<_, i32>(3)? == 1,
+                        pause_duration: row.get(4)?,
+                        pause_started_at: pause_started_at.map(|s| parse_datetime_flexible(&s)),
+                        work_session_id: row.get(6)?,
+                    })
+                },
+            )
+            .optional()
+            .context("Failed to load timer session snapshot")
+    }
+
+    /// Deletes the snapshot if its card no longer exists - e.g. the card was
+    /// deleted while the app was closed with a timer still running on it.
+    pub fn discard_orphaned_timer_session_snapshot(&self) -> anyhow::Result<()> {
+        self.connection.execute(
+            "DELETE FROM timer_session_snapshot WHERE card_id NOT IN (SELECT id FROM cards)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    // Review-timer operations. Review sessions are recorded in the same
+    // `time_sessions` table as regular card timers - they're just started
+    // with a link back to the most recent prior session for the card, so
+    // the review mode UI can show what it's reviewing.
+    pub fn start_review_timer_session(&mut self, card_id: &str) -> anyhow::Result<(ReviewTimerSessionStart, String)> {
+        let original_session_id = self.get_sessions_for_card(card_id)?.into_iter().next().map(|s| s.id);
+        let (session, work_session_id) = self.start_timer_session(card_id)?;
+        Ok((
+            ReviewTimerSessionStart {
+                id: session.id,
+                start_time: session.start_time,
+                original_session_id,
             },
-            None => Err(anyhow::anyhow!("Session with id '{}' not found", session_id))
+            work_session_id,
+        ))
+    }
+
+    pub fn end_review_timer_session(&mut self, session_id: &str, review_work_session_id: Option<&str>) -> anyhow::Result<()> {
+        self.end_timer_session(session_id, review_work_session_id)
+    }
+
+    pub fn get_card_review_sessions(&self, card_id: &str) -> anyhow::Result<Vec<ReviewSession>> {
+        self.get_sessions_for_card(card_id)
+    }
+
+    pub fn delete_review_session(&mut self, session_id: &str) -> anyhow::Result<()> {
+        self.delete_time_session(session_id)
+    }
+
+    /// All time sessions (including any still running, with no `end_time`
+    /// yet) whose `date` falls within `[start_date, end_date]`
+    /// (inclusive, `YYYY-MM-DD`), joined with the session's problem difficulty for
+    /// tagging in exports like the InfluxDB line-protocol serializer.
+    pub fn get_sessions_in_range(&self, start_date: &str, end_date: &str) -> anyhow::Result<Vec<(TimeSession, Option<String>)>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT ts.id, ts.card_id, ts.start_time, ts.end_time, ts.duration, ts.date, ts.is_active, ts.notes, p.difficulty
+             FROM time_sessions ts
+             LEFT JOIN cards c ON c.id = ts.card_id
+             LEFT JOIN problems p ON p.id = c.problem_id
+             WHERE ts.date BETWEEN ?1 AND ?2
+             ORDER BY ts.start_time ASC"
+        )?;
+
+        let session_iter = stmt.query_map(params![start_date, end_date], |row| {
+            let start_time_str: String = row.get(2)?;
+            let end_time_str: Option<String> = row.get(3)?;
+
+            let session = TimeSession {
+                id: row.get(0)?,
+                card_id: row.get(1)?,
+                start_time: start_time_str.parse().unwrap_or_else(|_| Utc::now()),
+                end_time: end_time_str.and_then(|s| s.parse().ok()),
+                duration: row.get(4)?,
+                date: row.get(5)?,
+                is_active: row.get::<_, i32>(6)? == 1,
+                notes: row.get(7)?,
+            };
+            let difficulty: Option<String> = row.get(8)?;
+
+            Ok((session, difficulty))
+        })?;
+
+        let mut sessions = Vec::new();
+        for session in session_iter {
+            sessions.push(session?);
+        }
+
+        Ok(sessions)
+    }
+
+    /// Aggregated study analytics for the "Analytics" dashboard - `today` is
+    /// the caller's local date (`YYYY-MM-DD`), anchoring `study_streak_days`
+    /// and the trailing-7-day `weekly_progress` window. Everything that's a
+    /// straightforward aggregate is computed in SQL; `study_streak_days` and
+    /// `weekly_progress` still need a little Rust-side postprocessing since
+    /// SQLite has no `generate_series` to synthesize days with zero sessions.
+    pub fn get_study_analytics(&self, today: &str) -> anyhow::Result<StudyAnalytics> {
+        let total_problems: i32 = self.connection.query_row(
+            "SELECT COUNT(*) FROM problems WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let completed_problems: i32 = self.connection.query_row(
+            "SELECT COUNT(DISTINCT c.problem_id)
+             FROM cards c
+             JOIN problems p ON p.id = c.problem_id
+             WHERE c.status = 'Completed' AND c.deleted_at IS NULL AND p.deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let total_study_time: i32 = self.connection.query_row(
+            "SELECT COALESCE(SUM(duration), 0) FROM time_sessions WHERE duration IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let average_session_time: f64 = self.connection.query_row(
+            "SELECT COALESCE(AVG(duration), 0.0) FROM time_sessions WHERE duration IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let mut problems_by_difficulty = serde_json::json!({ "Easy": 0, "Medium": 0, "Hard": 0 });
+        {
+            let mut stmt = self.connection.prepare(
+                "SELECT difficulty, COUNT(*) FROM problems WHERE deleted_at IS NULL GROUP BY difficulty",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
+            })?;
+            for row in rows {
+                let (difficulty, count) = row?;
+                problems_by_difficulty[difficulty] = serde_json::json!(count);
+            }
+        }
+
+        let most_productive_hour: i32 = self.connection.query_row(
+            "SELECT CAST(strftime('%H', start_time) AS INTEGER) AS hour
+             FROM time_sessions
+             GROUP BY hour
+             ORDER BY COUNT(*) DESC, hour ASC
+             LIMIT 1",
+            [],
+            |row| row.get(0),
+        ).optional()?.unwrap_or(14);
+
+        let mut top_tags_stmt = self.connection.prepare(
+            "SELECT t.name, COUNT(DISTINCT pt.problem_id) AS usage_count
+             FROM tags t
+             JOIN problem_tags pt ON pt.tag_id = t.id
+             JOIN problems p ON p.id = pt.problem_id AND p.deleted_at IS NULL
+             GROUP BY t.id
+             ORDER BY usage_count DESC, t.name ASC
+             LIMIT 10",
+        )?;
+        let top_tags = top_tags_stmt
+            .query_map([], |row| {
+                Ok(TagUsageCount {
+                    tag_name: row.get(0)?,
+                    problem_count: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(top_tags_stmt);
+
+        let today_date = chrono::NaiveDate::parse_from_str(today, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}'", today))?;
+
+        let mut distinct_dates_stmt = self.connection.prepare(
+            "SELECT DISTINCT date FROM time_sessions WHERE date <= ?1 ORDER BY date DESC",
+        )?;
+        let distinct_dates = distinct_dates_stmt
+            .query_map(params![today], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(distinct_dates_stmt);
+
+        let mut study_streak_days = 0i32;
+        let mut expected_date = today_date;
+        for date_str in &distinct_dates {
+            let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") else {
+                continue;
+            };
+            if date == expected_date {
+                study_streak_days += 1;
+                expected_date = date - chrono::Duration::days(1);
+            } else if date < expected_date {
+                break;
+            }
+        }
+
+        let week_start = today_date - chrono::Duration::days(6);
+        let week_start_str = week_start.format("%Y-%m-%d").to_string();
+
+        let mut daily_stmt = self.connection.prepare(
+            "SELECT ts.date,
+                    COALESCE(SUM(ts.duration), 0),
+                    COUNT(DISTINCT c.problem_id)
+             FROM time_sessions ts
+             LEFT JOIN cards c ON c.id = ts.card_id
+             WHERE ts.date BETWEEN ?1 AND ?2
+             GROUP BY ts.date",
+        )?;
+        let daily_rows: std::collections::HashMap<String, (i32, i32)> = daily_stmt
+            .query_map(params![week_start_str, today], |row| {
+                Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+            })?
+            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+        drop(daily_stmt);
+
+        let mut weekly_progress = Vec::with_capacity(7);
+        for offset in 0..7 {
+            let date = week_start + chrono::Duration::days(offset);
+            let date_str = date.format("%Y-%m-%d").to_string();
+            let (study_time, problems_worked) = daily_rows.get(&date_str).copied().unwrap_or((0, 0));
+            weekly_progress.push(DailyStudyProgress {
+                date: date_str,
+                study_time,
+                problems_worked,
+            });
         }
+
+        Ok(StudyAnalytics {
+            total_problems,
+            completed_problems,
+            total_study_time,
+            average_session_time,
+            problems_by_difficulty,
+            study_streak_days,
+            most_productive_hour,
+            weekly_progress,
+            top_tags,
+        })
     }
 
     // Recording operations
     pub fn save_recording(&mut self, card_id: &str, filename: &str, filepath: &str, duration: Option<i32>) -> anyhow::Result<Recording> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
-        
-        // Get file size - resolve relative path to absolute path
-        let file_size = if filepath.starts_with("dev-data/") || filepath.starts_with("app-data/") || filepath.starts_with("attachments/") {
-            // Convert relative path to absolute path based on environment
-            let absolute_path = if filepath.starts_with("dev-data/") {
-                // Development path: project_root/dev-data/...
-                let current_dir = std::env::current_dir().context("Failed to get current directory")?;
-                current_dir.join(filepath)
-            } else if filepath.starts_with("app-data/") {
-                // Production path: resolve to actual app data directory
-                if cfg!(debug_assertions) {
-                    // In development, this shouldn't happen, but handle it
-                    let current_dir = std::env::current_dir().context("Failed to get current directory")?;
-                    current_dir.join("dev-data").join(&filepath[9..]) // Remove "app-data/" prefix
-                } else {
-                    // Production: resolve to actual app data directory
-                    let app_data_dir = if cfg!(target_os = "macos") {
-                        dirs::home_dir()
-                            .context("Failed to get home directory")?
-                            .join("Library")
-                            .join("Application Support")
-                            .join("com.dsalearning.app")
-                    } else if cfg!(target_os = "windows") {
-                        dirs::data_dir()
-                            .context("Failed to get data directory")?
-                            .join("com.dsalearning.app")
-                    } else {
-                        dirs::data_local_dir()
-                            .context("Failed to get local data directory")?
-                            .join("com.dsalearning.app")
-                    };
-                    app_data_dir.join(&filepath[9..]) // Remove "app-data/" prefix
-                }
-            } else {
-                // Legacy "attachments/" path - assume project root for backward compatibility
-                let current_dir = std::env::current_dir().context("Failed to get current directory")?;
-                current_dir.join(filepath)
-            };
-            
+
+        // Resolve through the configured storage roots to get file size at save
+        // time - `resolve_media_path` already understands a `root_id:relative`
+        // stored path (the form `filepath` now arrives in from `start_recording`),
+        // an already-absolute path, and the legacy `dev-data/`/`app-data/` forms
+        // older rows were written with.
+        let file_size = self.resolve_media_path(filepath).ok().and_then(|absolute_path| {
             std::fs::metadata(&absolute_path)
                 .map(|m| m.len() as i64)
-                .map_err(|e| {
-                    println!("Warning: Failed to get file metadata for {}: {}", absolute_path.display(), e);
-                    e
-                })
-                .ok()
-        } else {
-            // Already an absolute path
-            std::fs::metadata(filepath)
-                .map(|m| m.len() as i64)
-                .map_err(|e| {
-                    println!("Warning: Failed to get file metadata for {}: {}", filepath, e);
-                    e
-                })
+                .map_err(|e| println!("Warning: Failed to get file metadata for {}: {}", absolute_path.display(), e))
                 .ok()
-        };
-        
+        });
+
         self.connection.execute(
             "INSERT INTO recordings (id, card_id, audio_url, filename, filepath, duration, file_size, created_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
@@ -1402,6 +2810,35 @@ impl DatabaseManager {
         Ok(recordings)
     }
     
+    /// Look up a single recording by id, e.g. for the `transcribe_recording`
+    /// job handler to resolve `payload.recording_id` back to a `filepath`.
+    pub fn get_recording_by_id(&self, recording_id: &str) -> anyhow::Result<Option<Recording>> {
+        self.connection
+            .query_row(
+                "SELECT id, card_id, time_session_id, audio_url, duration, transcript, created_at, filename, filepath, file_size
+                 FROM recordings WHERE id = ?1",
+                [recording_id],
+                |row| {
+                    Ok(Recording {
+                        id: row.get(0)?,
+                        card_id: row.get(1)?,
+                        time_session_id: row.get(2)?,
+                        audio_url: row.get(3)?,
+                        duration: row.get(4)?,
+                        transcript: row.get(5)?,
+                        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
+                            .map_err(|e| rusqlite::Error::InvalidColumnType(6, e.to_string().into(), rusqlite::types::Type::Text))?
+                            .with_timezone(&chrono::Utc),
+                        filename: row.get(7)?,
+                        filepath: row.get(8)?,
+                        file_size: row.get(9)?,
+                    })
+                },
+            )
+            .optional()
+            .context("Failed to fetch recording")
+    }
+
     pub fn delete_recording(&mut self, recording_id: &str) -> anyhow::Result<()> {
         // Check if recording exists before attempting deletion
         let rows_affected = self.connection.execute(
@@ -1417,110 +2854,724 @@ impl DatabaseManager {
         Ok(())
     }
 
-    // Database analysis functions
-    pub fn get_database_stats(&self) -> anyhow::Result<DatabaseStats> {
-        // Count problems
-        let problem_count: i32 = self.connection.query_row(
-            "SELECT COUNT(*) FROM problems",
-            [],
-            |row| row.get(0),
-        )?;
+    /// Offline consistency pass ("fsck") over the rows nothing else in the
+    /// codebase reconciles against the filesystem or against each other:
+    /// `recordings`/`problem_images` rows whose file vanished, files left
+    /// behind in the recordings/images directories with no row pointing at
+    /// them, `cards.total_duration` drifting from what its `time_sessions`
+    /// actually sum to, and `connections` dangling off a deleted card. Pass a
+    /// default [`maintenance::CheckOptions`] for a dry run; whichever row
+    /// repairs are set to `true` are applied afterwards inside a single
+    /// transaction, so a failure partway through them leaves the database
+    /// untouched. `trash_orphan_files` moves files only after that
+    /// transaction commits, so a failed move is not rolled back with it.
+    pub fn check_and_repair(&mut self, opts: maintenance::CheckOptions) -> anyhow::Result<maintenance::CheckReport> {
+        let mut report = maintenance::CheckReport::default();
 
-        // Count total cards
-        let total_cards: i32 = self.connection.query_row(
-            "SELECT COUNT(*) FROM cards",
-            [],
-            |row| row.get(0),
-        )?;
+        let recordings: Vec<(String, String)> = {
+            let mut stmt = self.connection.prepare("SELECT id, filepath FROM recordings")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let mut referenced_recording_files = std::collections::HashSet::new();
+        for (id, filepath) in &recordings {
+            // Resolves through whichever storage root the recording actually
+            // landed on (or the legacy single-root forms `resolve_recording_path`
+            // used to handle alone), so recordings saved to a non-default root
+            // since storage roots were introduced aren't flagged as missing.
+            let resolved = self.resolve_media_path(filepath)?;
+            if std::fs::metadata(&resolved).is_err() {
+                report.orphan_recording_rows.push(id.clone());
+            } else {
+                referenced_recording_files.insert(maintenance::canonical_or_self(&resolved));
+            }
+        }
 
-        // Count main cards (parent_card_id IS NULL)
-        let main_cards: i32 = self.connection.query_row(
-            "SELECT COUNT(*) FROM cards WHERE parent_card_id IS NULL OR parent_card_id = ''",
-            [],
-            |row| row.get(0),
-        )?;
+        let images: Vec<(String, String)> = {
+            let mut stmt = self.connection.prepare("SELECT id, image_path FROM problem_images")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let mut referenced_image_files = std::collections::HashSet::new();
+        for (id, image_path) in &images {
+            // Same storage-roots-aware resolution as the recordings loop
+            // above, rather than the prefix-blind `maintenance::resolve_image_path`.
+            let resolved = self.resolve_media_path(image_path)?;
+            if std::fs::metadata(&resolved).is_err() {
+                report.orphan_image_rows.push(id.clone());
+            } else {
+                referenced_image_files.insert(maintenance::canonical_or_self(&resolved));
+            }
+        }
 
-        // Count child cards (parent_card_id IS NOT NULL)
-        let child_cards: i32 = self.connection.query_row(
-            "SELECT COUNT(*) FROM cards WHERE parent_card_id IS NOT NULL AND parent_card_id != ''",
-            [],
-            |row| row.get(0),
-        )?;
+        // `problem_images.thumbnail_path` and both of `card_images`'
+        // `image_path`/`thumbnail_path` share the same `images_dir()` tree
+        // the orphan-file walk below scans, so they have to be in
+        // `referenced_image_files` too or the very first repair trashes
+        // every card image and thumbnail. None of these three columns has
+        // its own orphan-*row* tracking in `CheckReport` (only
+        // `problem_images.image_path` does), so a missing file here is
+        // simply left out of the set rather than flagged.
+        {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT thumbnail_path FROM problem_images WHERE thumbnail_path IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            for thumbnail_path in rows {
+                let thumbnail_path = thumbnail_path?;
+                if let Ok(resolved) = self.resolve_media_path(&thumbnail_path) {
+                    if std::fs::metadata(&resolved).is_ok() {
+                        referenced_image_files.insert(maintenance::canonical_or_self(&resolved));
+                    }
+                }
+            }
+        }
+        {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT image_path, thumbnail_path FROM card_images")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?;
+            for row in rows {
+                let (image_path, thumbnail_path) = row?;
+                if let Ok(resolved) = self.resolve_media_path(&image_path) {
+                    if std::fs::metadata(&resolved).is_ok() {
+                        referenced_image_files.insert(maintenance::canonical_or_self(&resolved));
+                    }
+                }
+                if let Some(thumbnail_path) = thumbnail_path {
+                    if let Ok(resolved) = self.resolve_media_path(&thumbnail_path) {
+                        if std::fs::metadata(&resolved).is_ok() {
+                            referenced_image_files.insert(maintenance::canonical_or_self(&resolved));
+                        }
+                    }
+                }
+            }
+        }
 
-        Ok(DatabaseStats {
-            problem_count,
-            total_cards,
-            main_cards,
-            child_cards,
-        })
-    }
+        let mut recording_files_on_disk = Vec::new();
+        for dir in maintenance::recording_dirs_for_roots(&self.storage_roots) {
+            recording_files_on_disk.extend(maintenance::list_files_recursive(&dir)?);
+        }
+        let images_dir = maintenance::images_dir()?;
+        let pending_dir = maintenance::pending_dir()?;
+        report.orphan_recording_files = maintenance::orphan_files(
+            recording_files_on_disk,
+            &referenced_recording_files,
+        );
+        // `images/pending/` holds uploads the `jobs` queue hasn't finished
+        // validating/hashing into a CAS entry yet (see `pending_dir`'s doc
+        // comment) - they're not referenced by any row by design, so the
+        // walk below has to skip that subtree rather than flag every
+        // in-flight upload as orphaned.
+        report.orphan_image_files = maintenance::orphan_files(
+            maintenance::list_files_recursive(&images_dir)?
+                .into_iter()
+                .filter(|file| !file.starts_with(&pending_dir))
+                .collect(),
+            &referenced_image_files,
+        );
 
-    pub fn get_card_hierarchy(&self) -> anyhow::Result<Vec<CardHierarchy>> {
-        let mut stmt = self.connection.prepare(
-            "SELECT 
-                c.id, c.problem_id, c.card_number, c.parent_card_id,
-                p.title as problem_title,
-                (SELECT COUNT(*) FROM cards WHERE parent_card_id = c.id) as child_count
-             FROM cards c
-             JOIN problems p ON c.problem_id = p.id
-             ORDER BY p.title, c.card_number"
-        )?;
+        {
+            let mut stmt = self.connection.prepare(
+                "SELECT c.id, c.total_duration, COALESCE(SUM(ts.duration), 0)
+                 FROM cards c
+                 LEFT JOIN time_sessions ts ON ts.card_id = c.id AND ts.is_active = 0
+                 GROUP BY c.id
+                 HAVING c.total_duration != COALESCE(SUM(ts.duration), 0)"
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(maintenance::DurationMismatch {
+                    card_id: row.get(0)?,
+                    stored_total_duration: row.get(1)?,
+                    computed_total_duration: row.get(2)?,
+                })
+            })?;
+            for row in rows {
+                report.duration_mismatches.push(row?);
+            }
+        }
 
-        let hierarchy_iter = stmt.query_map([], |row| {
-            Ok(CardHierarchy {
-                card_id: row.get(0)?,
-                problem_id: row.get(1)?,
-                problem_title: row.get(4)?,
-                card_number: row.get(2)?,
-                parent_card_id: row.get(3)?,
-                child_count: row.get(5)?,
-            })
-        })?;
+        {
+            let mut stmt = self.connection.prepare(
+                "SELECT id, source_card_id, target_card_id FROM connections
+                 WHERE source_card_id NOT IN (SELECT id FROM cards)
+                    OR target_card_id NOT IN (SELECT id FROM cards)"
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(maintenance::DanglingConnection {
+                    connection_id: row.get(0)?,
+                    source_card_id: row.get(1)?,
+                    target_card_id: row.get(2)?,
+                })
+            })?;
+            for row in rows {
+                report.dangling_connections.push(row?);
+            }
+        }
 
-        let mut hierarchies = Vec::new();
-        for hierarchy in hierarchy_iter {
-            hierarchies.push(hierarchy?);
+        if !opts.delete_orphan_rows && !opts.trash_orphan_files && !opts.fix_durations && !opts.delete_dangling_connections {
+            return Ok(report);
         }
 
-        Ok(hierarchies)
-    }
+        let tx = self.connection.unchecked_transaction()?;
 
-    pub fn get_cards_per_problem(&self) -> anyhow::Result<Vec<CardCountPerProblem>> {
-        let mut stmt = self.connection.prepare(
-            "SELECT 
-                p.id, p.title,
-                COUNT(c.id) as total_cards,
-                COUNT(CASE WHEN c.parent_card_id IS NULL OR c.parent_card_id = '' THEN 1 END) as main_cards,
-                COUNT(CASE WHEN c.parent_card_id IS NOT NULL AND c.parent_card_id != '' THEN 1 END) as child_cards
-             FROM problems p
-             LEFT JOIN cards c ON p.id = c.problem_id
-             GROUP BY p.id, p.title
-             ORDER BY p.title"
-        )?;
+        if opts.delete_orphan_rows {
+            for id in &report.orphan_recording_rows {
+                tx.execute("DELETE FROM recordings WHERE id = ?1", params![id])?;
+            }
+            for id in &report.orphan_image_rows {
+                tx.execute("DELETE FROM problem_images WHERE id = ?1", params![id])?;
+            }
+        }
 
-        let count_iter = stmt.query_map([], |row| {
-            Ok(CardCountPerProblem {
-                problem_id: row.get(0)?,
-                problem_title: row.get(1)?,
-                total_cards: row.get(2)?,
-                main_cards: row.get(3)?,
-                child_cards: row.get(4)?,
-            })
-        })?;
+        if opts.fix_durations {
+            for mismatch in &report.duration_mismatches {
+                tx.execute(
+                    "UPDATE cards SET total_duration = ?1 WHERE id = ?2",
+                    params![mismatch.computed_total_duration, &mismatch.card_id],
+                )?;
+            }
+        }
 
-        let mut counts = Vec::new();
-        for count in count_iter {
-            counts.push(count?);
+        if opts.delete_dangling_connections {
+            for dangling in &report.dangling_connections {
+                tx.execute("DELETE FROM connections WHERE id = ?1", params![dangling.connection_id])?;
+            }
+        }
+
+        tx.commit()?;
+
+        if opts.trash_orphan_files {
+            // File moves happen after the transaction commits - a mid-pass I/O
+            // failure here can't roll back database changes that already
+            // landed, it only leaves that file untrashed for the next pass.
+            let base_dir = maintenance::app_data_dir()?;
+            for file in report.orphan_recording_files.iter().chain(report.orphan_image_files.iter()) {
+                if let Err(e) = maintenance::trash_file(&base_dir, Path::new(file)) {
+                    println!("⚠️ [Database] check_and_repair: failed to trash orphan file '{}': {}", file, e);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Janitor pass over `roots` (on-disk media directories, e.g. the
+    /// recordings/images dirs `check_and_repair` already knows about, or any
+    /// other directory recordings/images might live under): counts
+    /// `recordings`/`problem_images` rows whose backing file is missing or
+    /// not a regular file (`dangling_rows_found`), then walks `roots` and
+    /// permanently deletes every file not referenced by either table,
+    /// returning how many files that was and how many bytes it freed.
+    /// Unlike `check_and_repair`'s `trash_orphan_files`, this never leaves
+    /// the freed space sitting in a `trash/` directory - run
+    /// `check_and_repair` first if you want a dry-run look at what this
+    /// would do.
+    pub fn reconcile_media(&mut self, roots: &[std::path::PathBuf]) -> anyhow::Result<maintenance::ReconcileReport> {
+        let mut report = maintenance::ReconcileReport::default();
+
+        let mut referenced: std::collections::HashSet<std::path::PathBuf> = std::collections::HashSet::new();
+
+        let recordings: Vec<String> = {
+            let mut stmt = self.connection.prepare("SELECT filepath FROM recordings")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        for filepath in &recordings {
+            // Storage-roots-aware, like `check_and_repair`'s recordings loop -
+            // the prefix-blind `maintenance::resolve_recording_path` falls
+            // through to a bogus relative path for anything saved through a
+            // non-default storage root, which would count its (real,
+            // present) file as dangling and delete it below.
+            let resolved = self.resolve_media_path(filepath)?;
+            match std::fs::metadata(&resolved) {
+                Ok(meta) if meta.is_file() => {
+                    referenced.insert(maintenance::canonical_or_self(&resolved));
+                }
+                _ => report.dangling_rows_found += 1,
+            }
+        }
+
+        let images: Vec<String> = {
+            let mut stmt = self.connection.prepare("SELECT image_path FROM problem_images")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        for image_path in &images {
+            let resolved = self.resolve_media_path(image_path)?;
+            match std::fs::metadata(&resolved) {
+                Ok(meta) if meta.is_file() => {
+                    referenced.insert(maintenance::canonical_or_self(&resolved));
+                }
+                _ => report.dangling_rows_found += 1,
+            }
+        }
+
+        // `problem_images.thumbnail_path` and both of `card_images`'
+        // `image_path`/`thumbnail_path` live under the same `images_dir()`
+        // tree a caller-supplied root can point at, so they need to be
+        // referenced too or this permanently deletes every card image and
+        // thumbnail the first time it runs. Missing files here don't bump
+        // `dangling_rows_found` - that counter is specifically about
+        // `recordings`/`problem_images` rows, and neither of these queries
+        // maps back to a single dangling row the way those do.
+        {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT thumbnail_path FROM problem_images WHERE thumbnail_path IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            for thumbnail_path in rows {
+                let thumbnail_path = thumbnail_path?;
+                if let Ok(resolved) = self.resolve_media_path(&thumbnail_path) {
+                    if let Ok(meta) = std::fs::metadata(&resolved) {
+                        if meta.is_file() {
+                            referenced.insert(maintenance::canonical_or_self(&resolved));
+                        }
+                    }
+                }
+            }
+        }
+        {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT image_path, thumbnail_path FROM card_images")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?;
+            for row in rows {
+                let (image_path, thumbnail_path) = row?;
+                if let Ok(resolved) = self.resolve_media_path(&image_path) {
+                    if let Ok(meta) = std::fs::metadata(&resolved) {
+                        if meta.is_file() {
+                            referenced.insert(maintenance::canonical_or_self(&resolved));
+                        }
+                    }
+                }
+                if let Some(thumbnail_path) = thumbnail_path {
+                    if let Ok(resolved) = self.resolve_media_path(&thumbnail_path) {
+                        if let Ok(meta) = std::fs::metadata(&resolved) {
+                            if meta.is_file() {
+                                referenced.insert(maintenance::canonical_or_self(&resolved));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let pending_dir = maintenance::pending_dir()?;
+        for root in roots {
+            for file in maintenance::list_files_recursive(root)? {
+                // `images/pending/` holds uploads the `jobs` queue hasn't
+                // finished validating/hashing yet (see `pending_dir`'s doc
+                // comment) - they're not referenced by any row by design, so
+                // they'd otherwise be permanently deleted mid-upload.
+                if file.starts_with(&pending_dir) {
+                    continue;
+                }
+                if referenced.contains(&maintenance::canonical_or_self(&file)) {
+                    continue;
+                }
+                let size = std::fs::metadata(&file).map(|meta| meta.len()).unwrap_or(0);
+                match std::fs::remove_file(&file) {
+                    Ok(()) => {
+                        report.orphan_files_deleted += 1;
+                        report.bytes_reclaimed += size as i64;
+                    }
+                    Err(e) => {
+                        println!("⚠️ [Database] reconcile_media: failed to delete orphan file '{}': {}", file.display(), e);
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// One-time backfill for `problem_images` rows saved before
+    /// content-addressed storage (migration 21): hashes each legacy
+    /// `<uuid>.<ext>` file with BLAKE3, moves it (and its thumbnail, if any)
+    /// into the shared `images/cas/` directory under its hash, and points the
+    /// row at the new location. If two rows' files happen to hash to the same
+    /// content (the same screenshot saved from different cards before dedup
+    /// existed), the second one found simply adopts the first's CAS file and
+    /// its own copy is deleted. Not run at startup - a Tauri command triggers
+    /// it explicitly, since it touches every legacy image file on disk.
+    pub fn migrate_images_to_cas(&mut self) -> anyhow::Result<maintenance::CasMigrationReport> {
+        let mut report = maintenance::CasMigrationReport::default();
+
+        let rows: Vec<(String, String, Option<String>)> = {
+            let mut stmt = self.connection.prepare(
+                "SELECT id, image_path, thumbnail_path FROM problem_images WHERE content_hash IS NULL"
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+
+        let cas_dir = maintenance::cas_dir()?;
+        std::fs::create_dir_all(&cas_dir)?;
+        let prefix = if cfg!(debug_assertions) { "dev-data/" } else { "app-data/" };
+
+        for (id, image_path, thumbnail_path) in rows {
+            let full_path = match maintenance::resolve_image_path(&image_path) {
+                Ok(path) if path.exists() => path,
+                _ => {
+                    report.images_skipped += 1;
+                    continue;
+                }
+            };
+            let data = match std::fs::read(&full_path) {
+                Ok(data) => data,
+                Err(_) => {
+                    report.images_skipped += 1;
+                    continue;
+                }
+            };
+
+            let hash = maintenance::blake3_hex(&data);
+            let extension = Path::new(&image_path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("png")
+                .to_string();
+
+            let cas_full_path = cas_dir.join(format!("{}.{}", hash, extension));
+            let deduplicated = cas_full_path.exists();
+            if deduplicated {
+                std::fs::remove_file(&full_path).ok();
+            } else {
+                std::fs::rename(&full_path, &cas_full_path)?;
+            }
+
+            let new_thumbnail_path = thumbnail_path.and_then(|old_thumb| {
+                let old_thumb_full = maintenance::resolve_image_path(&old_thumb).ok()?;
+                if !old_thumb_full.exists() {
+                    return None;
+                }
+                let cas_thumb_full = cas_dir.join(format!("{}.thumb.webp", hash));
+                if cas_thumb_full.exists() {
+                    std::fs::remove_file(&old_thumb_full).ok();
+                } else {
+                    std::fs::rename(&old_thumb_full, &cas_thumb_full).ok()?;
+                }
+                Some(format!("{}images/cas/{}.thumb.webp", prefix, hash))
+            });
+            let new_image_path = format!("{}images/cas/{}.{}", prefix, hash, extension);
+
+            self.register_image_blob(&hash, &extension)?;
+            self.connection.execute(
+                "UPDATE problem_images SET image_path = ?1, thumbnail_path = ?2, content_hash = ?3 WHERE id = ?4",
+                params![&new_image_path, new_thumbnail_path, &hash, &id],
+            )?;
+
+            report.images_migrated += 1;
+            if deduplicated {
+                report.images_deduplicated += 1;
+            }
+        }
+
+        // Triggers only maintain `ref_count` on INSERT/DELETE, not this
+        // UPDATE, so recompute it by hand the same way `install_triggers`
+        // backfills it.
+        self.connection.execute_batch(
+            "UPDATE image_blobs SET ref_count = (
+                SELECT COUNT(*) FROM problem_images WHERE problem_images.content_hash = image_blobs.hash
+            ) + (
+                SELECT COUNT(*) FROM card_images WHERE card_images.content_hash = image_blobs.hash
+            );",
+        )?;
+
+        Ok(report)
+    }
+
+    /// Gathers `problem_id` and everything hanging off it (cards, time
+    /// sessions, recordings with audio inlined, images inlined, connections,
+    /// tags) into a single passphrase-encrypted, portable file - see
+    /// `problem_bundle` for the manifest shape and cipher.
+    pub fn export_problem_bundle(&self, problem_id: &str, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+        problem_bundle::export(&self.connection, problem_id, passphrase)
+    }
+
+    /// Inverse of [`Self::export_problem_bundle`]: decrypts `bytes`, checks
+    /// the manifest isn't from a newer format than this build understands,
+    /// and inserts everything as a brand-new problem (fresh UUIDs throughout,
+    /// every foreign key remapped) inside one transaction. Returns the new
+    /// problem's id.
+    pub fn import_problem_bundle(&mut self, bytes: &[u8], passphrase: &str) -> anyhow::Result<String> {
+        let tx = self.connection.unchecked_transaction()?;
+        let new_problem_id = problem_bundle::import(&tx, bytes, passphrase)?;
+        tx.commit()?;
+        Ok(new_problem_id)
+    }
+
+    // Database analysis functions
+    pub fn get_database_stats(&self) -> anyhow::Result<DatabaseStats> {
+        // Count problems
+        let problem_count: i32 = self.connection.query_row(
+            "SELECT COUNT(*) FROM problems",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // Count total cards
+        let total_cards: i32 = self.connection.query_row(
+            "SELECT COUNT(*) FROM cards",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // Count main cards (parent_card_id IS NULL)
+        let main_cards: i32 = self.connection.query_row(
+            "SELECT COUNT(*) FROM cards WHERE parent_card_id IS NULL OR parent_card_id = ''",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // Count child cards (parent_card_id IS NOT NULL)
+        let child_cards: i32 = self.connection.query_row(
+            "SELECT COUNT(*) FROM cards WHERE parent_card_id IS NOT NULL AND parent_card_id != ''",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // Index/page stats so the frontend can surface when a reindex is warranted -
+        // a large freelist count relative to the page count means VACUUM would help.
+        let index_count: i32 = self.connection.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='index' AND name NOT LIKE 'sqlite_%'",
+            [],
+            |row| row.get(0),
+        )?;
+        let database_page_count: i32 =
+            self.connection
+                .query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let database_freelist_count: i32 =
+            self.connection
+                .query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+
+        Ok(DatabaseStats {
+            problem_count,
+            total_cards,
+            main_cards,
+            child_cards,
+            index_count,
+            database_page_count,
+            database_freelist_count,
+        })
+    }
+
+    /// Drops and recreates every secondary index, then runs `ANALYZE`/`VACUUM`
+    /// so the query planner's statistics and the on-disk layout are fresh.
+    /// Exposed as an administrative command for users who've imported a
+    /// large problem set and want the search/tag hot paths back up to speed.
+    pub fn rebuild_indexes(&mut self) -> anyhow::Result<()> {
+        println!("🔧 [Database] Rebuilding indexes...");
+
+        let existing_indexes: Vec<String> = self
+            .connection
+            .prepare("SELECT name FROM sqlite_master WHERE type='index' AND name NOT LIKE 'sqlite_%'")?
+            .query_map([], |row| Ok(row.get::<_, String>(0)?))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for index_name in &existing_indexes {
+            self.connection
+                .execute(&format!("DROP INDEX IF EXISTS {}", index_name), [])?;
+        }
+
+        self.connection.execute_batch(CREATE_INDEXES_SQL)?;
+        search::ensure_fts_index(&self.connection)?;
+        indexes::ensure_indexes(&self.connection)?;
+        triggers::install_triggers(&self.connection)?;
+
+        println!("🔧 [Database] Running ANALYZE...");
+        self.connection.execute_batch("ANALYZE")?;
+
+        println!("🔧 [Database] Running VACUUM...");
+        self.connection.execute_batch("VACUUM")?;
+
+        println!("✅ [Database] Index rebuild completed successfully");
+        Ok(())
+    }
+
+    /// Creates a single index on `table(columns)` if it doesn't already
+    /// exist. For one-off/ad-hoc indexes outside `indexes::MANAGED_INDEXES` -
+    /// most indexes the app needs are provisioned automatically by
+    /// `ensure_indexes`.
+    pub fn create_index(&mut self, table: &str, name: &str, columns: &str) -> anyhow::Result<()> {
+        indexes::create_index(&self.connection, table, name, columns)
+    }
+
+    /// Drops an index by name, managed or not.
+    pub fn drop_index(&mut self, name: &str) -> anyhow::Result<()> {
+        indexes::drop_index(&self.connection, name)
+    }
+
+    /// Idempotently (re-)provisions every index in `indexes::MANAGED_INDEXES` -
+    /// the expression/composite indexes the query layer relies on but that
+    /// the migration registry doesn't own. Already run automatically on
+    /// every connect; exposed so it can be re-triggered without a full
+    /// `rebuild_indexes` pass.
+    pub fn ensure_indexes(&mut self) -> anyhow::Result<()> {
+        indexes::ensure_indexes(&self.connection)
+    }
+
+    /// Idempotently (re-)installs every trigger in `triggers::MANAGED_TRIGGERS`
+    /// - reciprocal `problem_relations` maintenance, `tags.usage_count` and
+    /// `image_blobs.ref_count` bookkeeping, `time_sessions.duration` and
+    /// `cards.total_duration` derivation, `cards.last_modified` stamping and
+    /// `work_sessions`' derived columns - and backfills `usage_count` and
+    /// `ref_count` from the current rows. Already run automatically on every connect;
+    /// exposed so it can be re-triggered (e.g. after `drop_triggers`).
+    pub fn install_triggers(&mut self) -> anyhow::Result<()> {
+        triggers::install_triggers(&self.connection)
+    }
+
+    /// Drops every trigger in `triggers::MANAGED_TRIGGERS`. Reciprocal
+    /// relations are still maintained by hand by `add_problem_relation`/
+    /// `remove_problem_relation`; the other invariants they enforced (tag
+    /// usage counts, image blob ref counts, session durations and
+    /// `cards.total_duration`) are not kept up to date until
+    /// `install_triggers` runs again.
+    pub fn drop_triggers(&mut self) -> anyhow::Result<()> {
+        triggers::drop_triggers(&self.connection)
+    }
+
+    /// Lists which of `triggers::MANAGED_TRIGGERS` are currently installed.
+    pub fn list_triggers(&self) -> anyhow::Result<Vec<String>> {
+        triggers::list_triggers(&self.connection)
+    }
+
+    pub fn get_card_hierarchy(&self) -> anyhow::Result<Vec<CardHierarchy>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT 
+                c.id, c.problem_id, c.card_number, c.parent_card_id,
+                p.title as problem_title,
+                (SELECT COUNT(*) FROM cards WHERE parent_card_id = c.id) as child_count
+             FROM cards c
+             JOIN problems p ON c.problem_id = p.id
+             ORDER BY p.title, c.card_number"
+        )?;
+
+        let hierarchy_iter = stmt.query_map([], |row| {
+            Ok(CardHierarchy {
+                card_id: row.get(0)?,
+                problem_id: row.get(1)?,
+                problem_title: row.get(4)?,
+                card_number: row.get(2)?,
+                parent_card_id: row.get(3)?,
+                child_count: row.get(5)?,
+            })
+        })?;
+
+        let mut hierarchies = Vec::new();
+        for hierarchy in hierarchy_iter {
+            hierarchies.push(hierarchy?);
+        }
+
+        Ok(hierarchies)
+    }
+
+    pub fn get_cards_per_problem(&self) -> anyhow::Result<Vec<CardCountPerProblem>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT 
+                p.id, p.title,
+                COUNT(c.id) as total_cards,
+                COUNT(CASE WHEN c.parent_card_id IS NULL OR c.parent_card_id = '' THEN 1 END) as main_cards,
+                COUNT(CASE WHEN c.parent_card_id IS NOT NULL AND c.parent_card_id != '' THEN 1 END) as child_cards
+             FROM problems p
+             LEFT JOIN cards c ON p.id = c.problem_id
+             GROUP BY p.id, p.title
+             ORDER BY p.title"
+        )?;
+
+        let count_iter = stmt.query_map([], |row| {
+            Ok(CardCountPerProblem {
+                problem_id: row.get(0)?,
+                problem_title: row.get(1)?,
+                total_cards: row.get(2)?,
+                main_cards: row.get(3)?,
+                child_cards: row.get(4)?,
+            })
+        })?;
+
+        let mut counts = Vec::new();
+        for count in count_iter {
+            counts.push(count?);
         }
 
         Ok(counts)
     }
 
     // Image-related operations
-    pub fn save_problem_image(&mut self, problem_id: &str, image_path: &str, caption: Option<String>, position: Option<i32>) -> anyhow::Result<ProblemImage> {
+
+    /// Registers a content hash in `image_blobs` (if not already present)
+    /// before a `problem_images` row referencing it is inserted, so
+    /// `trg_image_blobs_ref_count_insert` has a row to increment. A no-op if
+    /// the hash is already known - e.g. the same screenshot pasted into a
+    /// second card.
+    pub fn register_image_blob(&mut self, hash: &str, extension: &str) -> anyhow::Result<()> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO image_blobs (hash, extension, ref_count, created_at) VALUES (?1, ?2, 0, ?3)",
+            params![hash, extension, &Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Current `ref_count` for a content hash, or `None` if `image_blobs` has
+    /// no row for it (never registered, or already pruned).
+    pub fn image_blob_ref_count(&self, hash: &str) -> anyhow::Result<Option<i64>> {
+        self.connection
+            .query_row(
+                "SELECT ref_count FROM image_blobs WHERE hash = ?1",
+                [hash],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Removes a content hash's `image_blobs` row once its last
+    /// `problem_images` reference is gone - called by the `delete_problem_image`
+    /// command after it confirms `ref_count` reached zero and unlinks the
+    /// physical file.
+    pub fn forget_image_blob(&mut self, hash: &str) -> anyhow::Result<()> {
+        self.connection.execute("DELETE FROM image_blobs WHERE hash = ?1", [hash])?;
+        Ok(())
+    }
+
+    /// A prior row's `blur_hash` for `hash`, if one was already computed -
+    /// `save_problem_image` reuses it instead of re-decoding a content hash
+    /// it's already seen.
+    pub fn find_image_blur_hash_by_content_hash(&self, hash: &str) -> anyhow::Result<Option<String>> {
+        self.connection
+            .query_row(
+                "SELECT blur_hash FROM problem_images WHERE content_hash = ?1 AND blur_hash IS NOT NULL LIMIT 1",
+                [hash],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(anyhow::Error::from)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn save_problem_image(
+        &mut self,
+        problem_id: &str,
+        image_path: &str,
+        thumbnail_path: Option<&str>,
+        blur_hash: Option<&str>,
+        content_hash: Option<&str>,
+        width: Option<i32>,
+        height: Option<i32>,
+        byte_size: Option<i64>,
+        status: &str,
+        caption: Option<String>,
+        position: Option<i32>,
+    ) -> anyhow::Result<ProblemImage> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
-        
+
         // Get the next position if not provided
         let position = match position {
             Some(pos) => pos,
@@ -1533,103 +3584,518 @@ impl DatabaseManager {
                 max_position.unwrap_or(-1) + 1
             }
         };
+
+        self.connection.execute(
+            "INSERT INTO problem_images (id, problem_id, image_path, caption, position, created_at, thumbnail_path, blur_hash, content_hash, width, height, byte_size, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                &id,
+                problem_id,
+                image_path,
+                &caption,
+                position,
+                &now.to_rfc3339(),
+                thumbnail_path,
+                blur_hash,
+                content_hash,
+                width,
+                height,
+                byte_size,
+                status,
+            ],
+        )?;
+
+        Ok(ProblemImage {
+            id,
+            problem_id: problem_id.to_string(),
+            image_path: image_path.to_string(),
+            caption,
+            position,
+            created_at: now,
+            thumbnail_path: thumbnail_path.map(|s| s.to_string()),
+            blur_hash: blur_hash.map(|s| s.to_string()),
+            content_hash: content_hash.map(|s| s.to_string()),
+            width,
+            height,
+            byte_size,
+            status: status.to_string(),
+        })
+    }
+
+    pub fn get_problem_images(&self, problem_id: &str) -> anyhow::Result<Vec<ProblemImage>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, problem_id, image_path, caption, position, created_at, thumbnail_path, blur_hash, content_hash, width, height, byte_size, status
+             FROM problem_images WHERE problem_id = ?1 ORDER BY position"
+        )?;
+
+        let image_iter = stmt.query_map([problem_id], |row| {
+            let created_at_str: String = row.get(5)?;
+
+            Ok(ProblemImage {
+                id: row.get(0)?,
+                problem_id: row.get(1)?,
+                image_path: row.get(2)?,
+                caption: row.get(3)?,
+                position: row.get(4)?,
+                created_at: created_at_str.parse().unwrap_or_else(|_| Utc::now()),
+                thumbnail_path: row.get(6)?,
+                blur_hash: row.get(7)?,
+                content_hash: row.get(8)?,
+                width: row.get(9)?,
+                height: row.get(10)?,
+                byte_size: row.get(11)?,
+                status: row.get(12)?,
+            })
+        })?;
+
+        let mut images = Vec::new();
+        for image in image_iter {
+            images.push(image?);
+        }
+
+        Ok(images)
+    }
+
+    /// Single `problem_images` row by id, e.g. for `get_image_processing_status`
+    /// polling a `pending` upload, or the `process_image` job worker looking up
+    /// the row it's about to finish.
+    pub fn get_problem_image(&self, image_id: &str) -> anyhow::Result<Option<ProblemImage>> {
+        self.connection
+            .query_row(
+                "SELECT id, problem_id, image_path, caption, position, created_at, thumbnail_path, blur_hash, content_hash, width, height, byte_size, status
+                 FROM problem_images WHERE id = ?1",
+                [image_id],
+                |row| {
+                    let created_at_str: String = row.get(5)?;
+                    Ok(ProblemImage {
+                        id: row.get(0)?,
+                        problem_id: row.get(1)?,
+                        image_path: row.get(2)?,
+                        caption: row.get(3)?,
+                        position: row.get(4)?,
+                        created_at: created_at_str.parse().unwrap_or_else(|_| Utc::now()),
+                        thumbnail_path: row.get(6)?,
+                        blur_hash: row.get(7)?,
+                        content_hash: row.get(8)?,
+                        width: row.get(9)?,
+                        height: row.get(10)?,
+                        byte_size: row.get(11)?,
+                        status: row.get(12)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Finishes the `process_image` job: records the processed file's final
+    /// path/thumbnail/BlurHash/content hash/dimensions and flips `status` to
+    /// `ready`. Called once the worker has replaced the staged original with
+    /// its stripped, re-encoded, CAS-stored counterpart.
+    #[allow(clippy::too_many_arguments)]
+    pub fn finish_image_processing(
+        &mut self,
+        image_id: &str,
+        image_path: &str,
+        thumbnail_path: Option<&str>,
+        blur_hash: Option<&str>,
+        content_hash: Option<&str>,
+        width: Option<i32>,
+        height: Option<i32>,
+        byte_size: Option<i64>,
+    ) -> anyhow::Result<()> {
+        self.connection.execute(
+            "UPDATE problem_images
+             SET image_path = ?2, thumbnail_path = ?3, blur_hash = ?4, content_hash = ?5,
+                 width = ?6, height = ?7, byte_size = ?8, status = 'ready'
+             WHERE id = ?1",
+            params![image_id, image_path, thumbnail_path, blur_hash, content_hash, width, height, byte_size],
+        )?;
+        Ok(())
+    }
+
+    /// Marks a `pending` image `failed` - the upload's bytes didn't decode as
+    /// the format they claimed, or exceeded a size/dimension limit. Unlike a
+    /// transient `jobs` failure, re-running the same bytes will never
+    /// succeed, so `process_image` calls this and reports the job itself as
+    /// done rather than letting the queue retry it.
+    pub fn fail_image_processing(&mut self, image_id: &str) -> anyhow::Result<()> {
+        self.connection.execute(
+            "UPDATE problem_images SET status = 'failed' WHERE id = ?1",
+            [image_id],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes a `problem_images` row and returns `(image_path, thumbnail_path,
+    /// content_hash, remaining_ref_count)` so the caller can tell whether it's
+    /// safe to unlink the backing file: for a CAS-backed image
+    /// (`content_hash` is `Some`), `trg_image_blobs_ref_count_delete` has
+    /// already decremented `image_blobs.ref_count` by the time this returns,
+    /// so `remaining_ref_count` reflects the post-delete count and the file
+    /// should only be removed once it reaches 0. A `None` `content_hash`
+    /// means the image predates content-addressed storage and the file is
+    /// unshared, so it's always safe to remove.
+    pub fn delete_problem_image(&mut self, image_id: &str) -> anyhow::Result<(String, Option<String>, Option<String>, Option<i64>)> {
+        // First get the paths so we can delete the files
+        let (image_path, thumbnail_path, content_hash): (String, Option<String>, Option<String>) = self.connection.query_row(
+            "SELECT image_path, thumbnail_path, content_hash FROM problem_images WHERE id = ?1",
+            [image_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let rows_affected = self.connection.execute(
+            "DELETE FROM problem_images WHERE id = ?1",
+            [image_id]
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Image not found"));
+        }
+
+        let remaining_ref_count = match &content_hash {
+            Some(hash) => self.image_blob_ref_count(hash)?,
+            None => None,
+        };
+
+        Ok((image_path, thumbnail_path, content_hash, remaining_ref_count))
+    }
+    
+    pub fn update_image_positions(&mut self, updates: Vec<(String, i32)>) -> anyhow::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        
+        for (image_id, position) in updates {
+            tx.execute(
+                "UPDATE problem_images SET position = ?1 WHERE id = ?2",
+                params![position, &image_id]
+            )?;
+        }
         
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts a `card_images` row. `content_hash` is `Some` for every image
+    /// saved through the content-addressed path (migration 24); a `None`
+    /// means the caller never computed one and the backing file is unshared.
+    /// `thumbnail_path` is `None` until the downscaled variant has been
+    /// generated (migration 25) - `save_card_image` generates it eagerly for
+    /// raster formats, `get_card_image_thumbnail` lazily otherwise.
+    pub fn save_card_image(
+        &mut self,
+        card_id: &str,
+        image_path: &str,
+        content_hash: Option<&str>,
+        thumbnail_path: Option<&str>,
+        caption: Option<String>,
+        position: Option<i32>,
+    ) -> anyhow::Result<CardImage> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let position = match position {
+            Some(pos) => pos,
+            None => {
+                let max_position: Option<i32> = self.connection.query_row(
+                    "SELECT MAX(position) FROM card_images WHERE card_id = ?1",
+                    [card_id],
+                    |row| row.get(0),
+                ).optional()?.flatten();
+                max_position.unwrap_or(-1) + 1
+            }
+        };
+
+        self.connection.execute(
+            "INSERT INTO card_images (id, card_id, image_path, caption, position, created_at, content_hash, thumbnail_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![&id, card_id, image_path, &caption, position, &now.to_rfc3339(), content_hash, thumbnail_path],
+        )?;
+
+        Ok(CardImage {
+            id,
+            card_id: card_id.to_string(),
+            image_path: image_path.to_string(),
+            caption,
+            position,
+            created_at: now,
+            content_hash: content_hash.map(|s| s.to_string()),
+            thumbnail_path: thumbnail_path.map(|s| s.to_string()),
+        })
+    }
+
+    pub fn get_card_images(&self, card_id: &str) -> anyhow::Result<Vec<CardImage>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, card_id, image_path, caption, position, created_at, content_hash, thumbnail_path
+             FROM card_images WHERE card_id = ?1 ORDER BY position"
+        )?;
+
+        let image_iter = stmt.query_map([card_id], |row| {
+            let created_at_str: String = row.get(5)?;
+
+            Ok(CardImage {
+                id: row.get(0)?,
+                card_id: row.get(1)?,
+                image_path: row.get(2)?,
+                caption: row.get(3)?,
+                position: row.get(4)?,
+                created_at: created_at_str.parse().unwrap_or_else(|_| Utc::now()),
+                content_hash: row.get(6)?,
+                thumbnail_path: row.get(7)?,
+            })
+        })?;
+
+        let mut images = Vec::new();
+        for image in image_iter {
+            images.push(image?);
+        }
+
+        Ok(images)
+    }
+
+    /// A single `card_images` row by id, for `run_ocr_card_image` to read the
+    /// image it was enqueued for.
+    pub fn get_card_image_by_id(&self, image_id: &str) -> anyhow::Result<Option<CardImage>> {
+        self.connection
+            .query_row(
+                "SELECT id, card_id, image_path, caption, position, created_at, content_hash, thumbnail_path
+                 FROM card_images WHERE id = ?1",
+                [image_id],
+                |row| {
+                    let created_at_str: String = row.get(5)?;
+                    Ok(CardImage {
+                        id: row.get(0)?,
+                        card_id: row.get(1)?,
+                        image_path: row.get(2)?,
+                        caption: row.get(3)?,
+                        position: row.get(4)?,
+                        created_at: created_at_str.parse().unwrap_or_else(|_| Utc::now()),
+                        content_hash: row.get(6)?,
+                        thumbnail_path: row.get(7)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Deletes a `card_images` row and returns `(image_path, thumbnail_path,
+    /// content_hash, remaining_ref_count)`, mirroring `delete_problem_image`:
+    /// for a CAS-backed image (`content_hash` is `Some`),
+    /// `trg_card_image_blobs_ref_count_delete` has already decremented
+    /// `image_blobs.ref_count` by the time this returns, so the file should
+    /// only be unlinked once `remaining_ref_count` reaches 0 - another card
+    /// (or a problem gallery) may still reference it.
+    pub fn delete_card_image(&mut self, image_id: &str) -> anyhow::Result<(String, Option<String>, Option<String>, Option<i64>)> {
+        let (image_path, thumbnail_path, content_hash): (String, Option<String>, Option<String>) = self.connection.query_row(
+            "SELECT image_path, thumbnail_path, content_hash FROM card_images WHERE id = ?1",
+            [image_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let rows_affected = self.connection.execute(
+            "DELETE FROM card_images WHERE id = ?1",
+            [image_id]
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Image not found"));
+        }
+
+        let remaining_ref_count = match &content_hash {
+            Some(hash) => self.image_blob_ref_count(hash)?,
+            None => None,
+        };
+
+        Ok((image_path, thumbnail_path, content_hash, remaining_ref_count))
+    }
+
+    pub fn update_card_image_positions(&mut self, updates: Vec<(String, i32)>) -> anyhow::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+
+        for (image_id, position) in updates {
+            tx.execute(
+                "UPDATE card_images SET position = ?1 WHERE id = ?2",
+                params![position, &image_id]
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Deletes every `card_images` row named in `image_ids` in one
+    /// transaction, so a multi-select "delete" doesn't leave the gallery
+    /// half-pruned if one id turns out stale. Returns each row's
+    /// `(image_id, image_path, thumbnail_path, content_hash, remaining_ref_count)`
+    /// - the same shape `delete_card_image` returns for a single id - so the
+    /// caller can still unlink each file (once its ref count reaches zero)
+    /// after the rows are committed.
+    pub fn delete_card_images(&mut self, image_ids: &[String]) -> anyhow::Result<Vec<(String, String, Option<String>, Option<String>, Option<i64>)>> {
+        let tx = self.connection.unchecked_transaction()?;
+        let mut results = Vec::with_capacity(image_ids.len());
+
+        for image_id in image_ids {
+            let (image_path, thumbnail_path, content_hash): (String, Option<String>, Option<String>) = tx.query_row(
+                "SELECT image_path, thumbnail_path, content_hash FROM card_images WHERE id = ?1",
+                [image_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+
+            let rows_affected = tx.execute("DELETE FROM card_images WHERE id = ?1", [image_id])?;
+            if rows_affected == 0 {
+                return Err(anyhow::anyhow!("Image not found"));
+            }
+
+            let remaining_ref_count: Option<i64> = match &content_hash {
+                Some(hash) => tx.query_row(
+                    "SELECT ref_count FROM image_blobs WHERE hash = ?1",
+                    [hash],
+                    |row| row.get(0),
+                ).optional()?,
+                None => None,
+            };
+
+            results.push((image_id.clone(), image_path, thumbnail_path, content_hash, remaining_ref_count));
+        }
+
+        tx.commit()?;
+        Ok(results)
+    }
+
+    /// Reassigns `image_ids` to `target_card_id` in one transaction, for a
+    /// context-menu "move to card" action over a multi-select rather than N
+    /// separate round trips. `image_path` is a shared, content-addressed
+    /// location (see migration 24), not a per-card directory, so moving an
+    /// image between cards is just a `card_id`/`position` update - no file
+    /// relocation or path rewrite is needed, and the file stays exactly
+    /// where it was even if another card's row still references it.
+    pub fn move_card_images(&mut self, image_ids: &[String], target_card_id: &str) -> anyhow::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+
+        let mut next_position: i32 = tx.query_row(
+            "SELECT MAX(position) FROM card_images WHERE card_id = ?1",
+            [target_card_id],
+            |row| row.get(0),
+        ).optional()?.flatten().unwrap_or(-1) + 1;
+
+        for image_id in image_ids {
+            let rows_affected = tx.execute(
+                "UPDATE card_images SET card_id = ?1, position = ?2 WHERE id = ?3",
+                params![target_card_id, next_position, image_id],
+            )?;
+            if rows_affected == 0 {
+                return Err(anyhow::anyhow!("Image not found"));
+            }
+            next_position += 1;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replaces `image_id`'s `image_labels` rows with `labels` in one
+    /// transaction - used both for a fresh OCR pass and for
+    /// `regenerate_image_labels` re-running after a model upgrade, where the
+    /// stale labels need to go first rather than being appended to.
+    pub fn insert_image_labels(&mut self, image_id: &str, labels: &[ocr::ImageLabel]) -> anyhow::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute("DELETE FROM image_labels WHERE image_id = ?1", [image_id])?;
+
+        for label in labels {
+            tx.execute(
+                "INSERT INTO image_labels (id, image_id, label, confidence, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![Uuid::new_v4().to_string(), image_id, &label.label, label.confidence, &Utc::now().to_rfc3339()],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Every label `run_ocr` found for `image_id`, highest confidence first,
+    /// for `get_image_labels`.
+    pub fn get_image_labels(&self, image_id: &str) -> anyhow::Result<Vec<ImageLabelRow>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, image_id, label, confidence, created_at FROM image_labels WHERE image_id = ?1 ORDER BY confidence DESC",
+        )?;
+        let rows = stmt
+            .query_map([image_id], |row| {
+                Ok(ImageLabelRow {
+                    id: row.get(0)?,
+                    image_id: row.get(1)?,
+                    label: row.get(2)?,
+                    confidence: row.get(3)?,
+                    created_at: row.get::<_, String>(4)?.parse().unwrap_or_else(|_| Utc::now()),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    // Test case operations
+    pub fn add_test_case(&mut self, req: AddTestCaseRequest) -> anyhow::Result<TestCase> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
         self.connection.execute(
-            "INSERT INTO problem_images (id, problem_id, image_path, caption, position, created_at)
+            "INSERT INTO test_cases (id, problem_id, input, expected_output, is_hidden, created_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
                 &id,
-                problem_id,
-                image_path,
-                &caption,
-                position,
+                &req.problem_id,
+                &req.input,
+                &req.expected_output,
+                req.is_hidden,
                 &now.to_rfc3339(),
             ],
         )?;
-        
-        Ok(ProblemImage {
+
+        Ok(TestCase {
             id,
-            problem_id: problem_id.to_string(),
-            image_path: image_path.to_string(),
-            caption,
-            position,
+            problem_id: req.problem_id,
+            input: req.input,
+            expected_output: req.expected_output,
+            is_hidden: req.is_hidden,
             created_at: now,
         })
     }
-    
-    pub fn get_problem_images(&self, problem_id: &str) -> anyhow::Result<Vec<ProblemImage>> {
+
+    pub fn get_test_cases_for_problem(&self, problem_id: &str) -> anyhow::Result<Vec<TestCase>> {
         let mut stmt = self.connection.prepare(
-            "SELECT id, problem_id, image_path, caption, position, created_at 
-             FROM problem_images WHERE problem_id = ?1 ORDER BY position"
+            "SELECT id, problem_id, input, expected_output, is_hidden, created_at
+             FROM test_cases WHERE problem_id = ?1 ORDER BY created_at"
         )?;
-        
-        let image_iter = stmt.query_map([problem_id], |row| {
+
+        let test_case_iter = stmt.query_map([problem_id], |row| {
             let created_at_str: String = row.get(5)?;
-            
-            Ok(ProblemImage {
+            Ok(TestCase {
                 id: row.get(0)?,
                 problem_id: row.get(1)?,
-                image_path: row.get(2)?,
-                caption: row.get(3)?,
-                position: row.get(4)?,
+                input: row.get(2)?,
+                expected_output: row.get(3)?,
+                is_hidden: row.get(4)?,
                 created_at: created_at_str.parse().unwrap_or_else(|_| Utc::now()),
             })
         })?;
-        
-        let mut images = Vec::new();
-        for image in image_iter {
-            images.push(image?);
+
+        let mut test_cases = Vec::new();
+        for test_case in test_case_iter {
+            test_cases.push(test_case?);
         }
-        
-        Ok(images)
+
+        Ok(test_cases)
     }
-    
-    pub fn delete_problem_image(&mut self, image_id: &str) -> anyhow::Result<String> {
-        println!("🗃️ Database: Attempting to delete image with id: {}", image_id);
-        
-        // First get the image path so we can delete the file
-        let image_path: String = self.connection.query_row(
-            "SELECT image_path FROM problem_images WHERE id = ?1",
-            [image_id],
-            |row| row.get(0),
-        ).map_err(|e| {
-            println!("❌ Database: Failed to find image with id '{}': {}", image_id, e);
-            e
-        })?;
-        
-        println!("✅ Database: Found image path: {}", image_path);
-        
-        // Delete from database
+
+    pub fn delete_test_case(&mut self, test_case_id: &str) -> anyhow::Result<()> {
         let rows_affected = self.connection.execute(
-            "DELETE FROM problem_images WHERE id = ?1",
-            [image_id]
-        ).map_err(|e| {
-            println!("❌ Database: Failed to execute delete query: {}", e);
-            e
-        })?;
-        
-        println!("🔄 Database: Delete query executed, rows_affected: {}", rows_affected);
-        
+            "DELETE FROM test_cases WHERE id = ?1",
+            [test_case_id],
+        )?;
+
         if rows_affected == 0 {
-            println!("❌ Database: No rows were affected - image not found");
-            return Err(anyhow::anyhow!("Image not found"));
-        }
-        
-        println!("✅ Database: Image deleted successfully from database");
-        Ok(image_path)
-    }
-    
-    pub fn update_image_positions(&mut self, updates: Vec<(String, i32)>) -> anyhow::Result<()> {
-        let tx = self.connection.unchecked_transaction()?;
-        
-        for (image_id, position) in updates {
-            tx.execute(
-                "UPDATE problem_images SET position = ?1 WHERE id = ?2",
-                params![position, &image_id]
-            )?;
+            return Err(anyhow::anyhow!("Test case not found"));
         }
-        
-        tx.commit()?;
+
         Ok(())
     }
 
@@ -1748,38 +4214,25 @@ impl DatabaseManager {
     }
     
     pub fn get_tag_suggestions(&self, query: &str, limit: i32) -> anyhow::Result<Vec<String>> {
-        let search_pattern = format!("%{}%", query);
-        
-        let mut stmt = self.connection.prepare(
-            "SELECT DISTINCT name FROM tags 
-             WHERE name LIKE ?1 
-             ORDER BY name 
-             LIMIT ?2"
-        )?;
-        
-        let suggestions = stmt.query_map(params![search_pattern, limit], |row| {
-            Ok(row.get::<_, String>(0)?)
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-        
-        Ok(suggestions)
+        let mut stmt = self.connection.prepare("SELECT DISTINCT name, usage_count FROM tags")?;
+        let names = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(search::rank_tag_suggestions(names, query, limit))
     }
     
     // Problem connection functions
     pub fn search_problems_by_title(&self, query: &str, limit: i32, exclude_id: Option<&str>) -> anyhow::Result<Vec<FrontendProblem>> {
         let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        // Check if related_problem_ids column exists for backward compatibility
-        let has_related_column = self.has_related_problem_ids_column();
-        let related_column_sql = if has_related_column { "related_problem_ids" } else { "NULL as related_problem_ids" };
-        
+
         let (sql, params): (String, Vec<Box<dyn rusqlite::ToSql>>) = if let Some(exclude_id) = exclude_id {
             (
-                format!("SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, {}, created_at 
-                         FROM problems 
-                         WHERE LOWER(title) LIKE ?1 AND id != ?2 
-                         ORDER BY title 
-                         LIMIT ?3", related_column_sql),
+                "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at
+                 FROM problems
+                 WHERE LOWER(title) LIKE ?1 AND id != ?2 AND deleted_at IS NULL
+                 ORDER BY title
+                 LIMIT ?3".to_string(),
                 vec![
                     Box::new(search_pattern),
                     Box::new(exclude_id.to_string()),
@@ -1788,20 +4241,20 @@ impl DatabaseManager {
             )
         } else {
             (
-                format!("SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, {}, created_at 
-                         FROM problems 
-                         WHERE LOWER(title) LIKE ?1 
-                         ORDER BY title 
-                         LIMIT ?2", related_column_sql),
+                "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at
+                 FROM problems
+                 WHERE LOWER(title) LIKE ?1 AND deleted_at IS NULL
+                 ORDER BY title
+                 LIMIT ?2".to_string(),
                 vec![
                     Box::new(search_pattern),
                     Box::new(limit),
                 ],
             )
         };
-        
+
         let mut stmt = self.connection.prepare(&sql)?;
-        
+
         let problem_iter = stmt.query_map(
             rusqlite::params_from_iter(params.iter().map(|v| v.as_ref())),
             |row| {
@@ -1814,161 +4267,211 @@ impl DatabaseManager {
                     leetcode_url: row.get(5)?,
                     constraints: row.get(6)?,
                     hints: row.get(7)?,
-                    related_problem_ids: row.get(8).ok(), // Use .ok() to handle NULL gracefully
-                    created_at: row.get::<_, String>(9)?.parse().unwrap_or_else(|_| Utc::now()),
-                    updated_at: row.get::<_, String>(10).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
+                    related_problem_ids: None,
+                    created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+                    updated_at: row.get::<_, String>(9).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
                 };
-                Ok(convert_problem_to_frontend(db_problem))
+                let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+                Ok(convert_problem_to_frontend(db_problem, related_ids))
             },
         )?;
-        
+
         let mut problems = Vec::new();
         for problem in problem_iter {
             problems.push(problem?);
         }
-        
+
         Ok(problems)
     }
     
     pub fn add_problem_relation(&mut self, problem_id: &str, related_problem_id: &str) -> anyhow::Result<()> {
-        // Add relation to the first problem
-        self.add_relation_to_problem(problem_id, related_problem_id)?;
-        
-        // Add bidirectional relation to the second problem
-        self.add_relation_to_problem(related_problem_id, problem_id)?;
-        
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO problem_relations (problem_id, related_problem_id) VALUES (?1, ?2)",
+            params![problem_id, related_problem_id],
+        )?;
+        // Bidirectional: the relation also holds from the other side.
+        tx.execute(
+            "INSERT OR IGNORE INTO problem_relations (problem_id, related_problem_id) VALUES (?1, ?2)",
+            params![related_problem_id, problem_id],
+        )?;
+        tx.commit()?;
         Ok(())
     }
-    
+
     pub fn remove_problem_relation(&mut self, problem_id: &str, related_problem_id: &str) -> anyhow::Result<()> {
-        // Remove relation from the first problem
-        self.remove_relation_from_problem(problem_id, related_problem_id)?;
-        
-        // Remove bidirectional relation from the second problem
-        self.remove_relation_from_problem(related_problem_id, problem_id)?;
-        
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM problem_relations WHERE problem_id = ?1 AND related_problem_id = ?2",
+            params![problem_id, related_problem_id],
+        )?;
+        tx.execute(
+            "DELETE FROM problem_relations WHERE problem_id = ?1 AND related_problem_id = ?2",
+            params![related_problem_id, problem_id],
+        )?;
+        tx.commit()?;
         Ok(())
     }
-    
+
     pub fn get_related_problems(&self, problem_id: &str) -> anyhow::Result<Vec<FrontendProblem>> {
-        // Get the problem to access its related_problem_ids
-        if let Some(problem) = self.get_problem_by_id(problem_id)? {
-            if problem.related_problem_ids.is_empty() {
-                return Ok(Vec::new());
-            }
-            
-            // Build query to get all related problems
-            let placeholders = problem.related_problem_ids.iter()
-                .map(|_| "?")
-                .collect::<Vec<_>>()
-                .join(", ");
-            
-            let sql = format!(
-                "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, related_problem_ids, created_at 
-                 FROM problems 
-                 WHERE id IN ({}) 
-                 ORDER BY title",
-                placeholders
-            );
-            
-            let mut stmt = self.connection.prepare(&sql)?;
-            
-            let params: Vec<&dyn rusqlite::ToSql> = problem.related_problem_ids.iter()
-                .map(|id| id as &dyn rusqlite::ToSql)
-                .collect();
-                
-            let problem_iter = stmt.query_map(params.as_slice(), |row| {
-                let db_problem = Problem {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    difficulty: row.get(3)?,
-                    topic: row.get(4)?,
-                    leetcode_url: row.get(5)?,
-                    constraints: row.get(6)?,
-                    hints: row.get(7)?,
-                    related_problem_ids: row.get(8)?,
-                    created_at: row.get::<_, String>(9)?.parse().unwrap_or_else(|_| Utc::now()),
-                    updated_at: row.get::<_, String>(10).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
-                };
-                Ok(convert_problem_to_frontend(db_problem))
-            })?;
-            
-            let mut related_problems = Vec::new();
-            for problem in problem_iter {
-                related_problems.push(problem?);
+        let sql = "SELECT p.id, p.title, p.description, p.difficulty, p.topic, p.leetcode_url, p.constraints, p.hints, p.created_at
+                   FROM problems p
+                   INNER JOIN problem_relations pr ON pr.related_problem_id = p.id
+                   WHERE pr.problem_id = ?1
+                   ORDER BY p.title";
+
+        let mut stmt = self.connection.prepare(sql)?;
+
+        let problem_iter = stmt.query_map(params![problem_id], |row| {
+            let db_problem = Problem {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                difficulty: row.get(3)?,
+                topic: row.get(4)?,
+                leetcode_url: row.get(5)?,
+                constraints: row.get(6)?,
+                hints: row.get(7)?,
+                related_problem_ids: None,
+                created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+                updated_at: Utc::now(),
+            };
+            Ok(db_problem)
+        })?;
+
+        let mut related_problems = Vec::new();
+        for problem in problem_iter {
+            let db_problem = problem?;
+            let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+            related_problems.push(convert_problem_to_frontend(db_problem, related_ids));
+        }
+
+        Ok(related_problems)
+    }
+
+    /// Reverse lookup of [`get_related_problems`]: every problem that lists
+    /// `problem_id` as one of *its* related problems.
+    pub fn get_problems_referencing(&self, problem_id: &str) -> anyhow::Result<Vec<FrontendProblem>> {
+        let sql = "SELECT p.id, p.title, p.description, p.difficulty, p.topic, p.leetcode_url, p.constraints, p.hints, p.created_at
+                   FROM problems p
+                   INNER JOIN problem_relations pr ON pr.problem_id = p.id
+                   WHERE pr.related_problem_id = ?1
+                   ORDER BY p.title";
+
+        let mut stmt = self.connection.prepare(sql)?;
+
+        let problem_iter = stmt.query_map(params![problem_id], |row| {
+            let db_problem = Problem {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                difficulty: row.get(3)?,
+                topic: row.get(4)?,
+                leetcode_url: row.get(5)?,
+                constraints: row.get(6)?,
+                hints: row.get(7)?,
+                related_problem_ids: None,
+                created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+                updated_at: Utc::now(),
+            };
+            Ok(db_problem)
+        })?;
+
+        let mut problems = Vec::new();
+        for problem in problem_iter {
+            let db_problem = problem?;
+            let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+            problems.push(convert_problem_to_frontend(db_problem, related_ids));
+        }
+
+        Ok(problems)
+    }
+
+    /// Breadth-first expansion of the relation graph rooted at `problem_id`,
+    /// returning every problem reachable within `depth` hops annotated with
+    /// its hop distance (the starting problem is not included). `max_nodes`
+    /// caps how many problems the expansion visits - pass `None` to fall
+    /// back to `graph::MAX_TRAVERSAL_NODES`.
+    pub fn get_related_problems_within(
+        &self,
+        problem_id: &str,
+        depth: i32,
+        max_nodes: Option<usize>,
+    ) -> anyhow::Result<Vec<RelatedProblemHop>> {
+        let hops = graph::related_within(
+            &self.connection,
+            problem_id,
+            depth,
+            max_nodes.unwrap_or(graph::MAX_TRAVERSAL_NODES),
+        )?;
+
+        let mut result = Vec::with_capacity(hops.len());
+        for (id, hop_distance) in hops {
+            if let Some(problem) = self.get_problem_by_id(&id)? {
+                result.push(RelatedProblemHop { problem, hop_distance });
             }
-            
-            Ok(related_problems)
-        } else {
-            Err(anyhow::anyhow!("Problem with id '{}' not found", problem_id))
         }
+        Ok(result)
     }
-    
-    // Helper functions for managing relations
-    fn add_relation_to_problem(&mut self, problem_id: &str, new_related_id: &str) -> anyhow::Result<()> {
-        if let Some(mut problem) = self.get_problem_by_id(problem_id)? {
-            // Check if relation already exists
-            if !problem.related_problem_ids.contains(&new_related_id.to_string()) {
-                problem.related_problem_ids.push(new_related_id.to_string());
-                
-                // Update the problem in database
-                let update_req = UpdateProblemRequest {
-                    id: problem_id.to_string(),
-                    title: None,
-                    description: None,
-                    difficulty: None,
-                    topic: None,
-                    leetcode_url: None,
-                    constraints: None,
-                    hints: None,
-                    related_problem_ids: Some(problem.related_problem_ids),
-                };
-                
-                self.update_problem(update_req)?;
+
+    /// Shortest chain of relations from `from_id` to `to_id`, as an ordered
+    /// list of problems including both endpoints. Empty if the two problems
+    /// aren't connected by any chain of relations.
+    pub fn shortest_relation_path(&self, from_id: &str, to_id: &str) -> anyhow::Result<Vec<FrontendProblem>> {
+        let path_ids = graph::shortest_path(&self.connection, from_id, to_id)?;
+
+        let mut result = Vec::with_capacity(path_ids.len());
+        for id in path_ids {
+            if let Some(problem) = self.get_problem_by_id(&id)? {
+                result.push(problem);
             }
         }
-        Ok(())
+        Ok(result)
     }
-    
-    fn remove_relation_from_problem(&mut self, problem_id: &str, related_id_to_remove: &str) -> anyhow::Result<()> {
-        if let Some(mut problem) = self.get_problem_by_id(problem_id)? {
-            // Remove the relation if it exists
-            problem.related_problem_ids.retain(|id| id != related_id_to_remove);
-            
-            // Update the problem in database
-            let update_req = UpdateProblemRequest {
-                id: problem_id.to_string(),
-                title: None,
-                description: None,
-                difficulty: None,
-                topic: None,
-                leetcode_url: None,
-                constraints: None,
-                hints: None,
-                related_problem_ids: Some(problem.related_problem_ids),
-            };
-            
-            self.update_problem(update_req)?;
+
+    /// Recommends problems within `depth` hops of `problem_id`, ranked by
+    /// how many distinct shortest paths reach them so strongly-connected
+    /// topics surface first - see `graph::recommend_related`.
+    pub fn recommend_related_problems(
+        &self,
+        problem_id: &str,
+        depth: i32,
+        limit: i32,
+    ) -> anyhow::Result<Vec<RecommendedProblem>> {
+        let ranked = graph::recommend_related(&self.connection, problem_id, depth, graph::MAX_TRAVERSAL_NODES)?;
+
+        let mut result = Vec::new();
+        for (id, hop_distance, path_count) in ranked.into_iter().take(limit.max(0) as usize) {
+            if let Some(problem) = self.get_problem_by_id(&id)? {
+                result.push(RecommendedProblem { problem, hop_distance, path_count });
+            }
         }
-        Ok(())
+        Ok(result)
+    }
+
+    /// Unified full-text search over problems (title/description/topic/tags),
+    /// ranked by BM25 with a trigram+Levenshtein typo-tolerant fallback. See
+    /// `database::search` for the FTS5 table/trigger setup.
+    pub fn search_problems(
+        &self,
+        query: &str,
+        fields: &[String],
+        limit: i32,
+    ) -> anyhow::Result<Vec<search::ScoredProblem>> {
+        search::search_problems(&self.connection, query, fields, limit)
     }
 
     // Search operations for Name/Topic/Tags system
     pub fn search_problems_by_topic(&self, query: &str) -> anyhow::Result<Vec<FrontendProblem>> {
         let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        // Check if related_problem_ids column exists for backward compatibility
-        let has_related_column = self.has_related_problem_ids_column();
-        let related_column_sql = if has_related_column { "related_problem_ids" } else { "NULL as related_problem_ids" };
-        
-        let sql = format!("SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, {}, created_at 
-                          FROM problems 
-                          WHERE LOWER(topic) LIKE ?1 
-                          ORDER BY title 
-                          LIMIT 50", related_column_sql);
-        
-        let mut stmt = self.connection.prepare(&sql)?;
+
+        let sql = "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at
+                   FROM problems
+                   WHERE LOWER(topic) LIKE ?1 AND deleted_at IS NULL
+                   ORDER BY title
+                   LIMIT 50";
+
+        let mut stmt = self.connection.prepare(sql)?;
         let problem_iter = stmt.query_map([search_pattern], |row| {
             let problem = Problem {
                 id: row.get(0)?,
@@ -1979,16 +4482,18 @@ impl DatabaseManager {
                 leetcode_url: row.get(5)?,
                 constraints: row.get(6)?,
                 hints: row.get(7)?,
-                related_problem_ids: row.get(8)?,
-                created_at: row.get::<_, String>(9)?.parse().unwrap_or_else(|_| Utc::now()),
-                updated_at: row.get::<_, String>(10).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
+                related_problem_ids: None,
+                created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+                updated_at: row.get::<_, String>(9).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
             };
-            Ok(convert_problem_to_frontend(problem))
+            Ok(problem)
         })?;
 
         let mut problems = Vec::new();
         for problem in problem_iter {
-            problems.push(problem?);
+            let db_problem = problem?;
+            let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+            problems.push(convert_problem_to_frontend(db_problem, related_ids));
         }
 
         Ok(problems)
@@ -1997,20 +4502,16 @@ impl DatabaseManager {
     pub fn search_problems_by_tags(&self, query: &str) -> anyhow::Result<Vec<FrontendProblem>> {
         let search_pattern = format!("%{}%", query.to_lowercase());
         println!("DEBUG: Tag search query: '{}', pattern: '{}'", query, search_pattern);
-        
-        // Check if related_problem_ids column exists for backward compatibility
-        let has_related_column = self.has_related_problem_ids_column();
-        let related_column_sql = if has_related_column { "related_problem_ids" } else { "NULL as related_problem_ids" };
-        
+
         // Search in problem_tags table (normalized tags)
-        let sql = format!("SELECT DISTINCT p.id, p.title, p.description, p.difficulty, p.topic, p.leetcode_url, p.constraints, p.hints, {}, p.created_at 
-                          FROM problems p
-                          INNER JOIN problem_tags pt ON p.id = pt.problem_id
-                          INNER JOIN tags t ON pt.tag_id = t.id
-                          WHERE LOWER(t.name) LIKE ?1
-                          ORDER BY p.title 
-                          LIMIT 50", related_column_sql);
-        
+        let sql = "SELECT DISTINCT p.id, p.title, p.description, p.difficulty, p.topic, p.leetcode_url, p.constraints, p.hints, p.created_at
+                   FROM problems p
+                   INNER JOIN problem_tags pt ON p.id = pt.problem_id
+                   INNER JOIN tags t ON pt.tag_id = t.id
+                   WHERE LOWER(t.name) LIKE ?1 AND p.deleted_at IS NULL
+                   ORDER BY p.title
+                   LIMIT 50";
+
         println!("DEBUG: Executing SQL: {}", sql);
         
         // Debug: Check if the tag exists at all
@@ -2040,16 +4541,18 @@ impl DatabaseManager {
                 leetcode_url: row.get(5)?,
                 constraints: row.get(6)?,
                 hints: row.get(7)?,
-                related_problem_ids: row.get(8)?,
-                created_at: row.get::<_, String>(9)?.parse().unwrap_or_else(|_| Utc::now()),
-                updated_at: row.get::<_, String>(10).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
+                related_problem_ids: None,
+                created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+                updated_at: row.get::<_, String>(9).ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| Utc::now()),
             };
-            Ok(convert_problem_to_frontend(problem))
+            Ok(problem)
         })?;
 
         let mut problems = Vec::new();
         for problem in problem_iter {
-            problems.push(problem?);
+            let db_problem = problem?;
+            let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+            problems.push(convert_problem_to_frontend(db_problem, related_ids));
         }
 
         println!("DEBUG: Tag search found {} problems", problems.len());
@@ -2061,62 +4564,32 @@ impl DatabaseManager {
     }
 
     pub fn get_title_suggestions(&self, query: &str) -> anyhow::Result<Vec<String>> {
-        let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        let sql = "SELECT DISTINCT title 
-                   FROM problems 
-                   WHERE LOWER(title) LIKE ?1 
-                   ORDER BY title 
-                   LIMIT 10";
-        
-        let mut stmt = self.connection.prepare(sql)?;
-        let suggestion_iter = stmt.query_map([search_pattern], |row| {
-            Ok(row.get::<_, String>(0)?)
-        })?;
-
-        let mut suggestions = Vec::new();
-        for suggestion in suggestion_iter {
-            suggestions.push(suggestion?);
-        }
+        let mut stmt = self.connection.prepare("SELECT DISTINCT title FROM problems")?;
+        let titles = stmt
+            .query_map([], |row| Ok(row.get::<_, String>(0)?))?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(suggestions)
+        Ok(search::rank_suggestions(titles, query, 10))
     }
 
     pub fn get_topic_suggestions(&self, query: &str) -> anyhow::Result<Vec<String>> {
-        let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        // Since topic is stored as JSON array, we need to search within the JSON content
-        let sql = "SELECT DISTINCT topic 
-                   FROM problems 
-                   WHERE LOWER(topic) LIKE ?1 AND topic IS NOT NULL AND topic != '[]'
-                   ORDER BY topic 
-                   LIMIT 10";
-        
-        let mut stmt = self.connection.prepare(sql)?;
-        let topic_iter = stmt.query_map([search_pattern], |row| {
-            let topic_json: String = row.get(0)?;
-            Ok(topic_json)
-        })?;
+        // Topic is stored as a JSON array per problem, so flatten every
+        // problem's topics into a deduped candidate pool before ranking.
+        let mut stmt = self
+            .connection
+            .prepare("SELECT topic FROM problems WHERE topic IS NOT NULL AND topic != '[]'")?;
+        let topic_iter = stmt.query_map([], |row| Ok(row.get::<_, String>(0)?))?;
 
-        let mut suggestions = Vec::new();
-        for topic_result in topic_iter {
-            let topic_json = topic_result?;
-            // Parse JSON array and extract individual topics
-            let topics: Vec<String> = parse_json_array(&topic_json);
-            for topic in topics {
-                if topic.to_lowercase().contains(&query.to_lowercase()) && !suggestions.contains(&topic) {
-                    suggestions.push(topic);
-                    if suggestions.len() >= 10 {
-                        break;
-                    }
+        let mut all_topics = Vec::new();
+        for topic_json in topic_iter {
+            for topic in parse_json_array(&topic_json?) {
+                if !all_topics.contains(&topic) {
+                    all_topics.push(topic);
                 }
             }
-            if suggestions.len() >= 10 {
-                break;
-            }
         }
 
-        Ok(suggestions)
+        Ok(search::rank_suggestions(all_topics, query, 10))
     }
 
     // Solution Card Methods
@@ -2141,8 +4614,8 @@ impl DatabaseManager {
                 notes: row.get("notes")?,
                 status: row.get("status")?,
                 total_duration: row.get("total_duration")?,
-                created_at: row.get("created_at")?,
-                last_modified: row.get("last_modified")?,
+                created_at: parse_datetime_flexible(&row.get::<_, String>("created_at")?),
+                last_modified: parse_datetime_flexible(&row.get::<_, String>("last_modified")?),
                 is_solution: row.get::<_, i32>("is_solution")? == 1,
             })
         })?;
@@ -2157,7 +4630,8 @@ impl DatabaseManager {
     /// Create a new solution card for a problem
     pub fn create_solution_card(&self, problem_id: &str) -> anyhow::Result<SolutionCard> {
         let card_id = Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let now = Utc::now();
+        let now_text = now.to_rfc3339();
 
         // Insert the solution card
         self.connection.execute(
@@ -2165,7 +4639,7 @@ impl DatabaseManager {
                 id, problem_id, card_number, code, language, notes, status,
                 total_duration, created_at, last_modified, is_solution
              ) VALUES (?, ?, 0, '', 'javascript', '', 'In Progress', 0, ?, ?, 1)",
-            params![card_id, problem_id, now, now]
+            params![card_id, problem_id, now_text, now_text]
         )?;
 
         // Return the created card
@@ -2178,7 +4652,7 @@ impl DatabaseManager {
             notes: String::new(),
             status: "In Progress".to_string(),
             total_duration: 0,
-            created_at: now.clone(),
+            created_at: now,
             last_modified: now,
             is_solution: true,
         })
@@ -2186,8 +4660,8 @@ impl DatabaseManager {
 
     /// Update solution card code
     pub fn update_solution_card_code(&self, card_id: &str, code: &str, language: &str) -> anyhow::Result<()> {
-        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        
+        let now = Utc::now().to_rfc3339();
+
         self.connection.execute(
             "UPDATE cards 
              SET code = ?, language = ?, last_modified = ?
@@ -2200,8 +4674,8 @@ impl DatabaseManager {
 
     /// Update solution card notes
     pub fn update_solution_card_notes(&self, card_id: &str, notes: &str) -> anyhow::Result<()> {
-        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        
+        let now = Utc::now().to_rfc3339();
+
         self.connection.execute(
             "UPDATE cards 
              SET notes = ?, last_modified = ?
@@ -2229,10 +4703,10 @@ impl DatabaseManager {
     /// This is useful for the normal card navigation to exclude solution cards
     pub fn get_regular_cards(&self, problem_id: &str) -> anyhow::Result<Vec<SolutionCard>> {
         let mut stmt = self.connection.prepare(
-            "SELECT id, problem_id, card_number, code, language, notes, status, 
-                    total_duration, created_at, last_modified, COALESCE(is_solution, 0) as is_solution
-             FROM cards 
-             WHERE problem_id = ? AND (is_solution IS NULL OR is_solution = 0)
+            "SELECT id, problem_id, card_number, code, language, notes, status,
+                    total_duration, created_at, last_modified, is_solution
+             FROM cards
+             WHERE problem_id = ? AND is_solution = 0
              ORDER BY card_number ASC"
         )?;
 
@@ -2246,8 +4720,8 @@ impl DatabaseManager {
                 notes: row.get("notes")?,
                 status: row.get("status")?,
                 total_duration: row.get("total_duration")?,
-                created_at: row.get("created_at")?,
-                last_modified: row.get("last_modified")?,
+                created_at: parse_datetime_flexible(&row.get::<_, String>("created_at")?),
+                last_modified: parse_datetime_flexible(&row.get::<_, String>("last_modified")?),
                 is_solution: row.get::<_, i32>("is_solution")? == 1,
             })
         })?;
@@ -2262,132 +4736,49 @@ impl DatabaseManager {
 
     /// Delete a problem and all its related data
     /// Performs cascading deletion in proper order to maintain referential integrity
-    pub fn delete_problem(&mut self, problem_id: &str) -> anyhow::Result<()> {
-        println!("🗑️ [Database] Starting delete operation for problem: {}", problem_id);
-        
-        // First, verify the problem exists
-        let problem = self.get_problem_by_id(problem_id)?;
-        if problem.is_none() {
-            return Err(anyhow::anyhow!("Problem with id '{}' not found", problem_id));
-        }
-        
-        let problem = problem.unwrap();
-        println!("🗑️ [Database] Confirmed problem exists: '{}'", problem.title);
-
-        // Begin transaction for atomic deletion
-        let tx = self.connection.unchecked_transaction()?;
-        println!("🗑️ [Database] Transaction started for cascading deletion");
-
-        // Step 1: Get all cards for this problem to delete their related data
-        println!("🔍 [Database] Finding all cards for problem...");
-        let card_ids: Vec<String> = tx.prepare(
-            "SELECT id FROM cards WHERE problem_id = ?1"
-        )?
-        .query_map([problem_id], |row| Ok(row.get::<_, String>(0)?))?
-        .collect::<Result<Vec<String>, _>>()?;
-        
-        println!("🗑️ [Database] Found {} cards to delete", card_ids.len());
-
-        // Step 2: Delete time sessions for all cards
-        for card_id in &card_ids {
-            let sessions_deleted = tx.execute(
-                "DELETE FROM time_sessions WHERE card_id = ?1",
-                [card_id],
-            ).unwrap_or(0);
-            if sessions_deleted > 0 {
-                println!("🗑️ [Database] Deleted {} time sessions for card {}", sessions_deleted, card_id);
-            }
-        }
-
-        // Step 3: Delete recordings for all cards
-        for card_id in &card_ids {
-            let recordings_deleted = tx.execute(
-                "DELETE FROM recordings WHERE card_id = ?1",
-                [card_id],
-            ).unwrap_or(0);
-            if recordings_deleted > 0 {
-                println!("🗑️ [Database] Deleted {} recordings for card {}", recordings_deleted, card_id);
-            }
-        }
-
-        // Step 4: Delete connections where any of these cards are source or target
-        for card_id in &card_ids {
-            let connections_deleted = tx.execute(
-                "DELETE FROM connections WHERE source_card_id = ?1 OR target_card_id = ?1",
-                [card_id],
-            ).unwrap_or(0);
-            if connections_deleted > 0 {
-                println!("🗑️ [Database] Deleted {} connections for card {}", connections_deleted, card_id);
-            }
+    /// Cascading hard delete, returning everything it removed so the caller
+    /// can offer an "undo" or export-before-purge step - this can't actually
+    /// be undone once it returns, unlike `soft_delete_problem`.
+    pub fn delete_problem(&mut self, problem_id: &str) -> anyhow::Result<DeletedProblemPayload> {
+        println!("🗑️ [Database] Starting delete operation for problem: {}", problem_id);
+        
+        // First, verify the problem exists. Unfiltered: this is also the hard
+        // delete `purge_deleted_before` runs for rows already soft-deleted.
+        let problem = self.get_problem_by_id_filtered(problem_id, false)?;
+        if problem.is_none() {
+            return Err(anyhow::anyhow!("Problem with id '{}' not found", problem_id));
         }
+        
+        let problem = problem.unwrap();
+        println!("🗑️ [Database] Confirmed problem exists: '{}'", problem.title);
 
-        // Step 5: Delete card tags for all cards
-        for card_id in &card_ids {
-            let card_tags_deleted = tx.execute(
-                "DELETE FROM card_tags WHERE card_id = ?1",
-                [card_id],
-            ).unwrap_or(0);
-            if card_tags_deleted > 0 {
-                println!("🗑️ [Database] Deleted {} card tags for card {}", card_tags_deleted, card_id);
-            }
-        }
+        // Begin transaction for atomic deletion
+        let tx = self.connection.unchecked_transaction()?;
+        println!("🗑️ [Database] Transaction started for cascading deletion");
 
-        // Step 6: Delete all cards for this problem
-        let cards_deleted = tx.execute(
-            "DELETE FROM cards WHERE problem_id = ?1",
+        // `cards`, `time_sessions`, `recordings`, `connections`, `problem_tags`,
+        // `card_tags`, `problem_images`, and `problem_relations` all carry
+        // `ON DELETE CASCADE` foreign keys back to `problems`/`cards` (see the
+        // `add_cascade_on_delete` migration), so deleting the `problems` row is
+        // enough to take all of them with it. The counts `DeletedProblemPayload`
+        // reports are read up front since the cascade won't leave rows behind
+        // to count afterwards.
+        let cards_deleted: i32 = tx.query_row(
+            "SELECT COUNT(*) FROM cards WHERE problem_id = ?1",
             [problem_id],
+            |row| row.get(0),
         )?;
-        println!("🗑️ [Database] Deleted {} cards for problem", cards_deleted);
-
-        // Step 7: Delete problem images
-        let images_deleted = tx.execute(
-            "DELETE FROM problem_images WHERE problem_id = ?1",
+        let total_sessions_deleted: i32 = tx.query_row(
+            "SELECT COUNT(*) FROM time_sessions WHERE card_id IN (SELECT id FROM cards WHERE problem_id = ?1)",
             [problem_id],
-        ).unwrap_or(0);
-        if images_deleted > 0 {
-            println!("🗑️ [Database] Deleted {} problem images", images_deleted);
-        }
-
-        // Step 8: Delete problem tags relationships
-        let problem_tags_deleted = tx.execute(
-            "DELETE FROM problem_tags WHERE problem_id = ?1",
+            |row| row.get(0),
+        )?;
+        let total_recordings_deleted: i32 = tx.query_row(
+            "SELECT COUNT(*) FROM recordings WHERE card_id IN (SELECT id FROM cards WHERE problem_id = ?1)",
             [problem_id],
-        ).unwrap_or(0);
-        if problem_tags_deleted > 0 {
-            println!("🗑️ [Database] Deleted {} problem tag relationships", problem_tags_deleted);
-        }
-
-        // Step 9: Remove this problem from other problems' related_problem_ids
-        // This is complex due to JSON storage, so we'll update all problems that might reference this one
-        println!("🔍 [Database] Removing problem from related_problem_ids in other problems...");
-        if self.has_related_problem_ids_column() {
-            // Get all problems that might have this problem in their related_problem_ids
-            let mut stmt = tx.prepare(
-                "SELECT id, related_problem_ids FROM problems WHERE related_problem_ids IS NOT NULL AND related_problem_ids LIKE ?"
-            )?;
-            
-            let problem_pattern = format!("%{}%", problem_id);
-            let problems_to_update: Vec<(String, String)> = stmt.query_map([&problem_pattern], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?.collect::<Result<Vec<_>, _>>()?;
-
-            for (id, related_ids_json) in problems_to_update {
-                if let Ok(mut related_ids) = serde_json::from_str::<Vec<String>>(&related_ids_json) {
-                    if related_ids.contains(&problem_id.to_string()) {
-                        related_ids.retain(|id| id != problem_id);
-                        let updated_json = serde_json::to_string(&related_ids).unwrap_or_else(|_| "[]".to_string());
-                        
-                        tx.execute(
-                            "UPDATE problems SET related_problem_ids = ?1 WHERE id = ?2",
-                            params![updated_json, id]
-                        )?;
-                        println!("🗑️ [Database] Removed problem reference from problem {}", id);
-                    }
-                }
-            }
-        }
+            |row| row.get(0),
+        )?;
 
-        // Step 10: Finally, delete the problem itself
         let rows_affected = tx.execute(
             "DELETE FROM problems WHERE id = ?1",
             [problem_id],
@@ -2401,15 +4792,21 @@ impl DatabaseManager {
         // Commit the transaction
         tx.commit()?;
         println!("✅ [Database] Successfully deleted problem '{}' and all related data", problem.title);
-        
-        Ok(())
+
+        Ok(DeletedProblemPayload {
+            problem,
+            cards_deleted,
+            time_sessions_deleted: total_sessions_deleted,
+            recordings_deleted: total_recordings_deleted,
+        })
     }
 
-    pub fn delete_problem_with_files(&mut self, problem_id: &str) -> anyhow::Result<()> {
+    pub fn delete_problem_with_files(&mut self, problem_id: &str) -> anyhow::Result<DeletedProblemPayload> {
         println!("🗑️ [Database] Starting delete operation with file cleanup for problem: {}", problem_id);
         
-        // First, verify the problem exists
-        let problem = self.get_problem_by_id(problem_id)?;
+        // First, verify the problem exists. Unfiltered: this is also the hard
+        // delete `purge_deleted_before` runs for rows already soft-deleted.
+        let problem = self.get_problem_by_id_filtered(problem_id, false)?;
         if problem.is_none() {
             return Err(anyhow::anyhow!("Problem with id '{}' not found", problem_id));
         }
@@ -2439,60 +4836,504 @@ impl DatabaseManager {
         .collect::<Result<Vec<String>, _>>()?;
         
         files_to_delete.extend(image_files);
-        
+
         println!("🗑️ [Database] Found {} files to delete", files_to_delete.len());
 
-        // Step 2: Delete files from filesystem
+        // Step 2: Delete this problem's rows first - cards, recordings, and
+        // problem_images all cascade via the problem/cards delete, so the
+        // reference counts `file_reference_count` sees below already reflect
+        // this problem being gone rather than counting its own soon-to-be-deleted
+        // rows as still-live references.
+        let payload = self.delete_problem(problem_id)?;
+
+        // Step 3: Only unlink files nothing else still points at - a file could
+        // be shared across problems (e.g. after a future duplicate/merge
+        // feature), and deleting it out from under another problem's rows
+        // would orphan that problem's playback/image instead of this one's.
         for file_path in &files_to_delete {
-            match self.delete_file_safely(file_path) {
-                Ok(_) => println!("🗑️ [Database] Deleted file: {}", file_path),
-                Err(e) => println!("⚠️ [Database] Failed to delete file {}: {}", file_path, e),
+            match self.file_reference_count(file_path) {
+                Ok(0) => match self.delete_file_safely(file_path) {
+                    Ok(_) => println!("🗑️ [Database] Deleted file: {}", file_path),
+                    Err(e) => println!("⚠️ [Database] Failed to delete file {}: {}", file_path, e),
+                },
+                Ok(remaining) => {
+                    println!("⏭️ [Database] Skipping file still referenced by {} other row(s): {}", remaining, file_path);
+                }
+                Err(e) => println!("⚠️ [Database] Failed to check reference count for {}: {}", file_path, e),
             }
         }
 
-        // Step 3: Proceed with database deletion using existing method
-        self.delete_problem(problem_id)?;
-        
-        Ok(())
+        Ok(payload)
+    }
+
+    /// Counts how many `recordings`/`problem_images` rows still point at
+    /// `path`. Used to guard `delete_problem_with_files`'s file cleanup against
+    /// unlinking a file another problem's row still relies on, and exposed so
+    /// the delete-stats screen can show which of a problem's files will
+    /// actually be reclaimed versus kept around for another problem.
+    pub fn file_reference_count(&self, path: &str) -> anyhow::Result<i64> {
+        let recording_refs: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM recordings WHERE filepath = ?1",
+            [path],
+            |row| row.get(0),
+        )?;
+        let image_refs: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM problem_images WHERE image_path = ?1",
+            [path],
+            |row| row.get(0),
+        )?;
+        Ok(recording_refs + image_refs)
     }
 
     fn delete_file_safely(&self, file_path: &str) -> anyhow::Result<()> {
-        use std::path::Path;
-        
-        // Handle different path formats (dev-data, app-data, absolute paths)
-        let absolute_path = if file_path.starts_with("dev-data/") {
-            let current_dir = std::env::current_dir()?;
-            current_dir.join(file_path)
-        } else if file_path.starts_with("app-data/") {
-            // For production, we'd need to get app data directory
-            // For now, assume dev mode and convert to dev-data path
-            let current_dir = std::env::current_dir()?;
-            current_dir.join("dev-data").join(&file_path[9..])
-        } else if Path::new(file_path).is_absolute() {
-            Path::new(file_path).to_path_buf()
-        } else {
-            // Relative path, assume it's relative to current dir
-            let current_dir = std::env::current_dir()?;
-            current_dir.join(file_path)
-        };
-        
+        // Resolve against whichever configured storage root actually has the
+        // file, rather than hardcoding a dev-data/app-data guess that falls
+        // over as soon as media lives on a root other than the default one.
+        let absolute_path = self.resolve_media_path(file_path)?;
+
         if absolute_path.exists() {
             std::fs::remove_file(&absolute_path)?;
             println!("✅ [Database] Deleted file: {:?}", absolute_path);
         } else {
             println!("⚠️ [Database] File not found (may already be deleted): {:?}", absolute_path);
         }
-        
+
+        Ok(())
+    }
+
+    /// Moves a problem into the recycle bin: sets `deleted_at` instead of
+    /// touching any rows, so it vanishes from `get_problems`/search but a
+    /// mistaken deletion is still fully recoverable via `restore_problem`.
+    /// Cascades the same timestamp onto its cards, their recordings, and its
+    /// images, so each carries its own recycle-bin marker rather than one
+    /// only inferable from its parent problem.
+    pub fn soft_delete_problem(&mut self, problem_id: &str) -> anyhow::Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let tx = self.connection.unchecked_transaction()?;
+
+        let rows_affected = tx.execute(
+            "UPDATE problems SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![&now, problem_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!(
+                "Problem with id '{}' not found or already in the recycle bin",
+                problem_id
+            ));
+        }
+
+        tx.execute(
+            "UPDATE cards SET deleted_at = ?1 WHERE problem_id = ?2 AND deleted_at IS NULL",
+            params![&now, problem_id],
+        )?;
+        tx.execute(
+            "UPDATE recordings SET deleted_at = ?1
+             WHERE deleted_at IS NULL AND card_id IN (SELECT id FROM cards WHERE problem_id = ?2)",
+            params![&now, problem_id],
+        )?;
+        tx.execute(
+            "UPDATE problem_images SET deleted_at = ?1 WHERE problem_id = ?2 AND deleted_at IS NULL",
+            params![&now, problem_id],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Clears `deleted_at`, pulling a problem back out of the recycle bin,
+    /// along with the same cascade `soft_delete_problem` stamped onto its
+    /// cards/recordings/images.
+    pub fn restore_problem(&mut self, problem_id: &str) -> anyhow::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+
+        let rows_affected = tx.execute(
+            "UPDATE problems SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![problem_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!(
+                "Problem with id '{}' not found or not in the recycle bin",
+                problem_id
+            ));
+        }
+
+        tx.execute(
+            "UPDATE cards SET deleted_at = NULL WHERE problem_id = ?1",
+            params![problem_id],
+        )?;
+        tx.execute(
+            "UPDATE recordings SET deleted_at = NULL
+             WHERE card_id IN (SELECT id FROM cards WHERE problem_id = ?1)",
+            params![problem_id],
+        )?;
+        tx.execute(
+            "UPDATE problem_images SET deleted_at = NULL WHERE problem_id = ?1",
+            params![problem_id],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Every problem currently in the recycle bin, most recently deleted first.
+    pub fn list_deleted_problems(&self) -> anyhow::Result<Vec<FrontendProblem>> {
+        let sql = "SELECT id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at
+                   FROM problems
+                   WHERE deleted_at IS NOT NULL
+                   ORDER BY deleted_at DESC";
+
+        let mut stmt = self.connection.prepare(sql)?;
+        let problem_iter = stmt.query_map([], |row| {
+            Ok(Problem {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                difficulty: row.get(3)?,
+                topic: row.get(4)?,
+                leetcode_url: row.get(5)?,
+                constraints: row.get(6)?,
+                hints: row.get(7)?,
+                related_problem_ids: None,
+                created_at: parse_datetime_flexible(&row.get::<_, String>(8)?),
+                updated_at: parse_datetime_flexible(&row.get::<_, String>(9)?),
+            })
+        })?;
+
+        let mut problems = Vec::new();
+        for problem in problem_iter {
+            let db_problem = problem?;
+            let related_ids = self.related_problem_ids_for(&db_problem.id).unwrap_or_default();
+            problems.push(convert_problem_to_frontend(db_problem, related_ids));
+        }
+
+        Ok(problems)
+    }
+
+    /// Runs the real cascading hard delete (files included) on every
+    /// recycle-bin problem whose `deleted_at` is older than `cutoff` -
+    /// the retention window's actual reclaim step.
+    pub fn purge_deleted_before(&mut self, cutoff: DateTime<Utc>) -> anyhow::Result<Vec<DeletedProblemPayload>> {
+        let ids: Vec<String> = self
+            .connection
+            .prepare("SELECT id FROM problems WHERE deleted_at IS NOT NULL AND deleted_at < ?1")?
+            .query_map(params![cutoff.to_rfc3339()], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut purged = Vec::with_capacity(ids.len());
+        for id in ids {
+            purged.push(self.delete_problem_with_files(&id)?);
+        }
+
+        Ok(purged)
+    }
+
+    /// Sweeps recordings older than a retention cutoff, one transaction per
+    /// problem so a failure unlinking one problem's files doesn't abort the
+    /// sweep for the rest. `default_expiration` applies unless a problem has
+    /// set its own `recording_retention_days`, and any recording with
+    /// `retain_forever` set is skipped regardless of age. Unlike
+    /// `purge_deleted_before`, which applies one cutoff to whole
+    /// recycle-bin problems, this works on individual recordings.
+    pub fn prune_recordings(&mut self, default_expiration: chrono::Duration) -> anyhow::Result<maintenance::PruneReport> {
+        let now = Utc::now();
+        let mut report = maintenance::PruneReport::default();
+
+        let problem_ids: Vec<String> = self
+            .connection
+            .prepare(
+                "SELECT DISTINCT c.problem_id FROM recordings r
+                 JOIN cards c ON r.card_id = c.id
+                 WHERE r.retain_forever = 0",
+            )?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for problem_id in problem_ids {
+            let retention_days: Option<i64> = self.connection.query_row(
+                "SELECT recording_retention_days FROM problems WHERE id = ?1",
+                [&problem_id],
+                |row| row.get(0),
+            )?;
+            let cutoff = match retention_days {
+                Some(days) => now - chrono::Duration::days(days),
+                None => now - default_expiration,
+            };
+
+            let tx = self.connection.unchecked_transaction()?;
+            let expired: Vec<(String, String, i64)> = tx
+                .prepare(
+                    "SELECT r.id, r.filepath, COALESCE(r.file_size, 0)
+                     FROM recordings r
+                     JOIN cards c ON r.card_id = c.id
+                     WHERE c.problem_id = ?1 AND r.retain_forever = 0 AND r.created_at < ?2",
+                )?
+                .query_map(params![problem_id, cutoff.to_rfc3339()], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if expired.is_empty() {
+                continue;
+            }
+
+            for (recording_id, _, _) in &expired {
+                tx.execute("DELETE FROM recordings WHERE id = ?1", [recording_id])?;
+            }
+            tx.commit()?;
+
+            for (_, filepath, file_size) in &expired {
+                if matches!(self.file_reference_count(filepath), Ok(0)) {
+                    if self.delete_file_safely(filepath).is_ok() {
+                        report.bytes_reclaimed += file_size;
+                    }
+                }
+            }
+
+            report.recordings_deleted += expired.len() as i64;
+            report.problems_affected += 1;
+        }
+
+        Ok(report)
+    }
+
+    /// Enqueues a unit of background work for the job-worker thread to pick
+    /// up, `queued` and immediately available. `payload` is serialized to
+    /// JSON the same way `enqueue_transcription` does for
+    /// `jobs::TranscribeRecordingPayload`.
+    pub fn enqueue_job(&self, job_type: &str, payload: &str) -> anyhow::Result<jobs::Job> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        self.connection.execute(
+            "INSERT INTO jobs (id, job_type, payload, state, attempts, created_at, updated_at, available_at)
+             VALUES (?1, ?2, ?3, 'queued', 0, ?4, ?4, ?4)",
+            params![id, job_type, payload, now.to_rfc3339()],
+        )?;
+
+        Ok(jobs::Job {
+            id,
+            job_type: job_type.to_string(),
+            payload: payload.to_string(),
+            state: "queued".to_string(),
+            progress: None,
+            error_message: None,
+            attempts: 0,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Look up a single job by id, e.g. for `get_job_status`/`get_job_progress`.
+    pub fn get_job(&self, job_id: &str) -> anyhow::Result<Option<jobs::Job>> {
+        self.connection
+            .query_row(
+                "SELECT id, job_type, payload, state, progress, error_message, attempts, created_at, updated_at
+                 FROM jobs WHERE id = ?1",
+                [job_id],
+                Self::row_to_job,
+            )
+            .optional()
+            .context("Failed to fetch job")
+    }
+
+    fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<jobs::Job> {
+        Ok(jobs::Job {
+            id: row.get(0)?,
+            job_type: row.get(1)?,
+            payload: row.get(2)?,
+            state: row.get(3)?,
+            progress: row.get(4)?,
+            error_message: row.get(5)?,
+            attempts: row.get(6)?,
+            created_at: parse_datetime_flexible(&row.get::<_, String>(7)?),
+            updated_at: parse_datetime_flexible(&row.get::<_, String>(8)?),
+        })
+    }
+
+    /// Atomically claims the oldest `queued` job whose `available_at` has
+    /// passed, marking it `running` in the same statement so two worker
+    /// threads (or a worker racing a retry) can never both pick up the same
+    /// row. Returns `None` when there's nothing to do right now.
+    pub fn claim_next_queued_job(&self) -> anyhow::Result<Option<jobs::Job>> {
+        let now = Utc::now().to_rfc3339();
+        let tx = self.connection.unchecked_transaction()?;
+
+        let claimed_id: Option<String> = tx
+            .query_row(
+                "SELECT id FROM jobs WHERE state = 'queued' AND available_at <= ?1 ORDER BY created_at ASC LIMIT 1",
+                [&now],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let claimed_id = match claimed_id {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        tx.execute(
+            "UPDATE jobs SET state = 'running', updated_at = ?2 WHERE id = ?1",
+            params![claimed_id, now],
+        )?;
+        tx.commit()?;
+
+        self.get_job(&claimed_id)
+    }
+
+    /// Marks a job `completed`. Called by the worker thread after its handler
+    /// for `job.job_type` finishes successfully.
+    pub fn complete_job(&self, job_id: &str) -> anyhow::Result<()> {
+        self.connection.execute(
+            "UPDATE jobs SET state = 'completed', error_message = NULL, updated_at = ?2 WHERE id = ?1",
+            params![job_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Records a failed attempt. Below [`jobs::MAX_ATTEMPTS`] the job goes
+    /// back to `queued` with `available_at` pushed out by
+    /// [`jobs::backoff_delay`]; at the limit it's left `failed` for good.
+    pub fn fail_job(&self, job_id: &str, error_message: &str) -> anyhow::Result<()> {
+        let attempts: i32 = self.connection.query_row(
+            "SELECT attempts FROM jobs WHERE id = ?1",
+            [job_id],
+            |row| row.get(0),
+        )?;
+        let attempts = attempts + 1;
+        let now = Utc::now();
+
+        if attempts >= jobs::MAX_ATTEMPTS {
+            self.connection.execute(
+                "UPDATE jobs SET state = 'failed', attempts = ?2, error_message = ?3, updated_at = ?4 WHERE id = ?1",
+                params![job_id, attempts, error_message, now.to_rfc3339()],
+            )?;
+        } else {
+            let available_at = now + jobs::backoff_delay(attempts);
+            self.connection.execute(
+                "UPDATE jobs SET state = 'queued', attempts = ?2, error_message = ?3, updated_at = ?4, available_at = ?5 WHERE id = ?1",
+                params![job_id, attempts, error_message, now.to_rfc3339(), available_at.to_rfc3339()],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Convenience wrapper around [`Self::enqueue_job`] for the one job type
+    /// the worker currently knows how to run.
+    pub fn enqueue_transcription(&self, recording_id: &str) -> anyhow::Result<jobs::Job> {
+        let payload = serde_json::to_string(&jobs::TranscribeRecordingPayload {
+            recording_id: recording_id.to_string(),
+        })?;
+        self.enqueue_job(jobs::JOB_TYPE_TRANSCRIBE_RECORDING, &payload)
+    }
+
+    /// Convenience wrapper around [`Self::enqueue_job`] for `save_problem_image`'s
+    /// background decode/validate/strip/reencode pass.
+    pub fn enqueue_image_processing(&self, image_id: &str, staging_path: &str, sniffed_format: &str) -> anyhow::Result<jobs::Job> {
+        let payload = serde_json::to_string(&jobs::ProcessImagePayload {
+            image_id: image_id.to_string(),
+            staging_path: staging_path.to_string(),
+            sniffed_format: sniffed_format.to_string(),
+        })?;
+        self.enqueue_job(jobs::JOB_TYPE_PROCESS_IMAGE, &payload)
+    }
+
+    /// Enqueues a `bulk_import_card_images` job, pre-seeding `progress` with
+    /// every item at `current_index = 0` rather than leaving it `NULL` until
+    /// the worker's first iteration - so `get_job_progress` can report an
+    /// accurate total immediately, before the worker thread has even claimed it.
+    pub fn enqueue_bulk_import_card_images(&self, card_id: &str, items: Vec<jobs::BulkImportItem>) -> anyhow::Result<jobs::Job> {
+        let payload = serde_json::to_string(&jobs::BulkImportCardImagesPayload {
+            card_id: card_id.to_string(),
+        })?;
+        let progress = rmp_serde::to_vec(&jobs::BulkImportProgress {
+            items,
+            current_index: 0,
+            imported_image_ids: Vec::new(),
+            failed: Vec::new(),
+        })?;
+
+        let mut job = self.enqueue_job(jobs::JOB_TYPE_BULK_IMPORT_CARD_IMAGES, &payload)?;
+        self.update_job_progress(&job.id, &progress)?;
+        job.progress = Some(progress);
+        Ok(job)
+    }
+
+    /// Convenience wrapper around [`Self::enqueue_job`] for the optional
+    /// OCR/auto-labeling pass over a newly saved (or re-requested, see
+    /// `regenerate_image_labels`) card image. Only ever enqueued when the
+    /// `ocr` cargo feature is compiled in - see
+    /// `commands::card_images::save_card_image_bytes` - though enqueuing
+    /// itself doesn't need the feature; `run_ocr_card_image` is what fails
+    /// cleanly without it.
+    pub fn enqueue_ocr_card_image(&self, image_id: &str) -> anyhow::Result<jobs::Job> {
+        let payload = serde_json::to_string(&jobs::OcrCardImagePayload { image_id: image_id.to_string() })?;
+        self.enqueue_job(jobs::JOB_TYPE_OCR_CARD_IMAGE, &payload)
+    }
+
+    /// Overwrites a job's resume point. Called by the bulk-import worker loop
+    /// after every single item, not just at enqueue/completion, so `progress`
+    /// always reflects the last item that actually finished.
+    pub fn update_job_progress(&self, job_id: &str, progress: &[u8]) -> anyhow::Result<()> {
+        self.connection.execute(
+            "UPDATE jobs SET progress = ?2, updated_at = ?3 WHERE id = ?1",
+            params![job_id, progress, Utc::now().to_rfc3339()],
+        )?;
         Ok(())
     }
 
+    /// Requests that a `queued` or in-progress job stop after its current
+    /// item instead of continuing to the next one. The worker thread notices
+    /// on its next fresh read of the row (see
+    /// `commands::jobs::run_bulk_import_card_images`) and leaves `progress`
+    /// exactly as it was after the last completed item, ready for
+    /// `resume_job`. Returns `false` if the job wasn't in a pausable state
+    /// (already paused, or finished).
+    pub fn pause_job(&self, job_id: &str) -> anyhow::Result<bool> {
+        let rows_affected = self.connection.execute(
+            "UPDATE jobs SET state = 'paused', updated_at = ?2 WHERE id = ?1 AND state IN ('queued', 'running')",
+            params![job_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Puts a `paused` job back to `queued`, available immediately, so the
+    /// worker thread's next poll picks it up and continues from
+    /// `progress.current_index`. Returns `false` if the job wasn't paused.
+    pub fn resume_job(&self, job_id: &str) -> anyhow::Result<bool> {
+        let now = Utc::now().to_rfc3339();
+        let rows_affected = self.connection.execute(
+            "UPDATE jobs SET state = 'queued', available_at = ?2, updated_at = ?2 WHERE id = ?1 AND state = 'paused'",
+            params![job_id, now],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Called once from `setup()`: a job left `running` (the app crashed or
+    /// was quit mid-item) or `paused` (the user asked it to stop, but never
+    /// came back to resume it) from a previous run is put back to `queued` so
+    /// the worker thread picks it up again from its last persisted
+    /// `progress.current_index`, the same auto-resume behavior the `jobs`
+    /// queue already gives a `queued` job that loses the race against an app
+    /// quit. Returns how many jobs were requeued, for the startup log.
+    pub fn requeue_interrupted_jobs(&self) -> anyhow::Result<usize> {
+        let now = Utc::now().to_rfc3339();
+        let rows_affected = self.connection.execute(
+            "UPDATE jobs SET state = 'queued', available_at = ?1, updated_at = ?1 WHERE state IN ('running', 'paused')",
+            [&now],
+        )?;
+        Ok(rows_affected)
+    }
+
     /// Get statistics about what data will be deleted with a problem
     pub fn get_problem_delete_stats(&self, problem_id: &str) -> anyhow::Result<Option<crate::models::ProblemDeleteStats>> {
         println!("📊 [Database] Getting delete stats for problem: {}", problem_id);
         
-        // First verify the problem exists
+        // First verify the problem exists and isn't in the recycle bin
         let problem_exists: i32 = self.connection.query_row(
-            "SELECT COUNT(*) FROM problems WHERE id = ?1",
+            "SELECT COUNT(*) FROM problems WHERE id = ?1 AND deleted_at IS NULL",
             [problem_id],
             |row| row.get(0)
         )?;
@@ -2541,6 +5382,17 @@ impl DatabaseManager {
             |row| row.get(0)
         ).unwrap_or(0);
 
+        // Of this problem's own files, how many aren't shared with another
+        // problem's rows and will actually be unlinked by `delete_problem_with_files`.
+        let mut own_files = self.get_recording_files_for_problem(problem_id)?;
+        own_files.extend(self.get_image_files_for_problem(problem_id)?);
+        let mut files_to_reclaim = 0i32;
+        for path in &own_files {
+            if self.file_reference_count(path)? <= 1 {
+                files_to_reclaim += 1;
+            }
+        }
+
         let stats = crate::models::ProblemDeleteStats {
             total_cards,
             main_cards,
@@ -2548,11 +5400,12 @@ impl DatabaseManager {
             recordings_count,
             images_count,
             total_duration,
+            files_to_reclaim,
         };
 
-        println!("📊 [Database] Delete stats for {}: {} cards ({} main, {} child), {} recordings, {} images, {}s total duration", 
-                 problem_id, stats.total_cards, stats.main_cards, stats.child_cards, 
-                 stats.recordings_count, stats.images_count, stats.total_duration);
+        println!("📊 [Database] Delete stats for {}: {} cards ({} main, {} child), {} recordings, {} images, {}s total duration, {} files to reclaim",
+                 problem_id, stats.total_cards, stats.main_cards, stats.child_cards,
+                 stats.recordings_count, stats.images_count, stats.total_duration, stats.files_to_reclaim);
 
         Ok(Some(stats))
     }
@@ -2577,7 +5430,159 @@ impl DatabaseManager {
         )?
         .query_map([problem_id], |row| Ok(row.get::<_, String>(0)?))?
         .collect::<Result<Vec<String>, _>>()?;
-        
+
         Ok(image_files)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `new_with_path`/`connect_existing_with_path` are `async fn` only because
+    /// they're called from `#[tauri::command]`s - there's no real async I/O in
+    /// them. Bridging with a throwaway single-threaded runtime keeps the tests
+    /// plain `#[test]` fns, matching every other test module in this crate.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("failed to build test runtime")
+            .block_on(future)
+    }
+
+    /// `(table, column, sql_type, not_null, pk)` for every non-bookkeeping
+    /// table in the database, in a stable order - a schema "fingerprint" we
+    /// can diff between a fresh database and a migrated one.
+    fn schema_fingerprint(conn: &Connection) -> Vec<(String, String, String, bool, bool)> {
+        let mut tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_migrations'")
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(0))
+            .unwrap()
+            .collect::<Result<Vec<String>, _>>()
+            .unwrap();
+        tables.sort();
+
+        let mut fingerprint = Vec::new();
+        for table in tables {
+            let mut columns: Vec<(String, String, bool, bool)> = conn
+                .prepare(&format!("PRAGMA table_info({})", table))
+                .unwrap()
+                .query_map([], |row| {
+                    let name: String = row.get(1)?;
+                    let sql_type: String = row.get(2)?;
+                    let not_null: bool = row.get::<_, i64>(3)? != 0;
+                    let pk: bool = row.get::<_, i64>(5)? != 0;
+                    Ok((name, sql_type, not_null, pk))
+                })
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            columns.sort();
+            for (name, sql_type, not_null, pk) in columns {
+                fingerprint.push((table.clone(), name, sql_type, not_null, pk));
+            }
+        }
+        fingerprint
+    }
+
+    #[test]
+    fn fresh_database_matches_expected_schema() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let db = block_on(DatabaseManager::new_with_path(temp_dir.path().to_path_buf()))
+            .expect("fresh database should initialize");
+
+        let drift = db.validate_schema().expect("validate_schema should succeed");
+        assert!(drift.is_clean(), "fresh database has schema drift: {:?}", drift);
+
+        // `PRAGMA table_info` doesn't surface CHECK constraints, so the two
+        // CHECK-constrained enum columns are verified against the raw DDL text.
+        let connections_sql: String = db.connection.query_row(
+            "SELECT sql FROM sqlite_master WHERE type='table' AND name='connections'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert!(connections_sql.contains("connection_type"));
+
+        let tags_sql: String = db.connection.query_row(
+            "SELECT sql FROM sqlite_master WHERE type='table' AND name='tags'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert!(tags_sql.contains("category"));
+    }
+
+    #[test]
+    fn migrated_database_matches_fresh_database() {
+        let fresh_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let fresh_db = block_on(DatabaseManager::new_with_path(fresh_dir.path().to_path_buf()))
+            .expect("fresh database should initialize");
+
+        // Seed a database already tracked up through migration 13 - with real
+        // problems/cards/time_sessions rows in place, not empty tables - so
+        // connect_existing_with_path's replay of 14+ actually exercises
+        // migration 14's `DROP TABLE cards` against a time_sessions row still
+        // referencing that card, instead of the FK-violation path going
+        // untested because nothing was ever populated.
+        let old_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let old_db_path = old_dir.path().join("database.db");
+        {
+            let seed_connection = Connection::open(&old_db_path).expect("failed to create seed database");
+            seed_connection
+                .execute_batch(
+                    "CREATE TABLE schema_migrations (
+                        version INTEGER PRIMARY KEY,
+                        name TEXT NOT NULL,
+                        checksum TEXT,
+                        applied_at DATETIME NOT NULL
+                    )"
+                )
+                .expect("failed to create schema_migrations table");
+
+            for migration in migrations::MIGRATIONS.iter().filter(|m| m.version < 14) {
+                seed_connection
+                    .execute_batch(migration.up_sql)
+                    .unwrap_or_else(|e| panic!("failed to seed migration {}: {}", migration.version, e));
+                record_migration_applied_on(&seed_connection, migration)
+                    .expect("failed to record seed migration as applied");
+            }
+
+            seed_connection
+                .execute("INSERT INTO problems (id, title) VALUES ('p1', 'Two Sum')", [])
+                .expect("failed to seed problems row");
+            seed_connection
+                .execute(
+                    "INSERT INTO cards (id, problem_id, card_number, status) VALUES ('c1', 'p1', 1, 'Completed')",
+                    [],
+                )
+                .expect("failed to seed cards row");
+            seed_connection
+                .execute(
+                    "INSERT INTO time_sessions (id, card_id, start_time, duration, is_active) VALUES ('t1', 'c1', '2024-01-01T00:00:00Z', 60, 0)",
+                    [],
+                )
+                .expect("failed to seed time_sessions row");
+        }
+
+        let migrated_db = block_on(DatabaseManager::connect_existing_with_path(old_dir.path().to_path_buf()))
+            .expect("connect_existing_with_path should migrate a populated database through migration 14 without a foreign key violation");
+
+        assert_eq!(
+            schema_fingerprint(&migrated_db.connection),
+            schema_fingerprint(&fresh_db.connection),
+            "migrated database schema diverged from a fresh database's schema"
+        );
+
+        let card_count: i64 = migrated_db
+            .connection
+            .query_row("SELECT COUNT(*) FROM cards WHERE id = 'c1'", [], |row| row.get(0))
+            .expect("failed to query migrated cards row");
+        assert_eq!(card_count, 1, "seeded card row should survive migration 14's table rebuild");
+
+        let session_count: i64 = migrated_db
+            .connection
+            .query_row("SELECT COUNT(*) FROM time_sessions WHERE card_id = 'c1'", [], |row| row.get(0))
+            .expect("failed to query migrated time_sessions row");
+        assert_eq!(session_count, 1, "seeded time_sessions row should survive migration 14's table rebuild");
+    }
 }
\ No newline at end of file
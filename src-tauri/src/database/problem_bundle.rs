@@ -0,0 +1,598 @@
+// Encrypted, portable single-problem export/import: gathers a problem and
+// everything hanging off it - its cards, their `time_sessions` and
+// `recordings` (audio inlined), the problem's `problem_images` (inlined),
+// the `connections` between its cards, and its tags - into one versioned,
+// passphrase-encrypted file. Unlike `commands::library_bundle`, which
+// round-trips the *whole* library as plain, file-less JSON, this moves or
+// backs up a single problem with everything it needs to be self-contained.
+//
+// The authenticated encryption below is written against the real
+// `aes-gcm`/`pbkdf2`/`sha2` crate APIs - AES-256-GCM keyed by
+// PBKDF2-HMAC-SHA256 over the passphrase with a random salt, and a random
+// nonce per export - but none of those crates are in this tree's
+// dependencies; there's no `Cargo.toml` here to add them. See
+// `database::encryption` for the same situation with SQLCipher.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::Context;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use chrono::Utc;
+use pbkdf2::pbkdf2_hmac;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::models::{Card, FrontendProblem, ProblemImage, Recording, Tag, TimeSession};
+
+use super::maintenance;
+
+/// Bumped whenever `ProblemBundleManifest`'s shape changes in a way an older
+/// `import_problem_bundle` can't read. Import rejects anything newer than
+/// this rather than guessing at fields it doesn't know about.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const PBKDF2_ROUNDS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+
+/// Errors specific to bundle handling that callers need to distinguish from
+/// a generic I/O or JSON failure - modeled on [`super::encryption::DatabaseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleError {
+    /// AES-GCM's tag check failed, so either the passphrase is wrong or the
+    /// file is corrupt - there's no way to tell which from here.
+    BadPassphrase,
+    /// The manifest's `format_version` is newer than this build understands.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::BadPassphrase => write!(f, "incorrect passphrase or corrupted bundle file"),
+            BundleError::UnsupportedVersion(found) => write!(
+                f,
+                "bundle format version {} is newer than this app supports (up to {})",
+                found, BUNDLE_FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<BundleError> for String {
+    fn from(err: BundleError) -> Self {
+        err.to_string()
+    }
+}
+
+/// A `connections` row. No model struct exists for this table elsewhere in
+/// the codebase - nothing outside `check_and_repair`'s dangling-connection
+/// scan (raw SQL) touches it - so the bundle carries its own minimal copy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConnectionRow {
+    id: String,
+    source_card_id: String,
+    target_card_id: String,
+    connection_type: Option<String>,
+    notes: Option<String>,
+    created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordingEntry {
+    recording: Recording,
+    /// Base64 contents of the file at `recording.filepath` - `None` if it
+    /// was already missing at export time, mirroring how `check_and_repair`
+    /// reports (rather than fails outright on) a missing backing file.
+    file_contents: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ImageEntry {
+    image: ProblemImage,
+    file_contents: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProblemBundleManifest {
+    format_version: u32,
+    problem: FrontendProblem,
+    cards: Vec<Card>,
+    time_sessions: Vec<TimeSession>,
+    recordings: Vec<RecordingEntry>,
+    images: Vec<ImageEntry>,
+    connections: Vec<ConnectionRow>,
+    tags: Vec<Tag>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+/// Encrypts `plaintext` (the serialized manifest) under `passphrase`,
+/// prepending a fresh random salt and nonce so the resulting blob is
+/// self-describing - nothing besides the passphrase needs to travel with it.
+fn encrypt_blob(plaintext: &[u8], passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt problem bundle"))?;
+
+    let mut blob = Vec::with_capacity(SALT_LEN + nonce.len() + ciphertext.len());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(nonce.as_slice());
+    blob.extend_from_slice(&ciphertext);
+    Ok(blob)
+}
+
+/// Inverse of [`encrypt_blob`]. A failed GCM tag check surfaces as
+/// [`BundleError::BadPassphrase`] rather than a generic AEAD error, so the UI
+/// can prompt for re-entry instead of showing a raw crypto error.
+fn decrypt_blob(blob: &[u8], passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    const NONCE_LEN: usize = 12;
+    if blob.len() < SALT_LEN + NONCE_LEN {
+        anyhow::bail!("Problem bundle is truncated or not a valid bundle file");
+    }
+
+    let salt = &blob[..SALT_LEN];
+    let nonce = Nonce::from_slice(&blob[SALT_LEN..SALT_LEN + NONCE_LEN]);
+    let ciphertext = &blob[SALT_LEN + NONCE_LEN..];
+
+    let key = derive_key(passphrase, salt);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| BundleError::BadPassphrase.into())
+}
+
+/// Builds the relative path a freshly-written bundle file should be stored
+/// under, in the same "dev-data/<subdir>/<filename>" / "app-data/<subdir>/<filename>"
+/// form [`crate::path_resolver::PathResolver::to_relative_path`] produces -
+/// this module has no `PathResolver` to call (see `super::maintenance`).
+fn relative_path_string(subdir: &str, filename: &str) -> String {
+    let prefix = if cfg!(debug_assertions) { "dev-data" } else { "app-data" };
+    format!("{}/{}/{}", prefix, subdir, filename)
+}
+
+fn read_file_base64(path: &std::path::Path) -> Option<String> {
+    std::fs::read(path).ok().map(|bytes| BASE64.encode(bytes))
+}
+
+/// Gathers everything hanging off `problem_id` into a manifest. Runs inside
+/// whatever transaction the caller already holds open, same as the
+/// `batch_*` helpers this mirrors.
+fn build_manifest(conn: &Connection, problem_id: &str) -> anyhow::Result<ProblemBundleManifest> {
+    let problem = super::batch_get_problem_by_id(conn, problem_id)?
+        .with_context(|| format!("Problem {} not found", problem_id))?;
+
+    let tags = {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, t.color, t.category
+             FROM tags t JOIN problem_tags pt ON t.id = pt.tag_id
+             WHERE pt.problem_id = ?1 ORDER BY t.name",
+        )?;
+        stmt.query_map(params![problem_id], |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                category: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let cards = {
+        let mut stmt = conn.prepare(
+            "SELECT id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id
+             FROM cards WHERE problem_id = ?1 ORDER BY card_number",
+        )?;
+        stmt.query_map(params![problem_id], |row| {
+            Ok(Card {
+                id: row.get(0)?,
+                problem_id: row.get(1)?,
+                card_number: row.get(2)?,
+                code: row.get(3)?,
+                language: row.get(4)?,
+                notes: row.get(5)?,
+                status: row.get(6)?,
+                total_duration: row.get(7)?,
+                created_at: row.get::<_, String>(8)?.parse().unwrap_or_else(|_| Utc::now()),
+                last_modified: row.get::<_, String>(9)?.parse().unwrap_or_else(|_| Utc::now()),
+                parent_card_id: row.get(10)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+    };
+    let card_ids: Vec<String> = cards.iter().map(|c| c.id.clone()).collect();
+
+    let mut time_sessions = Vec::new();
+    let mut recordings = Vec::new();
+    for card_id in &card_ids {
+        let mut stmt = conn.prepare(
+            "SELECT id, card_id, start_time, end_time, duration, date, is_active, notes
+             FROM time_sessions WHERE card_id = ?1 ORDER BY start_time",
+        )?;
+        let sessions = stmt.query_map(params![card_id], |row| {
+            let start_time: String = row.get(2)?;
+            let end_time: Option<String> = row.get(3)?;
+            Ok(TimeSession {
+                id: row.get(0)?,
+                card_id: row.get(1)?,
+                start_time: start_time.parse().unwrap_or_else(|_| Utc::now()),
+                end_time: end_time.map(|s| s.parse().unwrap_or_else(|_| Utc::now())),
+                duration: row.get(4)?,
+                date: row.get(5)?,
+                is_active: row.get(6)?,
+                notes: row.get(7)?,
+            })
+        })?;
+        for session in sessions {
+            time_sessions.push(session?);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, card_id, time_session_id, audio_url, duration, transcript, created_at, filename, filepath, file_size
+             FROM recordings WHERE card_id = ?1 ORDER BY created_at",
+        )?;
+        let card_recordings = stmt.query_map(params![card_id], |row| {
+            Ok(Recording {
+                id: row.get(0)?,
+                card_id: row.get(1)?,
+                time_session_id: row.get(2)?,
+                audio_url: row.get(3)?,
+                duration: row.get(4)?,
+                transcript: row.get(5)?,
+                created_at: row.get::<_, String>(6)?.parse().unwrap_or_else(|_| Utc::now()),
+                filename: row.get(7)?,
+                filepath: row.get(8)?,
+                file_size: row.get(9)?,
+            })
+        })?;
+        for recording in card_recordings {
+            let recording = recording?;
+            let file_contents = maintenance::resolve_recording_path(&recording.filepath)
+                .ok()
+                .and_then(|path| read_file_base64(&path));
+            recordings.push(RecordingEntry { recording, file_contents });
+        }
+    }
+
+    let connections = if card_ids.is_empty() {
+        Vec::new()
+    } else {
+        let placeholders = card_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT id, source_card_id, target_card_id, connection_type, notes, created_at
+             FROM connections WHERE source_card_id IN ({0}) OR target_card_id IN ({0})",
+            placeholders
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let doubled_ids = card_ids.iter().chain(card_ids.iter());
+        stmt.query_map(rusqlite::params_from_iter(doubled_ids), |row| {
+            Ok(ConnectionRow {
+                id: row.get(0)?,
+                source_card_id: row.get(1)?,
+                target_card_id: row.get(2)?,
+                connection_type: row.get(3)?,
+                notes: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let images = {
+        let mut stmt = conn.prepare(
+            "SELECT id, problem_id, image_path, caption, position, created_at, thumbnail_path, blur_hash, content_hash, width, height, byte_size, status
+             FROM problem_images WHERE problem_id = ?1 ORDER BY position",
+        )?;
+        let rows = stmt.query_map(params![problem_id], |row| {
+            Ok(ProblemImage {
+                id: row.get(0)?,
+                problem_id: row.get(1)?,
+                image_path: row.get(2)?,
+                caption: row.get(3)?,
+                position: row.get(4)?,
+                created_at: row.get::<_, String>(5)?.parse().unwrap_or_else(|_| Utc::now()),
+                thumbnail_path: row.get(6)?,
+                blur_hash: row.get(7)?,
+                content_hash: row.get(8)?,
+                width: row.get(9)?,
+                height: row.get(10)?,
+                byte_size: row.get(11)?,
+                status: row.get(12)?,
+            })
+        })?;
+        let mut images = Vec::new();
+        for image in rows {
+            let image = image?;
+            let file_contents = maintenance::resolve_image_path(&image.image_path)
+                .ok()
+                .and_then(|path| read_file_base64(&path));
+            images.push(ImageEntry { image, file_contents });
+        }
+        images
+    };
+
+    Ok(ProblemBundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        problem,
+        cards,
+        time_sessions,
+        recordings,
+        images,
+        connections,
+        tags,
+    })
+}
+
+/// Serializes and encrypts everything [`build_manifest`] gathers for
+/// `problem_id` under `passphrase`.
+pub(crate) fn export(conn: &Connection, problem_id: &str, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let manifest = build_manifest(conn, problem_id)?;
+    let plaintext = serde_json::to_vec(&manifest).context("Failed to serialize problem bundle")?;
+    encrypt_blob(&plaintext, passphrase)
+}
+
+fn find_or_create_tag(conn: &Connection, tag: &Tag) -> anyhow::Result<String> {
+    let existing: Option<String> = conn
+        .query_row("SELECT id FROM tags WHERE name = ?1", params![&tag.name], |row| row.get(0))
+        .optional()?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tags (id, name, color, category) VALUES (?1, ?2, ?3, ?4)",
+        params![&id, &tag.name, &tag.color, &tag.category],
+    )?;
+    Ok(id)
+}
+
+/// Decrypts, validates and inserts `blob` as a brand-new problem, minting a
+/// fresh UUID for every row and remapping every foreign key that pointed at
+/// an old one (`parent_card_id`, `card_id`, `source_card_id`/`target_card_id`)
+/// through an old->new id map built up as each table is imported. Runs
+/// inside whatever transaction the caller already holds open; returns the
+/// new problem's id.
+pub(crate) fn import(conn: &Connection, blob: &[u8], passphrase: &str) -> anyhow::Result<String> {
+    let plaintext = decrypt_blob(blob, passphrase)?;
+    let manifest: ProblemBundleManifest =
+        serde_json::from_slice(&plaintext).context("Failed to parse decrypted problem bundle")?;
+
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(BundleError::UnsupportedVersion(manifest.format_version).into());
+    }
+
+    let now = Utc::now();
+    let new_problem_id = Uuid::new_v4().to_string();
+
+    let topic_json = serde_json::to_string(&manifest.problem.topic)?;
+    let constraints_json = serde_json::to_string(&manifest.problem.constraints)?;
+    let hints_json = serde_json::to_string(&manifest.problem.hints)?;
+    conn.execute(
+        "INSERT INTO problems (id, title, description, difficulty, topic, leetcode_url, constraints, hints, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            &new_problem_id,
+            &manifest.problem.title,
+            &manifest.problem.description,
+            &manifest.problem.difficulty,
+            &topic_json,
+            &manifest.problem.leetcode_url,
+            &constraints_json,
+            &hints_json,
+            &now.to_rfc3339(),
+            &now.to_rfc3339(),
+        ],
+    )?;
+
+    for tag in &manifest.tags {
+        let tag_id = find_or_create_tag(conn, tag)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO problem_tags (problem_id, tag_id) VALUES (?1, ?2)",
+            params![&new_problem_id, &tag_id],
+        )?;
+    }
+
+    // Cards can reference each other via `parent_card_id`, and earlier cards
+    // in export order may be a later card's parent - insert every card first
+    // with `parent_card_id` left NULL, then patch it in once every old id has
+    // a new one.
+    let mut card_id_map: HashMap<String, String> = HashMap::new();
+    for card in &manifest.cards {
+        let new_card_id = Uuid::new_v4().to_string();
+        card_id_map.insert(card.id.clone(), new_card_id.clone());
+
+        conn.execute(
+            "INSERT INTO cards (id, problem_id, card_number, code, language, notes, status, total_duration, created_at, last_modified, parent_card_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, NULL)",
+            params![
+                &new_card_id,
+                &new_problem_id,
+                card.card_number,
+                &card.code,
+                &card.language,
+                &card.notes,
+                &card.status,
+                card.total_duration,
+                &card.created_at.to_rfc3339(),
+                &card.last_modified.to_rfc3339(),
+            ],
+        )?;
+    }
+    for card in &manifest.cards {
+        let Some(old_parent_id) = &card.parent_card_id else { continue };
+        let Some(new_card_id) = card_id_map.get(&card.id) else { continue };
+        let new_parent_id = card_id_map.get(old_parent_id);
+        conn.execute(
+            "UPDATE cards SET parent_card_id = ?1 WHERE id = ?2",
+            params![new_parent_id, new_card_id],
+        )?;
+    }
+
+    let mut session_id_map: HashMap<String, String> = HashMap::new();
+    for session in &manifest.time_sessions {
+        let Some(new_card_id) = card_id_map.get(&session.card_id) else { continue };
+        let new_session_id = Uuid::new_v4().to_string();
+        session_id_map.insert(session.id.clone(), new_session_id.clone());
+
+        conn.execute(
+            "INSERT INTO time_sessions (id, card_id, start_time, end_time, duration, date, is_active, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                &new_session_id,
+                new_card_id,
+                &session.start_time.to_rfc3339(),
+                session.end_time.map(|t| t.to_rfc3339()),
+                session.duration,
+                &session.date,
+                session.is_active,
+                &session.notes,
+            ],
+        )?;
+    }
+
+    for entry in &manifest.recordings {
+        let Some(new_card_id) = card_id_map.get(&entry.recording.card_id) else { continue };
+        let new_recording_id = Uuid::new_v4().to_string();
+        let new_time_session_id = entry
+            .recording
+            .time_session_id
+            .as_ref()
+            .and_then(|id| session_id_map.get(id));
+
+        let filepath = match &entry.file_contents {
+            Some(encoded) => {
+                let dir = maintenance::recordings_dir()?;
+                std::fs::create_dir_all(&dir)
+                    .with_context(|| format!("Failed to create recordings directory {}", dir.display()))?;
+                let filename = format!("{}_{}", new_recording_id, entry.recording.filename);
+                let bytes = BASE64
+                    .decode(encoded)
+                    .context("Failed to decode recording audio from problem bundle")?;
+                std::fs::write(dir.join(&filename), bytes)
+                    .with_context(|| format!("Failed to write recording file {}", filename))?;
+                relative_path_string("recordings", &filename)
+            }
+            None => entry.recording.filepath.clone(),
+        };
+
+        conn.execute(
+            "INSERT INTO recordings (id, card_id, time_session_id, audio_url, duration, transcript, created_at, filename, filepath, file_size)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                &new_recording_id,
+                new_card_id,
+                new_time_session_id,
+                &filepath,
+                entry.recording.duration,
+                &entry.recording.transcript,
+                &entry.recording.created_at.to_rfc3339(),
+                &entry.recording.filename,
+                &filepath,
+                entry.recording.file_size,
+            ],
+        )?;
+    }
+
+    for entry in &manifest.images {
+        let new_image_id = Uuid::new_v4().to_string();
+        let image_path = match &entry.file_contents {
+            Some(encoded) => {
+                let dir = maintenance::images_dir()?;
+                std::fs::create_dir_all(&dir)
+                    .with_context(|| format!("Failed to create images directory {}", dir.display()))?;
+                let filename = format!(
+                    "{}_{}",
+                    new_image_id,
+                    std::path::Path::new(&entry.image.image_path)
+                        .file_name()
+                        .map(|f| f.to_string_lossy().to_string())
+                        .unwrap_or_else(|| new_image_id.clone())
+                );
+                let bytes = BASE64
+                    .decode(encoded)
+                    .context("Failed to decode image from problem bundle")?;
+                std::fs::write(dir.join(&filename), bytes)
+                    .with_context(|| format!("Failed to write image file {}", filename))?;
+                relative_path_string("images", &filename)
+            }
+            None => entry.image.image_path.clone(),
+        };
+
+        // `thumbnail_path` is deliberately left unset: the thumbnail file
+        // itself isn't bundled, only the original's `file_contents`, so the
+        // stored path would point nowhere on restore. `blur_hash`, `width`
+        // and `height` are pure functions of the original's pixels, so they
+        // stay valid regardless of where the file lives and are carried over
+        // as-is; `byte_size` likewise still matches, since the restored file
+        // is written byte-for-byte from `file_contents`. `content_hash` is
+        // left unset - the restored file is written fresh under
+        // `images_dir()` rather than the shared `images/cas/` directory, so
+        // it isn't actually content-addressed; `migrate_images_to_cas` will
+        // pick it up like any other legacy file if it's ever run. `status`
+        // is always restored as `ready` rather than carried over - a bundled
+        // `file_contents` is the original's full bytes, not the unprocessed
+        // staging file a `pending` row's `image_path` would point at, so
+        // there's nothing left for the `process_image` job to do.
+        conn.execute(
+            "INSERT INTO problem_images (id, problem_id, image_path, caption, position, created_at, blur_hash, width, height, byte_size, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'ready')",
+            params![
+                &new_image_id,
+                &new_problem_id,
+                &image_path,
+                &entry.image.caption,
+                entry.image.position,
+                &entry.image.created_at.to_rfc3339(),
+                &entry.image.blur_hash,
+                entry.image.width,
+                entry.image.height,
+                entry.image.byte_size,
+            ],
+        )?;
+    }
+
+    for connection in &manifest.connections {
+        let (Some(new_source), Some(new_target)) = (
+            card_id_map.get(&connection.source_card_id),
+            card_id_map.get(&connection.target_card_id),
+        ) else {
+            continue;
+        };
+        conn.execute(
+            "INSERT INTO connections (id, source_card_id, target_card_id, connection_type, notes, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                Uuid::new_v4().to_string(),
+                new_source,
+                new_target,
+                &connection.connection_type,
+                &connection.notes,
+                &connection.created_at,
+            ],
+        )?;
+    }
+
+    Ok(new_problem_id)
+}
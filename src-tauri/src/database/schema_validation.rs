@@ -0,0 +1,253 @@
+// Data-driven schema introspection: replaces the growing pile of hand-written
+// `columns.contains(&"...".to_string())` checks with a single declared
+// "expected schema" table, diffed against `sqlite_master`/`PRAGMA table_info`
+// the way ORMs like Diesel validate a database against its compiled schema.
+
+pub struct ExpectedColumn {
+    pub name: &'static str,
+    pub sql_type: &'static str,
+    pub not_null: bool,
+    pub pk: bool,
+}
+
+pub struct ExpectedTable {
+    pub name: &'static str,
+    pub columns: &'static [ExpectedColumn],
+}
+
+macro_rules! col {
+    ($name:literal, $ty:literal) => {
+        ExpectedColumn { name: $name, sql_type: $ty, not_null: false, pk: false }
+    };
+    ($name:literal, $ty:literal, not_null) => {
+        ExpectedColumn { name: $name, sql_type: $ty, not_null: true, pk: false }
+    };
+    ($name:literal, $ty:literal, pk) => {
+        ExpectedColumn { name: $name, sql_type: $ty, not_null: true, pk: true }
+    };
+}
+
+// Mirrors the cumulative end state of `database::migrations::MIGRATIONS`.
+pub const EXPECTED_SCHEMA: &[ExpectedTable] = &[
+    ExpectedTable {
+        name: "problems",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("title", "TEXT", not_null),
+            col!("description", "TEXT"),
+            col!("difficulty", "TEXT"),
+            col!("topic", "TEXT"),
+            col!("leetcode_url", "TEXT"),
+            col!("constraints", "TEXT"),
+            col!("examples", "TEXT"),
+            col!("hints", "TEXT"),
+            col!("related_problem_ids", "TEXT"),
+            col!("created_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "cards",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("problem_id", "TEXT", not_null),
+            col!("card_number", "INTEGER", not_null),
+            col!("code", "TEXT"),
+            col!("language", "TEXT"),
+            col!("notes", "TEXT"),
+            col!("status", "TEXT"),
+            col!("total_duration", "INTEGER"),
+            col!("created_at", "DATETIME"),
+            col!("last_modified", "DATETIME"),
+            col!("parent_card_id", "TEXT"),
+        ],
+    },
+    ExpectedTable {
+        name: "time_sessions",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("card_id", "TEXT", not_null),
+            col!("start_time", "DATETIME", not_null),
+            col!("end_time", "DATETIME"),
+            col!("duration", "INTEGER"),
+            col!("date", "DATE"),
+            col!("is_active", "INTEGER"),
+            col!("notes", "TEXT"),
+        ],
+    },
+    ExpectedTable {
+        name: "recordings",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("card_id", "TEXT", not_null),
+            col!("time_session_id", "TEXT"),
+            col!("audio_url", "TEXT", not_null),
+            col!("duration", "INTEGER"),
+            col!("transcript", "TEXT"),
+            col!("created_at", "DATETIME"),
+            col!("filename", "TEXT", not_null),
+            col!("filepath", "TEXT", not_null),
+            col!("file_size", "INTEGER"),
+        ],
+    },
+    ExpectedTable {
+        name: "recording_highlights",
+        columns: &[
+            col!("recording_id", "TEXT", pk),
+            col!("color", "TEXT", not_null),
+            col!("updated_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "connections",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("source_card_id", "TEXT", not_null),
+            col!("target_card_id", "TEXT", not_null),
+            col!("connection_type", "TEXT"),
+            col!("notes", "TEXT"),
+            col!("created_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "tags",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("name", "TEXT", not_null),
+            col!("color", "TEXT"),
+            col!("category", "TEXT"),
+        ],
+    },
+    ExpectedTable {
+        name: "problem_tags",
+        columns: &[
+            col!("problem_id", "TEXT", pk),
+            col!("tag_id", "TEXT", pk),
+        ],
+    },
+    ExpectedTable {
+        name: "card_tags",
+        columns: &[
+            col!("card_id", "TEXT", pk),
+            col!("tag_id", "TEXT", pk),
+        ],
+    },
+    ExpectedTable {
+        name: "problem_images",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("problem_id", "TEXT", not_null),
+            col!("image_path", "TEXT", not_null),
+            col!("caption", "TEXT"),
+            col!("position", "INTEGER"),
+            col!("created_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "card_images",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("card_id", "TEXT", not_null),
+            col!("image_path", "TEXT", not_null),
+            col!("caption", "TEXT"),
+            col!("position", "INTEGER"),
+            col!("created_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "test_cases",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("problem_id", "TEXT", not_null),
+            col!("input", "TEXT", not_null),
+            col!("expected_output", "TEXT", not_null),
+            col!("is_hidden", "INTEGER"),
+            col!("created_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "work_sessions",
+        columns: &[
+            col!("id", "TEXT", pk),
+            col!("problem_id", "TEXT", not_null),
+            col!("card_id", "TEXT", not_null),
+            col!("session_date", "DATE", not_null),
+            col!("start_timestamp", "DATETIME", not_null),
+            col!("end_timestamp", "DATETIME"),
+            col!("duration_seconds", "INTEGER"),
+            col!("hour_slot", "INTEGER", not_null),
+            col!("created_at", "DATETIME"),
+        ],
+    },
+    ExpectedTable {
+        name: "problems_history",
+        columns: &[
+            col!("history_id", "INTEGER", pk),
+            col!("row_id", "TEXT", not_null),
+            col!("title", "TEXT"),
+            col!("description", "TEXT"),
+            col!("difficulty", "TEXT"),
+            col!("topic", "TEXT"),
+            col!("leetcode_url", "TEXT"),
+            col!("constraints", "TEXT"),
+            col!("examples", "TEXT"),
+            col!("hints", "TEXT"),
+            col!("related_problem_ids", "TEXT"),
+            col!("created_at", "DATETIME"),
+            col!("operation", "TEXT", not_null),
+            col!("changed_at", "DATETIME", not_null),
+        ],
+    },
+    ExpectedTable {
+        name: "cards_history",
+        columns: &[
+            col!("history_id", "INTEGER", pk),
+            col!("row_id", "TEXT", not_null),
+            col!("problem_id", "TEXT"),
+            col!("card_number", "INTEGER"),
+            col!("code", "TEXT"),
+            col!("language", "TEXT"),
+            col!("notes", "TEXT"),
+            col!("status", "TEXT"),
+            col!("total_duration", "INTEGER"),
+            col!("created_at", "DATETIME"),
+            col!("last_modified", "DATETIME"),
+            col!("parent_card_id", "TEXT"),
+            col!("operation", "TEXT", not_null),
+            col!("changed_at", "DATETIME", not_null),
+        ],
+    },
+    ExpectedTable {
+        name: "problem_relations",
+        columns: &[
+            col!("problem_id", "TEXT", pk),
+            col!("related_problem_id", "TEXT", pk),
+        ],
+    },
+    ExpectedTable {
+        name: "leetcode_problem_cache",
+        columns: &[
+            col!("slug", "TEXT", pk),
+            col!("raw_json", "TEXT", not_null),
+            col!("fetched_at", "DATETIME", not_null),
+        ],
+    },
+];
+
+/// A structured report of how the live database's schema differs from
+/// [`EXPECTED_SCHEMA`].
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SchemaDrift {
+    pub missing_tables: Vec<String>,
+    /// `(table, column)` pairs present in `EXPECTED_SCHEMA` but not in the
+    /// live database.
+    pub missing_columns: Vec<(String, String)>,
+    /// `(table, column)` pairs present in the live database but not declared
+    /// in `EXPECTED_SCHEMA`.
+    pub unexpected_columns: Vec<(String, String)>,
+}
+
+impl SchemaDrift {
+    pub fn is_clean(&self) -> bool {
+        self.missing_tables.is_empty() && self.missing_columns.is_empty() && self.unexpected_columns.is_empty()
+    }
+}
@@ -0,0 +1,495 @@
+// Full-text search for problems, backed by an FTS5 virtual table with BM25
+// ranking. Falls back to trigram-overlap + Levenshtein distance when the FTS
+// query comes back empty, so a misspelled query ("dinamic programing") still
+// finds "Dynamic Programming".
+//
+// `problems.id` is a TEXT uuid (not an INTEGER rowid), so the FTS table
+// stores it as a plain UNINDEXED column rather than using FTS5's
+// `content`/`content_rowid` linkage, and keeps itself in sync via triggers
+// instead.
+
+use super::{convert_problem_to_frontend, related_problem_ids_for_conn};
+use crate::models::{FrontendProblem, Problem};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+const CREATE_FTS_SQL: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS problems_fts USING fts5(
+    problem_id UNINDEXED,
+    title,
+    description,
+    topic,
+    tags
+);
+"#;
+
+// Triggers keep problems_fts in sync with problems/tags without the rest of
+// the codebase having to remember to do it. The tags column is recomputed
+// from problem_tags on every problem or tag-membership change rather than
+// incrementally patched - problems rarely get re-tagged often enough for
+// that to matter.
+const CREATE_TRIGGERS_SQL: &str = r#"
+CREATE TRIGGER IF NOT EXISTS problems_fts_ai AFTER INSERT ON problems BEGIN
+    INSERT INTO problems_fts(problem_id, title, description, topic, tags)
+    VALUES (
+        new.id,
+        new.title,
+        new.description,
+        new.topic,
+        (SELECT COALESCE(GROUP_CONCAT(t.name, ' '), '')
+         FROM problem_tags pt JOIN tags t ON t.id = pt.tag_id
+         WHERE pt.problem_id = new.id)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS problems_fts_ad AFTER DELETE ON problems BEGIN
+    DELETE FROM problems_fts WHERE problem_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS problems_fts_au AFTER UPDATE ON problems BEGIN
+    DELETE FROM problems_fts WHERE problem_id = old.id;
+    INSERT INTO problems_fts(problem_id, title, description, topic, tags)
+    VALUES (
+        new.id,
+        new.title,
+        new.description,
+        new.topic,
+        (SELECT COALESCE(GROUP_CONCAT(t.name, ' '), '')
+         FROM problem_tags pt JOIN tags t ON t.id = pt.tag_id
+         WHERE pt.problem_id = new.id)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS problem_tags_fts_ai AFTER INSERT ON problem_tags BEGIN
+    UPDATE problems_fts
+    SET tags = (SELECT COALESCE(GROUP_CONCAT(t.name, ' '), '')
+                FROM problem_tags pt JOIN tags t ON t.id = pt.tag_id
+                WHERE pt.problem_id = new.problem_id)
+    WHERE problem_id = new.problem_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS problem_tags_fts_ad AFTER DELETE ON problem_tags BEGIN
+    UPDATE problems_fts
+    SET tags = (SELECT COALESCE(GROUP_CONCAT(t.name, ' '), '')
+                FROM problem_tags pt JOIN tags t ON t.id = pt.tag_id
+                WHERE pt.problem_id = old.problem_id)
+    WHERE problem_id = old.problem_id;
+END;
+"#;
+
+/// Fields `search_problems` is allowed to match against. Anything else in
+/// the `fields` argument is ignored rather than erroring, since the list is
+/// meant to narrow an otherwise-broad search, not gate it.
+const SEARCHABLE_FIELDS: &[&str] = &["title", "description", "topic", "tags"];
+
+/// Idempotently creates the FTS table and its sync triggers, and backfills
+/// it from `problems` the first time it's created against a database that
+/// already has rows (e.g. migrating an existing install).
+pub fn ensure_fts_index(conn: &Connection) -> anyhow::Result<()> {
+    let fts_existed: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='problems_fts'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    conn.execute_batch(CREATE_FTS_SQL)?;
+    conn.execute_batch(CREATE_TRIGGERS_SQL)?;
+
+    if fts_existed == 0 {
+        let indexed_count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM problems_fts", [], |row| row.get(0))?;
+        if indexed_count == 0 {
+            backfill_fts_index(conn)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn backfill_fts_index(conn: &Connection) -> anyhow::Result<()> {
+    let count = conn.execute(
+        "INSERT INTO problems_fts(problem_id, title, description, topic, tags)
+         SELECT p.id, p.title, p.description, p.topic,
+                COALESCE((SELECT GROUP_CONCAT(t.name, ' ')
+                          FROM problem_tags pt JOIN tags t ON t.id = pt.tag_id
+                          WHERE pt.problem_id = p.id), '')
+         FROM problems p",
+        [],
+    )?;
+    println!("🔧 [Database] Backfilled {} rows into problems_fts", count);
+    Ok(())
+}
+
+/// A problem paired with the relevance score it earned for a search query.
+/// Lower is better for both ranking sources: BM25 scores are negative (more
+/// negative = more relevant), and Levenshtein distance is a plain edit count.
+pub struct ScoredProblem {
+    pub problem: FrontendProblem,
+    pub score: f64,
+}
+
+/// Runs an FTS5 BM25 search across the requested fields, falling back to a
+/// trigram + Levenshtein typo-tolerant match when the FTS query matches
+/// nothing (e.g. the query has a misspelling FTS's prefix/token matching
+/// can't absorb).
+pub fn search_problems(
+    conn: &Connection,
+    query: &str,
+    fields: &[String],
+    limit: i32,
+) -> anyhow::Result<Vec<ScoredProblem>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let active_fields: Vec<&str> = if fields.is_empty() {
+        SEARCHABLE_FIELDS.to_vec()
+    } else {
+        SEARCHABLE_FIELDS
+            .iter()
+            .copied()
+            .filter(|f| fields.iter().any(|requested| requested == f))
+            .collect()
+    };
+    if active_fields.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut results = fts_search(conn, query, &active_fields, limit)?;
+    if results.is_empty() {
+        results = fuzzy_search(conn, query, &active_fields, limit)?;
+    }
+
+    let seen: std::collections::HashSet<String> =
+        results.iter().map(|r| r.problem.id.clone()).collect();
+    for hit in image_label_search(conn, query, limit)? {
+        if results.len() as i32 >= limit {
+            break;
+        }
+        if seen.contains(&hit.problem.id) {
+            continue;
+        }
+        results.push(hit);
+    }
+
+    Ok(results)
+}
+
+/// Supplementary match source for `search_problems`: text `database::ocr`
+/// found inside a card's pasted images (migration 28's `image_labels`
+/// table) but that never made it into `problems_fts`, since FTS5 virtual
+/// tables can't gain a column without a disruptive drop/recreate. Run
+/// unconditionally (not gated by `active_fields`) and merged in as a lower
+/// priority than a title/description/topic/tag match rather than ranked
+/// alongside it, so an image match fills remaining `limit` slots instead of
+/// crowding out a more direct hit.
+fn image_label_search(conn: &Connection, query: &str, limit: i32) -> anyhow::Result<Vec<ScoredProblem>> {
+    let pattern = format!("%{}%", query.to_lowercase());
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.title, p.description, p.difficulty, p.topic, p.leetcode_url,
+                p.constraints, p.hints, p.related_problem_ids, p.created_at
+         FROM image_labels il
+         JOIN card_images ci ON ci.id = il.image_id
+         JOIN cards c ON c.id = ci.card_id
+         JOIN problems p ON p.id = c.problem_id
+         WHERE LOWER(il.label) LIKE ?1 AND p.deleted_at IS NULL
+         GROUP BY p.id
+         ORDER BY MAX(il.confidence) DESC
+         LIMIT ?2",
+    )?;
+
+    let rows = stmt.query_map(params![pattern, limit], |row| {
+        let db_problem = Problem {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            difficulty: row.get(3)?,
+            topic: row.get(4)?,
+            leetcode_url: row.get(5)?,
+            constraints: row.get(6)?,
+            hints: row.get(7)?,
+            related_problem_ids: row.get(8).ok(),
+            created_at: row
+                .get::<_, String>(9)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: Utc::now(),
+        };
+        let related_ids = related_problem_ids_for_conn(conn, &db_problem.id).unwrap_or_default();
+        Ok(ScoredProblem {
+            problem: convert_problem_to_frontend(db_problem, related_ids),
+            score: 0.0,
+        })
+    })?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+fn fts_match_expr(query: &str, active_fields: &[&str]) -> String {
+    // Quote the query so punctuation (parens, colons) in problem titles
+    // doesn't get parsed as FTS5 query syntax, and scope the match to the
+    // requested columns with a column-filter prefix.
+    let escaped = query.replace('"', "\"\"");
+    format!("{{{}}} : \"{}\"*", active_fields.join(" "), escaped)
+}
+
+// Column weights passed to bm25(), in problems_fts's declared column order
+// (problem_id, title, description, topic, tags). Title matches should outrank
+// description matches, and a topic match is a bit more specific than a
+// description match, so they sit between the two.
+const BM25_WEIGHT_PROBLEM_ID: f64 = 0.0;
+const BM25_WEIGHT_TITLE: f64 = 3.0;
+const BM25_WEIGHT_DESCRIPTION: f64 = 1.0;
+const BM25_WEIGHT_TOPIC: f64 = 1.5;
+const BM25_WEIGHT_TAGS: f64 = 1.0;
+
+fn fts_search(
+    conn: &Connection,
+    query: &str,
+    active_fields: &[&str],
+    limit: i32,
+) -> anyhow::Result<Vec<ScoredProblem>> {
+    let match_expr = fts_match_expr(query, active_fields);
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.title, p.description, p.difficulty, p.topic, p.leetcode_url,
+                p.constraints, p.hints, p.related_problem_ids, p.created_at,
+                bm25(problems_fts, ?3, ?4, ?5, ?6, ?7) AS rank
+         FROM problems_fts
+         JOIN problems p ON p.id = problems_fts.problem_id
+         WHERE problems_fts MATCH ?1 AND p.deleted_at IS NULL
+         ORDER BY rank
+         LIMIT ?2",
+    )?;
+
+    let rows = stmt.query_map(
+        params![
+            match_expr,
+            limit,
+            BM25_WEIGHT_PROBLEM_ID,
+            BM25_WEIGHT_TITLE,
+            BM25_WEIGHT_DESCRIPTION,
+            BM25_WEIGHT_TOPIC,
+            BM25_WEIGHT_TAGS,
+        ],
+        |row| {
+            let db_problem = Problem {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                difficulty: row.get(3)?,
+                topic: row.get(4)?,
+                leetcode_url: row.get(5)?,
+                constraints: row.get(6)?,
+                hints: row.get(7)?,
+                related_problem_ids: row.get(8).ok(),
+                created_at: row
+                    .get::<_, String>(9)?
+                    .parse()
+                    .unwrap_or_else(|_| Utc::now()),
+                updated_at: Utc::now(),
+            };
+            let rank: f64 = row.get(10)?;
+            let related_ids = related_problem_ids_for_conn(conn, &db_problem.id).unwrap_or_default();
+            Ok(ScoredProblem {
+                problem: convert_problem_to_frontend(db_problem, related_ids),
+                score: rank,
+            })
+        },
+    )?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// Maximum edit distance a candidate may be from the query and still be
+/// considered a typo-tolerant match, rather than an unrelated result.
+const MAX_EDIT_DISTANCE: usize = 2;
+/// Minimum fraction of the query's trigrams a candidate must share before
+/// we bother running the (more expensive) Levenshtein distance on it.
+const MIN_TRIGRAM_OVERLAP: f64 = 0.3;
+
+fn trigrams(s: &str) -> Vec<String> {
+    let padded = format!("  {}  ", s.to_lowercase());
+    let chars: Vec<char> = padded.chars().collect();
+    if chars.len() < 3 {
+        return vec![padded];
+    }
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+fn trigram_overlap(a: &[String], b_set: &std::collections::HashSet<&str>) -> f64 {
+    if a.is_empty() {
+        return 0.0;
+    }
+    let shared = a.iter().filter(|t| b_set.contains(t.as_str())).count();
+    shared as f64 / a.len() as f64
+}
+
+/// Classic DP Levenshtein distance, with an early exit once every entry in
+/// the current row already exceeds `max_distance` - at that point no
+/// completion of the remaining suffix can bring the final distance back
+/// under the threshold.
+fn levenshtein_distance(a: &str, b: &str, max_distance: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        let mut row_min = curr[0];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1)
+                .min(curr[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+            row_min = row_min.min(curr[j]);
+        }
+        if row_min > max_distance {
+            return row_min;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Distance reduction granted to a candidate that starts with the query
+/// verbatim, so exact prefixes still win ties against equally-close typos.
+const PREFIX_MATCH_BOOST: i64 = 3;
+
+/// Ranks autocomplete candidates (tag names, problem titles, topics) by
+/// Levenshtein distance to `query`, with a prefix-match boost, and returns
+/// the closest `limit` of them. Unlike `search_problems`'s FTS path, this
+/// ranks the whole candidate list rather than falling back only on a miss -
+/// suggestion lists are small enough that there's no need for a fast path.
+pub(crate) fn rank_suggestions(candidates: Vec<String>, query: &str, limit: i32) -> Vec<String> {
+    let query_lower = query.to_lowercase();
+    let mut scored: Vec<(i64, String)> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let candidate_lower = candidate.to_lowercase();
+            let distance = levenshtein_distance(&query_lower, &candidate_lower, usize::MAX) as i64;
+            let score = if !query_lower.is_empty() && candidate_lower.starts_with(&query_lower) {
+                (distance - PREFIX_MATCH_BOOST).max(0)
+            } else {
+                distance
+            };
+            (score, candidate)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+    scored.truncate(limit.max(0) as usize);
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Like `rank_suggestions`, but for tags: ties in edit-distance are broken
+/// by `usage_count` (most-used tag first) instead of alphabetically, so a
+/// popular tag surfaces ahead of an equally-close but rarely-used one.
+pub(crate) fn rank_tag_suggestions(candidates: Vec<(String, i64)>, query: &str, limit: i32) -> Vec<String> {
+    let query_lower = query.to_lowercase();
+    let mut scored: Vec<(i64, i64, String)> = candidates
+        .into_iter()
+        .map(|(candidate, usage_count)| {
+            let candidate_lower = candidate.to_lowercase();
+            let distance = levenshtein_distance(&query_lower, &candidate_lower, usize::MAX) as i64;
+            let score = if !query_lower.is_empty() && candidate_lower.starts_with(&query_lower) {
+                (distance - PREFIX_MATCH_BOOST).max(0)
+            } else {
+                distance
+            };
+            (score, -usage_count, candidate)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)).then_with(|| a.2.cmp(&b.2)));
+    scored.truncate(limit.max(0) as usize);
+    scored.into_iter().map(|(_, _, candidate)| candidate).collect()
+}
+
+fn fuzzy_search(
+    conn: &Connection,
+    query: &str,
+    active_fields: &[&str],
+    limit: i32,
+) -> anyhow::Result<Vec<ScoredProblem>> {
+    let query_trigrams = trigrams(query);
+
+    let mut stmt = conn.prepare(
+        "SELECT id, title, description, difficulty, topic, leetcode_url,
+                constraints, hints, related_problem_ids, created_at
+         FROM problems
+         WHERE deleted_at IS NULL",
+    )?;
+
+    let problems = stmt.query_map([], |row| {
+        Ok(Problem {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            difficulty: row.get(3)?,
+            topic: row.get(4)?,
+            leetcode_url: row.get(5)?,
+            constraints: row.get(6)?,
+            hints: row.get(7)?,
+            related_problem_ids: row.get(8).ok(),
+            created_at: row
+                .get::<_, String>(9)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: Utc::now(),
+        })
+    })?;
+
+    let mut scored = Vec::new();
+    for problem in problems {
+        let problem = problem?;
+        let candidate_terms: Vec<&str> = active_fields
+            .iter()
+            .filter_map(|field| match *field {
+                "title" => Some(problem.title.as_str()),
+                "description" => problem.description.as_deref(),
+                "topic" => Some(problem.topic.as_str()),
+                "tags" => None, // tags live in a join table; title/topic/description cover typo search well
+                _ => None,
+            })
+            .collect();
+
+        let mut best: Option<usize> = None;
+        for term in &candidate_terms {
+            let term_trigrams = trigrams(term);
+            let term_set: std::collections::HashSet<&str> =
+                term_trigrams.iter().map(|t| t.as_str()).collect();
+            if trigram_overlap(&query_trigrams, &term_set) < MIN_TRIGRAM_OVERLAP {
+                continue;
+            }
+            let distance = levenshtein_distance(&query.to_lowercase(), &term.to_lowercase(), MAX_EDIT_DISTANCE);
+            if distance <= MAX_EDIT_DISTANCE {
+                best = Some(best.map_or(distance, |b: usize| b.min(distance)));
+            }
+        }
+
+        if let Some(distance) = best {
+            let related_ids = related_problem_ids_for_conn(conn, &problem.id).unwrap_or_default();
+            scored.push(ScoredProblem {
+                problem: convert_problem_to_frontend(problem, related_ids),
+                score: distance as f64,
+            });
+        }
+    }
+
+    scored.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+    scored.truncate(limit.max(0) as usize);
+    Ok(scored)
+}
@@ -0,0 +1,126 @@
+// Configurable, ordered list of base directories recordings/images (and, in
+// principle, the database file itself) can live under, so large media can
+// sit on a different drive than the small, fast SQLite file. A stored path
+// can be prefixed with the originating root's id (`"<root_id>:<relative>"`)
+// so a root's directory can be relocated without rewriting every row - only
+// `StorageRoots`'s own config needs to change. `DatabaseManager::resolve_media_path`
+// also still understands the legacy unprefixed `dev-data/`/`app-data/`/
+// absolute-path forms earlier rows were written with, by trying each
+// configured root in turn.
+
+use std::path::PathBuf;
+
+/// One configured storage location: `id` is the prefix stored paths use to
+/// reference it, `path` is its current base directory on disk. `priority`
+/// breaks ties when more than one root could take a new file - see
+/// `StorageRoots::pick_for_write` - higher goes first.
+#[derive(Debug, Clone)]
+pub struct StorageRoot {
+    pub id: String,
+    pub path: PathBuf,
+    pub priority: i32,
+}
+
+/// A [`StorageRoot`] as returned to the frontend by `list_storage_roots`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StorageRootRow {
+    pub id: String,
+    pub path: String,
+    pub priority: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Ordered list of [`StorageRoot`]s consulted by `DatabaseManager::resolve_media_path`.
+/// Order only matters for the legacy (un-prefixed) fallback, where every root
+/// is tried in turn until one has the file; a prefixed stored path resolves
+/// directly against its named root.
+#[derive(Debug, Clone)]
+pub struct StorageRoots {
+    roots: Vec<StorageRoot>,
+}
+
+impl StorageRoots {
+    /// Sorts by `priority` descending so callers (`pick_for_write`, the
+    /// legacy fallback scan in `resolve`) don't each need to re-sort - the
+    /// order passed in doesn't matter.
+    pub fn new(mut roots: Vec<StorageRoot>) -> Self {
+        roots.sort_by(|a, b| b.priority.cmp(&a.priority));
+        Self { roots }
+    }
+
+    /// A single root named `"default"`, matching the app's historical
+    /// behavior of keeping everything under one `app_data_dir`.
+    pub fn single(base_dir: PathBuf) -> Self {
+        Self::new(vec![StorageRoot { id: "default".to_string(), path: base_dir, priority: 0 }])
+    }
+
+    pub fn roots(&self) -> &[StorageRoot] {
+        &self.roots
+    }
+
+    fn find(&self, id: &str) -> Option<&StorageRoot> {
+        self.roots.iter().find(|root| root.id == id)
+    }
+
+    /// Whether `id` names one of the currently configured roots - used by
+    /// `verify_storage_roots` to flag a `card_images` row whose stored path
+    /// names a root that's since been removed (see `remove_storage_root`).
+    pub fn contains(&self, id: &str) -> bool {
+        self.find(id).is_some()
+    }
+
+    /// Picks the highest-priority root that currently reports at least
+    /// `min_free_bytes` free, skipping a full (or inaccessible, since a
+    /// brand-new root's directory may not exist yet) one rather than failing
+    /// the whole write. `None` only if every configured root is too full.
+    pub fn pick_for_write(&self, min_free_bytes: u64) -> Option<&StorageRoot> {
+        self.roots.iter().find(|root| {
+            std::fs::create_dir_all(&root.path).ok();
+            fs4::available_space(&root.path)
+                .map(|free| free >= min_free_bytes)
+                .unwrap_or(true)
+        })
+    }
+
+    /// Formats `relative` as a root-relative stored path prefixed with
+    /// `root_id`, e.g. `"default:recordings/take.wav"`.
+    pub fn to_stored_path(root_id: &str, relative: &str) -> String {
+        format!("{}:{}", root_id, relative)
+    }
+
+    /// Resolves a stored path to an absolute filesystem path. Tries, in
+    /// order: a `root_id:relative` prefix naming one of our configured roots;
+    /// an already-absolute path, used as-is; the legacy `dev-data/`/`app-data/`
+    /// prefixes, stripped and resolved against every configured root until one
+    /// exists; and finally the first configured root (or the current
+    /// directory, if none are configured) as a last-resort default.
+    pub fn resolve(&self, stored: &str) -> anyhow::Result<PathBuf> {
+        if let Some((prefix, relative)) = stored.split_once(':') {
+            if let Some(root) = self.find(prefix) {
+                return Ok(root.path.join(relative));
+            }
+        }
+
+        if std::path::Path::new(stored).is_absolute() {
+            return Ok(PathBuf::from(stored));
+        }
+
+        let legacy_relative = if stored.starts_with("dev-data/") || stored.starts_with("app-data/") {
+            stored.splitn(2, '/').nth(1).unwrap_or(stored)
+        } else {
+            stored
+        };
+
+        for root in &self.roots {
+            let candidate = root.path.join(legacy_relative);
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+
+        match self.roots.first() {
+            Some(root) => Ok(root.path.join(legacy_relative)),
+            None => Ok(std::env::current_dir()?.join(legacy_relative)),
+        }
+    }
+}
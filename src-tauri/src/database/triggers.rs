@@ -0,0 +1,315 @@
+// Trigger-based invariant enforcement, for invariants that must hold no
+// matter which code path writes to a table - not just the `DatabaseManager`
+// methods that happen to remember to maintain them by hand. This module is
+// to SQLite triggers what `database/indexes.rs` is to indexes: a managed,
+// named set that can be (re)installed or torn down independent of the
+// migration registry.
+
+use rusqlite::Connection;
+
+/// One trigger `install_triggers` is responsible for keeping present.
+pub struct ManagedTrigger {
+    pub name: &'static str,
+    pub create_sql: &'static str,
+}
+
+// `add_problem_relation`/`remove_problem_relation` already maintain both
+// directions of a relation in a single transaction, but that only holds for
+// writes that go through those two methods. These triggers make the
+// reciprocal edge a property of the table itself: anything that inserts or
+// deletes one `problem_relations` row gets the other side for free. The
+// `INSERT OR IGNORE`/guarded `DELETE` keep a single reciprocal hop from
+// re-triggering itself - by the time the trigger's statement runs, the
+// reverse row either already matches (no-op) or is brought in line, so there
+// is no infinite recursion.
+const RELATIONS_AFTER_INSERT: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_problem_relations_reciprocal_insert
+    AFTER INSERT ON problem_relations
+    WHEN NOT EXISTS (
+        SELECT 1 FROM problem_relations
+        WHERE problem_id = NEW.related_problem_id AND related_problem_id = NEW.problem_id
+    )
+    BEGIN
+        INSERT OR IGNORE INTO problem_relations (problem_id, related_problem_id)
+        VALUES (NEW.related_problem_id, NEW.problem_id);
+    END;
+";
+
+const RELATIONS_AFTER_DELETE: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_problem_relations_reciprocal_delete
+    AFTER DELETE ON problem_relations
+    WHEN EXISTS (
+        SELECT 1 FROM problem_relations
+        WHERE problem_id = OLD.related_problem_id AND related_problem_id = OLD.problem_id
+    )
+    BEGIN
+        DELETE FROM problem_relations
+        WHERE problem_id = OLD.related_problem_id AND related_problem_id = OLD.problem_id;
+    END;
+";
+
+// `tags.usage_count` lets `get_tag_suggestions` rank popular tags first
+// without a COUNT(*) join on every keystroke; these triggers keep it in
+// sync with the actual `problem_tags` rows regardless of what inserted or
+// deleted them. Decrementing is floored at 0 so it can never go negative if
+// a count ever drifted out of sync before these triggers existed.
+const TAGS_USAGE_COUNT_AFTER_INSERT: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_tags_usage_count_insert
+    AFTER INSERT ON problem_tags
+    BEGIN
+        UPDATE tags SET usage_count = usage_count + 1 WHERE id = NEW.tag_id;
+    END;
+";
+
+const TAGS_USAGE_COUNT_AFTER_DELETE: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_tags_usage_count_delete
+    AFTER DELETE ON problem_tags
+    BEGIN
+        UPDATE tags SET usage_count = MAX(usage_count - 1, 0) WHERE id = OLD.tag_id;
+    END;
+";
+
+// `image_blobs.ref_count` tracks how many `problem_images` rows share a
+// given content hash, so `delete_problem_image` knows whether it's safe to
+// unlink the CAS file or another card still needs it. Mirrors
+// `trg_tags_usage_count_insert`/`_delete` exactly, including the
+// floored-at-0 decrement; guarded on `content_hash IS NOT NULL` since rows
+// written before content-addressed storage (see migration 21) leave it
+// unset until `migrate_images_to_cas` backfills them.
+const IMAGE_BLOBS_REF_COUNT_AFTER_INSERT: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_image_blobs_ref_count_insert
+    AFTER INSERT ON problem_images
+    WHEN NEW.content_hash IS NOT NULL
+    BEGIN
+        UPDATE image_blobs SET ref_count = ref_count + 1 WHERE hash = NEW.content_hash;
+    END;
+";
+
+const IMAGE_BLOBS_REF_COUNT_AFTER_DELETE: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_image_blobs_ref_count_delete
+    AFTER DELETE ON problem_images
+    WHEN OLD.content_hash IS NOT NULL
+    BEGIN
+        UPDATE image_blobs SET ref_count = MAX(ref_count - 1, 0) WHERE hash = OLD.content_hash;
+    END;
+";
+
+// `card_images` shares `image_blobs` with `problem_images` (migration 24) -
+// the same content hash can back both a problem's gallery image and a card's
+// pasted diagram, so ref-counting needs its own insert/delete pair mirroring
+// `trg_image_blobs_ref_count_insert`/`_delete` above.
+const CARD_IMAGE_BLOBS_REF_COUNT_AFTER_INSERT: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_card_image_blobs_ref_count_insert
+    AFTER INSERT ON card_images
+    WHEN NEW.content_hash IS NOT NULL
+    BEGIN
+        UPDATE image_blobs SET ref_count = ref_count + 1 WHERE hash = NEW.content_hash;
+    END;
+";
+
+const CARD_IMAGE_BLOBS_REF_COUNT_AFTER_DELETE: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_card_image_blobs_ref_count_delete
+    AFTER DELETE ON card_images
+    WHEN OLD.content_hash IS NOT NULL
+    BEGIN
+        UPDATE image_blobs SET ref_count = MAX(ref_count - 1, 0) WHERE hash = OLD.content_hash;
+    END;
+";
+
+// `end_timer_session` used to compute `duration` from `start_time`/`end_time`
+// by hand and write it in the same statement as `end_time`; this trigger
+// takes over that computation so any other code path that stamps `end_time`
+// (a future bulk-edit command, a direct SQL fixup) gets a correct `duration`
+// for free. It only fires on `end_time` updates, so writing `duration` here
+// doesn't re-trigger itself.
+const TIME_SESSIONS_COMPUTE_DURATION: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_time_sessions_compute_duration
+    AFTER UPDATE OF end_time ON time_sessions
+    WHEN NEW.end_time IS NOT NULL
+    BEGIN
+        UPDATE time_sessions
+        SET duration = CAST((julianday(NEW.end_time) - julianday(NEW.start_time)) * 86400 AS INTEGER)
+        WHERE id = NEW.id;
+    END;
+";
+
+// `end_timer_session`/`delete_time_session` used to add/subtract a session's
+// `duration` into `cards.total_duration` by hand; re-summing from scratch on
+// every insert/update/delete instead means `total_duration` can never drift
+// from what `time_sessions` actually contains, regardless of which code path
+// touched a row.
+const TIME_SESSIONS_RESUM_CARD_TOTAL_DURATION_AFTER_INSERT: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_time_sessions_resum_card_total_duration_insert
+    AFTER INSERT ON time_sessions
+    BEGIN
+        UPDATE cards SET total_duration = (
+            SELECT COALESCE(SUM(duration), 0) FROM time_sessions WHERE card_id = NEW.card_id
+        ) WHERE id = NEW.card_id;
+    END;
+";
+
+const TIME_SESSIONS_RESUM_CARD_TOTAL_DURATION_AFTER_UPDATE: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_time_sessions_resum_card_total_duration_update
+    AFTER UPDATE OF duration ON time_sessions
+    BEGIN
+        UPDATE cards SET total_duration = (
+            SELECT COALESCE(SUM(duration), 0) FROM time_sessions WHERE card_id = NEW.card_id
+        ) WHERE id = NEW.card_id;
+    END;
+";
+
+const TIME_SESSIONS_RESUM_CARD_TOTAL_DURATION_AFTER_DELETE: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_time_sessions_resum_card_total_duration_delete
+    AFTER DELETE ON time_sessions
+    BEGIN
+        UPDATE cards SET total_duration = (
+            SELECT COALESCE(SUM(duration), 0) FROM time_sessions WHERE card_id = OLD.card_id
+        ) WHERE id = OLD.card_id;
+    END;
+";
+
+// `complete_work_session` only stamps `end_timestamp`; this derives
+// `duration_seconds`, `session_date`, and `hour_slot` from it the same way
+// `trg_time_sessions_compute_duration` derives `time_sessions.duration` -
+// it only fires on `end_timestamp` updates, so writing the other three
+// columns here doesn't re-trigger itself. `work_sessions` deliberately has
+// no trigger re-summing `cards.total_duration` the way `time_sessions` does:
+// `time_sessions` already owns that column, and a second table resumming it
+// independently would just make the two fight over the final value.
+const WORK_SESSIONS_DERIVE_FROM_END_TIMESTAMP: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_work_sessions_derive_from_end_timestamp
+    AFTER UPDATE OF end_timestamp ON work_sessions
+    WHEN NEW.end_timestamp IS NOT NULL
+    BEGIN
+        UPDATE work_sessions
+        SET duration_seconds = CAST((julianday(NEW.end_timestamp) - julianday(NEW.start_timestamp)) * 86400 AS INTEGER),
+            session_date = date(NEW.start_timestamp),
+            hour_slot = CAST(strftime('%H', NEW.start_timestamp) AS INTEGER)
+        WHERE id = NEW.id;
+    END;
+";
+
+// `update_card`/`batch_update_card` used to stamp `last_modified` by hand
+// alongside every column they touched - easy to forget on whatever update
+// path comes next. `WHEN NEW.last_modified IS OLD.last_modified` guards
+// against infinite recursion: the trigger's own `UPDATE` changes
+// `last_modified`, so it no longer matches the WHEN clause the second time
+// around.
+const CARDS_STAMP_LAST_MODIFIED: &str = "
+    CREATE TRIGGER IF NOT EXISTS trg_cards_stamp_last_modified
+    AFTER UPDATE ON cards
+    WHEN NEW.last_modified IS OLD.last_modified
+    BEGIN
+        UPDATE cards SET last_modified = CURRENT_TIMESTAMP WHERE id = NEW.id;
+    END;
+";
+
+pub const MANAGED_TRIGGERS: &[ManagedTrigger] = &[
+    ManagedTrigger {
+        name: "trg_problem_relations_reciprocal_insert",
+        create_sql: RELATIONS_AFTER_INSERT,
+    },
+    ManagedTrigger {
+        name: "trg_problem_relations_reciprocal_delete",
+        create_sql: RELATIONS_AFTER_DELETE,
+    },
+    ManagedTrigger {
+        name: "trg_tags_usage_count_insert",
+        create_sql: TAGS_USAGE_COUNT_AFTER_INSERT,
+    },
+    ManagedTrigger {
+        name: "trg_tags_usage_count_delete",
+        create_sql: TAGS_USAGE_COUNT_AFTER_DELETE,
+    },
+    ManagedTrigger {
+        name: "trg_time_sessions_compute_duration",
+        create_sql: TIME_SESSIONS_COMPUTE_DURATION,
+    },
+    ManagedTrigger {
+        name: "trg_time_sessions_resum_card_total_duration_insert",
+        create_sql: TIME_SESSIONS_RESUM_CARD_TOTAL_DURATION_AFTER_INSERT,
+    },
+    ManagedTrigger {
+        name: "trg_time_sessions_resum_card_total_duration_update",
+        create_sql: TIME_SESSIONS_RESUM_CARD_TOTAL_DURATION_AFTER_UPDATE,
+    },
+    ManagedTrigger {
+        name: "trg_time_sessions_resum_card_total_duration_delete",
+        create_sql: TIME_SESSIONS_RESUM_CARD_TOTAL_DURATION_AFTER_DELETE,
+    },
+    ManagedTrigger {
+        name: "trg_cards_stamp_last_modified",
+        create_sql: CARDS_STAMP_LAST_MODIFIED,
+    },
+    ManagedTrigger {
+        name: "trg_work_sessions_derive_from_end_timestamp",
+        create_sql: WORK_SESSIONS_DERIVE_FROM_END_TIMESTAMP,
+    },
+    ManagedTrigger {
+        name: "trg_image_blobs_ref_count_insert",
+        create_sql: IMAGE_BLOBS_REF_COUNT_AFTER_INSERT,
+    },
+    ManagedTrigger {
+        name: "trg_image_blobs_ref_count_delete",
+        create_sql: IMAGE_BLOBS_REF_COUNT_AFTER_DELETE,
+    },
+    ManagedTrigger {
+        name: "trg_card_image_blobs_ref_count_insert",
+        create_sql: CARD_IMAGE_BLOBS_REF_COUNT_AFTER_INSERT,
+    },
+    ManagedTrigger {
+        name: "trg_card_image_blobs_ref_count_delete",
+        create_sql: CARD_IMAGE_BLOBS_REF_COUNT_AFTER_DELETE,
+    },
+];
+
+/// Idempotently (re)installs every trigger in `MANAGED_TRIGGERS`, then
+/// backfills `tags.usage_count` from the current `problem_tags` rows so
+/// counts are correct even if triggers are being installed on a database
+/// that predates them. Safe to call on every startup.
+pub fn install_triggers(conn: &Connection) -> anyhow::Result<()> {
+    for trigger in MANAGED_TRIGGERS {
+        conn.execute_batch(trigger.create_sql)?;
+    }
+    conn.execute_batch(
+        "UPDATE tags SET usage_count = (
+            SELECT COUNT(*) FROM problem_tags WHERE problem_tags.tag_id = tags.id
+        );",
+    )?;
+    conn.execute_batch(
+        "UPDATE image_blobs SET ref_count = (
+            SELECT COUNT(*) FROM problem_images WHERE problem_images.content_hash = image_blobs.hash
+        ) + (
+            SELECT COUNT(*) FROM card_images WHERE card_images.content_hash = image_blobs.hash
+        );",
+    )?;
+    Ok(())
+}
+
+/// Drops every trigger in `MANAGED_TRIGGERS`. An invariant that a
+/// `DatabaseManager` method also maintains by hand (e.g.
+/// `add_problem_relation`'s explicit two-direction inserts) still holds for
+/// writes through that method; the rest (tag usage counts, image blob ref
+/// counts, session durations and card totals) are not kept up to date until
+/// `install_triggers` runs again.
+pub fn drop_triggers(conn: &Connection) -> anyhow::Result<()> {
+    for trigger in MANAGED_TRIGGERS {
+        conn.execute(&format!("DROP TRIGGER IF EXISTS {}", trigger.name), [])?;
+    }
+    Ok(())
+}
+
+/// Lists the names of triggers in `MANAGED_TRIGGERS` that are currently
+/// installed, for inspection (e.g. a `list_triggers` Tauri command or CLI
+/// subcommand to show what's active).
+pub fn list_triggers(conn: &Connection) -> anyhow::Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'trigger' AND name = ?1")?;
+    let mut installed = Vec::new();
+    for trigger in MANAGED_TRIGGERS {
+        let exists = stmt.exists([trigger.name])?;
+        if exists {
+            installed.push(trigger.name.to_string());
+        }
+    }
+    Ok(installed)
+}
@@ -0,0 +1,49 @@
+// A single tagged event enum pushed to the frontend over one Tauri event
+// channel, instead of each subsystem (timer, recording, device list, import)
+// inventing its own ad-hoc event name/payload. The frontend matches on
+// `type`, the same way a chat/race protocol dispatches on a discriminant.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::AudioDeviceList;
+
+/// Channel name every `AppEvent` is emitted on.
+pub const APP_EVENT_CHANNEL: &str = "app-event";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum AppEvent {
+    TimerTick {
+        session_id: String,
+        elapsed_time: i32,
+    },
+    RecordingProgress {
+        recording_id: String,
+        elapsed_recording_time: i32,
+        is_paused: bool,
+    },
+    DeviceListChanged(AudioDeviceList),
+    /// The local playback started by `commands::playback::play_recording` has
+    /// reached the end of the file.
+    PlaybackFinished {
+        filepath: String,
+    },
+    ImportProgress {
+        imported: i32,
+        skipped: i32,
+        errors: i32,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Emits an `AppEvent` on `APP_EVENT_CHANNEL`. Emission failures are logged
+/// rather than propagated - a dropped UI update shouldn't fail the command
+/// that triggered it.
+pub fn emit(app_handle: &AppHandle, event: AppEvent) {
+    if let Err(e) = app_handle.emit(APP_EVENT_CHANNEL, &event) {
+        eprintln!("⚠️ Failed to emit app event: {}", e);
+    }
+}
@@ -0,0 +1,164 @@
+// Turns imported problems into a spaced-repetition flashcard deck and back,
+// so a learner can study them in any external SRS tool. The deck format is
+// deliberately simple and line-based, matching common flashcard import
+// tools: `#` lines are comments, blank lines are ignored, and each card is
+// one `- front: back` line.
+
+use crate::import::ParseError;
+use crate::models::ParsedProblem;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlashCard {
+    pub front: String,
+    pub back: String,
+}
+
+/// Renders `problems` as a deck: one card testing the bare title against the
+/// description, followed by one card per hint as a progressive reveal
+/// (`(hint 1)`, `(hint 2)`, ...) for learners who want escalating help.
+pub fn problems_to_deck(problems: &[ParsedProblem]) -> String {
+    let mut deck = String::new();
+
+    for problem in problems {
+        deck.push_str(&format!("# {}\n", problem.title));
+        deck.push_str(&format!(
+            "- {}: {}\n",
+            escape(&problem.title),
+            escape(&problem.description)
+        ));
+        for (index, hint) in problem.hints.iter().enumerate() {
+            deck.push_str(&format!(
+                "- {} (hint {}): {}\n",
+                escape(&problem.title),
+                index + 1,
+                escape(hint)
+            ));
+        }
+        deck.push('\n');
+    }
+
+    deck
+}
+
+/// Parses a deck produced by `problems_to_deck` (or hand-written in the same
+/// format) back into its cards. Fails on the first malformed card line,
+/// anchored to its exact line/column via `ParseError`.
+pub fn read_deck(source: &str) -> Result<Vec<FlashCard>, ParseError> {
+    let mut cards = Vec::new();
+    let mut offset = 0usize;
+
+    for raw_line in source.split_inclusive('\n') {
+        let line_span = offset..offset + raw_line.trim_end_matches(['\n', '\r']).len();
+        offset += raw_line.len();
+        let trimmed = raw_line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let rest = trimmed.strip_prefix('-').ok_or_else(|| {
+            ParseError::new(
+                source,
+                line_span.clone(),
+                format!("Expected a card line starting with '-': '{}'", trimmed),
+            )
+        })?;
+        let rest = rest.trim();
+
+        let colon_pos = rest.find(':').ok_or_else(|| {
+            ParseError::new(
+                source,
+                line_span.clone(),
+                "Card line missing ':' separating front from back",
+            )
+        })?;
+
+        let front = unescape(rest[..colon_pos].trim());
+        let back = unescape(rest[colon_pos + 1..].trim());
+        if front.is_empty() {
+            return Err(ParseError::new(source, line_span, "Card front cannot be empty"));
+        }
+
+        cards.push(FlashCard { front, back });
+    }
+
+    Ok(cards)
+}
+
+/// Escapes newlines so a card's front/back always fits on one deck line.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('\\') => out.push('\\'),
+                Some(other) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(title: &str, description: &str, hints: &[&str]) -> ParsedProblem {
+        let mut problem = ParsedProblem::new();
+        problem.title = title.to_string();
+        problem.description = description.to_string();
+        problem.hints = hints.iter().map(|h| h.to_string()).collect();
+        problem
+    }
+
+    #[test]
+    fn renders_a_card_per_problem_and_hint() {
+        let problems = vec![problem("Two Sum", "Find two numbers", &["Use a hash map"])];
+        let deck = problems_to_deck(&problems);
+        let cards = read_deck(&deck).unwrap();
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].front, "Two Sum");
+        assert_eq!(cards[0].back, "Find two numbers");
+        assert_eq!(cards[1].front, "Two Sum (hint 1)");
+        assert_eq!(cards[1].back, "Use a hash map");
+    }
+
+    #[test]
+    fn round_trips_multiline_description() {
+        let problems = vec![problem("A", "Line one\nLine two", &[])];
+        let deck = problems_to_deck(&problems);
+        let cards = read_deck(&deck).unwrap();
+        assert_eq!(cards[0].back, "Line one\nLine two");
+    }
+
+    #[test]
+    fn ignores_comments_and_blank_lines() {
+        let deck = "# A deck\n\n- front: back\n";
+        let cards = read_deck(deck).unwrap();
+        assert_eq!(cards, vec![FlashCard { front: "front".to_string(), back: "back".to_string() }]);
+    }
+
+    #[test]
+    fn rejects_line_missing_dash_prefix() {
+        let err = read_deck("not a card line").unwrap_err();
+        assert_eq!(err.line, 1);
+    }
+
+    #[test]
+    fn rejects_card_missing_colon() {
+        let err = read_deck("- no separator here").unwrap_err();
+        assert_eq!(err.line, 1);
+    }
+}
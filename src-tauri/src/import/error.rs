@@ -0,0 +1,70 @@
+// Rich parse diagnostics for the TXT import format. Unlike the command-layer
+// `ImportError` (which reports per-problem outcomes to the frontend), this
+// type carries a precise source location so a caller can point at the exact
+// offending line/column within the raw import text.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number, matching the flashcard deck reader's convention.
+    pub line: usize,
+    /// 1-based column number (byte offset within the line, not a grapheme count).
+    pub col: usize,
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(source: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        let (line, col) = line_col(source, span.start);
+        Self {
+            line,
+            col,
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+/// Converts a byte offset into `source` to a 1-based (line, col) pair.
+pub fn line_col(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let mut line = 1usize;
+    let mut last_newline = None;
+    for (i, byte) in source.as_bytes()[..offset].iter().enumerate() {
+        if *byte == b'\n' {
+            line += 1;
+            last_newline = Some(i);
+        }
+    }
+    let col = match last_newline {
+        Some(i) => offset - i,
+        None => offset + 1,
+    };
+    (line, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_line_starts_at_col_1() {
+        assert_eq!(line_col("abc", 0), (1, 1));
+    }
+
+    #[test]
+    fn offset_on_second_line() {
+        let source = "first\nsecond";
+        assert_eq!(line_col(source, 6), (2, 1));
+    }
+
+    #[test]
+    fn parse_error_computes_location_from_span() {
+        let source = "title: a\ndescription:";
+        let err = ParseError::new(source, 9..21, "Description cannot be empty");
+        assert_eq!(err.line, 2);
+        assert_eq!(err.col, 1);
+    }
+}
@@ -0,0 +1,11 @@
+// Problem-import subsystem: a span-tracking tokenizer over the raw TXT
+// import format, plus (in later commits) the build/segmentation/error
+// layers on top of it. Kept separate from `commands::database` so the
+// lexer can be unit-tested and reused independently of problem assembly.
+
+pub mod error;
+pub mod segment;
+pub mod tokenizer;
+pub mod verbatim;
+
+pub use error::ParseError;
@@ -0,0 +1,130 @@
+// Splits one TXT import file into multiple problem-sized chunks before
+// field parsing runs, so a user can paste a whole batch of problems in one
+// file instead of importing them one at a time. Modeled as a two-phase
+// pipeline, like a PSPP-style segmenter: this pass only finds boundaries
+// and returns `(start, end)` byte ranges into the original source; the
+// actual field parsing still happens per-segment, independently, in
+// `commands::database`.
+//
+// A new segment starts at a top-level problem boundary:
+//   - a bare Markdown heading (`# Two Sum`) with no `field:` colon,
+//   - a `---` (or longer) separator line, or
+//   - a `title:` field reappearing after one has already been seen.
+// Heading and separator lines are boundary markers only and are excluded
+// from the segment they introduce; a reappearing `title:` line is kept,
+// since it's part of the next problem's own content.
+
+use std::ops::Range;
+
+fn is_heading_boundary(trimmed: &str) -> bool {
+    match trimmed.strip_prefix('#') {
+        Some(rest) => {
+            let rest = rest.trim_start_matches('#').trim_start();
+            !rest.is_empty() && !rest.contains(':')
+        }
+        None => false,
+    }
+}
+
+fn is_separator_boundary(trimmed: &str) -> bool {
+    trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-')
+}
+
+fn is_title_field(trimmed: &str) -> bool {
+    let cleaned = trimmed.trim_start_matches('#').trim();
+    match cleaned.find(':') {
+        Some(colon_pos) => cleaned[..colon_pos].trim().eq_ignore_ascii_case("title"),
+        None => false,
+    }
+}
+
+/// Scans `source` for top-level problem boundaries and returns the byte
+/// range of each resulting segment. Empty or whitespace-only segments are
+/// dropped.
+pub fn segment(source: &str) -> Vec<Range<usize>> {
+    let mut segments = Vec::new();
+    let mut current_start = 0usize;
+    let mut seen_title = false;
+    let mut offset = 0usize;
+
+    let mut push_if_non_blank = |segments: &mut Vec<Range<usize>>, range: Range<usize>| {
+        if !source[range.clone()].trim().is_empty() {
+            segments.push(range);
+        }
+    };
+
+    for raw_line in source.split_inclusive('\n') {
+        let line_start = offset;
+        let trimmed = raw_line.trim();
+        offset += raw_line.len();
+
+        if is_heading_boundary(trimmed) || is_separator_boundary(trimmed) {
+            push_if_non_blank(&mut segments, current_start..line_start);
+            current_start = offset;
+            seen_title = false;
+            continue;
+        }
+
+        if is_title_field(trimmed) {
+            if seen_title {
+                push_if_non_blank(&mut segments, current_start..line_start);
+                current_start = line_start;
+            }
+            seen_title = true;
+        }
+    }
+
+    push_if_non_blank(&mut segments, current_start..offset);
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_problem_is_one_segment() {
+        let source = "title: Two Sum\ndescription: d\ndifficulty: Easy";
+        assert_eq!(segment(source), vec![0..source.len()]);
+    }
+
+    #[test]
+    fn splits_on_reappearing_title_field() {
+        let source = "title: A\ndifficulty: Easy\ntitle: B\ndifficulty: Hard";
+        let segments = segment(source);
+        assert_eq!(segments.len(), 2);
+        assert!(source[segments[0].clone()].starts_with("title: A"));
+        assert!(source[segments[1].clone()].starts_with("title: B"));
+    }
+
+    #[test]
+    fn splits_on_dash_separator() {
+        let source = "title: A\ndifficulty: Easy\n---\ntitle: B\ndifficulty: Hard";
+        let segments = segment(source);
+        assert_eq!(segments.len(), 2);
+        assert!(!source[segments[0].clone()].contains("---"));
+    }
+
+    #[test]
+    fn splits_on_markdown_heading() {
+        let source = "# Problem One\ntitle: A\ndifficulty: Easy\n# Problem Two\ntitle: B";
+        let segments = segment(source);
+        assert_eq!(segments.len(), 2);
+        assert!(!source[segments[0].clone()].contains("# Problem One"));
+    }
+
+    #[test]
+    fn drops_whitespace_only_segments() {
+        let source = "---\n\n   \n---\ntitle: A\ndifficulty: Easy";
+        let segments = segment(source);
+        assert_eq!(segments.len(), 1);
+    }
+
+    #[test]
+    fn field_header_with_colon_is_not_a_heading_boundary() {
+        // "# title: Two Sum" is a field header (leading '#' stripped by the
+        // field parser), not a bare heading - it must not split the segment.
+        let source = "# title: Two Sum\ndescription: d\ndifficulty: Easy";
+        assert_eq!(segment(source), vec![0..source.len()]);
+    }
+}
@@ -0,0 +1,284 @@
+// A pure, reusable tokenizer for the problem import format, modeled after
+// rustc_lexer: a `Cursor` walks the raw `&str` and emits a flat `Vec<Token>`
+// where each token is just `{ kind, start, len, flags }` - no owned text, no
+// errors. `FLAG_MALFORMED` is declared but `tokenize` sets it on no token;
+// a line that fits no other kind comes out as `Unknown`. Classification
+// into `ParsedProblem`s happens in a later build stage that walks this
+// token stream.
+
+use std::ops::Range;
+
+pub const FLAG_MALFORMED: u32 = 1 << 0;
+/// Set on a `FieldHeader` token whose line carries a value after the colon
+/// (`field: value`) rather than leaving the value for following lines.
+pub const FLAG_HAS_IMMEDIATE_VALUE: u32 = 1 << 1;
+/// Set when the line's leading whitespace is large enough that it reads as
+/// an intentionally indented sub-item rather than a wrapped continuation.
+pub const FLAG_SIGNIFICANT_INDENT: u32 = 1 << 2;
+/// Set on a `BulletItem` whose content is nothing but dash characters -
+/// a stray separator line, not a real item.
+pub const FLAG_DASH_ONLY: u32 = 1 << 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A `field:` or `field: value` header line.
+    FieldHeader,
+    /// A line opening with a bullet marker (`-`, `•`, `*`, ...).
+    BulletItem,
+    /// A line opening with a numeric prefix (`1.`, `2.`, ...).
+    NumberedItem,
+    /// A line that reads as the wrapped continuation of the previous item.
+    ContinuationLine,
+    /// A blank (whitespace-only) line.
+    Blank,
+    /// Anything else - a bare first line of a list, free text, etc.
+    Unknown,
+}
+
+/// One line of the source, classified but not copied out of it. Callers
+/// slice the original `&str` with `span()`/`text()` when they need content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub len: usize,
+    pub flags: u32,
+}
+
+impl Token {
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.start + self.len
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span()]
+    }
+
+    pub fn has_flag(&self, flag: u32) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+/// The recognized bullet marker characters. `*` is a bullet but not a
+/// "dash" for `FLAG_DASH_ONLY` purposes, which uses its own narrower set.
+const BULLET_CHARS: [char; 10] = ['-', '–', '—', '−', '∙', '•', '◦', '▪', '▫', '*'];
+
+/// Field names `is_field_header_line` accepts before the colon, compared
+/// lowercased; any name starting with `example` is accepted as well.
+const KNOWN_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "difficulty",
+    "topics",
+    "leetcode_url",
+    "leetcode url",
+    "constraints",
+    "hints",
+    "tags",
+];
+
+fn is_field_header_line(trimmed: &str) -> bool {
+    let cleaned = trimmed.trim_start_matches('#').trim();
+    match cleaned.find(':') {
+        Some(colon_pos) => {
+            let field = cleaned[..colon_pos].trim().to_lowercase();
+            KNOWN_FIELDS.contains(&field.as_str()) || field.starts_with("example")
+        }
+        None => false,
+    }
+}
+
+fn line_has_immediate_value(trimmed: &str) -> bool {
+    let cleaned = trimmed.trim_start_matches('#').trim();
+    match cleaned.find(':') {
+        Some(colon_pos) => !cleaned[colon_pos + 1..].trim().is_empty(),
+        None => false,
+    }
+}
+
+/// Returns the byte length of the bullet marker + following separator
+/// (e.g. `"- "` or `"• "`) if `trimmed` opens with one.
+pub fn bullet_prefix_len(trimmed: &str) -> Option<usize> {
+    let mut chars = trimmed.chars();
+    let first = chars.next()?;
+    if !BULLET_CHARS.contains(&first) {
+        return None;
+    }
+    let marker_len = first.len_utf8();
+    match chars.next() {
+        Some(' ') => Some(marker_len + 1),
+        Some(_) if trimmed.len() > marker_len => Some(marker_len),
+        _ => None,
+    }
+}
+
+pub fn is_numbered_item(trimmed: &str) -> bool {
+    if let Some(pos) = trimmed.find('.') {
+        if pos > 0 {
+            let number_part = &trimmed[..pos];
+            return number_part.chars().all(|c| c.is_ascii_digit()) && trimmed.len() > pos + 1;
+        }
+    }
+    false
+}
+
+/// Byte offset of the first character after a numbered item's `N.` prefix.
+pub fn numbered_prefix_len(trimmed: &str) -> Option<usize> {
+    if is_numbered_item(trimmed) {
+        trimmed.find('.').map(|pos| pos + 1)
+    } else {
+        None
+    }
+}
+
+pub fn is_dash_only(text: &str) -> bool {
+    let dash_chars = ['-', '–', '—', '−'];
+    !text.is_empty() && text.chars().all(|c| dash_chars.contains(&c) || c.is_whitespace())
+}
+
+fn should_treat_as_continuation(trimmed: &str, leading_whitespace: usize) -> bool {
+    if trimmed.len() > 50
+        && trimmed.contains(' ')
+        && trimmed.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+    {
+        return false;
+    }
+
+    if leading_whitespace > 2 {
+        return false;
+    }
+
+    const SENTENCE_STARTERS: [&str; 10] = [
+        "The", "This", "When", "If", "Use", "Keep", "Remember", "Consider", "Try", "Again",
+    ];
+    for starter in SENTENCE_STARTERS {
+        if trimmed.starts_with(&format!("{} ", starter)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Walks the raw import text and emits one token per line, in source order.
+/// Spans cover the line's content without its trailing newline.
+pub struct Cursor<'a> {
+    source: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self { source }
+    }
+
+    pub fn tokenize(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut offset = 0usize;
+
+        for raw_line in self.source.split_inclusive('\n') {
+            let line = raw_line.trim_end_matches(['\n', '\r']);
+            let start = offset;
+            let len = line.len();
+            offset += raw_line.len();
+
+            let trimmed = line.trim();
+            let leading_whitespace = line.len() - line.trim_start().len();
+
+            let mut flags = 0u32;
+            if leading_whitespace > 2 {
+                flags |= FLAG_SIGNIFICANT_INDENT;
+            }
+
+            let kind = if trimmed.is_empty() {
+                TokenKind::Blank
+            } else if is_field_header_line(trimmed) {
+                if line_has_immediate_value(trimmed) {
+                    flags |= FLAG_HAS_IMMEDIATE_VALUE;
+                }
+                TokenKind::FieldHeader
+            } else if bullet_prefix_len(trimmed).is_some() {
+                if is_dash_only(trimmed) {
+                    flags |= FLAG_DASH_ONLY;
+                }
+                TokenKind::BulletItem
+            } else if is_numbered_item(trimmed) {
+                TokenKind::NumberedItem
+            } else if should_treat_as_continuation(trimmed, leading_whitespace) {
+                TokenKind::ContinuationLine
+            } else {
+                TokenKind::Unknown
+            };
+
+            tokens.push(Token {
+                kind,
+                start,
+                len,
+                flags,
+            });
+        }
+
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_field_header_with_immediate_value() {
+        let tokens = Cursor::new("title: Two Sum").tokenize();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind, TokenKind::FieldHeader);
+        assert!(tokens[0].has_flag(FLAG_HAS_IMMEDIATE_VALUE));
+    }
+
+    #[test]
+    fn classifies_field_header_without_immediate_value() {
+        let tokens = Cursor::new("description:").tokenize();
+        assert_eq!(tokens[0].kind, TokenKind::FieldHeader);
+        assert!(!tokens[0].has_flag(FLAG_HAS_IMMEDIATE_VALUE));
+    }
+
+    #[test]
+    fn classifies_bullet_and_numbered_items() {
+        let source = "- first\n1. second";
+        let tokens = Cursor::new(source).tokenize();
+        assert_eq!(tokens[0].kind, TokenKind::BulletItem);
+        assert_eq!(tokens[1].kind, TokenKind::NumberedItem);
+    }
+
+    #[test]
+    fn classifies_blank_lines() {
+        let tokens = Cursor::new("a\n\nb").tokenize();
+        assert_eq!(tokens[1].kind, TokenKind::Blank);
+    }
+
+    #[test]
+    fn flags_dash_only_bullet_lines() {
+        let tokens = Cursor::new("---").tokenize();
+        assert_eq!(tokens[0].kind, TokenKind::BulletItem);
+        assert!(tokens[0].has_flag(FLAG_DASH_ONLY));
+    }
+
+    #[test]
+    fn token_spans_slice_back_to_original_text() {
+        let source = "title: Two Sum\nhints:";
+        let tokens = Cursor::new(source).tokenize();
+        assert_eq!(tokens[0].text(source), "title: Two Sum");
+        assert_eq!(tokens[1].text(source), "hints:");
+    }
+
+    #[test]
+    fn treats_short_unindented_line_as_continuation() {
+        let tokens = Cursor::new("foo bar").tokenize();
+        assert_eq!(tokens[0].kind, TokenKind::ContinuationLine);
+    }
+
+    #[test]
+    fn significant_indent_breaks_continuation_detection() {
+        let tokens = Cursor::new("    indented text here").tokenize();
+        assert!(tokens[0].has_flag(FLAG_SIGNIFICANT_INDENT));
+        assert_eq!(tokens[0].kind, TokenKind::Unknown);
+    }
+}
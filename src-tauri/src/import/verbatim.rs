@@ -0,0 +1,106 @@
+// Detects delimited verbatim blocks in the TXT import format - Markdown
+// fences (```` ```lang ... ``` ````) or org-style `#+BEGIN_EXAMPLE ...
+// #+END_EXAMPLE` - so callers can capture their body untouched instead of
+// running it through list/bullet parsing. The closing line is found by
+// trimmed comparison against the expected end marker, the same approach
+// orgize's block parser uses.
+
+/// The delimiter style of a verbatim block, carrying whatever is needed to
+/// recognize its matching close line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockOpen {
+    /// A Markdown fence (```` ``` ```` or ```` ~~~ ````), with an optional
+    /// language info string.
+    Fence {
+        marker: char,
+        language: Option<String>,
+    },
+    /// An org-style `#+BEGIN_EXAMPLE` block.
+    OrgExample,
+}
+
+/// If `trimmed` opens a verbatim block, returns its `BlockOpen`.
+pub fn detect_open(trimmed: &str) -> Option<BlockOpen> {
+    if let Some(rest) = trimmed.strip_prefix("```") {
+        let language = rest.trim();
+        return Some(BlockOpen::Fence {
+            marker: '`',
+            language: if language.is_empty() { None } else { Some(language.to_string()) },
+        });
+    }
+    if let Some(rest) = trimmed.strip_prefix("~~~") {
+        let language = rest.trim();
+        return Some(BlockOpen::Fence {
+            marker: '~',
+            language: if language.is_empty() { None } else { Some(language.to_string()) },
+        });
+    }
+    if trimmed.to_uppercase().starts_with("#+BEGIN_EXAMPLE") {
+        return Some(BlockOpen::OrgExample);
+    }
+    None
+}
+
+/// Returns true if `trimmed` is the matching close line for `open`.
+pub fn is_close(trimmed: &str, open: &BlockOpen) -> bool {
+    match open {
+        BlockOpen::Fence { marker, .. } => {
+            !trimmed.is_empty() && trimmed.chars().all(|c| c == *marker) && trimmed.len() >= 3
+        }
+        BlockOpen::OrgExample => trimmed.to_uppercase().starts_with("#+END_EXAMPLE"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_fence_with_language() {
+        let open = detect_open("```python").unwrap();
+        assert_eq!(
+            open,
+            BlockOpen::Fence {
+                marker: '`',
+                language: Some("python".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn detects_bare_fence() {
+        let open = detect_open("```").unwrap();
+        assert_eq!(
+            open,
+            BlockOpen::Fence {
+                marker: '`',
+                language: None
+            }
+        );
+    }
+
+    #[test]
+    fn matches_closing_fence() {
+        let open = detect_open("```rust").unwrap();
+        assert!(is_close("```", &open));
+        assert!(!is_close("``", &open));
+    }
+
+    #[test]
+    fn detects_org_example_block() {
+        let open = detect_open("#+BEGIN_EXAMPLE").unwrap();
+        assert_eq!(open, BlockOpen::OrgExample);
+        assert!(is_close("#+END_EXAMPLE", &open));
+    }
+
+    #[test]
+    fn org_example_match_is_case_insensitive() {
+        let open = detect_open("#+begin_example").unwrap();
+        assert!(is_close("#+end_example", &open));
+    }
+
+    #[test]
+    fn plain_text_is_not_a_block_open() {
+        assert!(detect_open("just some text").is_none());
+    }
+}
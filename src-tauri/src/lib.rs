@@ -0,0 +1,19 @@
+//! Library surface for code that needs direct access to the database layer
+//! without going through Tauri's IPC commands - currently just
+//! `src/bin/dsa_cli.rs`, the headless companion CLI. Re-declares the same
+//! module files `main.rs` does (rather than `main.rs` depending on this
+//! crate) so the existing Tauri binary is untouched. `database` and `models`
+//! reach `path_resolver`, `storage_format` and `clock` through `crate::`, so
+//! those are declared here as well; `models` and `path_resolver` use Tauri
+//! types (`AppHandle`), so this lib crate links Tauri just as the binary does.
+
+#[path = "clock.rs"]
+pub mod clock;
+#[path = "database/mod.rs"]
+pub mod database;
+#[path = "models.rs"]
+pub mod models;
+#[path = "path_resolver.rs"]
+pub mod path_resolver;
+#[path = "storage_format.rs"]
+pub mod storage_format;
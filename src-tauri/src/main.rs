@@ -1,10 +1,18 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod blurhash;
+mod clock;
 mod commands;
+mod data_archive;
 mod database;
+mod events;
+mod flashcards;
+mod import;
 mod models;
 mod path_resolver;
+mod storage;
+mod storage_format;
 
 use tauri::Manager;
 use std::sync::{Arc, Mutex};
@@ -84,17 +92,50 @@ async fn main() {
             commands::database::init_database,
             commands::database::connect_database,
             commands::database::create_problem,
+            commands::database::apply_batch,
             commands::database::get_problems,
             commands::database::get_problem_by_id,
             commands::database::update_problem,
             commands::database::delete_problem,
+            commands::database::soft_delete_problem,
+            commands::database::restore_problem,
+            commands::database::list_deleted_problems,
+            commands::database::purge_deleted_before,
             commands::database::get_problem_delete_stats,
             commands::database::create_card,
             commands::database::get_cards_for_problem,
             commands::database::get_card_by_id,
             commands::database::update_card,
             commands::database::delete_card,
+            commands::database::get_problem_history,
+            commands::database::get_card_history,
+            commands::database::restore_card,
+            commands::database::restore_card_field,
             commands::database::get_database_stats,
+            commands::database::rebuild_indexes,
+            commands::database::create_index,
+            commands::database::drop_index,
+            commands::database::install_triggers,
+            commands::database::drop_triggers,
+            commands::database::list_triggers,
+            commands::database::check_and_repair_database,
+            commands::database::reconcile_media,
+            commands::database::prune_recordings,
+            commands::database::migrate_images_to_cas,
+            commands::database::configure_storage_roots,
+            commands::database::add_storage_root,
+            commands::database::list_storage_roots,
+            commands::database::remove_storage_root,
+            commands::database::verify_storage_roots,
+            commands::database::open_encrypted_database,
+            commands::database::change_database_passphrase,
+            commands::database::encrypt_database,
+            commands::database::get_schema_version,
+            commands::database::get_latest_known_schema_version,
+            commands::database::migrate_database_to_latest,
+            commands::database::rollback_schema_to,
+            commands::database::validate_database_schema,
+            commands::database::repair_database_schema,
             commands::database::get_card_hierarchy,
             commands::database::get_cards_per_problem,
             // Tag management commands
@@ -109,6 +150,7 @@ async fn main() {
             // Bulk delete operations
             commands::database::delete_problems_bulk,
             // Search commands for Name/Topic/Tags system
+            commands::database::search_problems,
             commands::database::search_problems_by_name,
             commands::database::search_problems_by_topic,
             commands::database::search_problems_by_tags,
@@ -120,9 +162,18 @@ async fn main() {
             commands::database::add_problem_relation,
             commands::database::remove_problem_relation,
             commands::database::get_related_problems,
+            commands::database::get_problems_referencing,
+            commands::database::get_related_problems_within,
+            commands::database::shortest_relation_path,
+            commands::database::recommend_related_problems,
             // Stats-related database commands
             commands::database::get_problems_worked_today_list,
             commands::database::get_worked_today_total_duration,
+            // Test case commands
+            commands::code_runner::add_test_case,
+            commands::code_runner::get_test_cases_for_problem,
+            commands::code_runner::delete_test_case,
+            commands::code_runner::run_card_tests,
             // Timer commands
             commands::timer::start_timer_session,
             commands::timer::stop_timer_session,
@@ -133,11 +184,31 @@ async fn main() {
             commands::timer::delete_session,
             // Image commands
             commands::images::save_problem_image,
+            commands::images::get_image_processing_status,
             commands::images::get_problem_images,
             commands::images::delete_problem_image,
             commands::images::update_image_positions,
             commands::images::get_image_path,
             commands::images::get_image_data_url,
+            commands::images::get_problem_image_thumbnail,
+            // Card image commands
+            commands::card_images::save_card_image,
+            commands::card_images::save_card_images,
+            commands::card_images::get_card_images,
+            commands::card_images::delete_card_image,
+            commands::card_images::delete_card_images,
+            commands::card_images::move_card_images,
+            commands::card_images::update_card_image_positions,
+            commands::card_images::get_card_image_path,
+            commands::card_images::get_card_image_data_url,
+            commands::card_images::get_card_image_thumbnail,
+            commands::card_images::bulk_import_card_images,
+            // Job queue commands
+            commands::jobs::enqueue_transcription,
+            commands::jobs::get_job_status,
+            commands::jobs::pause_job,
+            commands::jobs::resume_job,
+            commands::jobs::get_job_progress,
             // Audio commands
             commands::audio::start_recording,
             commands::audio::stop_recording,
@@ -147,12 +218,37 @@ async fn main() {
             commands::audio::get_all_recordings,
             commands::audio::get_card_recordings,
             commands::audio::get_audio_data,
+            commands::audio::get_audio_metadata,
+            commands::audio::get_audio_chunk,
             commands::audio::get_current_dir,
             commands::audio::delete_recording,
             // Enhanced audio device management commands
             commands::audio::get_audio_devices,
             commands::audio::switch_audio_device,
             commands::audio::refresh_audio_devices,
+            commands::audio::get_input_level,
+            // Local playback commands
+            commands::playback::play_recording,
+            commands::playback::pause_playback,
+            commands::playback::resume_playback,
+            commands::playback::stop_playback,
+            commands::playback::seek_playback,
+            commands::playback::get_playback_position,
+            commands::playback::set_playback_volume,
+            commands::playback::get_playback_state,
+            // Cast (network playback) commands
+            commands::cast::get_cast_devices,
+            commands::cast::play_recording_on_device,
+            commands::cast::pause_cast_playback,
+            commands::cast::resume_cast_playback,
+            commands::cast::stop_cast_playback,
+            // Input device enumeration/selection commands
+            commands::audio_devices::list_input_devices,
+            commands::audio_devices::get_default_input_device,
+            commands::audio_devices::set_preferred_input_device,
+            commands::audio_devices::get_preferred_input_device,
+            // Storage usage analytics
+            commands::storage::compute_storage_usage,
             // Debug commands
             commands::debug::debug_paths,
             commands::debug::debug_recording_paths,
@@ -168,6 +264,16 @@ async fn main() {
             commands::solution_card::get_regular_cards,
             // TXT Import command
             commands::database::import_problems_from_txt,
+            commands::database::import_problems_from_json,
+            // Structured library backup/migration bundle
+            commands::library_bundle::export_library_bundle,
+            commands::library_bundle::import_library_bundle,
+            // Encrypted single-problem export/import bundle
+            commands::problem_bundle::export_problem_bundle,
+            commands::problem_bundle::import_problem_bundle,
+            // LeetCode import command
+            commands::leetcode::import_problem_from_leetcode,
+            commands::leetcode::sync_problem_metadata,
             // Debug commands continued
             commands::debug::check_microphone_permission,
             commands::debug::write_file,
@@ -176,6 +282,11 @@ async fn main() {
             commands::stats::get_problems_worked_today,
             commands::stats::get_daily_work_stats,
             commands::stats::get_dashboard_stats,
+            // Analytics commands
+            commands::analytics::get_study_analytics,
+            // Analytics export commands (InfluxDB line protocol)
+            commands::analytics_export::export_study_metrics_line_protocol,
+            commands::analytics_export::export_study_metrics_to_influxdb,
             // Work Sessions commands for detailed time tracking and visualization
             commands::work_sessions::get_work_sessions_date_range,
             commands::work_sessions::get_work_sessions_today,
@@ -186,7 +297,21 @@ async fn main() {
             commands::work_sessions::get_daily_aggregates,
             commands::work_sessions::get_productivity_by_hour,
             commands::work_sessions::get_most_productive_hour,
-            commands::work_sessions::get_most_worked_problem
+            commands::work_sessions::get_most_worked_problem,
+            commands::work_sessions::edit_work_session,
+            commands::work_sessions::delete_work_session,
+            commands::work_sessions::split_work_session,
+            commands::work_sessions::save_filter,
+            commands::work_sessions::list_filters,
+            commands::work_sessions::delete_filter,
+            // On-device OCR / auto-labeling of card images
+            commands::ocr::download_ocr_model,
+            commands::ocr::get_ocr_model_status,
+            commands::ocr::get_image_labels,
+            commands::ocr::regenerate_image_labels,
+            // Whole-data-directory backup/restore
+            commands::data_archive::export_data_archive,
+            commands::data_archive::import_data_archive
         ]);
     
     log_to_file("DSA Learning App: Command handlers registered");
@@ -304,18 +429,64 @@ async fn main() {
                 })
             });
             
+            // Rehydrate the active timer from its durable snapshot, if one
+            // survived a previous crash/quit. A snapshot whose card was
+            // deleted in the meantime is discarded rather than resumed.
+            if let Err(e) = db_manager.discard_orphaned_timer_session_snapshot() {
+                eprintln!("DSA Learning App: Failed to discard orphaned timer session snapshot: {}", e);
+            }
+            let rehydrated_timer = db_manager.load_timer_session_snapshot().unwrap_or_else(|e| {
+                eprintln!("DSA Learning App: Failed to load timer session snapshot: {}", e);
+                None
+            });
+            if let Some(ref timer_session) = rehydrated_timer {
+                eprintln!("DSA Learning App: Resumed timer session {} for card {}", timer_session.id, timer_session.card_id);
+            }
+
             // Create and manage app state with the initialized database
             let app_state = AppState {
                 db: Arc::new(Mutex::new(db_manager)),
-                current_timer: Arc::new(Mutex::new(None)),
+                current_timer: Arc::new(Mutex::new(rehydrated_timer)),
+                current_review_timer: Arc::new(Mutex::new(None)),
                 recording_state: Arc::new(Mutex::new(None)),
                 audio_thread_sender: Arc::new(Mutex::new(None)),
+                level_consumer: Arc::new(Mutex::new(None)),
+                playback_thread_sender: Arc::new(Mutex::new(None)),
+                playback_position: Arc::new(Mutex::new(None)),
                 path_resolver,
+                preferred_input_device: Arc::new(Mutex::new(None)),
+                clock: Arc::new(clock::SystemClocks),
+                app_handle: app_handle.clone(),
+                cast_session: Arc::new(Mutex::new(None)),
             };
             
+            let job_worker_db = app_state.db.clone();
+            let reconciliation_db = app_state.db.clone();
             app.manage(app_state);
             eprintln!("DSA Learning App: App state with database initialized successfully");
 
+            // A job left `running` (the app crashed or quit mid-item) or
+            // `paused` (the user asked it to stop but never came back to
+            // resume it) from a previous run needs to be put back to
+            // `queued` before the worker thread starts polling, or it sits
+            // forever since nothing else will ever pick it up.
+            match job_worker_db.lock() {
+                Ok(db) => match db.requeue_interrupted_jobs() {
+                    Ok(count) if count > 0 => {
+                        eprintln!("DSA Learning App: Requeued {} job(s) interrupted by the last shutdown", count);
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("DSA Learning App: Failed to requeue interrupted jobs: {}", e),
+                },
+                Err(e) => eprintln!("DSA Learning App: Failed to lock database to requeue interrupted jobs: {}", e),
+            }
+
+            commands::jobs::spawn_worker(job_worker_db);
+            eprintln!("DSA Learning App: Job worker thread started");
+
+            commands::database::spawn_startup_reconciliation_scan(reconciliation_db);
+            eprintln!("DSA Learning App: Startup reconciliation scan started");
+
             // Always try to get the main window
             match app.get_webview_window("main") {
                 Some(_window) => {
@@ -1,25 +1,191 @@
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use tauri::AppHandle;
 use crate::database::DatabaseManager;
 use crate::path_resolver::PathResolver;
 
+/// Deserializes a timestamp that may arrive as either an RFC3339 string or an
+/// integer Unix epoch (seconds), so a `LibraryBundle` produced by a different
+/// tool (or assembled by hand) doesn't have to match this app's own
+/// `DateTime<Utc>::to_rfc3339()` output exactly.
+pub fn datetime_from_unix_or_rfc3339<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TimestampRepr {
+        Unix(i64),
+        Text(String),
+    }
+
+    match TimestampRepr::deserialize(deserializer)? {
+        TimestampRepr::Unix(seconds) => Utc
+            .timestamp_opt(seconds, 0)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid unix timestamp: {}", seconds))),
+        TimestampRepr::Text(text) => DateTime::parse_from_rfc3339(&text)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| serde::de::Error::custom(format!("invalid RFC3339 timestamp '{}': {}", text, e))),
+    }
+}
+
 // Audio command types (moved here to avoid circular dependency)
+//
+// Every variant carries an `ack_tx` the audio thread replies on, so a command
+// is request/response instead of fire-and-forget - the caller can tell
+// `StartRecording` actually succeeded (and what sample rate/channels cpal
+// negotiated) instead of assuming the capture thread is still alive. This is
+// what lets `RefreshDevices`/`SwitchDevice` hand a real device list and a real
+// success/failure back to `commands::audio::get_audio_devices`/
+// `switch_audio_device`, rather than the Tauri command returning before the
+// audio thread has done anything.
 #[derive(Debug)]
 pub enum AudioCommand {
     StartRecording {
         filepath: String,
         sample_rate: u32,
-        channels: u16,
+        format: RecordingFormat,
+        buffering: AudioBufferingConfig,
+        silence_rms_threshold: f32,
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
+    },
+    StopRecording {
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
+    },
+    PauseRecording {
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
+    },
+    ResumeRecording {
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
+    },
+    RefreshDevices {
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
     },
-    StopRecording,
-    PauseRecording,
-    ResumeRecording,
-    RefreshDevices,
     SwitchDevice {
         device_name: String,
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
     },
+    /// Heartbeat sent by the watchdog thread on an interval; a missed
+    /// `AudioAck::Pong` within the timeout means the capture thread has
+    /// died or deadlocked.
+    Ping {
+        ack_tx: mpsc::Sender<Result<AudioAck, AudioError>>,
+    },
+}
+
+/// Successful reply to an `AudioCommand`.
+#[derive(Debug, Clone)]
+pub enum AudioAck {
+    Started { sample_rate: u32, channels: u16 },
+    /// `had_audio` is false when the finalized recording had zero samples or
+    /// never crossed its `silence_rms_threshold` - the caller discards the
+    /// file and skips the database row in that case instead of saving it.
+    Stopped { had_audio: bool },
+    Paused,
+    Resumed,
+    DevicesRefreshed(Vec<AudioDevice>),
+    DeviceSwitched(String),
+    Pong,
+}
+
+/// Configuration for the capture pipeline's per-callback buffering,
+/// analogous to ALVR's `AudioBufferingConfig`: trades latency against
+/// dropout safety by requesting a specific frames-per-callback size instead
+/// of always accepting whatever the host/device defaults to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AudioBufferingConfig {
+    /// Desired frames per callback. `None` leaves it to
+    /// `cpal::BufferSize::Default`; a device that can't honor the requested
+    /// size (outside its `SupportedBufferSize` range) also falls back to
+    /// `Default` rather than failing the recording.
+    #[serde(default)]
+    pub frames_per_callback: Option<u32>,
+}
+
+impl Default for AudioBufferingConfig {
+    fn default() -> Self {
+        Self { frames_per_callback: None }
+    }
+}
+
+/// Minimum RMS loudness (on the `0.0..=1.0` scale used by `LevelFrame::rms`)
+/// a recording must reach at least once to be kept. Recordings that never
+/// cross this - including zero-sample ones from an immediate stop - are
+/// discarded instead of being saved, so a muted input or a start/stop
+/// misfire doesn't leave a dangling database row. A plain constant rather
+/// than a config struct since `start_recording`'s `silence_rms_threshold`
+/// parameter and `RecordingSession::silence_rms_threshold` are themselves
+/// already the override points tests need to force-discard or force-keep
+/// a recording.
+pub const DEFAULT_SILENCE_RMS_THRESHOLD: f32 = 0.01;
+
+/// Requested channel layout for a recording. `start_recording_stream`
+/// validates this against the device's `default_input_config` channel count
+/// before building the stream - stereo can't be synthesized from a mono-only
+/// device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordingChannels {
+    Mono,
+    Stereo,
+}
+
+impl Default for RecordingChannels {
+    fn default() -> Self {
+        RecordingChannels::Mono
+    }
+}
+
+impl RecordingChannels {
+    pub fn count(self) -> u16 {
+        match self {
+            RecordingChannels::Mono => 1,
+            RecordingChannels::Stereo => 2,
+        }
+    }
+}
+
+/// Requested sample bit depth for a recording's WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordingBitDepth {
+    Sixteen,
+    TwentyFour,
+    ThirtyTwoFloat,
+}
+
+impl Default for RecordingBitDepth {
+    fn default() -> Self {
+        RecordingBitDepth::Sixteen
+    }
+}
+
+/// Recording format option carried through `AudioCommand::StartRecording`.
+/// Defaults to the app's original behavior (mono, 16-bit PCM) so existing
+/// callers and recordings are unaffected.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RecordingFormat {
+    #[serde(default)]
+    pub channels: RecordingChannels,
+    #[serde(default)]
+    pub bit_depth: RecordingBitDepth,
+}
+
+/// Failure reply to an `AudioCommand`.
+#[derive(Debug, Clone)]
+pub struct AudioError(pub String);
+
+impl std::fmt::Display for AudioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<AudioError> for String {
+    fn from(err: AudioError) -> Self {
+        err.0
+    }
 }
 
 // Audio device information for UI display
@@ -35,15 +201,185 @@ pub struct AudioDevice {
 pub struct AudioDeviceList {
     pub devices: Vec<AudioDevice>,
     pub current_device: Option<String>,
+    /// Cast-enabled receivers discovered on the LAN, offered alongside local
+    /// `cpal` devices so the frontend can show them in the same device picker.
+    #[serde(default)]
+    pub cast_devices: Vec<CastDevice>,
+}
+
+/// One loudness sample computed from a block of incoming audio, for a
+/// recording VU meter / clipping indicator. Pushed into a lock-free SPSC
+/// `ringbuf` by the capture callback in `commands::audio::start_recording_stream`
+/// without ever taking a lock or allocating, and drained by
+/// `commands::audio::get_input_level` on request. Written against the real
+/// `ringbuf` crate API (`ringbuf::HeapRb::split` producer/consumer pair) -
+/// it isn't in this tree's dependencies, since there's no `Cargo.toml` here
+/// to add it to (see `database::problem_bundle`'s top comment for the same
+/// situation with `aes-gcm`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LevelFrame {
+    /// Peak absolute sample value in the block, normalized to `0.0..=1.0`.
+    pub peak: f32,
+    /// RMS (root-mean-square) loudness of the block, normalized to `0.0..=1.0`.
+    pub rms: f32,
+    pub timestamp: DateTime<Utc>,
+}
+
+// Local playback command types (mirrors `AudioCommand`'s request/response
+// design - every variant carries its own `ack_tx` so `commands::playback`'s
+// Tauri commands can block on a real result instead of firing blind).
+#[derive(Debug)]
+pub enum PlaybackCommand {
+    PlayRecording {
+        recording_id: String,
+        filepath: String,
+        start_offset_secs: f64,
+        ack_tx: mpsc::Sender<Result<PlaybackAck, PlaybackError>>,
+    },
+    PausePlayback {
+        ack_tx: mpsc::Sender<Result<PlaybackAck, PlaybackError>>,
+    },
+    ResumePlayback {
+        ack_tx: mpsc::Sender<Result<PlaybackAck, PlaybackError>>,
+    },
+    StopPlayback {
+        ack_tx: mpsc::Sender<Result<PlaybackAck, PlaybackError>>,
+    },
+    Seek {
+        seconds: f64,
+        ack_tx: mpsc::Sender<Result<PlaybackAck, PlaybackError>>,
+    },
+    SetVolume {
+        volume: Volume,
+        ack_tx: mpsc::Sender<Result<PlaybackAck, PlaybackError>>,
+    },
+}
+
+/// Successful reply to a `PlaybackCommand`.
+#[derive(Debug, Clone)]
+pub enum PlaybackAck {
+    Started { duration_seconds: f64 },
+    Paused,
+    Resumed,
+    Stopped,
+    Sought { position_seconds: f64 },
+    VolumeSet { volume: f32 },
+}
+
+/// Failure reply to a `PlaybackCommand`.
+#[derive(Debug, Clone)]
+pub struct PlaybackError(pub String);
+
+impl std::fmt::Display for PlaybackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<PlaybackError> for String {
+    fn from(err: PlaybackError) -> Self {
+        err.0
+    }
+}
+
+/// Current position of the in-progress local playback, published by
+/// `commands::playback`'s output callback so `get_playback_position` can
+/// drive a scrubber without round-tripping through the playback thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackPosition {
+    pub recording_id: Option<String>,
+    pub position_seconds: f64,
+    pub duration_seconds: f64,
+    pub is_paused: bool,
+    pub is_finished: bool,
+}
+
+/// Playback gain, clamped to `0.0..=1.0` at construction so every reader
+/// (the output callback, `get_playback_state`) can use it directly without
+/// re-checking the range itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Volume(f32);
+
+impl Volume {
+    pub fn new(raw: f32) -> Self {
+        Self(raw.clamp(0.0, 1.0))
+    }
+
+    pub fn as_f32(self) -> f32 {
+        self.0
+    }
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Snapshot returned by `get_playback_state`: everything the UI needs to
+/// render transport controls without polling multiple commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackState {
+    pub recording_id: Option<String>,
+    pub position_seconds: f64,
+    pub duration_seconds: f64,
+    pub is_paused: bool,
 }
 
 // App state shared across Tauri commands
 pub struct AppState {
     pub db: Arc<Mutex<DatabaseManager>>,
     pub current_timer: Arc<Mutex<Option<TimerSession>>>,
+    pub current_review_timer: Arc<Mutex<Option<ReviewTimerSession>>>,
     pub recording_state: Arc<Mutex<Option<RecordingSession>>>,
     pub audio_thread_sender: Arc<Mutex<Option<mpsc::Sender<AudioCommand>>>>,
+    /// Consumer side of the current recording's input-level ring buffer - see
+    /// [`LevelFrame`]. Set by `commands::audio::ensure_audio_thread_started`
+    /// alongside `audio_thread_sender`; `None` until the audio thread has
+    /// started at least once.
+    pub level_consumer: Arc<Mutex<Option<ringbuf::HeapConsumer<LevelFrame>>>>,
+    /// Dedicated playback thread's command channel - see `commands::playback`.
+    /// `None` until the first `play_recording` call, mirroring `audio_thread_sender`.
+    pub playback_thread_sender: Arc<Mutex<Option<mpsc::Sender<PlaybackCommand>>>>,
+    /// Current position/duration of the in-progress local playback, if any -
+    /// see [`PlaybackPosition`].
+    pub playback_position: Arc<Mutex<Option<PlaybackPosition>>>,
     pub path_resolver: Arc<PathResolver>,
+    /// User's preferred input device name, set via `set_preferred_input_device` and
+    /// consulted by the recording commands when opening a device.
+    pub preferred_input_device: Arc<Mutex<Option<String>>>,
+    /// Injected clock for timer/recording elapsed-time math, so tests can
+    /// swap in a `SimulatedClocks` instead of relying on real sleeps.
+    pub clock: Arc<dyn crate::clock::Clocks>,
+    /// Handle back into the running Tauri app, so background threads (e.g.
+    /// the audio heartbeat watchdog) can emit `AppEvent`s without needing a
+    /// command invocation's own handle.
+    pub app_handle: AppHandle,
+    /// The in-progress Cast playback session, if a recording is currently
+    /// loaded on a receiver. `commands::cast`'s pause/resume/stop commands
+    /// look up the receiver address and `media_session_id` here instead of
+    /// keeping a live socket around between commands.
+    pub cast_session: Arc<Mutex<Option<CastPlaybackSession>>>,
+}
+
+/// A Chromecast/Cast-enabled receiver discovered on the LAN via mDNS.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CastDevice {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Tracks the recording currently loaded on a Cast receiver, so play/pause/stop
+/// commands know where to send their media-control requests without holding a
+/// live connection open between them.
+#[derive(Debug, Clone)]
+pub struct CastPlaybackSession {
+    pub device: CastDevice,
+    pub media_session_id: i32,
+    /// CASTV2 requests need a monotonically increasing `requestId`; this is
+    /// the next one to use.
+    pub next_request_id: i32,
 }
 
 // Recording session state (without the non-Send cpal Stream)
@@ -55,6 +391,17 @@ pub struct RecordingSession {
     pub is_paused: bool,
     pub filename: String,
     pub filepath: String,
+    /// Minimum RMS loudness this recording must reach to be kept; see
+    /// `DEFAULT_SILENCE_RMS_THRESHOLD`. Carried on the session (rather than
+    /// only the `AudioCommand::StartRecording` sent to the audio thread) so
+    /// it's inspectable/overridable by whatever constructed the session.
+    pub silence_rms_threshold: f32,
+    /// The `root_id:relative` form of `filepath` (see
+    /// `database::storage_roots::StorageRoots::to_stored_path`), computed once
+    /// when the storage root is picked at recording start and persisted as-is
+    /// to `recordings.filepath` on stop, so a later `resolve_media_path` finds
+    /// it regardless of which root it landed on.
+    pub storage_path: String,
 }
 
 // Database models matching the database schema
@@ -85,7 +432,9 @@ pub struct FrontendProblem {
     pub constraints: Vec<String>,
     pub hints: Vec<String>,
     pub related_problem_ids: Vec<String>, // Array of related problem IDs
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
     pub created_at: DateTime<Utc>,
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
     pub updated_at: DateTime<Utc>,
     pub tags: Vec<String>, // For compatibility with frontend expectations
 }
@@ -100,11 +449,54 @@ pub struct Card {
     pub notes: Option<String>,
     pub status: String, // 'In Progress', 'Completed', 'Paused'
     pub total_duration: i32, // in seconds
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
     pub created_at: DateTime<Utc>,
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
     pub last_modified: DateTime<Utc>,
     pub parent_card_id: Option<String>,
 }
 
+// Snapshot of a `problems` row captured by the `problems_after_update`/
+// `problems_after_delete` triggers, as it existed immediately before the
+// change.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemHistoryEntry {
+    pub history_id: i64,
+    pub row_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub difficulty: Option<String>,
+    pub topic: Option<String>,
+    pub leetcode_url: Option<String>,
+    pub constraints: Option<String>,
+    pub examples: Option<String>,
+    pub hints: Option<String>,
+    pub related_problem_ids: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub operation: String, // 'update' or 'delete'
+    pub changed_at: DateTime<Utc>,
+}
+
+// Snapshot of a `cards` row captured by the `cards_after_update`/
+// `cards_after_delete` triggers, as it existed immediately before the change.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardHistoryEntry {
+    pub history_id: i64,
+    pub row_id: String,
+    pub problem_id: Option<String>,
+    pub card_number: Option<i32>,
+    pub code: Option<String>,
+    pub language: Option<String>,
+    pub notes: Option<String>,
+    pub status: Option<String>,
+    pub total_duration: Option<i32>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub parent_card_id: Option<String>,
+    pub operation: String, // 'update' or 'delete'
+    pub changed_at: DateTime<Utc>,
+}
+
 // Request/Response models
 #[derive(Debug, Deserialize)]
 pub struct CreateProblemRequest {
@@ -147,6 +539,67 @@ pub struct UpdateCardRequest {
     pub status: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SaveRecordingRequest {
+    pub card_id: String,
+    pub filename: String,
+    pub filepath: String,
+    pub duration: Option<i32>,
+}
+
+/// A single step of a [`DatabaseManager::apply_batch`] run. The whole batch
+/// executes inside one transaction, so any op failing rolls every prior op in
+/// the same call back out.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum BatchOp {
+    CreateProblem(CreateProblemRequest),
+    UpdateProblem(UpdateProblemRequest),
+    CreateCard(CreateCardRequest),
+    UpdateCard(UpdateCardRequest),
+    DeleteCard(String),
+    AddTag(AddProblemTagRequest),
+    RemoveTag(RemoveProblemTagRequest),
+    ReorderImages(Vec<(String, i32)>),
+    SaveRecording(SaveRecordingRequest),
+    DeleteRecording(String),
+}
+
+/// The result of one [`BatchOp`], in the same order as the request.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum BatchOpResult {
+    ProblemCreated(FrontendProblem),
+    ProblemUpdated(Option<FrontendProblem>),
+    CardCreated(Card),
+    CardUpdated(Option<Card>),
+    CardDeleted(String),
+    TagAdded(Tag),
+    TagRemoved,
+    ImagesReordered,
+    RecordingSaved(Recording),
+    RecordingDeleted(String),
+}
+
+/// Which step of an [`BatchOp`] list failed, and why - the transaction still
+/// rolls back the whole batch, but this pinpoints what to fix before retrying
+/// rather than making the caller bisect the list themselves.
+#[derive(Debug, Serialize)]
+pub struct BatchOpError {
+    pub index: usize,
+    pub message: String,
+}
+
+/// What [`DatabaseManager::apply_batch`] hands back: every op's result up to
+/// (but not including) whichever one failed, plus that failure if the batch
+/// didn't run to completion. `results` reflects what *would* have persisted
+/// had the batch not been rolled back in full.
+#[derive(Debug, Serialize)]
+pub struct BatchRunResult {
+    pub results: Vec<BatchOpResult>,
+    pub failed_at: Option<BatchOpError>,
+}
+
 // Database analysis structs
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabaseStats {
@@ -154,6 +607,9 @@ pub struct DatabaseStats {
     pub total_cards: i32,
     pub main_cards: i32,
     pub child_cards: i32,
+    pub index_count: i32,
+    pub database_page_count: i32,
+    pub database_freelist_count: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -183,7 +639,74 @@ pub struct ProblemImage {
     pub image_path: String,
     pub caption: Option<String>,
     pub position: i32,
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
     pub created_at: DateTime<Utc>,
+    /// Relative path to the downscaled `<uuid>.thumb.webp` variant generated
+    /// alongside `image_path` (migration 19). `None` for images saved before
+    /// thumbnailing existed; `get_problem_image_thumbnail` generates one
+    /// lazily in that case.
+    pub thumbnail_path: Option<String>,
+    /// BlurHash placeholder string (migration 20, see `blurhash::encode`),
+    /// `None` for images saved before this feature.
+    pub blur_hash: Option<String>,
+    /// BLAKE3 hex digest of the decoded file content (migration 21) - the
+    /// primary key of its `image_blobs` row, and the stem `save_problem_image`
+    /// names the CAS file after. `None` for images saved before content-addressed
+    /// storage existed, until `migrate_images_to_cas` backfills them.
+    pub content_hash: Option<String>,
+    /// Pixel dimensions and final encoded size of `image_path`'s file
+    /// (migration 22), recorded during `save_problem_image`'s
+    /// decode/validate/strip pipeline so the frontend can lay out a gallery
+    /// without loading every image first. `None` for images saved before
+    /// this feature and for `svg` uploads, which skip decoding entirely.
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub byte_size: Option<i64>,
+    /// `pending` while the `jobs`-queue worker still has to generate a
+    /// thumbnail/BlurHash and re-encode-strip the original (migration 23,
+    /// see `commands/jobs.rs`), `ready` once that's done. Every row from
+    /// before this feature backfills to `ready`, since it was already fully
+    /// processed synchronously by the old `save_problem_image`. Defaults to
+    /// `ready` when missing, so a library bundle exported before this
+    /// feature still deserializes.
+    #[serde(default = "default_ready_status")]
+    pub status: String,
+}
+
+fn default_ready_status() -> String {
+    "ready".to_string()
+}
+
+// Test case model - a single (input, expected_output) pair attached to a problem,
+// exercised against every card's code via `run_card_tests`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TestCase {
+    pub id: String,
+    pub problem_id: String,
+    pub input: String,
+    pub expected_output: String,
+    pub is_hidden: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddTestCaseRequest {
+    pub problem_id: String,
+    pub input: String,
+    pub expected_output: String,
+    pub is_hidden: bool,
+}
+
+// Result of running one test case's input through a card's code
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TestResult {
+    pub test_case_id: String,
+    pub passed: bool,
+    pub actual_output: String,
+    pub expected_output: String,
+    pub runtime_ms: u64,
+    pub stderr: Option<String>,
+    pub is_hidden: bool,
 }
 
 // Solution card model - special type of card for storing problem solutions
@@ -197,8 +720,13 @@ pub struct SolutionCard {
     pub notes: String,
     pub status: String,
     pub total_duration: i32,
-    pub created_at: String,
-    pub last_modified: String,
+    // Used to store "%Y-%m-%d %H:%M:%S" text while `Card` stored RFC3339 -
+    // unified onto `DateTime<Utc>` (same as `Card`) so both card flavors
+    // round-trip through a `LibraryBundle` the same way.
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
+    pub last_modified: DateTime<Utc>,
     pub is_solution: bool,
 }
 
@@ -216,6 +744,89 @@ pub struct DeleteImageRequest {
     pub image_id: String,
 }
 
+// Card images model - content-addressed like `ProblemImage`, but without the
+// decode/validate/strip/reencode/thumbnail/BlurHash pipeline `problem_images`
+// grew (migrations 19-23, see `commands::images::process_uploaded_image`):
+// a card image is saved as-is, just deduplicated by content hash.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CardImage {
+    pub id: String,
+    pub card_id: String,
+    pub image_path: String,
+    pub caption: Option<String>,
+    pub position: i32,
+    #[serde(deserialize_with = "datetime_from_unix_or_rfc3339")]
+    pub created_at: DateTime<Utc>,
+    /// BLAKE3 hex digest of the saved bytes (see `database::maintenance::blake3_hex`),
+    /// shared with `image_blobs.hash` so the same pasted image across many
+    /// cards is stored once. `None` for rows saved before this feature.
+    pub content_hash: Option<String>,
+    /// Relative path to the downscaled `<hash>.thumb.webp` variant generated
+    /// alongside `image_path` (migration 25). `None` for images saved before
+    /// thumbnailing existed or whose format has no raster form (`svg`);
+    /// `get_card_image_thumbnail` generates one lazily in that case.
+    pub thumbnail_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveCardImageRequest {
+    pub card_id: String,
+    pub image_data: String, // Base64 encoded image data
+    pub caption: Option<String>,
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCardImageRequest {
+    pub image_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkImportCardImagesRequest {
+    pub card_id: String,
+    pub images: Vec<BulkImportImageInput>,
+}
+
+/// One image in a `bulk_import_card_images` batch - same shape as
+/// `SaveCardImageRequest` minus `card_id` (shared by the whole batch) and
+/// `position` (appended in upload order).
+#[derive(Debug, Deserialize)]
+pub struct BulkImportImageInput {
+    pub image_data: String,
+    pub caption: Option<String>,
+}
+
+/// One problem found by `verify_storage_roots`: either `"missing"` (the
+/// stored path's root resolved but the file isn't there) or `"misplaced"`
+/// (the stored path names a root id that's since been removed via
+/// `remove_storage_root`).
+#[derive(Debug, Serialize)]
+pub struct StorageRootIntegrityIssue {
+    pub card_image_id: String,
+    pub stored_path: String,
+    pub kind: String,
+}
+
+/// One row of the `image_labels` table, as returned by `get_image_labels`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageLabelRow {
+    pub id: String,
+    pub image_id: String,
+    pub label: String,
+    pub confidence: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `get_ocr_model_status`'s response: whether the OCR feature was compiled
+/// into this build, whether its weights have been downloaded, and the
+/// model version those weights/labels were generated against.
+#[derive(Debug, Serialize)]
+pub struct OcrModelStatus {
+    pub feature_enabled: bool,
+    pub model_downloaded: bool,
+    pub model_version: String,
+}
+
 // Additional models for timer sessions and recordings
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TimeSession {
@@ -229,6 +840,150 @@ pub struct TimeSession {
     pub notes: Option<String>,
 }
 
+/// Row-level mirror of the `work_sessions` table (migration 5), logged
+/// alongside `time_sessions` for problem-level analytics. See
+/// `DatabaseManager::create_work_session`/`complete_work_session`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkSession {
+    pub id: String,
+    pub problem_id: String,
+    pub card_id: String,
+    pub session_date: String,
+    pub start_timestamp: DateTime<Utc>,
+    pub end_timestamp: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i32>,
+    pub hour_slot: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Retroactively corrects a `work_sessions` row, e.g. a timer left running
+/// overnight. Any field left `None` keeps its current value. Moving
+/// `card_id` to a different card also updates `problem_id` to that card's
+/// problem, since the two must stay consistent.
+#[derive(Debug, Deserialize)]
+pub struct EditWorkSessionRequest {
+    pub session_id: String,
+    pub start_timestamp: Option<DateTime<Utc>>,
+    pub end_timestamp: Option<DateTime<Utc>>,
+    pub card_id: Option<String>,
+}
+
+/// Breaks one `work_sessions` row into two at `split_at`: the original
+/// session is shortened to end at `split_at`, and a new session starting at
+/// `split_at` inherits its original `end_timestamp`.
+#[derive(Debug, Deserialize)]
+pub struct SplitWorkSessionRequest {
+    pub session_id: String,
+    pub split_at: DateTime<Utc>,
+}
+
+/// Facets for slicing work-session analytics beyond a plain date range.
+/// An empty vec (the default) means "no restriction on this facet" - see
+/// `DatabaseManager::work_session_filter_clause`, which every analytics
+/// query below threads this through.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkSessionFilter {
+    #[serde(default)]
+    pub difficulties: Vec<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub tag_ids: Vec<String>,
+    #[serde(default)]
+    pub problem_ids: Vec<String>,
+    pub min_duration_seconds: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkSessionsDateRangeRequest {
+    pub start_date: String,
+    pub end_date: String,
+    pub filter: Option<WorkSessionFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkSessionsByProblemRequest {
+    pub problem_id: String,
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HourlyProductivityRequest {
+    pub days: Option<i32>,
+    pub filter: Option<WorkSessionFilter>,
+}
+
+/// A `work_sessions` row joined with the problem it belongs to, for
+/// `get_work_sessions_date_range` - the dashboard wants a title to display,
+/// not just a `problem_id`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkSessionWithProblem {
+    pub id: String,
+    pub problem_id: String,
+    pub problem_title: String,
+    pub card_id: String,
+    pub session_date: String,
+    pub start_timestamp: DateTime<Utc>,
+    pub end_timestamp: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i32>,
+    pub hour_slot: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DailyWorkSummary {
+    pub session_date: String,
+    pub total_duration_seconds: i32,
+    pub session_count: i32,
+    pub unique_problems_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemWorkBreakdown {
+    pub problem_id: String,
+    pub problem_title: String,
+    pub total_duration_seconds: i32,
+    pub session_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HourlyWorkBreakdown {
+    pub hour_slot: i32,
+    pub total_duration_seconds: i32,
+    pub session_count: i32,
+}
+
+/// A reusable `WorkSessionFilter` preset, e.g. "Hard dynamic-programming,
+/// last 30 days" - saved as JSON since the filter's shape may grow facets
+/// over time without needing a schema migration for each one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub id: String,
+    pub name: String,
+    pub filter_json: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveFilterRequest {
+    pub name: String,
+    pub filter: WorkSessionFilter,
+}
+
+// Review sessions currently share the same `time_sessions` table as regular
+// card timers - review mode doesn't (yet) need its own history, just its own
+// in-memory pause bookkeeping (see `ReviewTimerSession` below).
+pub type ReviewSession = TimeSession;
+
+/// What `DatabaseManager::start_review_timer_session` hands back: the new
+/// session's id/start time, plus the most recent prior (non-review) session
+/// for the card, if any, so the review can be linked back to what it's
+/// reviewing.
+pub struct ReviewTimerSessionStart {
+    pub id: String,
+    pub start_time: DateTime<Utc>,
+    pub original_session_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Recording {
     pub id: String,
@@ -295,6 +1050,26 @@ pub struct TimerSession {
     pub start_time: DateTime<Utc>,
     pub is_paused: bool,
     pub pause_duration: i32, // in seconds
+    /// When the current pause began, if `is_paused`. Folded into
+    /// `pause_duration` on resume so elapsed-time math stays correct.
+    pub pause_started_at: Option<DateTime<Utc>>,
+    /// The parallel `work_sessions` row created alongside this `time_sessions`
+    /// one, completed together with it by `end_timer_session`.
+    pub work_session_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReviewTimerSession {
+    pub id: String,
+    pub card_id: String,
+    pub start_time: DateTime<Utc>,
+    pub is_paused: bool,
+    pub pause_duration: i32, // in seconds
+    /// When the current pause began, if `is_paused`. Folded into
+    /// `pause_duration` on resume so elapsed-time math stays correct.
+    pub pause_started_at: Option<DateTime<Utc>>,
+    pub review_work_session_id: Option<String>,
+    pub original_session_id: Option<String>,
 }
 
 // Recording-specific models for in-memory recording state
@@ -318,6 +1093,27 @@ pub struct RecordingInfo {
     pub filepath: String,
 }
 
+/// WAV header fields returned by `commands::audio::get_audio_metadata` -
+/// enough for a caller to size a range-based fetch with `get_audio_chunk`
+/// without reading the (potentially large) PCM body first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioMetadata {
+    pub total_len: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration_seconds: f64,
+}
+
+/// One byte range of a recording file, returned by
+/// `commands::audio::get_audio_chunk`. `next_offset` is `None` once the
+/// range reaches the end of the file, so a caller can loop until it sees it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioChunk {
+    pub bytes_base64: String,
+    pub total_len: u64,
+    pub next_offset: Option<u64>,
+}
+
 // TXT Import system models
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImportResult {
@@ -337,6 +1133,23 @@ pub struct ImportError {
     pub severity: String,
 }
 
+// Structured JSON import/export - a portable bundle of a user's whole library
+// (as opposed to the TXT importer's plain problem list), so the related
+// cards/images/tags for a problem travel with it when backed up or migrated
+// between machines.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemBundle {
+    pub problem: FrontendProblem,
+    pub cards: Vec<Card>,
+    pub images: Vec<ProblemImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryBundle {
+    pub problems: Vec<ProblemBundle>,
+    pub tags: Vec<Tag>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ParsedProblem {
     pub title: String,
@@ -347,6 +1160,10 @@ pub struct ParsedProblem {
     pub leetcode_url: Option<String>,
     pub constraints: Vec<String>,
     pub hints: Vec<String>,
+    /// Verbatim code/example blocks captured from fenced (```` ``` ````) or
+    /// org-style (`#+BEGIN_EXAMPLE`) delimiters, preserved untouched - no
+    /// whitespace normalization or bullet stripping.
+    pub code_blocks: Vec<CodeBlock>,
 }
 
 impl ParsedProblem {
@@ -360,10 +1177,20 @@ impl ParsedProblem {
             leetcode_url: None,
             constraints: Vec::new(),
             hints: Vec::new(),
+            code_blocks: Vec::new(),
         }
     }
 }
 
+/// A verbatim block captured from a delimited region of the TXT import
+/// format (Markdown fence or org-style `#+BEGIN_EXAMPLE`). `language` holds
+/// the fence's info string (e.g. `python` in ```` ```python ````) when present.
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub content: String,
+}
+
 // Problem deletion stats
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProblemDeleteStats {
@@ -373,6 +1200,39 @@ pub struct ProblemDeleteStats {
     pub recordings_count: i32,
     pub images_count: i32,
     pub total_duration: i32, // in seconds
+    /// Of this problem's recording/image files, how many aren't also
+    /// referenced by another problem's rows (see `DatabaseManager::file_reference_count`)
+    /// and so will actually be unlinked from disk rather than kept around.
+    pub files_to_reclaim: i32,
+}
+
+/// Everything `DatabaseManager::delete_problem`/`delete_problem_with_files`
+/// irreversibly removed, so the caller can offer an "undo"/export-before-purge
+/// step instead of the deletion being a complete dead end.
+#[derive(Debug, Serialize)]
+pub struct DeletedProblemPayload {
+    pub problem: FrontendProblem,
+    pub cards_deleted: i32,
+    pub time_sessions_deleted: i32,
+    pub recordings_deleted: i32,
+}
+
+/// One problem reached by `DatabaseManager::get_related_problems_within`'s
+/// breadth-first expansion, annotated with how many relation edges away
+/// from the starting problem it is.
+#[derive(Debug, Serialize)]
+pub struct RelatedProblemHop {
+    pub problem: FrontendProblem,
+    pub hop_distance: i32,
+}
+
+/// A candidate surfaced by `DatabaseManager::recommend_related_problems`,
+/// ranked by how many distinct shortest relation paths reach it.
+#[derive(Debug, Serialize)]
+pub struct RecommendedProblem {
+    pub problem: FrontendProblem,
+    pub hop_distance: i32,
+    pub path_count: i64,
 }
 
 // Dashboard statistics models
@@ -395,4 +1255,35 @@ pub struct DailyWorkStats {
     pub problems_worked: i32,
     pub total_study_time_today: i32, // in seconds
     pub date: String, // ISO date string (YYYY-MM-DD)
+}
+
+/// One day's entry in `StudyAnalytics::weekly_progress` - always one row per
+/// day of the trailing 7-day window, even if no sessions happened that day.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyStudyProgress {
+    pub date: String, // ISO date string (YYYY-MM-DD)
+    pub study_time: i32, // in seconds
+    pub problems_worked: i32,
+}
+
+/// One entry in `StudyAnalytics::top_tags`, ranked by how many problems use it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagUsageCount {
+    pub tag_name: String,
+    pub problem_count: i32,
+}
+
+/// Aggregated study analytics computed from `time_sessions`/`cards`/`problems`,
+/// see `DatabaseManager::get_study_analytics`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StudyAnalytics {
+    pub total_problems: i32,
+    pub completed_problems: i32,
+    pub total_study_time: i32, // in seconds
+    pub average_session_time: f64, // in seconds
+    pub problems_by_difficulty: serde_json::Value,
+    pub study_streak_days: i32,
+    pub most_productive_hour: i32, // 0-23, UTC hour with the most session starts
+    pub weekly_progress: Vec<DailyStudyProgress>,
+    pub top_tags: Vec<TagUsageCount>,
 }
\ No newline at end of file
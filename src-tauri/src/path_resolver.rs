@@ -1,50 +1,247 @@
-use std::path::PathBuf;
-use tauri::AppHandle;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
 
 /// Production-ready path resolver that handles development vs production paths
 /// Development uses local dev-data folder, production uses proper app data directory
 pub struct PathResolver {
     app_data_dir: PathBuf,
+    cache_dir: PathBuf,
+    config_dir: PathBuf,
+    log_dir: PathBuf,
+    is_debug_mode: bool,
+    /// Active named profile, if any - see [`Self::switch_profile`]. Guarded by a
+    /// lock (rather than requiring `&mut self`) since `PathResolver` is shared
+    /// behind an `Arc` across every command.
+    profile: std::sync::RwLock<Option<String>>,
 }
 
+/// Capability scope for [`PathResolver::resolve_scoped`] - each variant maps to one
+/// of the existing `get_*_dir` roots so callers can only ever land inside the
+/// directory their capability grants them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Recordings,
+    Images,
+    AppData,
+}
+
+/// Errors produced while resolving an untrusted relative path against a [`Scope`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// The input was an absolute path (or carried a Windows prefix/root component).
+    AbsoluteInput(String),
+    /// The normalized input would climb above the scope root (e.g. via `..`).
+    Escapes(String),
+    /// The resolved path could not be canonicalized (missing parent dir, I/O error, etc).
+    Io(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::AbsoluteInput(p) => write!(f, "path must be relative, got absolute path: {}", p),
+            PathError::Escapes(p) => write!(f, "path escapes its scope root: {}", p),
+            PathError::Io(e) => write!(f, "failed to resolve path: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl From<PathError> for String {
+    fn from(err: PathError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Name of the environment variable that pins the data directory, checked between
+/// [`PathResolver::with_override`]'s explicit argument and the platform default.
+const DATA_DIR_ENV_VAR: &str = "DSA_DATA_DIR";
+
+/// App-specific subdirectory joined onto `$XDG_DATA_HOME` when falling back to it -
+/// kept distinct from `com.dsalearning.dsaapp`, the bundle id used elsewhere, since
+/// this is a plain data-home layout rather than a bundle-identified one.
+const XDG_DATA_HOME_SUBDIR: &str = "dsa-learning-app";
+
 impl PathResolver {
-    /// Create a new path resolver
-    pub fn new(_app_handle: &AppHandle) -> Result<Self, String> {
-        let app_data_dir = if cfg!(debug_assertions) {
-            // Development: use project dev-data folder
-            std::env::current_dir()
-                .map_err(|e| format!("Failed to get current directory: {}", e))?
-                .join("dev-data")
+    /// Create a new path resolver using the dev/prod platform defaults, with no
+    /// override. Equivalent to `Self::with_override(app_handle, None)`.
+    pub fn new(app_handle: &AppHandle) -> Result<Self, String> {
+        Self::with_override(app_handle, None)
+    }
+
+    /// Like [`Self::new`], but lets a caller pin the data directory instead of using
+    /// the dev/prod platform defaults - so integration tests can point at an
+    /// isolated `tempfile` directory without touching the real one, and a portable
+    /// install can relocate its library without recompiling.
+    ///
+    /// The directory is resolved in priority order, modeled on reth's
+    /// `MaybePlatformPath` dirs layer:
+    /// 1. `data_dir_override`, passed in directly.
+    /// 2. the [`DATA_DIR_ENV_VAR`] (`DSA_DATA_DIR`) environment variable.
+    /// 3. on Linux, `$XDG_DATA_HOME` joined with this app's data subdirectory.
+    /// 4. the existing dev/prod platform-default logic, unchanged.
+    ///
+    /// Whichever of 1-3 wins has a leading `~`/`$HOME` expanded first (see
+    /// [`Self::expand_home`]). When an override applies, `cache_dir`/`config_dir`/
+    /// `log_dir` are derived as subdirectories of it, the same way dev mode derives
+    /// them from `dev-data`, rather than the platform cache/config/log roots.
+    pub fn with_override(app_handle: &AppHandle, data_dir_override: Option<PathBuf>) -> Result<Self, String> {
+        // Detect the cargo build layout (target/debug, target/release, or the
+        // target/<triple>/debug|release layout used by cross builds) by walking up
+        // from the current executable, the same way tauri-utils locates dev resources.
+        let dev_workspace_root = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Self::dev_workspace_root_from_exe(&exe));
+        let is_debug_mode = dev_workspace_root.is_some() || cfg!(debug_assertions);
+
+        let (app_data_dir, cache_dir, config_dir, log_dir) = if let Some(override_dir) = Self::resolve_data_dir_override(data_dir_override) {
+            (
+                override_dir.clone(),
+                override_dir.join("cache"),
+                override_dir.join("config"),
+                override_dir.join("logs"),
+            )
+        } else if is_debug_mode {
+            // Development: everything lives under one workspace dev-data folder
+            // rather than the platform-correct cache/config/log roots, so a dev
+            // checkout stays self-contained and easy to wipe. Fall back to the
+            // current directory if we couldn't discover the layout (e.g. running
+            // under `cargo test` where the exe lives outside target/<profile>).
+            let dev_root = dev_workspace_root
+                .or_else(|| std::env::current_dir().ok())
+                .ok_or("Failed to get current directory")?
+                .join("dev-data");
+            (
+                dev_root.clone(),
+                dev_root.join("cache"),
+                dev_root.join("config"),
+                dev_root.join("logs"),
+            )
         } else {
-            // Production: use proper app data directory
-            Self::get_production_app_data_dir()?
+            // Production: delegate to the AppHandle's path resolver instead of
+            // reconstructing OS paths by hand - it derives the platform-correct
+            // roots from the bundle identifier in `tauri.conf.json`, the single
+            // source of truth, rather than a copy hardcoded here.
+            (
+                app_handle.path().app_data_dir().map_err(|e| format!("Failed to get app data directory: {}", e))?,
+                app_handle.path().app_cache_dir().map_err(|e| format!("Failed to get app cache directory: {}", e))?,
+                app_handle.path().app_config_dir().map_err(|e| format!("Failed to get app config directory: {}", e))?,
+                app_handle.path().app_log_dir().map_err(|e| format!("Failed to get app log directory: {}", e))?,
+            )
         };
 
-        // Ensure the directory exists
-        std::fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        // Ensure the directories exist
+        for dir in [&app_data_dir, &cache_dir, &config_dir, &log_dir] {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+        }
+
+        let resolver = Self {
+            app_data_dir,
+            cache_dir,
+            config_dir,
+            log_dir,
+            is_debug_mode,
+            profile: std::sync::RwLock::new(None),
+        };
 
-        Ok(Self { app_data_dir })
+        // Gate access on the storage-format requirements file, migrating an
+        // older data directory forward (or refusing to open one this build
+        // is too old to understand) before handing the resolver back out.
+        crate::storage_format::ensure_storage_format(&resolver)?;
+
+        Ok(resolver)
     }
 
-    /// Get production app data directory using dirs crate
-    fn get_production_app_data_dir() -> Result<PathBuf, String> {
-        let app_data_dir = if cfg!(target_os = "macos") {
-            dirs::data_dir()
-                .ok_or("Failed to get data directory")?
-                .join("com.dsalearning.dsaapp")
-        } else if cfg!(target_os = "windows") {
-            dirs::data_dir()
-                .ok_or("Failed to get data directory")?
-                .join("com.dsalearning.dsaapp")
-        } else {
-            // Linux
-            dirs::data_local_dir()
-                .ok_or("Failed to get local data directory")?
-                .join("com.dsalearning.dsaapp")
+    /// Priority chain described on [`Self::with_override`]; returns `None` when
+    /// nothing overrides the platform default.
+    fn resolve_data_dir_override(explicit: Option<PathBuf>) -> Option<PathBuf> {
+        if let Some(path) = explicit {
+            return Some(Self::expand_home(&path));
+        }
+
+        if let Ok(value) = std::env::var(DATA_DIR_ENV_VAR) {
+            if !value.is_empty() {
+                return Some(Self::expand_home(Path::new(&value)));
+            }
+        }
+
+        if cfg!(target_os = "linux") {
+            if let Ok(value) = std::env::var("XDG_DATA_HOME") {
+                if !value.is_empty() {
+                    return Some(Self::expand_home(Path::new(&value)).join(XDG_DATA_HOME_SUBDIR));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Expand a leading `~` or `$HOME` segment against the `HOME` environment
+    /// variable. Leaves `path` untouched if it doesn't start with either, or if
+    /// `HOME` isn't set.
+    fn expand_home(path: &Path) -> PathBuf {
+        let raw = path.to_string_lossy();
+
+        let Ok(home) = std::env::var("HOME") else {
+            return path.to_path_buf();
         };
 
-        Ok(app_data_dir)
+        if let Some(rest) = raw.strip_prefix("~/") {
+            PathBuf::from(home).join(rest)
+        } else if raw == "~" {
+            PathBuf::from(home)
+        } else if let Some(rest) = raw.strip_prefix("$HOME/") {
+            PathBuf::from(home).join(rest)
+        } else if raw == "$HOME" {
+            PathBuf::from(home)
+        } else {
+            path.to_path_buf()
+        }
+    }
+
+    /// Whether this resolver determined it is running out of a dev build layout
+    /// (`target/debug`, `target/release`, or `target/<triple>/debug|release`)
+    /// rather than an installed/bundled release.
+    pub fn is_debug_mode(&self) -> bool {
+        self.is_debug_mode
+    }
+
+    /// Walk up from the current executable path and, if it sits inside a cargo
+    /// build-profile directory (`debug`/`release`, optionally nested under a target
+    /// triple directory like `x86_64-unknown-linux-gnu`), return the workspace root
+    /// that contains the `target` directory - i.e. the `src-tauri` crate root whose
+    /// `dev-data` folder we should use. Returns `None` for any other layout.
+    fn dev_workspace_root_from_exe(exe_path: &Path) -> Option<PathBuf> {
+        let profile_dir = exe_path.parent()?;
+        let profile_name = profile_dir.file_name()?.to_str()?;
+        if profile_name != "debug" && profile_name != "release" {
+            return None;
+        }
+
+        let maybe_target_dir = profile_dir.parent()?;
+        let maybe_target_name = maybe_target_dir.file_name()?.to_str()?;
+
+        if maybe_target_name == "target" {
+            return maybe_target_dir.parent().map(Path::to_path_buf);
+        }
+
+        if Self::looks_like_target_triple(maybe_target_name) {
+            let target_dir = maybe_target_dir.parent()?;
+            if target_dir.file_name()?.to_str()? == "target" {
+                return target_dir.parent().map(Path::to_path_buf);
+            }
+        }
+
+        None
+    }
+
+    /// Rough heuristic for a rustc target triple, e.g. `x86_64-unknown-linux-gnu` or
+    /// `aarch64-apple-darwin` - at least three `-`-separated components.
+    fn looks_like_target_triple(name: &str) -> bool {
+        name.splitn(4, '-').count() >= 3
     }
 
     /// Get the base app data directory
@@ -52,35 +249,266 @@ impl PathResolver {
         &self.app_data_dir
     }
 
-    /// Get the recordings directory path
+    /// The logical base dir, as configured - symlinks left intact. This is what
+    /// gets persisted to the DB and shown to the user; it stays stable even if the
+    /// user relocates their data folder behind a symlink.
+    pub fn logical_base_dir(&self) -> &PathBuf {
+        &self.app_data_dir
+    }
+
+    /// The fully symlink-resolved base dir, for filesystem comparisons and equality
+    /// checks. Falls back to the logical dir if canonicalization fails (e.g. the
+    /// directory doesn't exist yet).
+    pub fn canonical_base_dir(&self) -> PathBuf {
+        self.app_data_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.app_data_dir.clone())
+    }
+
+    /// Resolve a relative path against the logical base dir. Equivalent to
+    /// [`Self::resolve_relative_path`]; this is the form to persist to the DB and to
+    /// display, since it keeps any symlink in the configured base dir intact.
+    pub fn resolve_logical(&self, relative_path: &str) -> Result<PathBuf, String> {
+        self.resolve_relative_path(relative_path)
+    }
+
+    /// Resolve a relative path against the fully symlink-resolved base dir. Use this
+    /// for filesystem comparisons and existence checks, so a data dir reached through
+    /// a symlink (e.g. relocated to an external drive) doesn't produce a path that
+    /// `exists()` agrees with but other canonicalized paths in the app don't.
+    pub fn resolve_canonical(&self, relative_path: &str) -> Result<PathBuf, String> {
+        let logical = self.resolve_relative_path(relative_path)?;
+        match logical.canonicalize() {
+            Ok(canonical) => Ok(canonical),
+            Err(_) => {
+                // Path doesn't exist (yet): canonicalize the base dir and re-append the
+                // (already-validated) relative tail instead of failing outright.
+                let tail = logical.strip_prefix(&self.app_data_dir).unwrap_or(&logical);
+                Ok(self.canonical_base_dir().join(tail))
+            }
+        }
+    }
+
+    /// Get the recordings directory path - under the active profile's root, if any.
     pub fn get_recordings_dir(&self) -> PathBuf {
-        self.app_data_dir.join("recordings")
+        self.profile_root().join("recordings")
     }
 
-    /// Get the images directory path
+    /// Get the images directory path - under the active profile's root, if any.
     pub fn get_images_dir(&self) -> PathBuf {
-        self.app_data_dir.join("images")
+        self.profile_root().join("images")
     }
 
-    /// Get the database file path
+    /// Get the database file path - under the active profile's root, if any.
     pub fn get_database_path(&self) -> PathBuf {
-        self.app_data_dir.join("database.db")
+        self.profile_root().join("database.db")
     }
 
-    /// Convert a relative path (like "dev-data/recordings/file.wav") to absolute path
-    pub fn resolve_relative_path(&self, relative_path: &str) -> PathBuf {
-        if relative_path.starts_with("dev-data/") || relative_path.starts_with("app-data/") {
+    /// The active profile's name, or `None` for the default (backward-compatible)
+    /// layout.
+    pub fn current_profile(&self) -> Option<String> {
+        self.profile.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// `app_data_dir`, or `app_data_dir/profiles/<name>` when a profile is active.
+    /// `resolve_relative_path`/`to_relative_path` deliberately keep operating
+    /// against `app_data_dir` rather than this - the `profiles/<name>/` segment
+    /// falls naturally out of `get_recordings_dir`/`get_images_dir` already living
+    /// under it, so a stored relative path encodes whichever profile it belonged
+    /// to and stays resolvable after later switching to a different one.
+    fn profile_root(&self) -> PathBuf {
+        match self.current_profile() {
+            Some(name) => self.app_data_dir.join("profiles").join(name),
+            None => self.app_data_dir.clone(),
+        }
+    }
+
+    /// List the names of profiles that have a directory under
+    /// `app_data_dir/profiles/`, sorted alphabetically. Empty if no profile has
+    /// ever been created.
+    pub fn list_profiles(&self) -> Result<Vec<String>, String> {
+        let profiles_dir = self.app_data_dir.join("profiles");
+        if !profiles_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut profiles = Vec::new();
+        for entry in std::fs::read_dir(&profiles_dir)
+            .map_err(|e| format!("Failed to read profiles directory {}: {}", profiles_dir.display(), e))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read profiles directory entry: {}", e))?;
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                if let Some(name) = entry.file_name().to_str() {
+                    profiles.push(name.to_string());
+                }
+            }
+        }
+        profiles.sort();
+        Ok(profiles)
+    }
+
+    /// Switch the active profile, re-rooting [`Self::get_database_path`],
+    /// [`Self::get_recordings_dir`], and [`Self::get_images_dir`] under
+    /// `app_data_dir/profiles/<name>/` - or back to the default layout for `None`.
+    /// Creates the profile's `recordings`/`images` subdirectories up front so
+    /// callers can rely on them existing immediately after switching.
+    pub fn switch_profile(&self, profile: Option<String>) -> Result<(), String> {
+        if let Some(name) = &profile {
+            Self::validate_profile_name(name)?;
+        }
+
+        let root = match &profile {
+            Some(name) => self.app_data_dir.join("profiles").join(name),
+            None => self.app_data_dir.clone(),
+        };
+        for dir in [root.join("recordings"), root.join("images")] {
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+        }
+
+        let mut guard = self.profile.write().map_err(|_| "Profile lock poisoned".to_string())?;
+        *guard = profile;
+        Ok(())
+    }
+
+    /// A profile name becomes a literal directory segment under
+    /// `app_data_dir/profiles/`, so it gets the same validation as any other
+    /// untrusted path segment: no `..`, no separators, no absolute/Windows-prefix
+    /// tricks (see [`Self::normalize_relative`]).
+    fn validate_profile_name(name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("Profile name cannot be empty".to_string());
+        }
+        let normalized = Self::normalize_relative(name).map_err(|e| e.to_string())?;
+        if normalized.components().count() != 1 {
+            return Err(format!("Profile name must be a single path segment, got: {}", name));
+        }
+        Ok(())
+    }
+
+    /// Directory for transient, OS-reclaimable caches (`$APPCACHE`) -
+    /// e.g. future thumbnail or model-download staging that's fine to lose
+    /// and regenerate, unlike `app_data_dir`'s durable state.
+    pub fn get_cache_dir(&self) -> &PathBuf {
+        &self.cache_dir
+    }
+
+    /// Directory for user-editable configuration (`$APPCONFIG`), kept
+    /// separate from `app_data_dir` so an OS or user backup/sync tool that
+    /// treats config and data differently doesn't have to guess which is which.
+    pub fn get_config_dir(&self) -> &PathBuf {
+        &self.config_dir
+    }
+
+    /// Directory for log files (`$APPLOG`), so logs land somewhere an OS
+    /// log-rotation/cleanup policy expects rather than mixed into `app_data_dir`.
+    pub fn get_log_dir(&self) -> &PathBuf {
+        &self.log_dir
+    }
+
+    /// Map a [`Scope`] to its root directory.
+    fn scope_root(&self, scope: Scope) -> PathBuf {
+        match scope {
+            Scope::Recordings => self.get_recordings_dir(),
+            Scope::Images => self.get_images_dir(),
+            Scope::AppData => self.app_data_dir.clone(),
+        }
+    }
+
+    /// Lexically normalize a relative path, resolving `.`/`..` segments in-memory
+    /// without touching the filesystem. Rejects absolute inputs and any path whose
+    /// `..` segments would pop above the (implicit) root.
+    fn normalize_relative(relative: &str) -> Result<PathBuf, PathError> {
+        let input_path = Path::new(relative);
+        let mut normalized = PathBuf::new();
+        let mut depth: i32 = 0;
+
+        for component in input_path.components() {
+            match component {
+                Component::Normal(segment) => {
+                    normalized.push(segment);
+                    depth += 1;
+                }
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return Err(PathError::Escapes(relative.to_string()));
+                    }
+                    normalized.pop();
+                }
+                Component::RootDir | Component::Prefix(_) => {
+                    return Err(PathError::AbsoluteInput(relative.to_string()));
+                }
+            }
+        }
+
+        Ok(normalized)
+    }
+
+    /// Resolve `relative` against the given capability [`Scope`], guaranteeing the
+    /// result stays inside that scope's root directory. The input is lexically
+    /// normalized first (rejecting absolute paths and upward `..` escapes), then the
+    /// joined result is canonicalized and checked to still live under the
+    /// canonicalized scope root.
+    pub fn resolve_scoped(&self, relative: &str, scope: Scope) -> Result<PathBuf, PathError> {
+        let normalized = Self::normalize_relative(relative)?;
+        let scope_root = self.scope_root(scope);
+
+        std::fs::create_dir_all(&scope_root).map_err(|e| PathError::Io(e.to_string()))?;
+
+        let candidate = scope_root.join(&normalized);
+
+        // canonicalize() requires the path to exist; fall back to canonicalizing the
+        // deepest existing ancestor and re-appending the remaining (not-yet-created)
+        // components so callers can resolve paths for files they are about to write.
+        let canonical_root = scope_root
+            .canonicalize()
+            .map_err(|e| PathError::Io(e.to_string()))?;
+
+        let canonical_candidate = if candidate.exists() {
+            candidate.canonicalize().map_err(|e| PathError::Io(e.to_string()))?
+        } else {
+            let mut ancestor = candidate.as_path();
+            let mut missing = Vec::new();
+            while !ancestor.exists() {
+                match (ancestor.file_name(), ancestor.parent()) {
+                    (Some(name), Some(parent)) => {
+                        missing.push(name);
+                        ancestor = parent;
+                    }
+                    _ => break,
+                }
+            }
+            let mut resolved = ancestor.canonicalize().map_err(|e| PathError::Io(e.to_string()))?;
+            resolved.extend(missing.iter().rev());
+            resolved
+        };
+
+        if !canonical_candidate.starts_with(&canonical_root) {
+            return Err(PathError::Escapes(relative.to_string()));
+        }
+
+        Ok(canonical_candidate)
+    }
+
+    /// Convert a relative path (like "dev-data/recordings/file.wav") to an absolute
+    /// path rooted at `app_data_dir`. Values passed here ultimately come from the
+    /// database (recording/image paths) and could be corrupted or tampered with, so
+    /// the input is lexically normalized first via [`Self::normalize_relative`] -
+    /// rejecting absolute paths, Windows drive/UNC prefixes, and `..` components that
+    /// would climb above `app_data_dir` - before it's ever joined onto a real path.
+    pub fn resolve_relative_path(&self, relative_path: &str) -> Result<PathBuf, String> {
+        let path_without_prefix = if relative_path.starts_with("dev-data/") || relative_path.starts_with("app-data/") {
             // Strip the environment prefix and resolve relative to our app data dir
-            let path_without_prefix = relative_path
-                .split('/')
-                .skip(1)
-                .collect::<Vec<&str>>()
-                .join("/");
-            self.app_data_dir.join(path_without_prefix)
+            relative_path.splitn(2, '/').nth(1).unwrap_or("")
         } else {
             // Assume it's relative to app data dir
-            self.app_data_dir.join(relative_path)
-        }
+            relative_path
+        };
+
+        let normalized = Self::normalize_relative(path_without_prefix).map_err(|e| e.to_string())?;
+        Ok(self.app_data_dir.join(normalized))
     }
 
     /// Generate a relative path for storing in database
@@ -102,9 +530,12 @@ impl PathResolver {
         }
     }
 
-    /// Ensure a subdirectory exists and return its path
+    /// Ensure a subdirectory exists and return its path. `subdir` is normalized the
+    /// same way [`Self::resolve_relative_path`] is, so a caller can't be tricked into
+    /// creating (or returning a handle to) a directory outside `app_data_dir`.
     pub fn ensure_subdir(&self, subdir: &str) -> Result<PathBuf, String> {
-        let dir_path = self.app_data_dir.join(subdir);
+        let normalized = Self::normalize_relative(subdir).map_err(|e| e.to_string())?;
+        let dir_path = self.app_data_dir.join(normalized);
         std::fs::create_dir_all(&dir_path)
             .map_err(|e| format!("Failed to create directory {}: {}", subdir, e))?;
         Ok(dir_path)
@@ -137,4 +568,78 @@ pub fn get_app_data_dir_fallback() -> PathBuf {
                 .join("com.dsalearning.dsaapp")
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_rejects_absolute_input() {
+        assert_eq!(
+            PathResolver::normalize_relative("/etc/passwd"),
+            Err(PathError::AbsoluteInput("/etc/passwd".to_string()))
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_parent_escape() {
+        assert_eq!(
+            PathResolver::normalize_relative("../../etc/passwd"),
+            Err(PathError::Escapes("../../etc/passwd".to_string()))
+        );
+    }
+
+    #[test]
+    fn expand_home_leaves_plain_path_untouched() {
+        let expanded = PathResolver::expand_home(Path::new("foo/bar"));
+        assert_eq!(expanded, PathBuf::from("foo/bar"));
+    }
+
+    #[test]
+    fn normalize_allows_internal_dotdot_that_stays_inside() {
+        let normalized = PathResolver::normalize_relative("foo/../bar/baz.wav").unwrap();
+        assert_eq!(normalized, PathBuf::from("bar/baz.wav"));
+    }
+
+    #[test]
+    fn normalize_passes_through_plain_relative_path() {
+        let normalized = PathResolver::normalize_relative("recordings/card_1/take.wav").unwrap();
+        assert_eq!(normalized, PathBuf::from("recordings/card_1/take.wav"));
+    }
+
+    #[test]
+    fn detects_plain_debug_layout() {
+        let exe = PathBuf::from("/workspace/src-tauri/target/debug/dsa-learning-app");
+        assert_eq!(
+            PathResolver::dev_workspace_root_from_exe(&exe),
+            Some(PathBuf::from("/workspace/src-tauri"))
+        );
+    }
+
+    #[test]
+    fn detects_plain_release_layout() {
+        let exe = PathBuf::from("/workspace/src-tauri/target/release/dsa-learning-app");
+        assert_eq!(
+            PathResolver::dev_workspace_root_from_exe(&exe),
+            Some(PathBuf::from("/workspace/src-tauri"))
+        );
+    }
+
+    #[test]
+    fn detects_target_triple_layout() {
+        let exe = PathBuf::from(
+            "/workspace/src-tauri/target/x86_64-unknown-linux-gnu/debug/dsa-learning-app",
+        );
+        assert_eq!(
+            PathResolver::dev_workspace_root_from_exe(&exe),
+            Some(PathBuf::from("/workspace/src-tauri"))
+        );
+    }
+
+    #[test]
+    fn rejects_installed_bundle_layout() {
+        let exe = PathBuf::from("/usr/bin/dsa-learning-app");
+        assert_eq!(PathResolver::dev_workspace_root_from_exe(&exe), None);
+    }
+}
@@ -0,0 +1,203 @@
+// Storage usage analytics for the recordings and images directories.
+//
+// Recordings (.wav) and problem images accumulate indefinitely with no visibility
+// into what is consuming disk. This module walks both directories with a small,
+// bounded worker pool (not the global rayon pool, so threads don't linger after the
+// scan finishes) and reports per-directory totals plus the largest files.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Number of worker threads used to walk a single directory tree.
+const WORKER_COUNT: usize = 4;
+
+/// Largest-files list is capped to this many entries per directory.
+const TOP_N: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSizeInfo {
+    pub path: String,
+    pub apparent_size: u64,
+    pub on_disk_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryUsage {
+    pub label: String,
+    pub file_count: u64,
+    pub apparent_size: u64,
+    pub on_disk_size: u64,
+    pub largest_files: Vec<FileSizeInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageUsageReport {
+    pub recordings: DirectoryUsage,
+    pub images: DirectoryUsage,
+}
+
+/// Identifies a file uniquely on disk so a file linked twice is only counted once.
+/// On platforms without inode numbers (Windows) this always reports "unseen" and we
+/// fall back to counting every entry by apparent size.
+#[cfg(unix)]
+fn file_identity(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Blocks actually allocated on disk (512-byte units on unix), falling back to the
+/// apparent size on platforms that don't expose block counts.
+#[cfg(unix)]
+fn on_disk_size(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn on_disk_size(metadata: &std::fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// Walk `root` with a bounded pool of `WORKER_COUNT` threads, staying on the root's
+/// filesystem (skipping mount points, e.g. a symlinked external volume) and dedupe
+/// hard-linked files by `(device, inode)`.
+fn scan_directory(root: &Path, label: &str) -> DirectoryUsage {
+    if !root.exists() {
+        return DirectoryUsage {
+            label: label.to_string(),
+            file_count: 0,
+            apparent_size: 0,
+            on_disk_size: 0,
+            largest_files: Vec::new(),
+        };
+    }
+
+    let root_device = std::fs::metadata(root).ok().and_then(|m| file_identity(&m)).map(|(dev, _)| dev);
+
+    // `queue` holds directories still to visit; `active` counts workers currently
+    // processing one. Both live behind one lock so "queue empty AND nobody is mid-
+    // directory" (the only valid stop condition) can be checked atomically - checking
+    // them via two separate locks would race a worker that just popped the last entry.
+    struct Work {
+        queue: VecDeque<PathBuf>,
+        active: usize,
+    }
+    let work: Arc<Mutex<Work>> = Arc::new(Mutex::new(Work {
+        queue: VecDeque::from([root.to_path_buf()]),
+        active: 0,
+    }));
+    let seen_inodes: Arc<Mutex<std::collections::HashSet<(u64, u64)>>> = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let files: Arc<Mutex<Vec<FileSizeInfo>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let mut handles = Vec::with_capacity(WORKER_COUNT);
+    for _ in 0..WORKER_COUNT {
+        let work = Arc::clone(&work);
+        let seen_inodes = Arc::clone(&seen_inodes);
+        let files = Arc::clone(&files);
+
+        handles.push(thread::spawn(move || loop {
+            let next_dir = {
+                let mut guard = work.lock().unwrap();
+                match guard.queue.pop_front() {
+                    Some(dir) => {
+                        guard.active += 1;
+                        Some(dir)
+                    }
+                    None if guard.active == 0 => return,
+                    None => None,
+                }
+            };
+
+            let Some(dir) = next_dir else {
+                thread::yield_now();
+                continue;
+            };
+
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => {
+                    work.lock().unwrap().active -= 1;
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata() else { continue };
+
+                if metadata.is_dir() {
+                    // Stay on one filesystem: skip directories that are mount points
+                    // for a different device than the scan root.
+                    if let (Some(root_dev), Some((entry_dev, _))) = (root_device, file_identity(&metadata)) {
+                        if entry_dev != root_dev {
+                            continue;
+                        }
+                    }
+                    work.lock().unwrap().queue.push_back(path);
+                    continue;
+                }
+
+                if !metadata.is_file() {
+                    continue;
+                }
+
+                if let Some(identity) = file_identity(&metadata) {
+                    let mut seen = seen_inodes.lock().unwrap();
+                    if !seen.insert(identity) {
+                        continue; // already counted this inode via another hard link
+                    }
+                }
+
+                files.lock().unwrap().push(FileSizeInfo {
+                    path: path.display().to_string(),
+                    apparent_size: metadata.len(),
+                    on_disk_size: on_disk_size(&metadata),
+                });
+            }
+
+            work.lock().unwrap().active -= 1;
+        }));
+    }
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    let mut files = Arc::try_unwrap(files).unwrap().into_inner().unwrap();
+    let file_count = files.len() as u64;
+    let apparent_size: u64 = files.iter().map(|f| f.apparent_size).sum();
+    let on_disk_total: u64 = files.iter().map(|f| f.on_disk_size).sum();
+
+    files.sort_by(|a, b| b.apparent_size.cmp(&a.apparent_size));
+    files.truncate(TOP_N);
+
+    DirectoryUsage {
+        label: label.to_string(),
+        file_count,
+        apparent_size,
+        on_disk_size: on_disk_total,
+        largest_files: files,
+    }
+}
+
+pub fn compute_storage_usage(recordings_dir: &Path, images_dir: &Path) -> StorageUsageReport {
+    // Scan both directories concurrently; each scan already uses its own bounded pool.
+    let recordings_dir = recordings_dir.to_path_buf();
+    let images_dir = images_dir.to_path_buf();
+
+    let recordings_handle = thread::spawn(move || scan_directory(&recordings_dir, "recordings"));
+    let images_handle = thread::spawn(move || scan_directory(&images_dir, "images"));
+
+    StorageUsageReport {
+        recordings: recordings_handle.join().unwrap(),
+        images: images_handle.join().unwrap(),
+    }
+}
@@ -0,0 +1,232 @@
+// Storage-format requirements file, the filesystem-layout counterpart to
+// `database::migrations`' schema-version tracking. Borrows the idea from
+// Mercurial's `.hg/requires`: a small flat file recorded inside
+// `app_data_dir` the first time it's created, naming which on-disk
+// capabilities this install's data directory uses and which layout version
+// it's at. `PathResolver::new` reads it on every startup so a binary that's
+// older than the data it's pointed at fails loudly (and tells the user to
+// upgrade) instead of silently misreading a directory structure it doesn't
+// understand, while a binary that's newer runs `StorageMigrator` to bring
+// an older data directory forward.
+
+use crate::path_resolver::PathResolver;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Bump whenever the physical layout of `app_data_dir` changes in a way that
+/// requires more than just adding a new, independently-discoverable file -
+/// e.g. relocating a directory or rewriting stored path prefixes. Unrelated
+/// to `database::migrations::LATEST_VERSION`, which tracks the SQL schema.
+pub const CURRENT_FORMAT_VERSION: i64 = 1;
+
+/// Capability tokens this binary knows how to read. A requirements file
+/// naming a token outside this set was written by a newer build that added
+/// an on-disk capability this one doesn't understand, so `PathResolver::new`
+/// refuses to open the directory rather than silently ignoring it.
+const KNOWN_CAPABILITIES: &[&str] = &["content-addressed-images", "storage-roots"];
+
+const REQUIREMENTS_FILENAME: &str = "requirements";
+
+/// Parsed contents of the `requirements` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageRequirements {
+    pub format_version: i64,
+    pub capabilities: HashSet<String>,
+}
+
+impl StorageRequirements {
+    fn current() -> Self {
+        Self {
+            format_version: CURRENT_FORMAT_VERSION,
+            capabilities: KNOWN_CAPABILITIES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<Self, StorageFormatError> {
+        let mut format_version = None;
+        let mut capabilities = HashSet::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(version_str) = line.strip_prefix("format_version:") {
+                format_version = Some(
+                    version_str
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|e| StorageFormatError::Parse(format!("invalid format_version: {}", e)))?,
+                );
+            } else {
+                capabilities.insert(line.to_string());
+            }
+        }
+
+        let format_version = format_version
+            .ok_or_else(|| StorageFormatError::Parse("requirements file is missing format_version".to_string()))?;
+
+        Ok(Self { format_version, capabilities })
+    }
+
+    fn serialize(&self) -> String {
+        let mut out = format!("format_version:{}\n", self.format_version);
+        let mut sorted: Vec<&String> = self.capabilities.iter().collect();
+        sorted.sort();
+        for capability in sorted {
+            out.push_str(capability);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Errors that can come out of reading or validating the requirements file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageFormatError {
+    Io(String),
+    Parse(String),
+    /// The on-disk `format_version` is newer than `CURRENT_FORMAT_VERSION` -
+    /// this binary is older than the data directory it's pointed at.
+    TooNew { on_disk: i64, supported: i64 },
+    /// The requirements file names a capability this binary doesn't
+    /// recognize - written by a newer build, not safe to open.
+    UnknownCapability(String),
+}
+
+impl fmt::Display for StorageFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageFormatError::Io(e) => write!(f, "failed to access requirements file: {}", e),
+            StorageFormatError::Parse(e) => write!(f, "failed to parse requirements file: {}", e),
+            StorageFormatError::TooNew { on_disk, supported } => write!(
+                f,
+                "data directory format version {} is newer than this build supports ({}) - please upgrade the app",
+                on_disk, supported
+            ),
+            StorageFormatError::UnknownCapability(token) => write!(
+                f,
+                "data directory requires capability '{}', which this build doesn't understand - please upgrade the app",
+                token
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StorageFormatError {}
+
+impl From<StorageFormatError> for String {
+    fn from(err: StorageFormatError) -> Self {
+        err.to_string()
+    }
+}
+
+fn requirements_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(REQUIREMENTS_FILENAME)
+}
+
+fn read_requirements(app_data_dir: &Path) -> Result<Option<StorageRequirements>, StorageFormatError> {
+    let path = requirements_path(app_data_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| StorageFormatError::Io(e.to_string()))?;
+    StorageRequirements::parse(&contents).map(Some)
+}
+
+/// Writes `requirements` atomically: the new contents land in a sibling
+/// `.tmp` file first, then `rename` swaps it into place, so a crash
+/// mid-write never leaves a half-written requirements file behind.
+fn write_requirements(app_data_dir: &Path, requirements: &StorageRequirements) -> Result<(), StorageFormatError> {
+    let path = requirements_path(app_data_dir);
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, requirements.serialize()).map_err(|e| StorageFormatError::Io(e.to_string()))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| StorageFormatError::Io(e.to_string()))?;
+    Ok(())
+}
+
+/// Reads (or, for a fresh `app_data_dir`, creates) the requirements file,
+/// running any pending `StorageMigrator` steps first if it's behind
+/// `CURRENT_FORMAT_VERSION`. Called once from `PathResolver::new`.
+pub(crate) fn ensure_storage_format(resolver: &PathResolver) -> Result<(), StorageFormatError> {
+    let app_data_dir = resolver.get_app_data_dir();
+
+    let existing = read_requirements(app_data_dir)?;
+
+    let Some(existing) = existing else {
+        // Fresh app_data_dir: nothing to migrate, just record what this
+        // build's current format looks like.
+        return write_requirements(app_data_dir, &StorageRequirements::current());
+    };
+
+    if existing.format_version > CURRENT_FORMAT_VERSION {
+        return Err(StorageFormatError::TooNew {
+            on_disk: existing.format_version,
+            supported: CURRENT_FORMAT_VERSION,
+        });
+    }
+
+    for capability in &existing.capabilities {
+        if !KNOWN_CAPABILITIES.contains(&capability.as_str()) {
+            return Err(StorageFormatError::UnknownCapability(capability.clone()));
+        }
+    }
+
+    if existing.format_version < CURRENT_FORMAT_VERSION {
+        StorageMigrator::new()
+            .migrate(resolver, existing.format_version)
+            .map_err(|e| StorageFormatError::Io(e.to_string()))?;
+        write_requirements(app_data_dir, &StorageRequirements::current())?;
+    }
+
+    Ok(())
+}
+
+/// One step in bringing an `app_data_dir` forward from an older
+/// `format_version`. `run` may move recordings/images, rewrite relative
+/// path prefixes stored in the database, or bump the SQL schema - whatever
+/// that layout version's upgrade requires.
+pub struct MigrationStep {
+    pub to_version: i64,
+    pub name: &'static str,
+    pub run: fn(&PathResolver) -> anyhow::Result<()>,
+}
+
+/// Ordered pipeline of [`MigrationStep`]s, run by `ensure_storage_format`
+/// when an existing data directory's `format_version` is behind
+/// `CURRENT_FORMAT_VERSION`. Empty today - `CURRENT_FORMAT_VERSION` is the
+/// format's inaugural version, so there's nothing yet to migrate from; new
+/// steps get appended here the same way `database::migrations::MIGRATIONS`
+/// only ever grows.
+const STORAGE_MIGRATIONS: &[MigrationStep] = &[];
+
+pub struct StorageMigrator {
+    steps: &'static [MigrationStep],
+}
+
+impl StorageMigrator {
+    pub fn new() -> Self {
+        Self { steps: STORAGE_MIGRATIONS }
+    }
+
+    /// Runs every step newer than `from_version`, in order, against
+    /// `resolver`.
+    pub fn migrate(&self, resolver: &PathResolver, from_version: i64) -> anyhow::Result<()> {
+        for step in self.steps.iter().filter(|s| s.to_version > from_version) {
+            println!(
+                "🔧 [StorageMigrator] Applying storage format migration {} ({})...",
+                step.to_version, step.name
+            );
+            (step.run)(resolver)?;
+            println!("✅ [StorageMigrator] Applied storage format migration {} ({})", step.to_version, step.name);
+        }
+        Ok(())
+    }
+}
+
+impl Default for StorageMigrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}